@@ -2,10 +2,12 @@ use crate::{
 	ActiveEnumBuilder, ActiveEnumFilterInputBuilder, BuilderContext, ConnectionObjectBuilder,
 	CursorInputBuilder, EdgeObjectBuilder, EntityCreateBatchMutationBuilder,
 	EntityCreateOneMutationBuilder, EntityDeleteMutationBuilder, EntityInputBuilder,
-	EntityObjectBuilder, EntityQueryFieldBuilder, EntityUpdateMutationBuilder, FilterInputBuilder,
-	FilterTypeTrait, OffsetInputBuilder, OneToManyLoader, OneToOneLoader, OrderByEnumBuilder,
-	OrderInputBuilder, PageInfoObjectBuilder, PageInputBuilder, PaginationInfoObjectBuilder,
-	PaginationInputBuilder,
+	EntityObjectBuilder, EntityQueryFieldBuilder, EntityResolvers, EntityRestoreMutationBuilder,
+	EntityStreamQueryFieldBuilder, EntityUpdateMutationBuilder, EntityUpsertMutationBuilder,
+	FilterInputBuilder, FilterTypeTrait, NestedInsertRelation, NestedInsertRelations,
+	OffsetInputBuilder, OneToManyLoader, OneToOneLoader, OrderByEnumBuilder, OrderInputBuilder,
+	PageInfoObjectBuilder, PageInputBuilder, PaginationInfoObjectBuilder, PaginationInputBuilder,
+	entity_resolver, resolve_entity,
 };
 use dynamic::prelude::*;
 use sea_orm::{ActiveEnum, ActiveModelTrait, EntityTrait, IntoActiveModel};
@@ -39,6 +41,12 @@ where
 	/// holds all entities mutations
 	pub mutations: Vec<Field<Ty>>,
 
+	/// holds the relations that can be inserted together with each entity
+	pub nested_inserts: NestedInsertRelations,
+
+	/// holds the federation entity resolvers of each entity
+	pub entity_resolvers: EntityResolvers,
+
 	/// holds a copy to the database connection
 	pub connection: sea_orm::DatabaseConnection,
 
@@ -78,6 +86,8 @@ where
 			enumerations: Vec::new(),
 			queries: Vec::new(),
 			mutations: Vec::new(),
+			nested_inserts: NestedInsertRelations::default(),
+			entity_resolvers: EntityResolvers::default(),
 			connection,
 			context,
 			_marker: std::marker::PhantomData,
@@ -126,6 +136,33 @@ where
 		};
 		let query = entity_query_field_builder.to_field::<T, Ty, F>();
 		self.queries.push(query);
+
+		// the stream query is only served over grpc
+		let entity_stream_query_field_builder = EntityStreamQueryFieldBuilder {
+			context: self.context,
+		};
+		let stream_query = entity_stream_query_field_builder.to_field::<T, Ty, F>();
+		self.queries.push(stream_query);
+
+		if self.context.entity_object.federation {
+			self.entity_resolvers
+				.0
+				.insert(entity_object_builder.type_name::<T>(), resolve_entity::<T>);
+		}
+
+		#[cfg(feature = "with-json")]
+		{
+			let aggregate_object_builder = crate::AggregateObjectBuilder {
+				context: self.context,
+			};
+			self.outputs.extend(aggregate_object_builder.to_objects::<T, Ty>());
+
+			let entity_aggregate_query_field_builder = crate::EntityAggregateQueryFieldBuilder {
+				context: self.context,
+			};
+			let aggregate_query = entity_aggregate_query_field_builder.to_field::<T, Ty, F>();
+			self.queries.push(aggregate_query);
+		}
 	}
 
 	pub fn register_entity_methods<T, A>(&mut self)
@@ -146,9 +183,20 @@ where
 			context: self.context,
 		};
 
-		let entity_insert_input_object = entity_input_builder.insert_input_object::<T, Ty>();
+		let entity_insert_input_object =
+			match self.nested_inserts.0.get(&entity_object_builder.type_name::<T>()) {
+				Some(relations) => {
+					entity_input_builder.nested_insert_input_object::<T, Ty>(relations)
+				}
+				None => entity_input_builder.insert_input_object::<T, Ty>(),
+			};
 		let entity_update_input_object = entity_input_builder.update_input_object::<T, Ty>();
-		self.inputs.extend(vec![entity_insert_input_object, entity_update_input_object]);
+		let entity_upsert_input_object = entity_input_builder.upsert_input_object::<T, Ty>();
+		self.inputs.extend(vec![
+			entity_insert_input_object,
+			entity_update_input_object,
+			entity_upsert_input_object,
+		]);
 
 		// create one mutation
 		let entity_create_one_mutation_builder = EntityCreateOneMutationBuilder {
@@ -165,6 +213,13 @@ where
 		let create_batch_mutation = entity_create_batch_mutation_builder.to_field::<T, A, Ty>();
 		self.mutations.push(create_batch_mutation);
 
+		// upsert mutation
+		let entity_upsert_mutation_builder = EntityUpsertMutationBuilder {
+			context: self.context,
+		};
+		let upsert_mutation = entity_upsert_mutation_builder.to_field::<T, A, Ty>();
+		self.mutations.push(upsert_mutation);
+
 		// update mutation
 		let entity_update_mutation_builder = EntityUpdateMutationBuilder {
 			context: self.context,
@@ -177,6 +232,30 @@ where
 		};
 		let delete_mutation = entity_delete_mutation_builder.to_field::<T, A, Ty, F>();
 		self.mutations.push(delete_mutation);
+
+		// restore mutation, only soft deleted entities can be restored
+		let entity_restore_mutation_builder = EntityRestoreMutationBuilder {
+			context: self.context,
+		};
+		if let Some(restore_mutation) = entity_restore_mutation_builder.to_field::<T, Ty, F>() {
+			self.mutations.push(restore_mutation);
+		}
+	}
+
+	/// used to register the relations that can be inserted together with an entity
+	pub fn register_entity_nested_inserts<T>(&mut self, relations: Vec<NestedInsertRelation>)
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		if !self.context.entity_input.insert_relations || relations.is_empty() {
+			return;
+		}
+
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		self.nested_inserts.0.insert(entity_object_builder.type_name::<T>(), relations);
 	}
 
 	pub fn register_entity_dataloader_one_to_one<T>(mut self, _entity: T) -> Self
@@ -223,7 +302,15 @@ where
 	pub fn builder(self) -> DynamicBuilder<Ty, E> {
 		let query = self.query;
 		let mutation = self.mutation;
-		let builder = self.builder;
+		let builder = self.builder.data(self.nested_inserts);
+
+		// entities are resolved through their primary key when using federation
+		let builder = match self.entity_resolvers.0.is_empty() {
+			true => builder,
+			false => {
+				builder.data(self.entity_resolvers).entity_resolver(entity_resolver(self.context))
+			}
+		};
 
 		// register queries
 		let query = self.queries.into_iter().fold(query, |query, field| query.field(field));
@@ -258,6 +345,12 @@ where
 				}
 				.enumeration::<E>(),
 			)
+			.register(
+				OrderInputBuilder {
+					context: self.context,
+				}
+				.search_rank_input_object(),
+			)
 			.register(
 				CursorInputBuilder {
 					context: self.context,
@@ -307,6 +400,11 @@ pub trait RelationBuilder {
 	where
 		T: TypeRefTrait,
 		F: FilterTypeTrait;
+
+	fn get_nested_insert(
+		&self,
+		context: &'static BuilderContext,
+	) -> dynamic::SeaResult<Option<NestedInsertRelation>>;
 }
 
 #[macro_export]
@@ -325,6 +423,14 @@ macro_rules! register_entity {
 		);
 		$builder = $builder.register_entity_dataloader_one_to_one($module_path::Entity);
 		$builder = $builder.register_entity_dataloader_one_to_many($module_path::Entity);
+		// relations whose columns can not be resolved are left out of the nested inserts
+		$builder.register_entity_nested_inserts::<$module_path::Entity>(
+			<$module_path::RelatedEntity as sea_orm::Iterable>::iter()
+				.filter_map(|rel| {
+					apy::RelationBuilder::get_nested_insert(&rel, $builder.context).ok().flatten()
+				})
+				.collect(),
+		);
 		$builder.register_entity_methods::<$module_path::Entity, $module_path::ActiveModel>();
 	};
 }
@@ -1,8 +1,10 @@
 use crate::{
-	ActiveEnumConfig, ActiveEnumFilterInputConfig, ConnectionObjectConfig, CursorInputConfig,
-	EdgeObjectConfig, EntityCreateBatchMutationConfig, EntityCreateOneMutationConfig,
-	EntityDeleteMutationConfig, EntityInputConfig, EntityObjectConfig, EntityQueryFieldConfig,
-	EntityUpdateMutationConfig, FilterInputConfig, OffsetInputConfig, OrderByEnumConfig,
+	ActiveEnumConfig, ActiveEnumFilterInputConfig, AggregateObjectConfig, ConnectionObjectConfig,
+	CursorInputConfig, EdgeObjectConfig, EntityCreateBatchMutationConfig,
+	EntityCreateOneMutationConfig, EntityDeleteMutationConfig, EntityInputConfig,
+	EntityObjectConfig, EntityQueryFieldConfig, EntityRestoreMutationConfig,
+	EntityStreamQueryFieldConfig, EntityUpdateMutationConfig, EntityUpsertMutationConfig,
+	FilterInputConfig, JsonFilterInputConfig, OffsetInputConfig, OrderByEnumConfig,
 	OrderInputConfig, PageInfoObjectConfig, PageInputConfig, PaginationInfoObjectConfig,
 	PaginationInputConfig,
 };
@@ -10,6 +12,18 @@ use crate::{
 pub mod guards;
 pub use guards::*;
 
+pub mod policies;
+pub use policies::*;
+
+pub mod soft_deletes;
+pub use soft_deletes::*;
+
+pub mod versions;
+pub use versions::*;
+
+pub mod field_masks;
+pub use field_masks::*;
+
 pub mod types_map;
 pub use types_map::*;
 
@@ -34,22 +48,35 @@ pub struct BuilderContext {
 
 	pub filter_input: FilterInputConfig,
 	pub active_enum_filter_input: ActiveEnumFilterInputConfig,
+	pub json_filter_input: JsonFilterInputConfig,
+	#[cfg(feature = "with-postgres-array")]
+	pub array_filter_input: crate::ArrayFilterInputConfig,
 
 	pub page_info_object: PageInfoObjectConfig,
 	pub pagination_info_object: PaginationInfoObjectConfig,
 	pub edge_object: EdgeObjectConfig,
 	pub entity_object: EntityObjectConfig,
 	pub connection_object: ConnectionObjectConfig,
+	pub aggregate_object: AggregateObjectConfig,
 	pub entity_query_field: EntityQueryFieldConfig,
+	pub entity_stream_query_field: EntityStreamQueryFieldConfig,
+	#[cfg(feature = "with-json")]
+	pub entity_aggregate_query_field: crate::EntityAggregateQueryFieldConfig,
 
 	pub entity_create_one_mutation: EntityCreateOneMutationConfig,
 	pub entity_create_batch_mutation: EntityCreateBatchMutationConfig,
+	pub entity_upsert_mutation: EntityUpsertMutationConfig,
 	pub entity_update_mutation: EntityUpdateMutationConfig,
 	pub entity_delete_mutation: EntityDeleteMutationConfig,
+	pub entity_restore_mutation: EntityRestoreMutationConfig,
 
 	pub entity_input: EntityInputConfig,
 
 	pub guards: GuardsConfig<FnGuard>,
+	pub policies: PoliciesConfig,
+	pub soft_deletes: SoftDeletesConfig,
+	pub versions: VersionsConfig,
+	pub field_masks: FieldMasksConfig,
 	pub types: TypesMapConfig,
 	pub filter_types: FilterTypesMapConfig,
 	// is_skipped function
@@ -0,0 +1,275 @@
+use crate::{BuilderContext, EntityObjectBuilder};
+use dynamic::prelude::{Field, ObjectAccessor, SeaResult, SeaographyError, TypeRefTrait};
+use heck::ToSnakeCase;
+use sea_orm::{
+	ColumnTrait, ColumnType, EntityTrait, IdenStatic, Iterable, Order, PrimaryKeyToColumn,
+	QuerySelect, Select, Value,
+	sea_query::{Expr, Keyword, SimpleExpr},
+};
+
+/// Field masks configuration.
+/// The grpc requests may limit the fields they read and the columns they update to the paths
+/// of a `google.protobuf.FieldMask`.
+pub struct FieldMasksConfig {
+	/// name for the argument limiting the fields read by queries
+	pub read_mask: String,
+	/// name for the argument limiting the columns set by update mutations
+	pub update_mask: String,
+}
+
+impl std::default::Default for FieldMasksConfig {
+	fn default() -> Self {
+		Self {
+			read_mask: "read_mask".into(),
+			update_mask: "update_mask".into(),
+		}
+	}
+}
+
+/// used to add a field mask argument to a field, the argument is only served over grpc
+pub fn field_mask_argument<Ty>(name: &str, field: Field<Ty>) -> Field<Ty>
+where
+	Ty: TypeRefTrait,
+{
+	field.argument(Field::input(name, Ty::named(Ty::FIELD_MASK)).set_proto_only())
+}
+
+/// used to read the paths of a field mask argument,
+/// None indicates that the argument is missing or empty and every field is selected
+pub fn field_mask_paths(args: &ObjectAccessor, name: &str) -> SeaResult<Option<Vec<String>>> {
+	let value = match args.get(name) {
+		Some(value) if !value.is_null() => value,
+		_ => return Ok(None),
+	};
+
+	let paths: Vec<String> = value
+		.string()?
+		.split(',')
+		.map(str::trim)
+		.filter(|path| !path.is_empty())
+		.map(Into::into)
+		.collect();
+
+	match paths.is_empty() {
+		true => Ok(None),
+		false => Ok(Some(paths)),
+	}
+}
+
+/// used to check if a field is selected by the paths of a field mask,
+/// the first segment of a path names the field and names are compared in snake case
+pub fn field_mask_contains(paths: &[String], name: &str) -> bool {
+	let name = name.to_snake_case();
+	paths
+		.iter()
+		.any(|path| path.split('.').next().is_some_and(|field| field.to_snake_case() == name))
+}
+
+/// used to get the columns named by the paths of an update mask,
+/// paths that do not name a column of the entity are rejected
+pub fn update_mask_columns<T>(
+	context: &'static BuilderContext,
+	paths: &[String],
+) -> SeaResult<Vec<T::Column>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	mask_columns::<T>(context, paths, &context.field_masks.update_mask)
+}
+
+/// used to limit the columns read by a statement to the paths of a read mask,
+/// paths that do not name a column of the entity are rejected
+///
+/// only the masked, the primary key and the ordered columns, which the cursors are built
+/// from, are read, the other columns are selected as placeholders so the rows still decode
+/// into models
+pub fn apply_read_mask<T>(
+	context: &'static BuilderContext,
+	stmt: Select<T>,
+	paths: &[String],
+	order_by: &[(T::Column, Order)],
+) -> SeaResult<Select<T>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let masked = mask_columns::<T>(context, paths, &context.field_masks.read_mask)?;
+
+	Ok(T::Column::iter().fold(stmt.select_only(), |stmt, column| {
+		let selected = masked.iter().any(|masked| masked.as_str() == column.as_str())
+			|| T::PrimaryKey::iter().any(|key| key.into_column().as_str() == column.as_str())
+			|| order_by.iter().any(|(ordered, _)| ordered.as_str() == column.as_str());
+
+		match placeholder(&column) {
+			Some(placeholder) if !selected => stmt.column_as(placeholder, column.as_str()),
+			_ => stmt.column(column),
+		}
+	}))
+}
+
+/// used to get the columns named by the paths of a field mask,
+/// nested paths and paths that do not name a column of the entity are rejected
+fn mask_columns<T>(
+	context: &'static BuilderContext,
+	paths: &[String],
+	argument: &str,
+) -> SeaResult<Vec<T::Column>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+	let error = |message: String| {
+		SeaographyError::TypeConversionError(
+			message,
+			format!("FieldMask - {}.{}", entity_object_builder.type_name::<T>(), argument),
+		)
+	};
+
+	paths
+		.iter()
+		.map(|path| {
+			if path.contains('.') {
+				return Err(error(format!("nested field `{}` is not supported", path)));
+			}
+
+			T::Column::iter()
+				.find(|column| {
+					field_mask_contains(
+						std::slice::from_ref(path),
+						&entity_object_builder.column_name::<T>(column),
+					)
+				})
+				.ok_or_else(|| error(format!("unknown field `{}`", path)))
+		})
+		.collect()
+}
+
+/// used to get the value read in place of a column outside a read mask, nullable columns are
+/// read as null and the other ones as the zero value of their type,
+/// None indicates that the type has no zero value and the column itself is read
+fn placeholder<C>(column: &C) -> Option<SimpleExpr>
+where
+	C: ColumnTrait,
+{
+	let def = column.def();
+	if def.is_null() {
+		return Some(SimpleExpr::Keyword(Keyword::Null));
+	}
+
+	let value: Value = match def.get_column_type() {
+		ColumnType::String(_) | ColumnType::Text => "".into(),
+		ColumnType::TinyInteger => 0i8.into(),
+		ColumnType::SmallInteger => 0i16.into(),
+		ColumnType::Integer => 0i32.into(),
+		ColumnType::BigInteger => 0i64.into(),
+		ColumnType::TinyUnsigned => 0u8.into(),
+		ColumnType::SmallUnsigned => 0u16.into(),
+		ColumnType::Unsigned => 0u32.into(),
+		ColumnType::BigUnsigned => 0u64.into(),
+		ColumnType::Float => 0f32.into(),
+		ColumnType::Double => 0f64.into(),
+		ColumnType::Boolean => false.into(),
+		ColumnType::Binary(_) | ColumnType::VarBinary(_) | ColumnType::Blob => {
+			Vec::<u8>::new().into()
+		}
+		#[cfg(feature = "with-json")]
+		ColumnType::Json | ColumnType::JsonBinary => sea_orm::JsonValue::Null.into(),
+		#[cfg(feature = "with-uuid")]
+		ColumnType::Uuid => sea_orm::prelude::Uuid::nil().into(),
+		_ => return None,
+	};
+
+	Some(Expr::value(value))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+	use sea_orm::{DbBackend, QueryTrait};
+
+	fn context() -> &'static BuilderContext {
+		Box::leak(Box::default())
+	}
+
+	fn paths(paths: &[&str]) -> Vec<String> {
+		paths.iter().map(|path| path.to_string()).collect()
+	}
+
+	fn read_mask(paths: &[String], order_by: &[(film::Column, Order)]) -> SeaResult<String> {
+		let stmt =
+			apply_read_mask::<film::Entity>(context(), film::Entity::find(), paths, order_by)?;
+		Ok(stmt.build(DbBackend::Postgres).to_string())
+	}
+
+	/// Should select a field by the first segment of a path in snake case.
+	#[test]
+	fn contains() {
+		let paths = paths(&["releaseYear", "metadata.rating"]);
+
+		assert!(field_mask_contains(&paths, "release_year"));
+		assert!(field_mask_contains(&paths, "metadata"));
+		assert!(!field_mask_contains(&paths, "title"));
+	}
+
+	/// Should resolve the update mask to the named columns.
+	#[test]
+	fn update_mask() {
+		let columns =
+			update_mask_columns::<film::Entity>(context(), &paths(&["title", "release_year"]));
+
+		assert!(matches!(
+			columns.unwrap().as_slice(),
+			[film::Column::Title, film::Column::ReleaseYear]
+		));
+	}
+
+	/// Should reject unknown and nested paths.
+	#[test]
+	fn invalid_paths() {
+		let message = |result: SeaResult<Vec<film::Column>>| match result {
+			Err(SeaographyError::TypeConversionError(message, _)) => message,
+			_ => panic!("expected a type conversion error"),
+		};
+
+		assert_eq!(
+			message(update_mask_columns::<film::Entity>(context(), &paths(&["rating"]))),
+			"unknown field `rating`"
+		);
+		assert_eq!(
+			message(update_mask_columns::<film::Entity>(context(), &paths(&["metadata.rating"]))),
+			"nested field `metadata.rating` is not supported"
+		);
+		assert!(matches!(
+			read_mask(&paths(&["rating"]), &[]),
+			Err(SeaographyError::TypeConversionError(..))
+		));
+		assert!(matches!(
+			read_mask(&paths(&["metadata.rating"]), &[]),
+			Err(SeaographyError::TypeConversionError(..))
+		));
+	}
+
+	/// Should only read the masked, the primary key and the ordered columns.
+	#[test]
+	fn read() {
+		assert_eq!(
+			read_mask(&paths(&["releaseYear"]), &[]).unwrap(),
+			concat!(
+				r#"SELECT "film"."id", '' AS "title", "film"."release_year", NULL AS "metadata", "#,
+				r#"0 AS "version", NULL AS "deleted_at" FROM "film""#
+			)
+		);
+		assert_eq!(
+			read_mask(&paths(&["title"]), &[(film::Column::Version, Order::Asc)]).unwrap(),
+			concat!(
+				r#"SELECT "film"."id", "film"."title", NULL AS "release_year", NULL AS "metadata", "#,
+				r#""film"."version", NULL AS "deleted_at" FROM "film""#
+			)
+		);
+	}
+}
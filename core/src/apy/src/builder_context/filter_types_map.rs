@@ -1,11 +1,14 @@
 use crate::{
-	ActiveEnumFilterInputBuilder, BuilderContext, EntityObjectBuilder, TypesMapHelper,
-	prepare_enumeration_condition,
+	ActiveEnumFilterInputBuilder, BuilderContext, EntityObjectBuilder, JsonFilterInputBuilder,
+	TypesMapHelper, prepare_enumeration_condition, prepare_json_condition, search_condition,
 };
 use dynamic::prelude::*;
-use sea_orm::{ColumnTrait, ColumnType, Condition, EntityTrait};
+use sea_orm::{ColumnTrait, ColumnType, Condition, DbBackend, EntityTrait};
 use std::collections::{BTreeMap, BTreeSet};
 
+#[cfg(feature = "with-postgres-array")]
+use crate::{ArrayFilterInputBuilder, prepare_array_condition};
+
 pub type FnFilterCondition =
 	Box<dyn Fn(Condition, &ObjectAccessor) -> SeaResult<Condition> + Send + Sync>;
 
@@ -77,6 +80,9 @@ impl FilterInfo {
 					FilterOperation::NotBetween => {
 						Field::input("not_between", Ty::named_nn_list(self.base_type.clone()))
 					}
+					FilterOperation::Search => {
+						Field::input("search", Ty::named(self.base_type.clone()))
+					}
 				};
 				object.field(field)
 			},
@@ -103,6 +109,7 @@ pub enum FilterOperation {
 	NotLike,
 	Between,
 	NotBetween,
+	Search,
 }
 
 /// The configuration for FilterTypesMapHelper
@@ -145,6 +152,7 @@ impl std::default::Default for GraphQLFilterTypes {
 					FilterOperation::NotLike,
 					FilterOperation::Between,
 					FilterOperation::NotBetween,
+					FilterOperation::Search,
 				]),
 			},
 			text_filter_info: FilterInfo {
@@ -163,6 +171,7 @@ impl std::default::Default for GraphQLFilterTypes {
 					FilterOperation::IsNotNull,
 					FilterOperation::Between,
 					FilterOperation::NotBetween,
+					FilterOperation::Search,
 				]),
 			},
 			integer_filter_info: FilterInfo {
@@ -449,6 +458,7 @@ impl std::default::Default for ProtoFilterTypes {
 					FilterOperation::NotLike,
 					FilterOperation::Between,
 					FilterOperation::NotBetween,
+					FilterOperation::Search,
 				]),
 			},
 			binary_filter_info: FilterInfo {
@@ -484,6 +494,14 @@ pub struct FilterTypesMapConfig {
 	pub overwrites: BTreeMap<String, Option<FilterTypesMapWrapper>>,
 	/// used to map entity_name.column_name to a custom condition function
 	pub condition_functions: BTreeMap<String, FnFilterCondition>,
+	/// used to map entity_name.column_name to the text search configuration
+	/// of the search operator on Postgres, e.g. "Product.description" => "german"
+	pub search_languages: BTreeMap<String, String>,
+	/// text search configuration used by columns without one in `search_languages`
+	pub default_search_language: String,
+	/// used to map entity_name.column_name to the SQLite FTS5 table indexing it,
+	/// columns without one are searched with LIKE
+	pub search_fts_tables: BTreeMap<String, String>,
 
 	pub proto: ProtoFilterTypes,
 	pub graphql: GraphQLFilterTypes,
@@ -494,6 +512,9 @@ impl std::default::Default for FilterTypesMapConfig {
 		Self {
 			overwrites: BTreeMap::default(),
 			condition_functions: BTreeMap::default(),
+			search_languages: BTreeMap::default(),
+			default_search_language: "english".into(),
+			search_fts_tables: BTreeMap::default(),
 			proto: ProtoFilterTypes::default(),
 			graphql: GraphQLFilterTypes::default(),
 		}
@@ -513,6 +534,9 @@ pub enum GraphQlFilterType {
 	Float,
 	Boolean,
 	Id,
+	Json,
+	#[cfg(feature = "with-postgres-array")]
+	Array(Box<GraphQlFilterType>),
 	Enumeration(String),
 	Custom(String),
 }
@@ -530,6 +554,9 @@ pub enum ProtoFilterType {
 	Boolean,
 	String,
 	Binary,
+	Json,
+	#[cfg(feature = "with-postgres-array")]
+	Array(Box<ProtoFilterType>),
 	Enumeration(String),
 	Custom(String),
 }
@@ -544,6 +571,7 @@ pub trait FilterTypeTrait: Sized {
 		filter: &ObjectAccessor,
 		types_map_helper: &TypesMapHelper,
 		context: &'static BuilderContext,
+		backend: DbBackend,
 		column: &T::Column,
 	) -> SeaResult<Condition>
 	where
@@ -590,15 +618,29 @@ impl FilterTypeTrait for GraphQlFilterType {
 			ColumnType::Blob => None,
 			ColumnType::Boolean => Some(GraphQlFilterType::Boolean),
 			ColumnType::Money(_) => Some(GraphQlFilterType::Text),
-			ColumnType::Json => None,
-			ColumnType::JsonBinary => None,
+			ColumnType::Json => Some(GraphQlFilterType::Json),
+			ColumnType::JsonBinary => Some(GraphQlFilterType::Json),
 			ColumnType::Uuid => Some(GraphQlFilterType::Text),
 			ColumnType::Custom(name) => Some(GraphQlFilterType::Custom(name.to_string())),
 			ColumnType::Enum {
 				name,
 				variants: _,
 			} => Some(GraphQlFilterType::Enumeration(name.to_string())),
+			#[cfg(not(feature = "with-postgres-array"))]
 			ColumnType::Array(_) => None,
+			// arrays are filtered by the filter of their basic element type
+			#[cfg(feature = "with-postgres-array")]
+			ColumnType::Array(ty) => GraphQlFilterType::to_filter_type(ty.as_ref())
+				.filter(|ty| {
+					!matches!(
+						ty,
+						GraphQlFilterType::Json
+							| GraphQlFilterType::Array(_)
+							| GraphQlFilterType::Enumeration(_)
+							| GraphQlFilterType::Custom(_)
+					)
+				})
+				.map(|ty| GraphQlFilterType::Array(Box::new(ty))),
 			ColumnType::Cidr => Some(GraphQlFilterType::Text),
 			ColumnType::Inet => Some(GraphQlFilterType::Text),
 			ColumnType::MacAddr => Some(GraphQlFilterType::Text),
@@ -624,6 +666,15 @@ impl FilterTypeTrait for GraphQlFilterType {
 				context.filter_types.graphql.boolean_filter_info.type_name.clone()
 			}
 			GraphQlFilterType::Id => context.filter_types.graphql.id_filter_info.type_name.clone(),
+			GraphQlFilterType::Json => JsonFilterInputBuilder {
+				context,
+			}
+			.type_name(),
+			#[cfg(feature = "with-postgres-array")]
+			GraphQlFilterType::Array(ty) => ArrayFilterInputBuilder {
+				context,
+			}
+			.type_name(&ty.to_value(context)),
 			GraphQlFilterType::Enumeration(name) => {
 				let active_enum_filter_input_builder = ActiveEnumFilterInputBuilder {
 					context,
@@ -641,6 +692,7 @@ impl FilterTypeTrait for GraphQlFilterType {
 		filter: &ObjectAccessor,
 		types_map_helper: &TypesMapHelper,
 		context: &'static BuilderContext,
+		backend: DbBackend,
 		column: &T::Column,
 	) -> SeaResult<Condition>
 	where
@@ -654,6 +706,20 @@ impl FilterTypeTrait for GraphQlFilterType {
 			GraphQlFilterType::Float => &context.filter_types.graphql.float_filter_info,
 			GraphQlFilterType::Boolean => &context.filter_types.graphql.boolean_filter_info,
 			GraphQlFilterType::Id => &context.filter_types.graphql.id_filter_info,
+			GraphQlFilterType::Json => {
+				return prepare_json_condition::<T>(context, filter, backend, column, condition);
+			}
+			#[cfg(feature = "with-postgres-array")]
+			GraphQlFilterType::Array(_) => {
+				return prepare_array_condition::<T>(
+					context,
+					filter,
+					types_map_helper,
+					backend,
+					column,
+					condition,
+				);
+			}
 			GraphQlFilterType::Enumeration(_) => {
 				return prepare_enumeration_condition::<T>(filter, column, condition);
 			}
@@ -804,6 +870,13 @@ impl FilterTypeTrait for GraphQlFilterType {
 						condition = condition.add(column.not_between(a, b));
 					}
 				}
+				FilterOperation::Search => {
+					if let Some(value) = filter.get("search") {
+						let value = value.string()?;
+						condition =
+							condition.add(search_condition::<T>(context, backend, column, value));
+					}
+				}
 			}
 		}
 
@@ -814,14 +887,46 @@ impl FilterTypeTrait for GraphQlFilterType {
 	where
 		Ty: TypeRefTrait,
 	{
-		vec![
+		let filters = vec![
 			context.filter_types.graphql.text_filter_info.generate_filter_input(),
 			context.filter_types.graphql.string_filter_info.generate_filter_input(),
 			context.filter_types.graphql.integer_filter_info.generate_filter_input(),
 			context.filter_types.graphql.float_filter_info.generate_filter_input(),
 			context.filter_types.graphql.boolean_filter_info.generate_filter_input(),
 			context.filter_types.graphql.id_filter_info.generate_filter_input(),
-		]
+			JsonFilterInputBuilder {
+				context,
+			}
+			.input_object(),
+			JsonFilterInputBuilder {
+				context,
+			}
+			.path_input_object(),
+		];
+
+		#[cfg(feature = "with-postgres-array")]
+		let filters = filters
+			.into_iter()
+			.chain(
+				[
+					&context.filter_types.graphql.text_filter_info,
+					&context.filter_types.graphql.string_filter_info,
+					&context.filter_types.graphql.integer_filter_info,
+					&context.filter_types.graphql.float_filter_info,
+					&context.filter_types.graphql.boolean_filter_info,
+					&context.filter_types.graphql.id_filter_info,
+				]
+				.into_iter()
+				.map(|filter_info| {
+					ArrayFilterInputBuilder {
+						context,
+					}
+					.input_object(filter_info)
+				}),
+			)
+			.collect();
+
+		filters
 	}
 
 	fn from_filter_type(map: FilterTypesMapWrapper) -> Option<Self> {
@@ -863,15 +968,29 @@ impl FilterTypeTrait for ProtoFilterType {
 			ColumnType::Blob => None,
 			ColumnType::Boolean => Some(ProtoFilterType::Boolean),
 			ColumnType::Money(_) => Some(ProtoFilterType::String),
-			ColumnType::Json => None,
-			ColumnType::JsonBinary => None,
+			ColumnType::Json => Some(ProtoFilterType::Json),
+			ColumnType::JsonBinary => Some(ProtoFilterType::Json),
 			ColumnType::Uuid => Some(ProtoFilterType::String),
 			ColumnType::Custom(name) => Some(ProtoFilterType::Custom(name.to_string())),
 			ColumnType::Enum {
 				name,
 				variants: _,
 			} => Some(ProtoFilterType::Enumeration(name.to_string())),
+			#[cfg(not(feature = "with-postgres-array"))]
 			ColumnType::Array(_) => None,
+			// arrays are filtered by the filter of their basic element type
+			#[cfg(feature = "with-postgres-array")]
+			ColumnType::Array(ty) => ProtoFilterType::to_filter_type(ty.as_ref())
+				.filter(|ty| {
+					!matches!(
+						ty,
+						ProtoFilterType::Json
+							| ProtoFilterType::Array(_)
+							| ProtoFilterType::Enumeration(_)
+							| ProtoFilterType::Custom(_)
+					)
+				})
+				.map(|ty| ProtoFilterType::Array(Box::new(ty))),
 			ColumnType::Cidr => Some(ProtoFilterType::String),
 			ColumnType::Inet => Some(ProtoFilterType::String),
 			ColumnType::MacAddr => Some(ProtoFilterType::String),
@@ -914,6 +1033,15 @@ impl FilterTypeTrait for ProtoFilterType {
 			ProtoFilterType::Binary => {
 				context.filter_types.proto.binary_filter_info.type_name.clone()
 			}
+			ProtoFilterType::Json => JsonFilterInputBuilder {
+				context,
+			}
+			.type_name(),
+			#[cfg(feature = "with-postgres-array")]
+			ProtoFilterType::Array(ty) => ArrayFilterInputBuilder {
+				context,
+			}
+			.type_name(&ty.to_value(context)),
 			ProtoFilterType::Enumeration(name) => {
 				let active_enum_filter_input_builder = ActiveEnumFilterInputBuilder {
 					context,
@@ -931,6 +1059,7 @@ impl FilterTypeTrait for ProtoFilterType {
 		filter: &ObjectAccessor,
 		types_map_helper: &TypesMapHelper,
 		context: &'static BuilderContext,
+		backend: DbBackend,
 		column: &T::Column,
 	) -> SeaResult<Condition>
 	where
@@ -949,6 +1078,20 @@ impl FilterTypeTrait for ProtoFilterType {
 			ProtoFilterType::Boolean => &context.filter_types.proto.boolean_filter_info,
 			ProtoFilterType::String => &context.filter_types.proto.string_filter_info,
 			ProtoFilterType::Binary => &context.filter_types.proto.binary_filter_info,
+			ProtoFilterType::Json => {
+				return prepare_json_condition::<T>(context, filter, backend, column, condition);
+			}
+			#[cfg(feature = "with-postgres-array")]
+			ProtoFilterType::Array(_) => {
+				return prepare_array_condition::<T>(
+					context,
+					filter,
+					types_map_helper,
+					backend,
+					column,
+					condition,
+				);
+			}
 			ProtoFilterType::Enumeration(_) => {
 				return prepare_enumeration_condition::<T>(filter, column, condition);
 			}
@@ -1099,6 +1242,13 @@ impl FilterTypeTrait for ProtoFilterType {
 						condition = condition.add(column.not_between(a, b));
 					}
 				}
+				FilterOperation::Search => {
+					if let Some(value) = filter.get("search") {
+						let value = value.string()?;
+						condition =
+							condition.add(search_condition::<T>(context, backend, column, value));
+					}
+				}
 			}
 		}
 
@@ -1109,7 +1259,7 @@ impl FilterTypeTrait for ProtoFilterType {
 	where
 		Ty: TypeRefTrait,
 	{
-		vec![
+		let filters = vec![
 			context.filter_types.proto.int32_filter_info.generate_filter_input(),
 			context.filter_types.proto.int64_filter_info.generate_filter_input(),
 			context.filter_types.proto.uint32_filter_info.generate_filter_input(),
@@ -1121,7 +1271,44 @@ impl FilterTypeTrait for ProtoFilterType {
 			context.filter_types.proto.boolean_filter_info.generate_filter_input(),
 			context.filter_types.proto.string_filter_info.generate_filter_input(),
 			context.filter_types.proto.binary_filter_info.generate_filter_input(),
-		]
+			JsonFilterInputBuilder {
+				context,
+			}
+			.input_object(),
+			JsonFilterInputBuilder {
+				context,
+			}
+			.path_input_object(),
+		];
+
+		#[cfg(feature = "with-postgres-array")]
+		let filters = filters
+			.into_iter()
+			.chain(
+				[
+					&context.filter_types.proto.int32_filter_info,
+					&context.filter_types.proto.int64_filter_info,
+					&context.filter_types.proto.uint32_filter_info,
+					&context.filter_types.proto.uint64_filter_info,
+					&context.filter_types.proto.sint32_filter_info,
+					&context.filter_types.proto.sint64_filter_info,
+					&context.filter_types.proto.float_filter_info,
+					&context.filter_types.proto.double_filter_info,
+					&context.filter_types.proto.boolean_filter_info,
+					&context.filter_types.proto.string_filter_info,
+					&context.filter_types.proto.binary_filter_info,
+				]
+				.into_iter()
+				.map(|filter_info| {
+					ArrayFilterInputBuilder {
+						context,
+					}
+					.input_object(filter_info)
+				}),
+			)
+			.collect();
+
+		filters
 	}
 	fn from_filter_type(map: FilterTypesMapWrapper) -> Option<Self> {
 		Some(map.proto)
@@ -1187,6 +1374,7 @@ impl FilterTypesMapHelper {
 		&self,
 		condition: Condition,
 		filter: &ObjectAccessor,
+		backend: DbBackend,
 		column: &T::Column,
 	) -> SeaResult<Condition>
 	where
@@ -1204,6 +1392,7 @@ impl FilterTypesMapHelper {
 			filter,
 			&types_map_helper,
 			self.context,
+			backend,
 			column,
 		)
 	}
@@ -1215,6 +1404,7 @@ impl FilterTypesMapHelper {
 		F::to_filter_type(column_type)
 	}
 
+	#[allow(clippy::too_many_arguments)]
 	pub fn prepare<T, F>(
 		&self,
 		filter_type: &Option<F>,
@@ -1222,6 +1412,7 @@ impl FilterTypesMapHelper {
 		filter: &ObjectAccessor,
 		types_map_helper: &TypesMapHelper,
 		context: &'static BuilderContext,
+		backend: DbBackend,
 		column: &T::Column,
 	) -> SeaResult<Condition>
 	where
@@ -1230,9 +1421,14 @@ impl FilterTypesMapHelper {
 		F: FilterTypeTrait,
 	{
 		match filter_type {
-			Some(filter_type) => {
-				filter_type.prepare::<T>(condition, filter, types_map_helper, context, column)
-			}
+			Some(filter_type) => filter_type.prepare::<T>(
+				condition,
+				filter,
+				types_map_helper,
+				context,
+				backend,
+				column,
+			),
 			None => Ok(condition),
 		}
 	}
@@ -0,0 +1,163 @@
+use crate::GuardAction;
+use dynamic::prelude::{ResolverContext, SeaResult, SeaographyError};
+use sea_orm::Condition;
+use std::collections::BTreeMap;
+
+/// Entities row level policies configuration.
+/// The policies are used to control which rows of an entity can be accessed.
+#[derive(Default)]
+pub struct PoliciesConfig {
+	/// entity policies are ANDed into the queries, relations, updates and deletes of an entity
+	pub entity_policies: BTreeMap<String, FnPolicy>,
+	/// insert checks are executed before a row of an entity is inserted
+	pub insert_checks: BTreeMap<String, FnInsertCheck>,
+}
+
+/// policies are functions that receive the application context
+/// and return the condition the accessed rows must match
+pub type FnPolicy = Box<dyn Fn(&ResolverContext) -> Condition + Sync + Send>;
+
+/// insert checks are functions that receive the application context
+/// and the row about to be inserted, keyed by column field name
+pub type FnInsertCheck =
+	Box<dyn Fn(&ResolverContext, &BTreeMap<String, sea_orm::Value>) -> GuardAction + Sync + Send>;
+
+/// used to AND the entity policy condition into the given condition
+pub fn apply_policy(
+	policy: Option<&FnPolicy>,
+	ctx: &ResolverContext,
+	condition: Condition,
+) -> Condition {
+	match policy {
+		Some(policy) => condition.add((*policy)(ctx)),
+		None => condition,
+	}
+}
+
+/// used to execute the insert check of an entity on a row
+pub fn check_insert(
+	check: Option<&FnInsertCheck>,
+	ctx: &ResolverContext,
+	row: &BTreeMap<String, sea_orm::Value>,
+) -> SeaResult<()> {
+	let check_flag = match check {
+		Some(check) => (*check)(ctx, row),
+		None => GuardAction::Allow,
+	};
+
+	match check_flag {
+		GuardAction::Block(reason) => Err(SeaographyError::Forbidden(
+			reason.unwrap_or_else(|| "Insert check triggered.".into()),
+		)),
+		GuardAction::Allow => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::{film, with_context};
+	use sea_orm::{
+		ColumnTrait, DbBackend, EntityTrait, QueryFilter, QueryTrait,
+		sea_query::{Expr, ValueTuple},
+	};
+
+	fn policy() -> FnPolicy {
+		Box::new(|_| Condition::all().add(film::Column::DeletedAt.is_null()))
+	}
+
+	/// Should AND the policy into the filters of the list, relation, update and delete queries.
+	#[test]
+	fn policy_conditions() {
+		let policy = policy();
+		let filters = || Condition::all().add(film::Column::Title.eq("Alien"));
+
+		with_context(|ctx| {
+			assert_eq!(
+				film::Entity::find()
+					.filter(apply_policy(Some(&policy), ctx, filters()))
+					.build(DbBackend::Postgres)
+					.to_string(),
+				concat!(
+					r#"SELECT "film"."id", "film"."title", "film"."release_year", "#,
+					r#""film"."metadata", "film"."version", "film"."deleted_at" FROM "film" "#,
+					r#"WHERE "film"."title" = 'Alien' AND "film"."deleted_at" IS NULL"#,
+				)
+			);
+
+			// the related rows are loaded in batches by the key of their parents
+			let condition = Condition::all().add(apply_policy(Some(&policy), ctx, filters())).add(
+				Expr::tuple([Expr::col(film::Column::Id).into()])
+					.in_tuples([ValueTuple::Many(vec![1.into()])]),
+			);
+			assert_eq!(
+				film::Entity::find().filter(condition).build(DbBackend::Postgres).to_string(),
+				concat!(
+					r#"SELECT "film"."id", "film"."title", "film"."release_year", "#,
+					r#""film"."metadata", "film"."version", "film"."deleted_at" FROM "film" "#,
+					r#"WHERE "film"."title" = 'Alien' AND "film"."deleted_at" IS NULL "#,
+					r#"AND ("id") IN ((1))"#,
+				)
+			);
+
+			assert_eq!(
+				film::Entity::update_many()
+					.col_expr(film::Column::Title, Expr::value("Aliens"))
+					.filter(apply_policy(Some(&policy), ctx, filters()))
+					.build(DbBackend::Postgres)
+					.to_string(),
+				concat!(
+					r#"UPDATE "film" SET "title" = 'Aliens' "#,
+					r#"WHERE "film"."title" = 'Alien' AND "film"."deleted_at" IS NULL"#,
+				)
+			);
+
+			assert_eq!(
+				film::Entity::delete_many()
+					.filter(apply_policy(Some(&policy), ctx, filters()))
+					.build(DbBackend::Postgres)
+					.to_string(),
+				concat!(
+					r#"DELETE FROM "film" "#,
+					r#"WHERE "film"."title" = 'Alien' AND "film"."deleted_at" IS NULL"#,
+				)
+			);
+
+			// entities without policy are not restricted
+			assert_eq!(
+				film::Entity::delete_many()
+					.filter(apply_policy(None, ctx, filters()))
+					.build(DbBackend::Postgres)
+					.to_string(),
+				r#"DELETE FROM "film" WHERE "film"."title" = 'Alien'"#
+			);
+		});
+	}
+
+	/// Should forbid the rows the insert check blocks.
+	#[test]
+	fn insert_check() {
+		let check: FnInsertCheck = Box::new(|_, row| match row.get("title") {
+			Some(sea_orm::Value::String(Some(title))) if title.as_str() == "Alien" => {
+				GuardAction::Allow
+			}
+			Some(_) => GuardAction::Block(Some("Only Alien can be inserted.".into())),
+			None => GuardAction::Block(None),
+		});
+		let row =
+			|title: &str| BTreeMap::from([("title".to_string(), sea_orm::Value::from(title))]);
+
+		with_context(|ctx| {
+			assert!(check_insert(Some(&check), ctx, &row("Alien")).is_ok());
+			assert!(check_insert(None, ctx, &row("Aliens")).is_ok());
+			assert!(matches!(
+				check_insert(Some(&check), ctx, &row("Aliens")),
+				Err(SeaographyError::Forbidden(reason)) if reason == "Only Alien can be inserted."
+			));
+			assert!(matches!(
+				check_insert(Some(&check), ctx, &BTreeMap::new()),
+				Err(SeaographyError::Forbidden(reason)) if reason == "Insert check triggered."
+			));
+		});
+	}
+}
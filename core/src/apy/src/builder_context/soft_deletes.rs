@@ -0,0 +1,193 @@
+use crate::{BuilderContext, EntityObjectBuilder, next_version};
+use dynamic::prelude::{Field, ObjectAccessor, SeaResult, TypeRefTrait};
+use sea_orm::{
+	ColumnTrait, ColumnType, Condition, EntityTrait, Iterable, UpdateMany,
+	sea_query::{Expr, Keyword, SimpleExpr},
+};
+use std::collections::BTreeMap;
+
+/// Entities soft delete configuration.
+/// The rows of soft deleted entities are marked as deleted instead of being removed.
+pub struct SoftDeletesConfig {
+	/// used to map an entity name to the timestamp or boolean column marking its deleted rows,
+	/// e.g. "Post" => "deletedAt"
+	pub entity_soft_deletes: BTreeMap<String, String>,
+	/// name for the argument including the deleted rows into queries and relations
+	pub include_deleted: String,
+}
+
+impl std::default::Default for SoftDeletesConfig {
+	fn default() -> Self {
+		Self {
+			entity_soft_deletes: BTreeMap::default(),
+			include_deleted: "includeDeleted".into(),
+		}
+	}
+}
+
+/// used to get the column marking the deleted rows of an entity,
+/// None indicates that the entity is not soft deleted
+pub fn soft_delete_column<T>(context: &'static BuilderContext) -> Option<T::Column>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+
+	let column_name =
+		context.soft_deletes.entity_soft_deletes.get(&entity_object_builder.type_name::<T>())?;
+
+	T::Column::iter().find(|column| &entity_object_builder.column_name::<T>(column) == column_name)
+}
+
+/// used to add the argument including the deleted rows to a field querying the entity
+pub fn soft_delete_argument<C, Ty>(
+	context: &'static BuilderContext,
+	column: Option<C>,
+	field: Field<Ty>,
+) -> Field<Ty>
+where
+	C: ColumnTrait,
+	Ty: TypeRefTrait,
+{
+	match column {
+		Some(_) => {
+			field.argument(Field::input(&context.soft_deletes.include_deleted, Ty::named(Ty::BOOL)))
+		}
+		None => field,
+	}
+}
+
+/// used to AND the not deleted condition into the given condition,
+/// unless the deleted rows are included by the arguments
+pub fn apply_soft_delete<C>(
+	context: &'static BuilderContext,
+	column: Option<C>,
+	args: &ObjectAccessor,
+	condition: Condition,
+) -> SeaResult<Condition>
+where
+	C: ColumnTrait,
+{
+	let column = match column {
+		Some(column) => column,
+		None => return Ok(condition),
+	};
+
+	let include_deleted = match args.get(&context.soft_deletes.include_deleted) {
+		Some(value) => value.bool()?,
+		None => false,
+	};
+
+	if include_deleted {
+		return Ok(condition);
+	}
+
+	Ok(condition.add(soft_deleted_condition(&column, false)))
+}
+
+/// used to get the condition matching the deleted or the not deleted rows
+pub fn soft_deleted_condition<C>(column: &C, deleted: bool) -> Condition
+where
+	C: ColumnTrait,
+{
+	match (column.def().get_column_type(), deleted) {
+		(ColumnType::Boolean, true) => Condition::all().add(column.eq(true)),
+		(ColumnType::Boolean, false) => {
+			Condition::any().add(column.eq(false)).add(column.is_null())
+		}
+		(_, true) => Condition::all().add(column.is_not_null()),
+		(_, false) => Condition::all().add(column.is_null()),
+	}
+}
+
+/// used to get the value the column is set to when rows are deleted or restored,
+/// timestamps are set to the current timestamp and cleared on restore
+pub fn soft_delete_value<C>(column: &C, deleted: bool) -> SimpleExpr
+where
+	C: ColumnTrait,
+{
+	match (column.def().get_column_type(), deleted) {
+		(ColumnType::Boolean, deleted) => Expr::value(deleted),
+		(_, true) => Expr::current_timestamp().into(),
+		(_, false) => SimpleExpr::Keyword(Keyword::Null),
+	}
+}
+
+/// used to get the update marking the rows as deleted or restored,
+/// versioned rows also get their next version
+pub fn soft_delete_update<T>(
+	column: T::Column,
+	version: Option<T::Column>,
+	deleted: bool,
+) -> UpdateMany<T>
+where
+	T: EntityTrait,
+{
+	let update = T::update_many().col_expr(column, soft_delete_value(&column, deleted));
+
+	match version {
+		Some(version) => update.col_expr(version, next_version(&version)),
+		None => update,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+	use sea_orm::{DbBackend, QueryFilter, QueryTrait};
+
+	/// Should match the rows by whether their deletion timestamp is set.
+	#[test]
+	fn condition() {
+		let sql = |deleted| {
+			film::Entity::delete_many()
+				.filter(soft_deleted_condition(&film::Column::DeletedAt, deleted))
+				.build(DbBackend::Postgres)
+				.to_string()
+		};
+
+		assert_eq!(sql(true), r#"DELETE FROM "film" WHERE "film"."deleted_at" IS NOT NULL"#);
+		assert_eq!(sql(false), r#"DELETE FROM "film" WHERE "film"."deleted_at" IS NULL"#);
+	}
+
+	/// Should set the deletion timestamp on delete and clear it on restore.
+	#[test]
+	fn value() {
+		let sql = |deleted| {
+			film::Entity::update_many()
+				.col_expr(
+					film::Column::DeletedAt,
+					soft_delete_value(&film::Column::DeletedAt, deleted),
+				)
+				.build(DbBackend::Postgres)
+				.to_string()
+		};
+
+		assert_eq!(sql(true), r#"UPDATE "film" SET "deleted_at" = CURRENT_TIMESTAMP"#);
+		assert_eq!(sql(false), r#"UPDATE "film" SET "deleted_at" = NULL"#);
+	}
+
+	/// Should bump the version of versioned rows on delete and restore.
+	#[test]
+	fn update() {
+		let sql = |version, deleted| {
+			soft_delete_update::<film::Entity>(film::Column::DeletedAt, version, deleted)
+				.build(DbBackend::Postgres)
+				.to_string()
+		};
+
+		assert_eq!(
+			sql(Some(film::Column::Version), true),
+			r#"UPDATE "film" SET "deleted_at" = CURRENT_TIMESTAMP, "version" = "version" + 1"#
+		);
+		assert_eq!(
+			sql(Some(film::Column::Version), false),
+			r#"UPDATE "film" SET "deleted_at" = NULL, "version" = "version" + 1"#
+		);
+		assert_eq!(sql(None, false), r#"UPDATE "film" SET "deleted_at" = NULL"#);
+	}
+}
@@ -252,11 +252,10 @@ impl TypesMapHelper {
 			ColumnType::Float => Some(Ty::named(Ty::FLOAT)),
 			ColumnType::Double => Some(Ty::named(Ty::DOUBLE)),
 			ColumnType::Decimal(_) | ColumnType::Money(_) => Some(Ty::named(Ty::STRING)),
-			ColumnType::DateTime
-			| ColumnType::Timestamp
-			| ColumnType::TimestampWithTimeZone
-			| ColumnType::Time
-			| ColumnType::Date => Some(Ty::named(Ty::STRING)),
+			ColumnType::DateTime | ColumnType::Timestamp | ColumnType::TimestampWithTimeZone => {
+				Some(Ty::named(Ty::TIMESTAMP))
+			}
+			ColumnType::Time | ColumnType::Date => Some(Ty::named(Ty::STRING)),
 			ColumnType::Year => Some(Ty::named(Ty::INT32)),
 			ColumnType::Interval(_, _) => Some(Ty::named(Ty::STRING)),
 			ColumnType::Binary(_)
@@ -265,8 +264,10 @@ impl TypesMapHelper {
 			| ColumnType::VarBit(_)
 			| ColumnType::Blob => Some(Ty::named(Ty::BYTES)),
 			ColumnType::Boolean => Some(Ty::named(Ty::BOOL)),
-			// FIXME: support json type
+			#[cfg(not(feature = "with-json"))]
 			ColumnType::Json | ColumnType::JsonBinary => None,
+			#[cfg(feature = "with-json")]
+			ColumnType::Json | ColumnType::JsonBinary => Some(Ty::named(Ty::JSON)),
 			ColumnType::Uuid => Some(Ty::named(Ty::STRING)),
 			ColumnType::Enum {
 				name: enum_name,
@@ -307,8 +308,8 @@ impl TypesMapHelper {
 				// - always pass false:
 				//   pros: always technically workable for queries (annoying for non-null data)
 				//   conts: bad for inserts
-				let iden_type = self.sea_orm_column_type_to_graphql_type(iden.as_ref(), true);
-				iden_type.map(|it| Ty::List(Box::new(it)))
+				let iden_type = self.sea_orm_column_type_to_type::<Ty>(iden.as_ref(), true);
+				iden_type.map(|it| Ty::named_nn_list(it.type_name()))
 			}
 			ColumnType::Custom(_iden) => Some(Ty::named(Ty::STRING)),
 			_ => None,
@@ -321,6 +322,25 @@ impl TypesMapHelper {
 			}
 		})
 	}
+
+	/// used to map from a SeaORM column type to the type of an input field, binary columns
+	/// accept the files uploaded with a multipart request
+	pub fn sea_orm_column_type_to_input_type<Ty>(
+		&self,
+		ty: &ColumnType,
+		not_null: bool,
+	) -> Option<Ty>
+	where
+		Ty: TypeRefTrait,
+	{
+		match ty {
+			ColumnType::Binary(_) | ColumnType::VarBinary(_) | ColumnType::Blob => match not_null {
+				true => Some(Ty::named_nn(Ty::UPLOAD)),
+				false => Some(Ty::named(Ty::UPLOAD)),
+			},
+			_ => self.sea_orm_column_type_to_type(ty, not_null),
+		}
+	}
 }
 
 pub enum TimeLibrary {
@@ -433,33 +453,39 @@ pub fn converted_type_to_sea_orm_array_type(
 		ConvertedType::Enum(_) => Ok(sea_orm::sea_query::value::ArrayType::String),
 		ConvertedType::Custom(_) => Ok(sea_orm::sea_query::value::ArrayType::String),
 		#[cfg(feature = "with-json")]
-		ConvertedType::Json => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::Json => Ok(sea_orm::sea_query::value::ArrayType::Json),
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoDate => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoDate => Ok(sea_orm::sea_query::value::ArrayType::ChronoDate),
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoTime => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoTime => Ok(sea_orm::sea_query::value::ArrayType::ChronoTime),
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoDateTime => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoDateTime => Ok(sea_orm::sea_query::value::ArrayType::ChronoDateTime),
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoDateTimeUtc => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoDateTimeUtc => Ok(sea_orm::sea_query::value::ArrayType::ChronoDateTimeUtc),
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoDateTimeLocal => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoDateTimeLocal => {
+			Ok(sea_orm::sea_query::value::ArrayType::ChronoDateTimeLocal)
+		}
 		#[cfg(feature = "with-chrono")]
-		ConvertedType::ChronoDateTimeWithTimeZone => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::ChronoDateTimeWithTimeZone => {
+			Ok(sea_orm::sea_query::value::ArrayType::ChronoDateTimeWithTimeZone)
+		}
 		#[cfg(feature = "with-time")]
-		ConvertedType::TimeDate => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::TimeDate => Ok(sea_orm::sea_query::value::ArrayType::TimeDate),
 		#[cfg(feature = "with-time")]
-		ConvertedType::TimeTime => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::TimeTime => Ok(sea_orm::sea_query::value::ArrayType::TimeTime),
 		#[cfg(feature = "with-time")]
-		ConvertedType::TimeDateTime => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::TimeDateTime => Ok(sea_orm::sea_query::value::ArrayType::TimeDateTime),
 		#[cfg(feature = "with-time")]
-		ConvertedType::TimeDateTimeWithTimeZone => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::TimeDateTimeWithTimeZone => {
+			Ok(sea_orm::sea_query::value::ArrayType::TimeDateTimeWithTimeZone)
+		}
 		#[cfg(feature = "with-uuid")]
-		ConvertedType::Uuid => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::Uuid => Ok(sea_orm::sea_query::value::ArrayType::Uuid),
 		#[cfg(feature = "with-decimal")]
-		ConvertedType::Decimal => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::Decimal => Ok(sea_orm::sea_query::value::ArrayType::Decimal),
 		#[cfg(feature = "with-bigdecimal")]
-		ConvertedType::BigDecimal => Ok(sea_orm::sea_query::value::ArrayType::String),
+		ConvertedType::BigDecimal => Ok(sea_orm::sea_query::value::ArrayType::BigDecimal),
 		// #[cfg(feature = "with-ipnetwork")]
 		// ConvertedType::IpNetwork => Ok(sea_orm::sea_query::value::ArrayType::String),
 		// #[cfg(feature = "with-mac_address")]
@@ -594,12 +620,16 @@ pub fn converted_value_to_sea_orm_value<'a>(
 			sea_orm::Value::Char(Some(value))
 		}
 		ConvertedType::Bytes => {
-			let value = decode_hex(value.string().map_err(|e| {
-				SeaographyError::TypeConversionError(
-					format!("{:#?}", e),
-					format!("Bytes - {}.{}", entity_name, column_name),
-				)
-			})?)?;
+			// uploaded files are passed as bytes, every other value as a hex encoded string
+			let value = match value.bytes() {
+				Ok(value) => value.to_vec(),
+				Err(_) => decode_hex(value.string().map_err(|e| {
+					SeaographyError::TypeConversionError(
+						format!("{:#?}", e),
+						format!("Bytes - {}.{}", entity_name, column_name),
+					)
+				})?)?,
+			};
 			sea_orm::Value::Bytes(Some(Box::new(value)))
 		}
 		#[cfg(feature = "with-json")]
@@ -652,10 +682,17 @@ pub fn converted_value_to_sea_orm_value<'a>(
 		}
 		#[cfg(feature = "with-chrono")]
 		ConvertedType::ChronoDateTime => {
+			let value = input_string_map_error(value, entity_name, column_name)?;
+			// timestamps of the proto layer are sent in RFC 3339
 			let value = sea_orm::entity::prelude::ChronoDateTime::parse_from_str(
-				input_string_map_error(value, entity_name, column_name)?,
+				value,
 				"%Y-%m-%d %H:%M:%S",
 			)
+			.or_else(|e| {
+				sea_orm::entity::prelude::ChronoDateTimeWithTimeZone::parse_from_rfc3339(value)
+					.map(|value| value.naive_utc())
+					.map_err(|_| e)
+			})
 			.map_err(|e| {
 				SeaographyError::TypeConversionError(
 					format!("{:#?}", e),
@@ -837,7 +874,7 @@ pub fn converted_value_to_sea_orm_value<'a>(
 		ConvertedType::Array(ty) => {
 			let list_value = value
 				.list()?
-				.iter()
+				.to_iter()
 				.map(|value| {
 					converted_value_to_sea_orm_value(&*ty, &value, entity_name, column_name)
 				})
@@ -0,0 +1,96 @@
+use crate::{BuilderContext, EntityObjectBuilder};
+use sea_orm::{
+	ColumnTrait, ColumnType, EntityTrait, Iterable,
+	sea_query::{Expr, SimpleExpr},
+};
+use std::collections::BTreeMap;
+
+/// Entities optimistic concurrency configuration.
+/// The update mutations of versioned entities require the expected version of the rows,
+/// rows updated in the meantime are not overwritten.
+#[derive(Default)]
+pub struct VersionsConfig {
+	/// used to map an entity name to its integer or timestamp version column,
+	/// e.g. "Post" => "version" or "Post" => "updatedAt"
+	pub entity_versions: BTreeMap<String, String>,
+}
+
+/// used to get the version column of an entity,
+/// None indicates that the entity is not versioned
+pub fn version_column<T>(context: &'static BuilderContext) -> Option<T::Column>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+
+	let column_name =
+		context.versions.entity_versions.get(&entity_object_builder.type_name::<T>())?;
+
+	T::Column::iter().find(|column| &entity_object_builder.column_name::<T>(column) == column_name)
+}
+
+/// used to get the next version of the updated rows,
+/// integers are incremented and timestamps are set to the current timestamp
+pub fn next_version<C>(column: &C) -> SimpleExpr
+where
+	C: ColumnTrait,
+{
+	match column.def().get_column_type() {
+		ColumnType::TinyInteger
+		| ColumnType::SmallInteger
+		| ColumnType::Integer
+		| ColumnType::BigInteger
+		| ColumnType::TinyUnsigned
+		| ColumnType::SmallUnsigned
+		| ColumnType::Unsigned
+		| ColumnType::BigUnsigned => Expr::col(*column).add(1),
+		_ => Expr::current_timestamp().into(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+	use sea_orm::{DbBackend, QueryFilter, QueryTrait};
+
+	fn sql<C>(column: C) -> String
+	where
+		C: ColumnTrait,
+	{
+		film::Entity::update_many()
+			.col_expr(column, next_version(&column))
+			.filter(film::Column::Id.eq(1))
+			.build(DbBackend::Postgres)
+			.to_string()
+	}
+
+	/// Should find the configured version column.
+	#[test]
+	fn column() {
+		let context: &'static BuilderContext = Box::leak(Box::default());
+		assert!(version_column::<film::Entity>(context).is_none());
+
+		let mut context = BuilderContext::default();
+		context.versions.entity_versions.insert("Film".into(), "version".into());
+		let context: &'static BuilderContext = Box::leak(Box::new(context));
+
+		assert!(matches!(version_column::<film::Entity>(context), Some(film::Column::Version)));
+	}
+
+	/// Should increment integer versions and set timestamp versions.
+	#[test]
+	fn next() {
+		assert_eq!(
+			sql(film::Column::Version),
+			r#"UPDATE "film" SET "version" = "version" + 1 WHERE "film"."id" = 1"#
+		);
+		assert_eq!(
+			sql(film::Column::DeletedAt),
+			r#"UPDATE "film" SET "deleted_at" = CURRENT_TIMESTAMP WHERE "film"."id" = 1"#
+		);
+	}
+}
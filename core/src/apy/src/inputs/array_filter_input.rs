@@ -0,0 +1,281 @@
+use crate::{
+	BuilderContext, ConvertedType, EntityObjectBuilder, FilterInfo, TypesMapHelper,
+	converted_value_to_sea_orm_value,
+};
+use dynamic::prelude::*;
+use sea_orm::{
+	ColumnTrait, ColumnType, Condition, DbBackend, EntityTrait,
+	sea_query::{Alias, BinOper, Expr, Func, SimpleExpr},
+};
+
+/// The configuration structure for ArrayFilterInputBuilder
+pub struct ArrayFilterInputConfig {
+	/// used to format the array filter name from the element filter name
+	pub type_name: crate::SimpleNamingFn,
+	/// name for 'contains' field
+	pub contains: String,
+	/// name for 'contained_by' field
+	pub contained_by: String,
+	/// name for 'overlaps' field
+	pub overlaps: String,
+	/// name for 'any_eq' field
+	pub any_eq: String,
+	/// name for 'length_eq' field
+	pub length_eq: String,
+	/// name for 'length_gt' field
+	pub length_gt: String,
+	/// name for 'length_lt' field
+	pub length_lt: String,
+}
+
+impl std::default::Default for ArrayFilterInputConfig {
+	fn default() -> Self {
+		ArrayFilterInputConfig {
+			type_name: Box::new(|filter_name: &str| -> String {
+				format!("{}ArrayFilterInput", filter_name.trim_end_matches("FilterInput"))
+			}),
+			contains: "contains".into(),
+			contained_by: "containedBy".into(),
+			overlaps: "overlaps".into(),
+			any_eq: "anyEq".into(),
+			length_eq: "lengthEq".into(),
+			length_gt: "lengthGt".into(),
+			length_lt: "lengthLt".into(),
+		}
+	}
+}
+
+/// This builder produces the filter input of array columns for an element filter
+pub struct ArrayFilterInputBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl ArrayFilterInputBuilder {
+	/// used to get the array filter input name from the element filter input name
+	pub fn type_name(&self, filter_name: &str) -> String {
+		self.context.array_filter_input.type_name.as_ref()(filter_name)
+	}
+
+	/// used to get the array filter input object of an element filter
+	pub fn input_object<Ty>(&self, filter_info: &FilterInfo) -> Object<Ty>
+	where
+		Ty: TypeRefTrait,
+	{
+		let config = &self.context.array_filter_input;
+		let base_type = &filter_info.base_type;
+
+		Object::new(self.type_name(&filter_info.type_name), IO::Input)
+			.field(Field::input(&config.contains, Ty::named_nn_list(base_type)))
+			.field(Field::input(&config.contained_by, Ty::named_nn_list(base_type)))
+			.field(Field::input(&config.overlaps, Ty::named_nn_list(base_type)))
+			.field(Field::input(&config.any_eq, Ty::named(base_type)))
+			.field(Field::input(&config.length_eq, Ty::named(Ty::INT32)))
+			.field(Field::input(&config.length_gt, Ty::named(Ty::INT32)))
+			.field(Field::input(&config.length_lt, Ty::named(Ty::INT32)))
+	}
+}
+
+/// used to update the query condition with array filters
+///
+/// `contains`, `containedBy` and `overlaps` compare the column with the given array,
+/// `anyEq` matches arrays having an element equal to the given value and the length
+/// filters compare the number of elements of the array
+pub fn prepare_array_condition<T>(
+	context: &'static BuilderContext,
+	filter: &ObjectAccessor,
+	types_map_helper: &TypesMapHelper,
+	backend: DbBackend,
+	column: &T::Column,
+	condition: Condition,
+) -> SeaResult<Condition>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let config = &context.array_filter_input;
+
+	if backend != DbBackend::Postgres {
+		return Err(SeaographyError::new("Array filters are only supported on Postgres"));
+	}
+
+	let array = Expr::col((column.entity_name(), *column));
+
+	let condition =
+		[(&config.contains, "@>"), (&config.contained_by, "<@"), (&config.overlaps, "&&")]
+			.into_iter()
+			.try_fold(condition, |condition, (name, operator)| match filter.get(name) {
+				Some(value) => {
+					let value = types_map_helper.value_to_sea_orm_value::<T>(column, &value)?;
+
+					Ok(condition.add(
+						array.clone().binary(BinOper::Custom(operator), array_value(column, value)),
+					))
+				}
+				None => Ok::<Condition, SeaographyError>(condition),
+			})?;
+
+	let condition = match filter.get(&config.any_eq) {
+		Some(value) => {
+			let entity_object_builder = EntityObjectBuilder {
+				context,
+			};
+			let entity_name = entity_object_builder.type_name::<T>();
+			let column_name = entity_object_builder.column_name::<T>(column);
+
+			let value = match types_map_helper.get_column_type::<T>(column) {
+				ConvertedType::Array(ty) => {
+					converted_value_to_sea_orm_value(&ty, &value, &entity_name, &column_name)?
+				}
+				_ => {
+					return Err(SeaographyError::new(format!(
+						"{entity_name}.{column_name} is not an array column"
+					)));
+				}
+			};
+
+			condition.add(Expr::val(value).eq(Func::cust(Alias::new("ANY")).arg(array.clone())))
+		}
+		None => condition,
+	};
+
+	let length: SimpleExpr = Func::cust(Alias::new("cardinality")).arg(array).into();
+
+	[
+		(&config.length_eq, BinOper::Equal),
+		(&config.length_gt, BinOper::GreaterThan),
+		(&config.length_lt, BinOper::SmallerThan),
+	]
+	.into_iter()
+	.try_fold(condition, |condition, (name, operation)| match filter.get(name) {
+		Some(value) => {
+			let value = value.int32()?;
+
+			Ok(condition.add(Expr::expr(length.clone()).binary(operation, value)))
+		}
+		None => Ok(condition),
+	})
+}
+
+/// used to cast the given array into the array type of the column, Postgres has no
+/// array operators between varchar or char arrays and the text arrays strings are bound as
+fn array_value<C>(column: &C, value: sea_orm::Value) -> SimpleExpr
+where
+	C: ColumnTrait,
+{
+	match column.def().get_column_type() {
+		ColumnType::Array(ty) => match ty.as_ref() {
+			ColumnType::String(_) => Expr::val(value).cast_as(Alias::new("varchar[]")),
+			ColumnType::Char(_) => Expr::val(value).cast_as(Alias::new("bpchar[]")),
+			_ => Expr::val(value).into(),
+		},
+		_ => Expr::val(value).into(),
+	}
+}
+
+#[cfg(all(test, feature = "with-postgres-array"))]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::photo;
+	use sea_orm::{QueryFilter, QueryTrait};
+
+	fn sql(column: photo::Column, filter: &[(&str, Value)]) -> SeaResult<String> {
+		let context: &'static BuilderContext = Box::leak(Box::default());
+		let types_map_helper = TypesMapHelper {
+			context,
+		};
+
+		let mut object = ObjectAccessor::default();
+		for (name, value) in filter {
+			object.insert(Value::from(*name), value.clone());
+		}
+
+		let condition = prepare_array_condition::<photo::Entity>(
+			context,
+			&object,
+			&types_map_helper,
+			DbBackend::Postgres,
+			&column,
+			Condition::all(),
+		)?;
+
+		Ok(photo::Entity::find().filter(condition).build(DbBackend::Postgres).to_string())
+	}
+
+	fn list<V>(values: &[V]) -> Value
+	where
+		V: Clone + Into<Value>,
+	{
+		Value::List(values.iter().cloned().map(Into::into).collect())
+	}
+
+	fn filtered(condition: &str) -> String {
+		format!(
+			r#"SELECT "photo"."id", "photo"."tags", "photo"."ratings" FROM "photo" WHERE {condition}"#
+		)
+	}
+
+	/// Should compare the column with the given array and cast strings to the column type.
+	#[test]
+	fn array_operators() {
+		assert_eq!(
+			sql(photo::Column::Ratings, &[("contains", list(&[1i64, 2]))]).unwrap(),
+			filtered(r#""photo"."ratings" @> ARRAY [1,2]"#)
+		);
+		assert_eq!(
+			sql(photo::Column::Ratings, &[("containedBy", list(&[3i64]))]).unwrap(),
+			filtered(r#""photo"."ratings" <@ ARRAY [3]"#)
+		);
+		assert_eq!(
+			sql(photo::Column::Tags, &[("overlaps", list(&["sea", "sky"]))]).unwrap(),
+			filtered(r#""photo"."tags" && CAST(ARRAY ['sea','sky'] AS varchar[])"#)
+		);
+	}
+
+	/// Should match the arrays having an element equal to the given value.
+	#[test]
+	fn any_eq() {
+		assert_eq!(
+			sql(photo::Column::Tags, &[("anyEq", Value::from("sea"))]).unwrap(),
+			filtered(r#"'sea' = ANY("photo"."tags")"#)
+		);
+	}
+
+	/// Should compare the number of elements of the array.
+	#[test]
+	fn length() {
+		assert_eq!(
+			sql(
+				photo::Column::Ratings,
+				&[
+					("lengthEq", Value::from(2)),
+					("lengthGt", Value::from(1)),
+					("lengthLt", Value::from(5))
+				]
+			)
+			.unwrap(),
+			filtered(concat!(
+				r#"cardinality("photo"."ratings") = 2 AND "#,
+				r#"cardinality("photo"."ratings") > 1 AND "#,
+				r#"cardinality("photo"."ratings") < 5"#
+			))
+		);
+	}
+
+	/// Should only build array filters on Postgres.
+	#[test]
+	fn backend() {
+		let context: &'static BuilderContext = Box::leak(Box::default());
+		let result = prepare_array_condition::<photo::Entity>(
+			context,
+			&ObjectAccessor::default(),
+			&TypesMapHelper {
+				context,
+			},
+			DbBackend::Sqlite,
+			&photo::Column::Tags,
+			Condition::all(),
+		);
+
+		assert!(result.is_err());
+	}
+}
@@ -4,8 +4,14 @@ use dynamic::prelude::*;
 /// used to hold information about cursor pagination
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CursorInput {
+	/// cursor the returned rows follow
 	pub cursor: Option<String>,
-	pub limit: u64,
+	/// cursor the returned rows precede
+	pub before: Option<String>,
+	/// number of rows taken from the start of the window
+	pub limit: Option<u64>,
+	/// number of rows taken from the end of the window
+	pub last: Option<u64>,
 }
 
 /// The configuration structure for CursorInputBuilder
@@ -14,8 +20,12 @@ pub struct CursorInputConfig {
 	pub type_name: String,
 	/// name for 'cursor' field
 	pub cursor: String,
+	/// name for 'before' field
+	pub before: String,
 	/// name for 'limit' field
 	pub limit: String,
+	/// name for 'last' field
+	pub last: String,
 }
 
 impl std::default::Default for CursorInputConfig {
@@ -23,7 +33,9 @@ impl std::default::Default for CursorInputConfig {
 		Self {
 			type_name: "CursorInput".into(),
 			cursor: "cursor".into(),
+			before: "before".into(),
 			limit: "limit".into(),
+			last: "last".into(),
 		}
 	}
 }
@@ -46,7 +58,9 @@ impl CursorInputBuilder {
 	{
 		Object::new(&self.context.cursor_input.type_name, IO::Input)
 			.field(Field::input(&self.context.cursor_input.cursor, Ty::named(Ty::STRING)))
-			.field(Field::input(&self.context.cursor_input.limit, Ty::named_nn(Ty::UINT64)))
+			.field(Field::input(&self.context.cursor_input.before, Ty::named(Ty::STRING)))
+			.field(Field::input(&self.context.cursor_input.limit, Ty::named(Ty::UINT64)))
+			.field(Field::input(&self.context.cursor_input.last, Ty::named(Ty::UINT64)))
 	}
 
 	/// used to parse query input to cursor pagination options struct
@@ -57,20 +71,33 @@ impl CursorInputBuilder {
 			None => None,
 		};
 
+		let before = match object.get(&self.context.cursor_input.before) {
+			Some(before) => Some(before.string()?.to_string()),
+			None => None,
+		};
+
 		let limit = match object.get(&self.context.cursor_input.limit) {
-			Some(value_accessor) => value_accessor,
-			None => {
-				return Err(SeaographyError::new(format!(
-					"{} is a required argument but not provided.",
-					self.context.cursor_input.limit
-				)));
-			}
+			Some(limit) => Some(limit.uint64()?),
+			None => None,
+		};
+
+		let last = match object.get(&self.context.cursor_input.last) {
+			Some(last) => Some(last.uint64()?),
+			None => None,
+		};
+
+		if limit.is_none() && last.is_none() {
+			return Err(SeaographyError::new(format!(
+				"either {} or {} is required but none is provided.",
+				self.context.cursor_input.limit, self.context.cursor_input.last
+			)));
 		}
-		.uint64()?;
 
 		Ok(CursorInput {
 			cursor,
+			before,
 			limit,
+			last,
 		})
 	}
 }
@@ -1,4 +1,6 @@
-use crate::{BuilderContext, EntityObjectBuilder, TypesMapHelper};
+use crate::{
+	BuilderContext, EntityObjectBuilder, NestedInsertRelation, TypesMapHelper, version_column,
+};
 use dynamic::prelude::*;
 use sea_orm::{ColumnTrait, EntityTrait, Iterable};
 use std::collections::BTreeMap;
@@ -11,8 +13,12 @@ pub struct EntityInputConfig {
 	pub insert_skips: Vec<String>,
 	/// suffix that is appended on update input objects
 	pub update_suffix: String,
+	/// suffix that is appended on upsert input objects
+	pub upsert_suffix: String,
 	/// names of "{entity}.{column}" you want to skip the update input to be generated
 	pub update_skips: Vec<String>,
+	/// used to add relation fields to insert input objects for nested inserts
+	pub insert_relations: bool,
 }
 
 impl std::default::Default for EntityInputConfig {
@@ -21,7 +27,9 @@ impl std::default::Default for EntityInputConfig {
 			insert_suffix: "InsertInput".into(),
 			insert_skips: Vec::new(),
 			update_suffix: "UpdateInput".into(),
+			upsert_suffix: "UpsertInput".into(),
 			update_skips: Vec::new(),
+			insert_relations: true,
 		}
 	}
 }
@@ -58,6 +66,19 @@ impl EntityInputBuilder {
 		format!("{}{}", object_name, self.context.entity_input.update_suffix)
 	}
 
+	/// used to get SeaORM entity upsert input object name
+	pub fn upsert_type_name<T>(&self) -> String
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name = entity_object_builder.type_name::<T>();
+		format!("{}{}", object_name, self.context.entity_input.upsert_suffix)
+	}
+
 	/// used to produce the SeaORM entity insert input object
 	pub fn insert_input_object<T, Ty>(&self) -> Object<Ty>
 	where
@@ -65,7 +86,49 @@ impl EntityInputBuilder {
 		<T as EntityTrait>::Model: Sync,
 		Ty: TypeRefTrait,
 	{
-		self.input_object::<T, Ty>(true)
+		self.input_object::<T, Ty>(self.insert_type_name::<T>(), true, &[])
+	}
+
+	/// used to produce the SeaORM entity upsert input object, it has the columns of the
+	/// insert input object without its relation fields
+	pub fn upsert_input_object<T, Ty>(&self) -> Object<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		self.input_object::<T, Ty>(self.upsert_type_name::<T>(), true, &[])
+	}
+
+	/// used to produce the SeaORM entity insert input object including its relation fields,
+	/// foreign key columns filled by a nested relation become optional
+	pub fn nested_insert_input_object<T, Ty>(
+		&self,
+		relations: &[NestedInsertRelation],
+	) -> Object<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		let foreign_keys: Vec<String> = relations
+			.iter()
+			.filter(|relation| !relation.is_owner)
+			.map(|relation| relation.from_column.clone())
+			.collect();
+
+		relations.iter().fold(
+			self.input_object::<T, Ty>(self.insert_type_name::<T>(), true, &foreign_keys),
+			|object, relation| {
+				let type_ref = if relation.is_list {
+					Ty::named_nn_list(&relation.input_type_name)
+				} else {
+					Ty::named(&relation.input_type_name)
+				};
+
+				object.field(Field::input(&relation.name, type_ref))
+			},
+		)
 	}
 
 	/// used to produce the SeaORM entity update input object
@@ -75,22 +138,21 @@ impl EntityInputBuilder {
 		<T as EntityTrait>::Model: Sync,
 		Ty: TypeRefTrait,
 	{
-		self.input_object::<T, Ty>(false)
+		self.input_object::<T, Ty>(self.update_type_name::<T>(), false, &[])
 	}
 
 	/// used to produce the SeaORM entity input object
-	fn input_object<T, Ty>(&self, is_insert: bool) -> Object<Ty>
+	fn input_object<T, Ty>(
+		&self,
+		name: String,
+		is_insert: bool,
+		optional_columns: &[String],
+	) -> Object<Ty>
 	where
 		T: EntityTrait,
 		<T as EntityTrait>::Model: Sync,
 		Ty: TypeRefTrait,
 	{
-		let name = if is_insert {
-			self.insert_type_name::<T>()
-		} else {
-			self.update_type_name::<T>()
-		};
-
 		let entity_object_builder = EntityObjectBuilder {
 			context: self.context,
 		};
@@ -98,6 +160,13 @@ impl EntityInputBuilder {
 			context: self.context,
 		};
 
+		// update inputs require the expected version of versioned entities
+		let version = match is_insert {
+			true => None,
+			false => version_column::<T>(self.context)
+				.map(|column| entity_object_builder.column_name::<T>(&column)),
+		};
+
 		T::Column::iter().fold(Object::new(name, IO::Input), |object, column| {
 			let column_name = entity_object_builder.column_name::<T>(&column);
 
@@ -115,9 +184,10 @@ impl EntityInputBuilder {
 
 			let column_def = column.def();
 
-			let type_ref = match types_map_helper.sea_orm_column_type_to_type(
+			let type_ref = match types_map_helper.sea_orm_column_type_to_input_type(
 				column_def.get_column_type(),
-				!column_def.is_null() && is_insert,
+				(!column_def.is_null() && is_insert && !optional_columns.contains(&column_name))
+					|| version.as_ref() == Some(&column_name),
 			) {
 				Some(type_name) => type_name,
 				None => return object,
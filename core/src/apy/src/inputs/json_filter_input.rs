@@ -0,0 +1,238 @@
+use crate::BuilderContext;
+use dynamic::prelude::*;
+use sea_orm::{
+	ColumnTrait, Condition, DbBackend, EntityTrait,
+	sea_query::{Alias, BinOper, Expr, Func, SimpleExpr},
+};
+
+/// The configuration structure for JsonFilterInputBuilder
+pub struct JsonFilterInputConfig {
+	/// name of the JSON filter input object
+	pub type_name: String,
+	/// name of the JSON path filter input object
+	pub path_type_name: String,
+	/// name for 'contains' field
+	pub contains: String,
+	/// name for 'has_key' field
+	pub has_key: String,
+	/// name for 'paths' field
+	pub paths: String,
+	/// name for 'path' field of the path filter
+	pub path: String,
+}
+
+impl std::default::Default for JsonFilterInputConfig {
+	fn default() -> Self {
+		JsonFilterInputConfig {
+			type_name: "JsonFilterInput".into(),
+			path_type_name: "JsonPathFilterInput".into(),
+			contains: "contains".into(),
+			has_key: "has_key".into(),
+			paths: "paths".into(),
+			path: "path".into(),
+		}
+	}
+}
+
+/// comparisons supported by the JSON path filter, the compared values are JSON documents
+const PATH_OPERATIONS: [(&str, BinOper); 6] = [
+	("eq", BinOper::Equal),
+	("ne", BinOper::NotEqual),
+	("gt", BinOper::GreaterThan),
+	("gte", BinOper::GreaterThanOrEqual),
+	("lt", BinOper::SmallerThan),
+	("lte", BinOper::SmallerThanOrEqual),
+];
+
+/// This builder produces the filter input of JSON columns
+pub struct JsonFilterInputBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl JsonFilterInputBuilder {
+	/// used to get the JSON filter input name
+	pub fn type_name(&self) -> String {
+		self.context.json_filter_input.type_name.clone()
+	}
+
+	/// used to get the JSON filter input object
+	pub fn input_object<Ty>(&self) -> Object<Ty>
+	where
+		Ty: TypeRefTrait,
+	{
+		let config = &self.context.json_filter_input;
+
+		Object::new(&config.type_name, IO::Input)
+			.field(Field::input(&config.contains, Ty::named(Ty::STRING)))
+			.field(Field::input(&config.has_key, Ty::named(Ty::STRING)))
+			.field(Field::input(&config.paths, Ty::named_nn_list(&config.path_type_name)))
+	}
+
+	/// used to get the JSON path filter input object
+	pub fn path_input_object<Ty>(&self) -> Object<Ty>
+	where
+		Ty: TypeRefTrait,
+	{
+		let config = &self.context.json_filter_input;
+
+		PATH_OPERATIONS.iter().fold(
+			Object::new(&config.path_type_name, IO::Input)
+				.field(Field::input(&config.path, Ty::named_nn_list(Ty::STRING))),
+			|object, (name, _)| object.field(Field::input(*name, Ty::named(Ty::STRING))),
+		)
+	}
+}
+
+/// used to update the query condition with JSON filters
+///
+/// `contains` matches documents containing the given JSON document, `has_key` matches
+/// objects having the given top level key and `paths` compares the value found at a path
+/// of keys and array indexes with the given JSON document
+pub fn prepare_json_condition<T>(
+	context: &'static BuilderContext,
+	filter: &ObjectAccessor,
+	backend: DbBackend,
+	column: &T::Column,
+	condition: Condition,
+) -> SeaResult<Condition>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let config = &context.json_filter_input;
+	let document = Expr::col((column.entity_name(), *column));
+
+	let condition = match filter.get(&config.contains) {
+		Some(value) => {
+			let value = value.string()?;
+
+			condition.add(match backend {
+				DbBackend::Postgres => Expr::expr(jsonb(document.clone().into()))
+					.binary(BinOper::Custom("@>"), jsonb(Expr::val(value).into())),
+				DbBackend::MySql => {
+					Func::cust(Alias::new("JSON_CONTAINS")).arg(document.clone()).arg(value).into()
+				}
+				DbBackend::Sqlite => {
+					return Err(SeaographyError::new(format!(
+						"{} is not supported on SQLite",
+						config.contains
+					)));
+				}
+			})
+		}
+		None => condition,
+	};
+
+	let condition = match filter.get(&config.has_key) {
+		Some(value) => {
+			let key = value.string()?;
+
+			condition.add(match backend {
+				DbBackend::Postgres => Expr::expr(jsonb(document.clone().into()))
+					.binary(BinOper::Custom("?"), Expr::val(key)),
+				DbBackend::MySql => Func::cust(Alias::new("JSON_CONTAINS_PATH"))
+					.arg(document.clone())
+					.arg("one")
+					.arg(json_path(&[key.to_string()]))
+					.into(),
+				DbBackend::Sqlite => Expr::expr(
+					Func::cust(Alias::new("json_type"))
+						.arg(document.clone())
+						.arg(json_path(&[key.to_string()])),
+				)
+				.is_not_null(),
+			})
+		}
+		None => condition,
+	};
+
+	let condition = match filter.get(&config.paths) {
+		Some(paths) => paths.list()?.to_iter().try_fold(condition, |condition, path_filter| {
+			let path_filter = path_filter.object()?;
+
+			let path = path_filter
+				.try_get(&config.path)?
+				.list()?
+				.to_iter()
+				.map(|segment| segment.string().map(|segment| segment.to_string()))
+				.collect::<SeaResult<Vec<String>>>()?;
+
+			let extracted: SimpleExpr = match backend {
+				DbBackend::Postgres => path
+					.iter()
+					.fold(
+						Func::cust(Alias::new("jsonb_extract_path"))
+							.arg(jsonb(document.clone().into())),
+						|func, segment| func.arg(segment.as_str()),
+					)
+					.into(),
+				DbBackend::MySql => Func::cust(Alias::new("JSON_EXTRACT"))
+					.arg(document.clone())
+					.arg(json_path(&path))
+					.into(),
+				DbBackend::Sqlite => Func::cust(Alias::new("json_extract"))
+					.arg(document.clone())
+					.arg(json_path(&path))
+					.into(),
+			};
+
+			PATH_OPERATIONS.iter().try_fold(condition, |condition, (name, operation)| {
+				match path_filter.get(*name) {
+					Some(value) => {
+						let value = json_document(backend, value.string()?);
+
+						Ok(condition.add(Expr::expr(extracted.clone()).binary(*operation, value)))
+					}
+					None => Ok::<Condition, SeaographyError>(condition),
+				}
+			})
+		})?,
+		None => condition,
+	};
+
+	Ok(condition)
+}
+
+/// used to cast an expression into a Postgres jsonb document
+fn jsonb(expr: SimpleExpr) -> SimpleExpr {
+	expr.cast_as(Alias::new("jsonb"))
+}
+
+/// used to parse a JSON document parameter the way the backend compares extracted values
+fn json_document(backend: DbBackend, value: &str) -> SimpleExpr {
+	match backend {
+		DbBackend::Postgres => jsonb(Expr::val(value).into()),
+		DbBackend::MySql => Expr::val(value).cast_as(Alias::new("JSON")),
+		DbBackend::Sqlite => Func::cust(Alias::new("json_extract")).arg(value).arg("$").into(),
+	}
+}
+
+/// used to format a MySQL and SQLite JSON path, numeric segments address array items
+fn json_path(path: &[String]) -> String {
+	path.iter().fold("$".to_string(), |json_path, segment| {
+		if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+			format!("{json_path}[{segment}]")
+		} else {
+			format!("{json_path}.\"{}\"", segment.replace('"', "\\\""))
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn path(segments: &[&str]) -> String {
+		json_path(&segments.iter().map(|segment| segment.to_string()).collect::<Vec<_>>())
+	}
+
+	/// Should address array items by numeric segments and quote every key.
+	#[test]
+	fn json_path_format() {
+		assert_eq!(path(&[]), "$");
+		assert_eq!(path(&["cast", "0", "name"]), r#"$."cast"[0]."name""#);
+		assert_eq!(path(&["first name"]), r#"$."first name""#);
+		assert_eq!(path(&["say \"hi\""]), r#"$."say \"hi\"""#);
+		assert_eq!(path(&["", "-1"]), r#"$.""."-1""#);
+	}
+}
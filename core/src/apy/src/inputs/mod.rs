@@ -22,3 +22,11 @@ pub use entity_input::*;
 
 pub mod active_enum_filter_input;
 pub use active_enum_filter_input::*;
+
+pub mod json_filter_input;
+pub use json_filter_input::*;
+
+#[cfg(feature = "with-postgres-array")]
+pub mod array_filter_input;
+#[cfg(feature = "with-postgres-array")]
+pub use array_filter_input::*;
@@ -2,10 +2,31 @@ use crate::{BuilderContext, EntityObjectBuilder};
 use dynamic::prelude::*;
 use sea_orm::{EntityTrait, Iterable};
 
+/// used to hold information about the search rank ordering
+#[derive(Clone, Debug)]
+pub struct SearchRankInput<T>
+where
+	T: EntityTrait,
+{
+	pub column: T::Column,
+	pub query: String,
+	pub order: sea_orm::sea_query::Order,
+}
+
 /// The configuration structure for OrderInputBuilder
 pub struct OrderInputConfig {
 	/// used to format OrderInput object name
 	pub type_name: crate::SimpleNamingFn,
+	/// name for 'searchRank' field
+	pub search_rank: String,
+	/// name of the search rank input object
+	pub search_rank_type_name: String,
+	/// name for search rank 'column' field
+	pub search_rank_column: String,
+	/// name for search rank 'query' field
+	pub search_rank_query: String,
+	/// name for search rank 'order' field
+	pub search_rank_order: String,
 }
 
 impl std::default::Default for OrderInputConfig {
@@ -14,6 +35,11 @@ impl std::default::Default for OrderInputConfig {
 			type_name: Box::new(|object_name: &str| -> String {
 				format!("{object_name}OrderInput")
 			}),
+			search_rank: "searchRank".into(),
+			search_rank_type_name: "SearchRankInput".into(),
+			search_rank_column: "column".into(),
+			search_rank_query: "query".into(),
+			search_rank_order: "order".into(),
 		}
 	}
 }
@@ -43,12 +69,111 @@ impl OrderInputBuilder {
 		let object_name = entity_object_builder.type_name::<T>();
 		let name = self.type_name(&object_name);
 
-		T::Column::iter().fold(Object::new(name, IO::Input), |object, column| {
-			object.field(Field::input(
-				entity_object_builder.column_name::<T>(&column),
+		T::Column::iter()
+			.fold(Object::new(name, IO::Input), |object, column| {
+				object.field(Field::input(
+					entity_object_builder.column_name::<T>(&column),
+					Ty::named(&self.context.order_by_enum.type_name),
+				))
+			})
+			.field(Field::input(
+				&self.context.order_input.search_rank,
+				Ty::named(&self.context.order_input.search_rank_type_name),
+			))
+	}
+
+	/// used to get the search rank ordering input object
+	pub fn search_rank_input_object<Ty>(&self) -> Object<Ty>
+	where
+		Ty: TypeRefTrait,
+	{
+		Object::new(&self.context.order_input.search_rank_type_name, IO::Input)
+			.field(Field::input(
+				&self.context.order_input.search_rank_column,
+				Ty::named_nn(Ty::STRING),
+			))
+			.field(Field::input(
+				&self.context.order_input.search_rank_query,
+				Ty::named_nn(Ty::STRING),
+			))
+			.field(Field::input(
+				&self.context.order_input.search_rank_order,
 				Ty::named(&self.context.order_by_enum.type_name),
 			))
-		})
+	}
+
+	/// used to parse the search rank ordering, the best matches come first by default
+	pub fn parse_search_rank<'a, T>(
+		&self,
+		value: Option<ValueAccessor<'a>>,
+	) -> SeaResult<Option<SearchRankInput<T>>>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let Some(value) = value else {
+			return Ok(None);
+		};
+
+		let order_by = value.object()?;
+
+		let Some(search_rank) = order_by.get(&self.context.order_input.search_rank) else {
+			return Ok(None);
+		};
+
+		let search_rank = search_rank.object()?;
+
+		let entity_object = EntityObjectBuilder {
+			context: self.context,
+		};
+
+		let column_name = search_rank.try_get(&self.context.order_input.search_rank_column)?;
+		let column_name = column_name.string()?;
+
+		let column = T::Column::iter()
+			.find(|column| entity_object.column_name::<T>(column).eq(column_name))
+			.ok_or_else(|| {
+				SeaographyError::new(format!(
+					"{column_name} is not a column of {}",
+					entity_object.type_name::<T>()
+				))
+			})?;
+
+		let query = search_rank.try_get(&self.context.order_input.search_rank_query)?;
+		let query = query.string()?.to_string();
+
+		let order = match search_rank.get(&self.context.order_input.search_rank_order) {
+			Some(order) if order.enum_name()?.eq(&self.context.order_by_enum.asc_variant) => {
+				sea_orm::Order::Asc
+			}
+			_ => sea_orm::Order::Desc,
+		};
+
+		Ok(Some(SearchRankInput {
+			column,
+			query,
+			order,
+		}))
+	}
+
+	/// used to parse the column ordering where the search rank cannot be applied,
+	/// e.g. relations that are loaded in batches
+	pub fn parse_unranked_object<'a, T>(
+		&self,
+		value: Option<ValueAccessor<'a>>,
+	) -> SeaResult<Vec<(T::Column, sea_orm::sea_query::Order)>>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		if self.parse_search_rank::<T>(value.clone())?.is_some() {
+			return Err(SeaographyError::new(format!(
+				"{} is only supported on root query fields.",
+				self.context.order_input.search_rank
+			)));
+		}
+
+		self.parse_object::<T>(value)
 	}
 
 	pub fn parse_object<'a, T>(
@@ -21,6 +21,8 @@ pub struct PaginationInputConfig {
 	pub page: String,
 	/// name for 'offset' field
 	pub offset: String,
+	/// the number of rows an unpaginated list is estimated to load, used for its complexity
+	pub unpaginated_rows: usize,
 }
 
 impl std::default::Default for PaginationInputConfig {
@@ -30,6 +32,7 @@ impl std::default::Default for PaginationInputConfig {
 			cursor: "cursor".into(),
 			page: "page".into(),
 			offset: "offset".into(),
+			unpaginated_rows: 100,
 		}
 	}
 }
@@ -124,4 +127,29 @@ impl PaginationInputBuilder {
 			offset,
 		})
 	}
+
+	/// used to get the complexity of a paginated field, every requested row costs the
+	/// complexity of the selection set and unpaginated lists are estimated to load
+	/// `unpaginated_rows` rows
+	pub fn complexity<'a>(
+		&self,
+		value: Option<ValueAccessor<'a>>,
+		child_complexity: usize,
+	) -> SeaResult<usize> {
+		let pagination = self.parse_object(value)?;
+
+		let limit = match (pagination.cursor, pagination.page, pagination.offset) {
+			(Some(cursor), _, _) => cursor.limit.or(cursor.last),
+			(_, Some(page), _) => Some(page.limit),
+			(_, _, Some(offset)) => Some(offset.limit),
+			_ => None,
+		};
+
+		let limit = match limit {
+			Some(limit) => usize::try_from(limit)?,
+			None => self.context.pagination_input.unpaginated_rows,
+		};
+
+		Ok(limit.saturating_mul(child_complexity).saturating_add(1))
+	}
 }
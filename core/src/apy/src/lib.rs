@@ -29,6 +29,7 @@
 //! * Pagination for queries and relations (1-N)
 //! * Filtering with operators (e.g. gt, lt, eq)
 //! * Order by any column
+//! * Aggregations (count, sum, avg, min, max) with group by
 //! * Guard fields, queries or relations
 //! * Rename fields
 //! * Mutations (create, update, delete)
@@ -262,6 +263,9 @@ pub use builder_context::*;
 pub mod builder;
 pub use builder::*;
 
+#[cfg(test)]
+mod tests_cfg;
+
 // pub mod error;
 // pub use error::*;
 
@@ -5,8 +5,9 @@ use sea_orm::{
 
 use crate::{
 	BuilderContext, EntityInputBuilder, EntityObjectBuilder, EntityQueryFieldBuilder, GuardAction,
-	prepare_active_model,
+	insert_nested,
 };
+use std::collections::BTreeMap;
 
 /// The configuration structure of EntityCreateBatchMutationBuilder
 pub struct EntityCreateBatchMutationConfig {
@@ -82,11 +83,11 @@ impl EntityCreateBatchMutationBuilder {
 
 					if let GuardAction::Block(reason) = guard_flag {
 						return match reason {
-							Some(reason) => {
-								Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-								"Entity guard triggered.",
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
 							)),
 						};
 					}
@@ -94,9 +95,6 @@ impl EntityCreateBatchMutationBuilder {
 					let db = ctx.data::<DatabaseConnection>()?;
 					let transaction = db.begin().await?;
 
-					let entity_input_builder = EntityInputBuilder {
-						context,
-					};
 					let entity_object_builder = EntityObjectBuilder {
 						context,
 					};
@@ -130,22 +128,25 @@ impl EntityCreateBatchMutationBuilder {
 							if let GuardAction::Block(reason) = field_guard_flag {
 								return match reason {
 									Some(reason) => Err::<Option<_>, SeaographyError>(
-										SeaographyError::new(reason),
+										SeaographyError::Forbidden(reason),
 									),
 									None => Err::<Option<_>, SeaographyError>(
-										SeaographyError::new("GraphQLField guard triggered."),
+										SeaographyError::Forbidden(
+											"GraphQLField guard triggered.".into(),
+										),
 									),
 								};
 							}
 						}
 
-						let active_model = prepare_active_model::<T, A>(
-							&entity_input_builder,
-							&entity_object_builder,
+						let result = insert_nested::<T, A>(
+							context,
+							&ctx,
+							&transaction,
 							input_object,
-						)?;
-
-						let result = active_model.insert(&transaction).await?;
+							BTreeMap::new(),
+						)
+						.await?;
 						results.push(result);
 					}
 
@@ -159,5 +160,7 @@ impl EntityCreateBatchMutationBuilder {
 			&context.entity_create_batch_mutation.data_field,
 			Ty::named_nn_list_nn(entity_input_builder.insert_type_name::<T>()),
 		))
+		// grpc clients may stream the inserts, the data of all messages is inserted as one batch
+		.set_client_streaming()
 	}
 }
@@ -1,11 +1,13 @@
 use crate::{
 	BuilderContext, EntityInputBuilder, EntityObjectBuilder, EntityQueryFieldBuilder, GuardAction,
+	insert_nested,
 };
 use dynamic::prelude::*;
 use sea_orm::{
 	ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Iterable,
-	PrimaryKeyToColumn, PrimaryKeyTrait,
+	PrimaryKeyToColumn, PrimaryKeyTrait, TransactionTrait,
 };
+use std::collections::BTreeMap;
 
 /// The configuration structure of EntityCreateOneMutationBuilder
 pub struct EntityCreateOneMutationConfig {
@@ -81,18 +83,15 @@ impl EntityCreateOneMutationBuilder {
 
 					if let GuardAction::Block(reason) = guard_flag {
 						return match reason {
-							Some(reason) => {
-								Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-								"Entity guard triggered.",
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
 							)),
 						};
 					}
 
-					let entity_input_builder = EntityInputBuilder {
-						context,
-					};
 					let entity_object_builder = EntityObjectBuilder {
 						context,
 					};
@@ -122,23 +121,30 @@ impl EntityCreateOneMutationBuilder {
 						};
 						if let GuardAction::Block(reason) = field_guard_flag {
 							return match reason {
-								Some(reason) => {
-									Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
+								Some(reason) => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden(reason),
+								),
+								None => {
+									Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+										"GraphQLField guard triggered.".into(),
+									))
 								}
-								None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-									"GraphQLField guard triggered.",
-								)),
 							};
 						}
 					}
 
-					let active_model = prepare_active_model::<T, A>(
-						&entity_input_builder,
-						&entity_object_builder,
+					let transaction = db.begin().await?;
+
+					let result = insert_nested::<T, A>(
+						context,
+						&ctx,
+						&transaction,
 						input_object,
-					)?;
+						BTreeMap::new(),
+					)
+					.await?;
 
-					let result = active_model.insert(db).await?;
+					transaction.commit().await?;
 
 					Ok(Some(FieldValue::owned_any(result)))
 				})
@@ -1,10 +1,12 @@
 use crate::{
 	BuilderContext, EntityObjectBuilder, EntityQueryFieldBuilder, FilterInputBuilder,
-	FilterTypeTrait, get_filter_conditions,
+	FilterTypeTrait, apply_policy, get_filter_conditions, soft_delete_column, soft_delete_update,
+	soft_deleted_condition, version_column,
 };
 use dynamic::prelude::*;
 use sea_orm::{
-	ActiveModelTrait, DatabaseConnection, DeleteResult, EntityTrait, IntoActiveModel, QueryFilter,
+	ActiveModelTrait, ConnectionTrait, DatabaseConnection, DeleteResult, EntityTrait,
+	IntoActiveModel, QueryFilter, UpdateResult,
 };
 
 /// The configuration structure of EntityDeleteMutationBuilder
@@ -67,16 +69,38 @@ impl EntityDeleteMutationBuilder {
 
 		let context = self.context;
 
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<T>(context);
+		let version = version_column::<T>(context);
+
 		Field::output(&self.type_name::<T>(), Ty::named_nn(Ty::UINT64), move |ctx| {
 			FieldFuture::new(async move {
 				let db = ctx.data::<DatabaseConnection>()?;
 
 				let filters = ctx.args.get(&context.entity_delete_mutation.filter_field);
-				let filter_condition = get_filter_conditions::<T, F>(context, filters)?;
+				let filter_condition =
+					get_filter_conditions::<T, F>(context, db.get_database_backend(), filters)?;
+				let filter_condition = apply_policy(policy, &ctx, filter_condition);
+
+				// soft deleted entities mark the rows that are not deleted yet
+				let rows_affected = match soft_delete {
+					Some(column) => {
+						let res: UpdateResult = soft_delete_update::<T>(column, version, true)
+							.filter(filter_condition.add(soft_deleted_condition(&column, false)))
+							.exec(db)
+							.await?;
+
+						res.rows_affected
+					}
+					None => {
+						let res: DeleteResult =
+							T::delete_many().filter(filter_condition).exec(db).await?;
 
-				let res: DeleteResult = T::delete_many().filter(filter_condition).exec(db).await?;
+						res.rows_affected
+					}
+				};
 
-				Ok(Some(FieldValue::value(res.rows_affected)))
+				Ok(Some(FieldValue::value(rows_affected)))
 			})
 		})
 		.argument(Field::input(
@@ -0,0 +1,405 @@
+use crate::{
+	BuilderContext, EntityInputBuilder, EntityObjectBuilder, GuardAction, check_insert,
+	prepare_active_model,
+};
+use dynamic::prelude::*;
+use futures::future::BoxFuture;
+use sea_orm::{
+	ActiveModelTrait, ActiveValue, DatabaseTransaction, EntityTrait, IntoActiveModel, Iterable,
+	ModelTrait,
+};
+use std::collections::BTreeMap;
+
+/// used to insert a related entity from a nested input object,
+/// returns the inserted row keyed by column field name
+pub type NestedInsertFn =
+	for<'a, 'b> fn(
+		&'static BuilderContext,
+		&'a ResolverContext<'b>,
+		&'a DatabaseTransaction,
+		ObjectAccessor<'a>,
+		BTreeMap<String, sea_orm::Value>,
+	) -> BoxFuture<'a, SeaResult<BTreeMap<String, sea_orm::Value>>>;
+
+/// used to describe a relation that can be written together with its entity
+pub struct NestedInsertRelation {
+	/// name of the relation field on the insert input object
+	pub name: String,
+	/// name of the insert input object of the related entity
+	pub input_type_name: String,
+	/// true when the related entity holds the foreign key (1-N or 1-1),
+	/// false when the entity itself holds it (N-1)
+	pub is_owner: bool,
+	/// true when many related rows are written (1-N), false when a single row is written
+	pub is_list: bool,
+	/// column field name on the entity side of the relation
+	pub from_column: String,
+	/// column field name on the related entity side of the relation
+	pub to_column: String,
+	/// used to insert the related entity
+	pub insert: NestedInsertFn,
+}
+
+/// holds the nested insert relations of every registered entity, keyed by entity type name
+#[derive(Default)]
+pub struct NestedInsertRelations(pub BTreeMap<String, Vec<NestedInsertRelation>>);
+
+/// used to insert an entity together with the relations found in its input object
+///
+/// N-1 relations are inserted first so their keys can be written to the entity,
+/// 1-N and 1-1 relations are inserted afterwards with the key of the new entity
+pub async fn insert_nested<T, A>(
+	context: &'static BuilderContext,
+	ctx: &ResolverContext<'_>,
+	transaction: &DatabaseTransaction,
+	input_object: ObjectAccessor<'_>,
+	mut overrides: BTreeMap<String, sea_orm::Value>,
+) -> SeaResult<T::Model>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+	<T as EntityTrait>::Model: IntoActiveModel<A>,
+	A: ActiveModelTrait<Entity = T> + sea_orm::ActiveModelBehavior + std::marker::Send,
+{
+	let entity_input_builder = EntityInputBuilder {
+		context,
+	};
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+
+	let relations = ctx
+		.data_opt::<NestedInsertRelations>()
+		.and_then(|relations| relations.0.get(&entity_object_builder.type_name::<T>()))
+		.map(|relations| relations.as_slice())
+		.unwrap_or_default();
+
+	for relation in relations.iter().filter(|relation| !relation.is_owner) {
+		let value = match input_object.get(&relation.name) {
+			Some(value) => value,
+			None => continue,
+		};
+
+		let mut parent =
+			(relation.insert)(context, ctx, transaction, value.object()?, BTreeMap::new()).await?;
+
+		if let Some(key) = parent.remove(&relation.to_column) {
+			overrides.insert(relation.from_column.clone(), key);
+		}
+	}
+
+	let mut active_model = prepare_active_model::<T, A>(
+		&entity_input_builder,
+		&entity_object_builder,
+		input_object.clone(),
+	)?;
+
+	let mut row = BTreeMap::new();
+
+	for column in T::Column::iter() {
+		let column_name = entity_object_builder.column_name::<T>(&column);
+
+		if let Some(value) = overrides.remove(&column_name) {
+			active_model.set(column, value);
+		}
+
+		if let ActiveValue::Set(value) = active_model.get(column) {
+			row.insert(column_name, value);
+		}
+	}
+
+	check_insert(
+		context.policies.insert_checks.get(&entity_object_builder.type_name::<T>()),
+		ctx,
+		&row,
+	)?;
+
+	let model = active_model.insert(transaction).await?;
+
+	for relation in relations.iter().filter(|relation| relation.is_owner) {
+		let value = match input_object.get(&relation.name) {
+			Some(value) => value,
+			None => continue,
+		};
+
+		let column = T::Column::iter()
+			.find(|column| entity_object_builder.column_name::<T>(column).eq(&relation.from_column))
+			.ok_or_else(|| {
+				SeaographyError::new(format!("Unknown relation column '{}'", relation.from_column))
+			})?;
+		let key = model.get(column);
+
+		let list;
+		let children = match relation.is_list {
+			true => {
+				list = value.list()?;
+				list.to_iter().collect::<Vec<_>>()
+			}
+			false => vec![value],
+		};
+
+		for child in children {
+			(relation.insert)(
+				context,
+				ctx,
+				transaction,
+				child.object()?,
+				BTreeMap::from([(relation.to_column.clone(), key.clone())]),
+			)
+			.await?;
+		}
+	}
+
+	Ok(model)
+}
+
+/// used as NestedInsertFn of a related entity, applies its guards before inserting it
+pub fn insert_nested_related<'a, 'b, R>(
+	context: &'static BuilderContext,
+	ctx: &'a ResolverContext<'b>,
+	transaction: &'a DatabaseTransaction,
+	input_object: ObjectAccessor<'a>,
+	overrides: BTreeMap<String, sea_orm::Value>,
+) -> BoxFuture<'a, SeaResult<BTreeMap<String, sea_orm::Value>>>
+where
+	R: EntityTrait,
+	<R as EntityTrait>::Model: Sync,
+	<R as EntityTrait>::Model: IntoActiveModel<R::ActiveModel>,
+	<R as EntityTrait>::ActiveModel: std::marker::Send,
+{
+	Box::pin(async move {
+		let entity_object_builder = EntityObjectBuilder {
+			context,
+		};
+		let object_name = entity_object_builder.type_name::<R>();
+
+		let guard_flag = match context.guards.entity_guards.get(&object_name) {
+			Some(guard) => (*guard)(ctx),
+			None => GuardAction::Allow,
+		};
+
+		if let GuardAction::Block(reason) = guard_flag {
+			return Err(SeaographyError::Forbidden(
+				reason.unwrap_or_else(|| "Entity guard triggered.".into()),
+			));
+		}
+
+		for (column, _) in input_object.to_iter() {
+			let field_guard_flag =
+				match context.guards.field_guards.get(&format!("{}.{}", object_name, column)) {
+					Some(field_guard) => (*field_guard)(ctx),
+					None => GuardAction::Allow,
+				};
+
+			if let GuardAction::Block(reason) = field_guard_flag {
+				return Err(SeaographyError::Forbidden(
+					reason.unwrap_or_else(|| "GraphQLField guard triggered.".into()),
+				));
+			}
+		}
+
+		let model =
+			insert_nested::<R, R::ActiveModel>(context, ctx, transaction, input_object, overrides)
+				.await?;
+
+		Ok(R::Column::iter()
+			.map(|column| (entity_object_builder.column_name::<R>(&column), model.get(column)))
+			.collect())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		EntityObjectRelationBuilder,
+		tests_cfg::{author, post, profile},
+	};
+	use dynamic::prelude::{ApiType, ContextBase, Data, FieldValue};
+	use sea_orm::{
+		ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend, PaginatorTrait,
+		QueryFilter, RelationTrait, Schema, TransactionTrait,
+	};
+	use std::sync::Arc;
+
+	fn context() -> &'static BuilderContext {
+		let mut context = BuilderContext::default();
+		context.policies.insert_checks.insert(
+			"Post".into(),
+			Box::new(|_, row| match row.get("title") {
+				Some(sea_orm::Value::String(Some(title))) if title.is_empty() => {
+					GuardAction::Block(Some("A post needs a title.".into()))
+				}
+				_ => GuardAction::Allow,
+			}),
+		);
+		Box::leak(Box::new(context))
+	}
+
+	fn relations(context: &'static BuilderContext) -> NestedInsertRelations {
+		let builder = EntityObjectRelationBuilder {
+			context,
+		};
+		let relation = |relation: SeaResult<NestedInsertRelation>| relation.unwrap();
+
+		NestedInsertRelations(BTreeMap::from([
+			(
+				"Author".to_string(),
+				vec![
+					relation(builder.get_nested_insert::<author::Entity, post::Entity>(
+						"posts",
+						author::Relation::Post.def(),
+					)),
+					relation(builder.get_nested_insert::<author::Entity, profile::Entity>(
+						"profile",
+						author::Relation::Profile.def(),
+					)),
+				],
+			),
+			(
+				"Post".to_string(),
+				vec![relation(builder.get_nested_insert::<post::Entity, author::Entity>(
+					"author",
+					post::Relation::Author.def(),
+				))],
+			),
+		]))
+	}
+
+	async fn database() -> DatabaseConnection {
+		let db = Database::connect("sqlite::memory:").await.unwrap();
+		let schema = Schema::new(DbBackend::Sqlite);
+		for stmt in [
+			schema.create_table_from_entity(author::Entity),
+			schema.create_table_from_entity(post::Entity),
+			schema.create_table_from_entity(profile::Entity),
+		] {
+			db.execute(db.get_database_backend().build(&stmt)).await.unwrap();
+		}
+		db
+	}
+
+	fn object(fields: &[(&str, Value)]) -> ObjectAccessor<'static> {
+		let mut object = ObjectAccessor::default();
+		for (name, value) in fields {
+			object.insert(Value::from(*name), value.clone());
+		}
+		object
+	}
+
+	fn map(fields: &[(&str, Value)]) -> Value {
+		Value::Map(fields.iter().map(|(name, value)| (Value::from(*name), value.clone())).collect())
+	}
+
+	/// Should only write a list of related rows for 1-N relations.
+	#[test]
+	fn cardinality() {
+		let relations = relations(context());
+		let flags = |entity: &str, name: &str| {
+			let relation =
+				relations.0[entity].iter().find(|relation| relation.name == name).unwrap();
+			(relation.is_owner, relation.is_list)
+		};
+
+		assert_eq!(flags("Author", "posts"), (true, true));
+		assert_eq!(flags("Author", "profile"), (true, false));
+		assert_eq!(flags("Post", "author"), (false, false));
+	}
+
+	/// Should insert the related rows of 1-N, 1-1 and N-1 relations with their foreign keys
+	/// filled and roll them back together with a failed child.
+	#[tokio::test]
+	async fn nested_inserts() {
+		let context = context();
+		let db = database().await;
+
+		let mut data = Data::default();
+		data.insert(relations(context));
+		let base = ContextBase {
+			r#type: ApiType::GraphQL,
+			execute_data: Some(Arc::new(data)),
+		};
+		let ctx = ResolverContext {
+			ctx: &base,
+			args: ObjectAccessor::default(),
+			parent_value: &FieldValue::NULL,
+		};
+
+		// 1-N and 1-1 relations are inserted after the author with its key
+		let transaction = db.begin().await.unwrap();
+		let input = object(&[
+			("name", Value::from("Ann")),
+			(
+				"posts",
+				Value::List(vec![
+					map(&[("title", Value::from("First"))]),
+					map(&[("title", Value::from("Second"))]),
+				]),
+			),
+			("profile", map(&[("bio", Value::from("Writer"))])),
+		]);
+		let ann = insert_nested::<author::Entity, author::ActiveModel>(
+			context,
+			&ctx,
+			&transaction,
+			input,
+			BTreeMap::new(),
+		)
+		.await
+		.unwrap();
+		transaction.commit().await.unwrap();
+
+		let posts = post::Entity::find().filter(post::Column::AuthorId.eq(ann.id)).all(&db);
+		assert_eq!(posts.await.unwrap().len(), 2);
+		let profile = profile::Entity::find().one(&db).await.unwrap().unwrap();
+		assert_eq!(profile.author_id, ann.id);
+
+		// N-1 relations are inserted before the post and fill its foreign key
+		let transaction = db.begin().await.unwrap();
+		let input = object(&[
+			("title", Value::from("Third")),
+			("author", map(&[("name", Value::from("Bob"))])),
+		]);
+		let third = insert_nested::<post::Entity, post::ActiveModel>(
+			context,
+			&ctx,
+			&transaction,
+			input,
+			BTreeMap::new(),
+		)
+		.await
+		.unwrap();
+		transaction.commit().await.unwrap();
+
+		let bob = author::Entity::find_by_id(third.author_id).one(&db).await.unwrap().unwrap();
+		assert_eq!(bob.name, "Bob");
+
+		// a failed child leaves neither the author nor its other children behind
+		let transaction = db.begin().await.unwrap();
+		let input = object(&[
+			("name", Value::from("Cid")),
+			(
+				"posts",
+				Value::List(vec![
+					map(&[("title", Value::from("Fourth"))]),
+					map(&[("title", Value::from(""))]),
+				]),
+			),
+		]);
+		let result = insert_nested::<author::Entity, author::ActiveModel>(
+			context,
+			&ctx,
+			&transaction,
+			input,
+			BTreeMap::new(),
+		)
+		.await;
+		assert!(
+			matches!(result, Err(SeaographyError::Forbidden(reason)) if reason == "A post needs a title.")
+		);
+		drop(transaction);
+
+		assert_eq!(author::Entity::find().count(&db).await.unwrap(), 2);
+		assert_eq!(post::Entity::find().count(&db).await.unwrap(), 3);
+	}
+}
@@ -0,0 +1,115 @@
+use crate::{
+	BuilderContext, EntityObjectBuilder, EntityQueryFieldBuilder, FilterInputBuilder,
+	FilterTypeTrait, GuardAction, apply_policy, get_filter_conditions, soft_delete_column,
+	soft_delete_update, soft_deleted_condition, version_column,
+};
+use dynamic::prelude::*;
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, UpdateResult};
+
+/// The configuration structure of EntityRestoreMutationBuilder
+pub struct EntityRestoreMutationConfig {
+	/// suffix that is appended on restore mutations
+	pub mutation_suffix: String,
+
+	/// name for `filter` field
+	pub filter_field: String,
+}
+
+impl std::default::Default for EntityRestoreMutationConfig {
+	fn default() -> Self {
+		Self {
+			mutation_suffix: "Restore".into(),
+			filter_field: "filter".into(),
+		}
+	}
+}
+
+/// This builder produces the restore mutation for a soft deleted entity
+pub struct EntityRestoreMutationBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl EntityRestoreMutationBuilder {
+	/// used to get mutation name for a SeaORM entity
+	pub fn type_name<T>(&self) -> String
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_query_field_builder = EntityQueryFieldBuilder {
+			context: self.context,
+		};
+		format!(
+			"{}{}",
+			entity_query_field_builder.type_name::<T>(),
+			self.context.entity_restore_mutation.mutation_suffix
+		)
+	}
+
+	/// used to get the restore mutation field for a SeaORM entity,
+	/// None indicates that the entity is not soft deleted
+	pub fn to_field<T, Ty, F>(&self) -> Option<Field<Ty>>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+		F: FilterTypeTrait,
+	{
+		let entity_filter_input_builder = FilterInputBuilder {
+			context: self.context,
+		};
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name: String = entity_object_builder.type_name::<T>();
+
+		let context = self.context;
+
+		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let column = soft_delete_column::<T>(context)?;
+		let version = version_column::<T>(context);
+
+		Some(
+			Field::output(self.type_name::<T>(), Ty::named_nn(Ty::UINT64), move |ctx| {
+				FieldFuture::new(async move {
+					let guard_flag = if let Some(guard) = guard {
+						(*guard)(&ctx)
+					} else {
+						GuardAction::Allow
+					};
+
+					if let GuardAction::Block(reason) = guard_flag {
+						return match reason {
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
+							)),
+						};
+					}
+
+					let db = ctx.data::<DatabaseConnection>()?;
+
+					let filters = ctx.args.get(&context.entity_restore_mutation.filter_field);
+					let filter_condition =
+						get_filter_conditions::<T, F>(context, db.get_database_backend(), filters)?;
+					let filter_condition = apply_policy(policy, &ctx, filter_condition)
+						.add(soft_deleted_condition(&column, true));
+
+					let res: UpdateResult = soft_delete_update::<T>(column, version, false)
+						.filter(filter_condition)
+						.exec(db)
+						.await?;
+
+					Ok(Some(FieldValue::value(res.rows_affected)))
+				})
+			})
+			.argument(Field::input(
+				&context.entity_restore_mutation.filter_field,
+				Ty::named(entity_filter_input_builder.type_name(&object_name)),
+			)),
+		)
+	}
+}
@@ -1,10 +1,14 @@
 use crate::{
 	BuilderContext, EntityInputBuilder, EntityObjectBuilder, EntityQueryFieldBuilder,
-	FilterInputBuilder, FilterTypeTrait, GuardAction, get_filter_conditions, prepare_active_model,
+	FilterInputBuilder, FilterTypeTrait, GuardAction, TypesMapHelper, apply_policy,
+	apply_soft_delete, field_mask_argument, field_mask_contains, field_mask_paths,
+	get_filter_conditions, next_version, prepare_active_model, soft_delete_column,
+	update_mask_columns, version_column,
 };
 use dynamic::prelude::*;
 use sea_orm::{
-	ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+	ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+	IdenStatic, IntoActiveModel, Iterable, ModelTrait, PrimaryKeyToColumn, QueryFilter,
 	TransactionTrait,
 };
 
@@ -76,9 +80,12 @@ impl EntityUpdateMutationBuilder {
 		let context = self.context;
 
 		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let version = version_column::<T>(context);
+		let soft_delete = soft_delete_column::<T>(context);
 		let field_guards = &self.context.guards.field_guards;
 
-		Field::output(
+		let field = Field::output(
 			self.type_name::<T>(),
 			Ty::named_nn_list_nn(entity_object_builder.basic_type_name::<T>()),
 			move |ctx| {
@@ -91,11 +98,11 @@ impl EntityUpdateMutationBuilder {
 
 					if let GuardAction::Block(reason) = guard_flag {
 						return match reason {
-							Some(reason) => {
-								Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-								"Entity guard triggered.",
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
 							)),
 						};
 					}
@@ -111,7 +118,12 @@ impl EntityUpdateMutationBuilder {
 					};
 
 					let filters = ctx.args.get(&context.entity_update_mutation.filter_field);
-					let filter_condition = get_filter_conditions::<T, F>(context, filters)?;
+					let filter_condition =
+						get_filter_conditions::<T, F>(context, db.get_database_backend(), filters)?;
+					let filter_condition = apply_policy(policy, &ctx, filter_condition);
+					// deleted rows are only changed by the restore mutation
+					let filter_condition =
+						apply_soft_delete(context, soft_delete, &ctx.args, filter_condition)?;
 
 					let value_accessor =
 						match ctx.args.get(&context.entity_update_mutation.data_field) {
@@ -125,7 +137,23 @@ impl EntityUpdateMutationBuilder {
 						};
 					let input_object = value_accessor.object()?;
 
+					// only the columns of the update mask of a grpc request are set
+					let update_mask =
+						field_mask_paths(&ctx.args, &context.field_masks.update_mask)?;
+					let update_columns = match &update_mask {
+						Some(paths) => Some(update_mask_columns::<T>(context, paths)?),
+						None => None,
+					};
+
 					for (column, _) in input_object.to_iter() {
+						let masked = match (&update_mask, column) {
+							(Some(paths), Value::String(name)) => field_mask_contains(paths, name),
+							_ => true,
+						};
+						if !masked {
+							continue;
+						}
+
 						let field_guard = field_guards.get(&format!(
 							"{}.{}",
 							entity_object_builder.type_name::<T>(),
@@ -138,30 +166,98 @@ impl EntityUpdateMutationBuilder {
 						};
 						if let GuardAction::Block(reason) = field_guard_flag {
 							return match reason {
-								Some(reason) => {
-									Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
+								Some(reason) => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden(reason),
+								),
+								None => {
+									Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+										"GraphQLField guard triggered.".into(),
+									))
 								}
-								None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-									"GraphQLField guard triggered.",
-								)),
 							};
 						}
 					}
 
-					let active_model = prepare_active_model::<T, A>(
+					let mut active_model = prepare_active_model::<T, A>(
 						&entity_input_builder,
 						&entity_object_builder,
-						input_object,
+						input_object.clone(),
 					)?;
 
-					T::update_many()
-						.set(active_model)
-						.filter(filter_condition.clone())
-						.exec(&transaction)
+					if let Some(columns) = &update_columns {
+						for column in T::Column::iter() {
+							if !columns.iter().any(|masked| masked.as_str() == column.as_str()) {
+								active_model.not_set(column);
+							}
+						}
+					}
+
+					// versioned rows are only updated when they still have the expected version
+					let filter_condition = match version {
+						Some(column) => {
+							let types_map_helper = TypesMapHelper {
+								context,
+							};
+							let expected_version = input_object
+								.try_get(entity_object_builder.column_name::<T>(&column))?;
+							let expected_version = types_map_helper
+								.value_to_sea_orm_value::<T>(&column, &expected_version)?;
+
+							active_model.not_set(column);
+
+							filter_condition.add(column.eq(expected_version))
+						}
+						None => filter_condition,
+					};
+
+					// the updated rows are read back by primary key, so rows that no longer
+					// match the filters or the entity policy after the update are detected
+					let models =
+						T::find().filter(filter_condition.clone()).all(&transaction).await?;
+
+					if models.is_empty() {
+						if version.is_some() {
+							return Err(version_conflict(&entity_object_builder.type_name::<T>()));
+						}
+
+						return Ok(Some(FieldValue::list(Vec::<FieldValue>::new())));
+					}
+
+					let keys = models.iter().fold(Condition::any(), |condition, model| {
+						condition.add(T::PrimaryKey::iter().fold(
+							Condition::all(),
+							|condition, key| {
+								let column = key.into_column();
+								condition.add(column.eq(model.get(column)))
+							},
+						))
+					});
+
+					let update = T::update_many().set(active_model).filter(filter_condition);
+					let update = match version {
+						Some(column) => update.col_expr(column, next_version(&column)),
+						None => update,
+					};
+					let res = update.exec(&transaction).await?;
+
+					// rows updated by a concurrent transaction no longer match the version
+					if version.is_some() && res.rows_affected != models.len() as u64 {
+						return Err(version_conflict(&entity_object_builder.type_name::<T>()));
+					}
+
+					let updated: Vec<T::Model> =
+						T::find().filter(keys.clone()).all(&transaction).await?;
+
+					let result: Vec<T::Model> = T::find()
+						.filter(apply_policy(policy, &ctx, keys))
+						.all(&transaction)
 						.await?;
 
-					let result: Vec<T::Model> =
-						T::find().filter(filter_condition).all(&transaction).await?;
+					if result.len() != updated.len() {
+						return Err(SeaographyError::new(
+							"Updated rows violate the entity policy.",
+						));
+					}
 
 					transaction.commit().await?;
 
@@ -176,6 +272,15 @@ impl EntityUpdateMutationBuilder {
 		.argument(Field::input(
 			&context.entity_update_mutation.filter_field,
 			Ty::named(entity_filter_input_builder.type_name(&object_name)),
-		))
+		));
+
+		field_mask_argument(&context.field_masks.update_mask, field)
 	}
 }
+
+/// used to get the error of updates whose expected version does not match
+fn version_conflict(object_name: &str) -> SeaographyError {
+	SeaographyError::Conflict(format!(
+		"{object_name} was modified concurrently, the expected version does not match."
+	))
+}
@@ -0,0 +1,461 @@
+use crate::{
+	BuilderContext, EntityInputBuilder, EntityObjectBuilder, EntityQueryFieldBuilder, GuardAction,
+	apply_policy, check_insert, next_version, version_column,
+};
+use dynamic::prelude::*;
+use sea_orm::{
+	ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, DbBackend, EntityTrait,
+	IntoActiveModel, Iterable, PrimaryKeyToColumn, QueryFilter, Schema, TransactionTrait, Value,
+	sea_query::{ColumnSpec, OnConflict},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The configuration structure of EntityUpsertMutationBuilder
+pub struct EntityUpsertMutationConfig {
+	/// suffix that is appended on upsert mutations
+	pub mutation_suffix: String,
+	/// name for `data` field
+	pub data_field: String,
+	/// name for `conflictColumns` field
+	pub conflict_columns_field: String,
+	/// name for `updateColumns` field
+	pub update_columns_field: String,
+	/// additional unique keys that can be used as conflict target, the generated code fills it
+	/// with the unique indexes spanning several columns, e.g. "Film" => [["title", "releaseYear"]]
+	pub unique_keys: BTreeMap<String, Vec<Vec<String>>>,
+}
+
+impl std::default::Default for EntityUpsertMutationConfig {
+	fn default() -> Self {
+		EntityUpsertMutationConfig {
+			mutation_suffix: "Upsert".into(),
+			data_field: "data".into(),
+			conflict_columns_field: "conflictColumns".into(),
+			update_columns_field: "updateColumns".into(),
+			unique_keys: BTreeMap::new(),
+		}
+	}
+}
+
+/// This builder produces the upsert mutation for an entity
+pub struct EntityUpsertMutationBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl EntityUpsertMutationBuilder {
+	/// used to get mutation name for a SeaORM entity
+	pub fn type_name<T>(&self) -> String
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_query_field_builder = EntityQueryFieldBuilder {
+			context: self.context,
+		};
+		format!(
+			"{}{}",
+			entity_query_field_builder.type_name::<T>(),
+			self.context.entity_upsert_mutation.mutation_suffix
+		)
+	}
+
+	/// used to get the column sets that can be used as conflict target,
+	/// the primary key first, followed by unique columns and configured unique keys
+	pub fn conflict_targets<T>(&self) -> Vec<BTreeSet<String>>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let schema = Schema::new(DbBackend::Postgres);
+
+		let mut targets: Vec<BTreeSet<String>> = vec![
+			T::PrimaryKey::iter()
+				.map(|key| entity_object_builder.column_name::<T>(&key.into_column()))
+				.collect(),
+		];
+
+		for column in T::Column::iter() {
+			let is_unique = schema
+				.get_column_def::<T>(column)
+				.get_column_spec()
+				.iter()
+				.any(|spec| matches!(spec, ColumnSpec::UniqueKey));
+
+			if is_unique {
+				targets.push(BTreeSet::from([entity_object_builder.column_name::<T>(&column)]));
+			}
+		}
+
+		if let Some(keys) = self
+			.context
+			.entity_upsert_mutation
+			.unique_keys
+			.get(&entity_object_builder.type_name::<T>())
+		{
+			targets.extend(keys.iter().map(|key| key.iter().cloned().collect()));
+		}
+
+		targets
+	}
+
+	/// used to get the upsert mutation field for a SeaORM entity
+	pub fn to_field<T, A, Ty>(&self) -> Field<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		<T as EntityTrait>::Model: IntoActiveModel<A>,
+		A: ActiveModelTrait<Entity = T> + sea_orm::ActiveModelBehavior + std::marker::Send,
+		Ty: TypeRefTrait,
+	{
+		let entity_input_builder = EntityInputBuilder {
+			context: self.context,
+		};
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+
+		let context = self.context;
+
+		let object_name: String = entity_object_builder.type_name::<T>();
+		let guard = self.context.guards.entity_guards.get(&object_name);
+		let field_guards = &self.context.guards.field_guards;
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let insert_check = self.context.policies.insert_checks.get(&object_name);
+		let conflict_targets = self.conflict_targets::<T>();
+		let version = version_column::<T>(context);
+
+		Field::output(
+			self.type_name::<T>(),
+			Ty::named_nn(entity_object_builder.basic_type_name::<T>()),
+			move |ctx| {
+				let conflict_targets = conflict_targets.clone();
+				FieldFuture::new(async move {
+					let guard_flag = if let Some(guard) = guard {
+						(*guard)(&ctx)
+					} else {
+						GuardAction::Allow
+					};
+
+					if let GuardAction::Block(reason) = guard_flag {
+						return match reason {
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
+							)),
+						};
+					}
+
+					let entity_input_builder = EntityInputBuilder {
+						context,
+					};
+					let entity_object_builder = EntityObjectBuilder {
+						context,
+					};
+					let db = ctx.data::<DatabaseConnection>()?;
+					let value_accessor =
+						match ctx.args.get(&context.entity_upsert_mutation.data_field) {
+							Some(value_accessor) => value_accessor,
+							None => {
+								return Err(SeaographyError::new(format!(
+									"{} is a required argument but not provided.",
+									context.entity_upsert_mutation.data_field
+								)));
+							}
+						};
+					let input_object = value_accessor.object()?;
+
+					for (column, _) in input_object.to_iter() {
+						let field_guard = field_guards.get(&format!(
+							"{}.{}",
+							entity_object_builder.type_name::<T>(),
+							column
+						));
+						let field_guard_flag = if let Some(field_guard) = field_guard {
+							(*field_guard)(&ctx)
+						} else {
+							GuardAction::Allow
+						};
+						if let GuardAction::Block(reason) = field_guard_flag {
+							return match reason {
+								Some(reason) => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden(reason),
+								),
+								None => {
+									Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+										"GraphQLField guard triggered.".into(),
+									))
+								}
+							};
+						}
+					}
+
+					let mut data = entity_input_builder.parse_object::<T>(&input_object)?;
+
+					check_insert(insert_check, &ctx, &data)?;
+
+					let conflict_columns = match ctx
+						.args
+						.get(&context.entity_upsert_mutation.conflict_columns_field)
+					{
+						Some(value) => parse_column_names(value)?,
+						None => conflict_targets[0].clone(),
+					};
+
+					if !conflict_targets.contains(&conflict_columns) {
+						return Err(SeaographyError::new(format!(
+							"[{}] is neither the primary key nor a unique key of {}",
+							conflict_columns.iter().cloned().collect::<Vec<_>>().join(", "),
+							entity_object_builder.type_name::<T>()
+						)));
+					}
+
+					let mut update_columns =
+						match ctx.args.get(&context.entity_upsert_mutation.update_columns_field) {
+							Some(value) => parse_column_names(value)?,
+							None => data
+								.keys()
+								.filter(|name| !conflict_columns.contains(*name))
+								.cloned()
+								.collect(),
+						};
+
+					// the version of versioned rows is incremented instead of overwritten and the
+					// provided version is the version the existing row is expected to have
+					let expected_version = match version {
+						Some(column) => {
+							let column_name = entity_object_builder.column_name::<T>(&column);
+							update_columns.remove(&column_name);
+
+							let expected_version = data.get(&column_name).ok_or_else(|| {
+								SeaographyError::new(format!(
+									"{column_name} is the version of {} but not provided in {}.",
+									entity_object_builder.type_name::<T>(),
+									context.entity_upsert_mutation.data_field
+								))
+							})?;
+							Some((column, expected_version.clone()))
+						}
+						None => None,
+					};
+
+					let mut active_model = A::default();
+					let mut conflict = Vec::new();
+					let mut conflict_condition = Condition::all();
+					let mut update = Vec::new();
+
+					for column in T::Column::iter() {
+						let column_name = entity_object_builder.column_name::<T>(&column);
+
+						if conflict_columns.contains(&column_name) {
+							let value = data.get(&column_name).ok_or_else(|| {
+								SeaographyError::new(format!(
+									"{column_name} is a conflict column but not provided in {}.",
+									context.entity_upsert_mutation.data_field
+								))
+							})?;
+							conflict_condition = conflict_condition.add(column.eq(value.clone()));
+							conflict.push(column);
+						}
+
+						if update_columns.contains(&column_name) {
+							if !data.contains_key(&column_name) {
+								return Err(SeaographyError::new(format!(
+									"{column_name} is listed in {} but not provided in {}.",
+									context.entity_upsert_mutation.update_columns_field,
+									context.entity_upsert_mutation.data_field
+								)));
+							}
+							update.push(column);
+						}
+
+						if let Some(value) = data.remove(&column_name) {
+							active_model.set(column, value);
+						}
+					}
+
+					if update.len() != update_columns.len() {
+						return Err(SeaographyError::new(format!(
+							"{} contains unknown columns.",
+							context.entity_upsert_mutation.update_columns_field
+						)));
+					}
+
+					let is_update = !update.is_empty();
+
+					// existing rows outside of the entity policy are neither updated nor returned
+					let on_conflict = on_conflict(
+						conflict,
+						update,
+						expected_version,
+						policy.map(|policy| (*policy)(&ctx)),
+					);
+
+					// the row is read back through the conflict target, as RETURNING and
+					// the last insert id do not cover the update path on every backend
+					let transaction = db.begin().await?;
+
+					let rows_affected = T::insert(active_model)
+						.on_conflict(on_conflict)
+						.exec_without_returning(&transaction)
+						.await?;
+
+					let result = T::find()
+						.filter(apply_policy(policy, &ctx, conflict_condition))
+						.one(&transaction)
+						.await?
+						.ok_or_else(|| {
+							SeaographyError::NotFound("Upserted record not found.".into())
+						})?;
+
+					// an existing row that was neither inserted nor updated has another version
+					if version.is_some() && is_update && rows_affected == 0 {
+						return Err(SeaographyError::Conflict(format!(
+							"{} was modified concurrently, the expected version does not match.",
+							entity_object_builder.type_name::<T>()
+						)));
+					}
+
+					transaction.commit().await?;
+
+					Ok(Some(FieldValue::owned_any(result)))
+				})
+			},
+		)
+		.argument(Field::input(
+			&context.entity_upsert_mutation.data_field,
+			Ty::named_nn(entity_input_builder.upsert_type_name::<T>()),
+		))
+		.argument(Field::input(
+			&context.entity_upsert_mutation.conflict_columns_field,
+			Ty::named_nn_list(Ty::STRING),
+		))
+		.argument(Field::input(
+			&context.entity_upsert_mutation.update_columns_field,
+			Ty::named_nn_list(Ty::STRING),
+		))
+	}
+}
+
+/// used to build the conflict clause of an upsert, nothing is updated without update columns
+/// and existing rows are only updated when they have the expected version and match the policy
+/// condition
+fn on_conflict<C>(
+	conflict: Vec<C>,
+	update: Vec<C>,
+	version: Option<(C, Value)>,
+	policy: Option<Condition>,
+) -> OnConflict
+where
+	C: ColumnTrait,
+{
+	let mut on_conflict = OnConflict::columns(conflict);
+
+	if update.is_empty() {
+		on_conflict.do_nothing();
+		return on_conflict;
+	}
+
+	on_conflict.update_columns(update);
+
+	let mut condition = Condition::all();
+	if let Some((column, expected_version)) = version {
+		on_conflict.value(column, next_version(&column));
+		condition = condition.add(column.eq(expected_version));
+	}
+	if let Some(policy) = policy {
+		condition = condition.add(policy);
+	}
+	if !condition.is_empty() {
+		on_conflict.action_cond_where(condition);
+	}
+
+	on_conflict
+}
+
+fn parse_column_names(value: ValueAccessor) -> SeaResult<BTreeSet<String>> {
+	let list = value.list()?;
+
+	list.to_iter().map(|item| item.string().map(|name| name.to_string())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+	use sea_orm::{ActiveValue::Set, QueryTrait};
+
+	fn sql(
+		update: Vec<film::Column>,
+		version: Option<(film::Column, Value)>,
+		policy: Option<Condition>,
+	) -> String {
+		let active_model = film::ActiveModel {
+			id: Set(1),
+			title: Set("Alien".into()),
+			..Default::default()
+		};
+
+		film::Entity::insert(active_model)
+			.on_conflict(on_conflict(vec![film::Column::Id], update, version, policy))
+			.build(DbBackend::Postgres)
+			.to_string()
+	}
+
+	#[test]
+	fn conflict_clause() {
+		assert_eq!(
+			sql(vec![film::Column::Title], None, None),
+			concat!(
+				r#"INSERT INTO "film" ("id", "title") VALUES (1, 'Alien') "#,
+				r#"ON CONFLICT ("id") DO UPDATE SET "title" = "excluded"."title""#,
+			)
+		);
+		assert_eq!(
+			sql(vec![], None, None),
+			r#"INSERT INTO "film" ("id", "title") VALUES (1, 'Alien') ON CONFLICT ("id") DO NOTHING"#
+		);
+		assert_eq!(
+			sql(
+				vec![film::Column::Title],
+				None,
+				Some(Condition::all().add(film::Column::DeletedAt.is_null()))
+			),
+			concat!(
+				r#"INSERT INTO "film" ("id", "title") VALUES (1, 'Alien') "#,
+				r#"ON CONFLICT ("id") DO UPDATE SET "title" = "excluded"."title" "#,
+				r#"WHERE "film"."deleted_at" IS NULL"#,
+			)
+		);
+	}
+
+	/// Should increment the version of versioned rows that still have the expected version.
+	#[test]
+	fn versioned_conflict_clause() {
+		assert_eq!(
+			sql(vec![film::Column::Title], Some((film::Column::Version, Value::from(3))), None),
+			concat!(
+				r#"INSERT INTO "film" ("id", "title") VALUES (1, 'Alien') "#,
+				r#"ON CONFLICT ("id") DO UPDATE SET "title" = "excluded"."title", "#,
+				r#""version" = "version" + 1 WHERE "film"."version" = 3"#,
+			)
+		);
+		assert_eq!(
+			sql(
+				vec![film::Column::Title],
+				Some((film::Column::Version, Value::from(3))),
+				Some(Condition::all().add(film::Column::DeletedAt.is_null()))
+			),
+			concat!(
+				r#"INSERT INTO "film" ("id", "title") VALUES (1, 'Alien') "#,
+				r#"ON CONFLICT ("id") DO UPDATE SET "title" = "excluded"."title", "#,
+				r#""version" = "version" + 1 "#,
+				r#"WHERE "film"."version" = 3 AND "film"."deleted_at" IS NULL"#,
+			)
+		);
+	}
+}
@@ -1,11 +1,20 @@
 pub mod entity_create_one_mutation;
 pub use entity_create_one_mutation::*;
 
+pub mod entity_nested_insert;
+pub use entity_nested_insert::*;
+
 pub mod entity_create_batch_mutation;
 pub use entity_create_batch_mutation::*;
 
+pub mod entity_upsert_mutation;
+pub use entity_upsert_mutation::*;
+
 pub mod entity_update_mutation;
 pub use entity_update_mutation::*;
 
 pub mod entity_delete_mutation;
 pub use entity_delete_mutation::*;
+
+pub mod entity_restore_mutation;
+pub use entity_restore_mutation::*;
@@ -0,0 +1,327 @@
+use crate::{BuilderContext, EntityObjectBuilder, GuardAction, TypesMapHelper};
+use dynamic::prelude::*;
+use sea_orm::{ColumnTrait, ColumnType, EntityTrait, Iterable};
+use std::collections::BTreeMap;
+
+/// used to hold the result of an aggregation for one group of rows
+#[derive(Clone, Debug, Default)]
+pub struct Aggregate {
+	/// number of rows in the group
+	pub count: i64,
+	/// values of the columns used to group the rows
+	pub group: AggregateGroup,
+	/// sum of every numeric column
+	pub sum: AggregateValues,
+	/// average of every numeric column
+	pub avg: AggregateValues,
+	/// minimum of every numeric column
+	pub min: AggregateValues,
+	/// maximum of every numeric column
+	pub max: AggregateValues,
+}
+
+/// used to hold the grouped column values, keyed by column field name
+#[derive(Clone, Debug, Default)]
+pub struct AggregateGroup(pub BTreeMap<String, Value>);
+
+/// used to hold aggregated numeric values, keyed by column field name
+#[derive(Clone, Debug, Default)]
+pub struct AggregateValues(pub BTreeMap<String, Option<f64>>);
+
+/// The configuration structure for AggregateObjectBuilder
+pub struct AggregateObjectConfig {
+	/// used to format the type name of the aggregate object
+	pub type_name: crate::SimpleNamingFn,
+	/// used to format the type name of the numeric values object
+	pub values_type_name: crate::SimpleNamingFn,
+	/// used to format the type name of the group object
+	pub group_type_name: crate::SimpleNamingFn,
+	/// name for 'count' field
+	pub count: String,
+	/// name for 'group' field
+	pub group: String,
+	/// name for 'sum' field
+	pub sum: String,
+	/// name for 'avg' field
+	pub avg: String,
+	/// name for 'min' field
+	pub min: String,
+	/// name for 'max' field
+	pub max: String,
+}
+
+impl std::default::Default for AggregateObjectConfig {
+	fn default() -> Self {
+		AggregateObjectConfig {
+			type_name: Box::new(|object_name: &str| -> String {
+				format!("{object_name}Aggregate")
+			}),
+			values_type_name: Box::new(|object_name: &str| -> String {
+				format!("{object_name}AggregateValues")
+			}),
+			group_type_name: Box::new(|object_name: &str| -> String {
+				format!("{object_name}AggregateGroup")
+			}),
+			count: "count".into(),
+			group: "group".into(),
+			sum: "sum".into(),
+			avg: "avg".into(),
+			min: "min".into(),
+			max: "max".into(),
+		}
+	}
+}
+
+/// This builder produces the aggregation objects for a SeaORM entity
+pub struct AggregateObjectBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl AggregateObjectBuilder {
+	/// used to get type name of the aggregate object
+	pub fn type_name(&self, object_name: &str) -> String {
+		self.context.aggregate_object.type_name.as_ref()(object_name)
+	}
+
+	/// used to get type name of the numeric values object
+	pub fn values_type_name(&self, object_name: &str) -> String {
+		self.context.aggregate_object.values_type_name.as_ref()(object_name)
+	}
+
+	/// used to get type name of the group object
+	pub fn group_type_name(&self, object_name: &str) -> String {
+		self.context.aggregate_object.group_type_name.as_ref()(object_name)
+	}
+
+	/// used to get all the aggregation objects of a SeaORM entity
+	pub fn to_objects<T, Ty>(&self) -> Vec<Object<Ty>>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		let mut objects = vec![self.to_object::<T, Ty>(), self.group_object::<T, Ty>()];
+
+		if has_numeric_columns::<T>() {
+			objects.push(self.values_object::<T, Ty>());
+		}
+
+		objects
+	}
+
+	/// used to get the aggregate object of a SeaORM entity
+	pub fn to_object<T, Ty>(&self) -> Object<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name = entity_object_builder.type_name::<T>();
+
+		let object = Object::new(self.type_name(&object_name), IO::Output)
+			.field(Field::output(
+				&self.context.aggregate_object.count,
+				Ty::named_nn(Ty::INT64),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(Value::from(aggregate.count)))
+					})
+				},
+			))
+			.field(Field::output(
+				&self.context.aggregate_object.group,
+				Ty::named_nn(self.group_type_name(&object_name)),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(FieldValue::borrowed_any(&aggregate.group)))
+					})
+				},
+			));
+
+		if !has_numeric_columns::<T>() {
+			return object;
+		}
+
+		let values_type_name = self.values_type_name(&object_name);
+
+		object
+			.field(Field::output(
+				&self.context.aggregate_object.sum,
+				Ty::named_nn(&values_type_name),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(FieldValue::borrowed_any(&aggregate.sum)))
+					})
+				},
+			))
+			.field(Field::output(
+				&self.context.aggregate_object.avg,
+				Ty::named_nn(&values_type_name),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(FieldValue::borrowed_any(&aggregate.avg)))
+					})
+				},
+			))
+			.field(Field::output(
+				&self.context.aggregate_object.min,
+				Ty::named_nn(&values_type_name),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(FieldValue::borrowed_any(&aggregate.min)))
+					})
+				},
+			))
+			.field(Field::output(
+				&self.context.aggregate_object.max,
+				Ty::named_nn(&values_type_name),
+				|ctx| {
+					FieldFuture::new(async move {
+						let aggregate = ctx.parent_value.try_downcast_ref::<Aggregate>()?;
+						Ok(Some(FieldValue::borrowed_any(&aggregate.max)))
+					})
+				},
+			))
+	}
+
+	/// used to get the object holding the grouped column values of a SeaORM entity
+	pub fn group_object<T, Ty>(&self) -> Object<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let types_map_helper = TypesMapHelper {
+			context: self.context,
+		};
+		let object_name = entity_object_builder.type_name::<T>();
+
+		T::Column::iter().fold(
+			Object::new(self.group_type_name(&object_name), IO::Output),
+			|object, column| {
+				let column_name = entity_object_builder.column_name::<T>(&column);
+				let column_type = column.def().get_column_type().clone();
+
+				let type_ref: Ty =
+					match types_map_helper.sea_orm_column_type_to_type(&column_type, false) {
+						Some(type_ref) => type_ref,
+						None => return object,
+					};
+
+				let guard = self
+					.context
+					.guards
+					.field_guards
+					.get(&format!("{}.{}", &object_name, &column_name));
+
+				let name = column_name.clone();
+
+				object.field(Field::output(column_name, type_ref, move |ctx| {
+					let name = name.clone();
+					FieldFuture::new(async move {
+						check_field_guard(guard, &ctx)?;
+
+						let group = ctx.parent_value.try_downcast_ref::<AggregateGroup>()?;
+
+						Ok(group.0.get(&name).cloned())
+					})
+				}))
+			},
+		)
+	}
+
+	/// used to get the object holding the aggregated numeric values of a SeaORM entity
+	pub fn values_object<T, Ty>(&self) -> Object<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name = entity_object_builder.type_name::<T>();
+
+		T::Column::iter()
+			.filter(|column| is_numeric_column_type(column.def().get_column_type()))
+			.fold(Object::new(self.values_type_name(&object_name), IO::Output), |object, column| {
+				let column_name = entity_object_builder.column_name::<T>(&column);
+
+				let guard = self
+					.context
+					.guards
+					.field_guards
+					.get(&format!("{}.{}", &object_name, &column_name));
+
+				let name = column_name.clone();
+
+				object.field(Field::output(column_name, Ty::named(Ty::DOUBLE), move |ctx| {
+					let name = name.clone();
+					FieldFuture::new(async move {
+						check_field_guard(guard, &ctx)?;
+
+						let values = ctx.parent_value.try_downcast_ref::<AggregateValues>()?;
+
+						Ok(values.0.get(&name).copied().flatten().map(Value::from))
+					})
+				}))
+			})
+	}
+}
+
+/// used to check if the column type can be used in sum/avg/min/max aggregations
+pub fn is_numeric_column_type(column_type: &ColumnType) -> bool {
+	matches!(
+		column_type,
+		ColumnType::TinyInteger
+			| ColumnType::SmallInteger
+			| ColumnType::Integer
+			| ColumnType::BigInteger
+			| ColumnType::TinyUnsigned
+			| ColumnType::SmallUnsigned
+			| ColumnType::Unsigned
+			| ColumnType::BigUnsigned
+			| ColumnType::Float
+			| ColumnType::Double
+			| ColumnType::Decimal(_)
+			| ColumnType::Money(_)
+	)
+}
+
+fn has_numeric_columns<T>() -> bool
+where
+	T: EntityTrait,
+{
+	T::Column::iter().any(|column| is_numeric_column_type(column.def().get_column_type()))
+}
+
+/// used to check the field guard of a column, a blocked column is forbidden
+pub(crate) fn check_field_guard(
+	guard: Option<&crate::FnGuard>,
+	ctx: &ResolverContext,
+) -> SeaResult<()> {
+	let guard_flag = if let Some(guard) = guard {
+		(*guard)(ctx)
+	} else {
+		GuardAction::Allow
+	};
+
+	match guard_flag {
+		GuardAction::Block(Some(reason)) => Err(SeaographyError::Forbidden(reason)),
+		GuardAction::Block(None) => {
+			Err(SeaographyError::Forbidden("Field guard triggered.".into()))
+		}
+		GuardAction::Allow => Ok(()),
+	}
+}
@@ -1,6 +1,9 @@
 use dynamic::prelude::*;
 use heck::{ToLowerCamelCase, ToUpperCamelCase};
-use sea_orm::{ColumnTrait, ColumnType, EntityName, EntityTrait, IdenStatic, Iterable, ModelTrait};
+use sea_orm::{
+	ColumnTrait, ColumnType, EntityName, EntityTrait, IdenStatic, Iterable, ModelTrait,
+	PrimaryKeyToColumn,
+};
 
 /// The configuration structure for EntityObjectBuilder
 pub struct EntityObjectConfig {
@@ -10,6 +13,10 @@ pub struct EntityObjectConfig {
 	pub column_name: crate::ComplexNamingFn,
 	/// suffix that is appended on basic version of entity type
 	pub basic_type_suffix: String,
+	/// name for the 'path' argument of JSON fields
+	pub json_path: String,
+	/// used to mark the objects as federation entities keyed by their primary key
+	pub federation: bool,
 }
 
 impl std::default::Default for EntityObjectConfig {
@@ -22,11 +29,13 @@ impl std::default::Default for EntityObjectConfig {
 				column_name.to_lower_camel_case()
 			}),
 			basic_type_suffix: "Basic".into(),
+			json_path: "path".into(),
+			federation: false,
 		}
 	}
 }
 
-use crate::{BuilderContext, GuardAction, TypesMapHelper};
+use crate::{BuilderContext, GuardAction, TypesMapHelper, field_mask_contains, field_mask_paths};
 
 /// This builder produces the GraphQL object of a SeaORM entity
 pub struct EntityObjectBuilder {
@@ -77,8 +86,16 @@ impl EntityObjectBuilder {
 		Ty: TypeRefTrait,
 	{
 		let object_name = self.type_name::<T>();
+		let object = self.basic_object::<T, Ty>(&object_name);
 
-		self.basic_object::<T, Ty>(&object_name)
+		if !self.context.entity_object.federation {
+			return object;
+		}
+
+		let key: Vec<String> =
+			T::PrimaryKey::iter().map(|key| self.column_name::<T>(&key.into_column())).collect();
+
+		object.key(key.join(" "))
 	}
 
 	/// used to get the GraphQL basic object of a SeaORM entity
@@ -132,13 +149,29 @@ impl EntityObjectBuilder {
 				_ => false,
 			};
 
+			let is_json: bool =
+				matches!(column_def.get_column_type(), ColumnType::Json | ColumnType::JsonBinary);
+
 			let guard =
 				self.context.guards.field_guards.get(&format!("{}.{}", &object_name, &column_name));
 
+			let json_path = &self.context.entity_object.json_path;
+
+			let read_mask = &self.context.field_masks.read_mask;
+
 			let conversion_fn =
 				self.context.types.output_conversions.get(&format!("{entity_name}.{column_name}"));
 
 			let field = Field::output(column_name.clone(), proto_type, move |ctx| {
+				// the fields outside the read mask of a grpc request are not sent
+				match field_mask_paths(&ctx.args, read_mask) {
+					Ok(Some(paths)) if !field_mask_contains(&paths, &column_name) => {
+						return FieldFuture::from_value(None);
+					}
+					Ok(_) => {}
+					Err(err) => return FieldFuture::new(async move { Err::<Option<()>, _>(err) }),
+				}
+
 				let guard_flag = if let Some(guard) = guard {
 					(*guard)(&ctx)
 				} else {
@@ -148,11 +181,11 @@ impl EntityObjectBuilder {
 				if let GuardAction::Block(reason) = guard_flag {
 					return FieldFuture::new(async move {
 						match reason {
-							Some(reason) => {
-								Err::<Option<()>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<()>, SeaographyError>(SeaographyError::new(
-								"ProtoField guard triggered.",
+							Some(reason) => Err::<Option<()>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<()>, SeaographyError>(SeaographyError::Forbidden(
+								"ProtoField guard triggered.".into(),
 							)),
 						}
 					});
@@ -176,16 +209,73 @@ impl EntityObjectBuilder {
 					});
 				}
 
-				FieldFuture::new(async move {
-					Ok(sea_query_value_to_value(object.get(column), is_enum))
-				})
+				let value = object.get(column);
+
+				#[cfg(feature = "with-json")]
+				let value = match ctx.args.get(json_path) {
+					Some(path) if is_json => match json_path_value(value, &path) {
+						Ok(value) => value,
+						Err(err) => {
+							return FieldFuture::new(async move { Err::<Option<()>, _>(err) });
+						}
+					},
+					_ => value,
+				};
+
+				FieldFuture::new(async move { Ok(sea_query_value_to_value(value, is_enum)) })
 			});
 
+			let field = if is_json {
+				field.argument(Field::input(json_path, Ty::named_nn_list(Ty::STRING)))
+			} else {
+				field
+			};
+
 			object.field(field)
 		})
 	}
 }
 
+/// used to extract the sub-document found at a path of keys and array indexes,
+/// null when the path does not exist
+#[cfg(feature = "with-json")]
+fn json_path_value(
+	value: sea_orm::sea_query::Value,
+	path: &ValueAccessor,
+) -> SeaResult<sea_orm::sea_query::Value> {
+	let path = path
+		.list()?
+		.to_iter()
+		.map(|segment| segment.string().map(str::to_string))
+		.collect::<SeaResult<Vec<String>>>()?;
+
+	Ok(extract_json_path(value, &path))
+}
+
+#[cfg(feature = "with-json")]
+fn extract_json_path(
+	value: sea_orm::sea_query::Value,
+	path: &[String],
+) -> sea_orm::sea_query::Value {
+	let document = match value {
+		sea_orm::sea_query::Value::Json(Some(document)) => *document,
+		value => return value,
+	};
+
+	let mut current = Some(&document);
+
+	for segment in path {
+		current = current.and_then(|current| match current {
+			sea_orm::JsonValue::Array(items) => {
+				segment.parse::<usize>().ok().and_then(|index| items.get(index))
+			}
+			current => current.get(segment),
+		});
+	}
+
+	sea_orm::sea_query::Value::Json(current.cloned().map(Box::new))
+}
+
 fn sea_query_value_to_value(
 	sea_query_value: sea_orm::sea_query::Value,
 	is_enum: bool,
@@ -215,7 +305,7 @@ fn sea_query_value_to_value(
 		sea_orm::Value::Array(_array_value, value) => value.map(|it| {
 			Value::List(
 				it.into_iter()
-					.map(|item| sea_query_value_to_value(item, is_enum).unwrap_or(Value::null()))
+					.map(|item| sea_query_value_to_value(item, is_enum).unwrap_or(Value::Null))
 					.collect(),
 			)
 		}),
@@ -294,3 +384,43 @@ fn sea_query_value_to_value(
 		_ => panic!("Cannot convert SeaORM value"),
 	}
 }
+
+#[cfg(all(test, feature = "with-json"))]
+mod tests {
+	use super::*;
+	use sea_orm::{JsonValue, sea_query::Value};
+
+	fn json(document: &str) -> Value {
+		Value::Json(Some(Box::new(document.parse::<JsonValue>().unwrap())))
+	}
+
+	fn extract(document: &str, path: &[&str]) -> Value {
+		let path: Vec<String> = path.iter().map(|segment| segment.to_string()).collect();
+
+		extract_json_path(json(document), &path)
+	}
+
+	/// Should follow object keys and array indexes.
+	#[test]
+	fn json_path_extraction() {
+		let document =
+			r#"{"cast":[{"name":"Luke"},{"name":"Leia"}],"rating":{"imdb":8.6},"0":"key"}"#;
+
+		assert_eq!(extract(document, &[]), json(document));
+		assert_eq!(extract(document, &["cast", "1", "name"]), json(r#""Leia""#));
+		assert_eq!(extract(document, &["rating"]), json(r#"{"imdb":8.6}"#));
+		// numeric segments are keys on objects
+		assert_eq!(extract(document, &["0"]), json(r#""key""#));
+	}
+
+	/// Should be null when the path does not exist.
+	#[test]
+	fn json_path_missing() {
+		let document = r#"{"cast":[{"name":"Luke"}]}"#;
+
+		assert_eq!(extract(document, &["cast", "1", "name"]), Value::Json(None));
+		assert_eq!(extract(document, &["cast", "first"]), Value::Json(None));
+		assert_eq!(extract(document, &["rating", "imdb"]), Value::Json(None));
+		assert_eq!(extract_json_path(Value::Json(None), &["cast".into()]), Value::Json(None));
+	}
+}
@@ -0,0 +1,409 @@
+use crate::{
+	Aggregate, AggregateObjectBuilder, BuilderContext, EntityObjectBuilder, FilterInputBuilder,
+	FilterTypeTrait, GuardAction, apply_policy, apply_soft_delete, check_field_guard,
+	get_filter_conditions, is_numeric_column_type, soft_delete_argument, soft_delete_column,
+};
+use dynamic::prelude::*;
+use heck::{ToLowerCamelCase, ToUpperCamelCase};
+use sea_orm::{
+	ColumnTrait, ColumnType, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait,
+	FromQueryResult, Iterable, JsonValue, QueryFilter, QuerySelect, QueryTrait, Select,
+	sea_query::{Alias, Expr, Func, SimpleExpr},
+};
+
+/// The configuration structure for EntityAggregateQueryFieldBuilder
+pub struct EntityAggregateQueryFieldConfig {
+	/// used to format entity aggregate field name
+	pub type_name: crate::SimpleNamingFn,
+	/// name for 'filters' field
+	pub filters: String,
+	/// name for 'groupBy' field
+	pub group_by: String,
+}
+
+impl std::default::Default for EntityAggregateQueryFieldConfig {
+	fn default() -> Self {
+		EntityAggregateQueryFieldConfig {
+			type_name: Box::new(|object_name: &str| -> String {
+				format!("{}Aggregate", object_name.to_lower_camel_case())
+			}),
+			filters: "filters".into(),
+			group_by: "groupBy".into(),
+		}
+	}
+}
+
+/// This builder produces a field for the Query object that aggregates a SeaORM entity
+pub struct EntityAggregateQueryFieldBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl EntityAggregateQueryFieldBuilder {
+	/// used to get aggregate field name for a SeaORM entity
+	pub fn type_name<T>(&self) -> String
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_object = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name = entity_object.type_name::<T>();
+		self.context.entity_aggregate_query_field.type_name.as_ref()(&object_name)
+	}
+
+	/// used to get the Query message aggregate field for a SeaORM entity
+	pub fn to_field<T, Ty, F>(&self) -> Field<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+		F: FilterTypeTrait,
+	{
+		let aggregate_object_builder = AggregateObjectBuilder {
+			context: self.context,
+		};
+		let filter_input_builder = FilterInputBuilder {
+			context: self.context,
+		};
+		let entity_object = EntityObjectBuilder {
+			context: self.context,
+		};
+
+		let object_name = entity_object.type_name::<T>();
+		let type_name = aggregate_object_builder.type_name(&object_name);
+		let field_type = Ty::named_nn_list_nn(&type_name);
+
+		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<T>(self.context);
+
+		let context: &'static BuilderContext = self.context;
+		let field = Field::output(self.type_name::<T>(), field_type, move |ctx| {
+			let context: &'static BuilderContext = context;
+			FieldFuture::new(async move {
+				let guard_flag = if let Some(guard) = guard {
+					(*guard)(&ctx)
+				} else {
+					GuardAction::Allow
+				};
+
+				if let GuardAction::Block(reason) = guard_flag {
+					return match reason {
+						Some(reason) => {
+							Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(reason))
+						}
+						None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+							"Entity guard triggered.".into(),
+						)),
+					};
+				}
+
+				let db = ctx.data::<DatabaseConnection>()?;
+
+				let filters = ctx.args.get(&context.entity_aggregate_query_field.filters);
+				let filters =
+					get_filter_conditions::<T, F>(context, db.get_database_backend(), filters)?;
+				let filters = apply_policy(policy, &ctx, filters);
+				let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
+
+				let group_by = ctx.args.get(&context.entity_aggregate_query_field.group_by);
+				let group_by = parse_group_by::<T>(context, group_by)?;
+				check_group_by::<T>(context, &ctx, &group_by)?;
+
+				let numeric_columns = numeric_columns::<T>(context, &ctx);
+
+				let aggregates = aggregate::<T>(db, filters, group_by, numeric_columns).await?;
+
+				Ok(Some(FieldValue::list(aggregates.into_iter().map(FieldValue::owned_any))))
+			})
+		})
+		.argument(Field::input(
+			&self.context.entity_aggregate_query_field.filters,
+			Ty::named(filter_input_builder.type_name(&object_name)),
+		))
+		.argument(Field::input(
+			&self.context.entity_aggregate_query_field.group_by,
+			Ty::named_nn_list(Ty::STRING),
+		));
+
+		soft_delete_argument(context, soft_delete, field)
+	}
+}
+
+/// used to map the requested groupBy field names to the entity columns
+fn parse_group_by<T>(
+	context: &'static BuilderContext,
+	value: Option<ValueAccessor>,
+) -> SeaResult<Vec<(String, T::Column)>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+
+	let value = match value {
+		Some(value) => value,
+		None => return Ok(Vec::new()),
+	};
+
+	let list = value.list()?;
+
+	list.to_iter()
+		.map(|item| {
+			let name = item.string()?;
+
+			T::Column::iter()
+				.find(|column| entity_object_builder.column_name::<T>(column).eq(name))
+				.map(|column| (name.to_string(), column))
+				.ok_or_else(|| SeaographyError::new(format!("Unknown groupBy field '{name}'")))
+		})
+		.collect()
+}
+
+/// used to reject grouping by a field whose field guard blocks the request
+fn check_group_by<T>(
+	context: &'static BuilderContext,
+	ctx: &ResolverContext,
+	group_by: &[(String, T::Column)],
+) -> SeaResult<()>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let object_name = EntityObjectBuilder {
+		context,
+	}
+	.type_name::<T>();
+
+	group_by.iter().try_for_each(|(name, _)| {
+		check_field_guard(context.guards.field_guards.get(&format!("{object_name}.{name}")), ctx)
+	})
+}
+
+/// used to get the numeric columns that are aggregated, the columns whose field guard blocks
+/// the request are left out like the list query leaves them out of its results
+fn numeric_columns<T>(
+	context: &'static BuilderContext,
+	ctx: &ResolverContext,
+) -> Vec<(String, T::Column)>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+	let object_name = entity_object_builder.type_name::<T>();
+
+	T::Column::iter()
+		.filter(|column| is_numeric_column_type(column.def().get_column_type()))
+		.map(|column| (entity_object_builder.column_name::<T>(&column), column))
+		.filter(|(name, _)| {
+			let guard = context.guards.field_guards.get(&format!("{object_name}.{name}"));
+			check_field_guard(guard, ctx).is_ok()
+		})
+		.collect()
+}
+
+/// used to run the aggregation query and collect one result per group
+async fn aggregate<T>(
+	db: &DatabaseConnection,
+	filters: sea_orm::Condition,
+	group_by: Vec<(String, T::Column)>,
+	numeric_columns: Vec<(String, T::Column)>,
+) -> SeaResult<Vec<Aggregate>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let backend = db.get_database_backend();
+	let stmt = aggregate_query::<T>(backend, filters, &group_by, &numeric_columns);
+	let stmt = backend.build(&stmt.into_query());
+
+	db.query_all(stmt)
+		.await?
+		.into_iter()
+		.map(|row| {
+			// grouped columns keep their declared types, so the json decoding of sea-orm
+			// can be used, while the computed columns are read with explicit types
+			let json = JsonValue::from_query_result(&row, "")?;
+
+			let count = match row.try_get::<i64>("", "count") {
+				Ok(count) => count,
+				Err(_) => row.try_get::<i32>("", "count")? as i64,
+			};
+
+			let mut aggregate = Aggregate {
+				count,
+				..Default::default()
+			};
+
+			for (index, (name, column)) in group_by.iter().enumerate() {
+				let value = json
+					.get(format!("group_{index}"))
+					.and_then(|value| json_to_value(value, column.def().get_column_type()));
+
+				if let Some(value) = value {
+					aggregate.group.0.insert(name.clone(), value);
+				}
+			}
+
+			for (index, (name, _)) in numeric_columns.iter().enumerate() {
+				let get = |prefix: &str| {
+					row.try_get::<Option<f64>>("", &format!("{prefix}_{index}")).ok().flatten()
+				};
+
+				aggregate.sum.0.insert(name.clone(), get("sum"));
+				aggregate.avg.0.insert(name.clone(), get("avg"));
+				aggregate.min.0.insert(name.clone(), get("min"));
+				aggregate.max.0.insert(name.clone(), get("max"));
+			}
+
+			Ok(aggregate)
+		})
+		.collect()
+}
+
+/// used to build the aggregation query, the count is selected as `count`, the grouped columns
+/// as `group_{index}` and the aggregations of the numeric columns as `{sum|avg|min|max}_{index}`
+fn aggregate_query<T>(
+	backend: DatabaseBackend,
+	filters: sea_orm::Condition,
+	group_by: &[(String, T::Column)],
+	numeric_columns: &[(String, T::Column)],
+) -> Select<T>
+where
+	T: EntityTrait,
+{
+	let double_type = match backend {
+		DatabaseBackend::Postgres => "DOUBLE PRECISION",
+		DatabaseBackend::MySql => "DOUBLE",
+		DatabaseBackend::Sqlite => "REAL",
+	};
+
+	let mut stmt = T::find().filter(filters).select_only().expr_as(Expr::cust("COUNT(*)"), "count");
+
+	for (index, (_, column)) in group_by.iter().enumerate() {
+		stmt = stmt
+			.expr_as(column.select_as(Expr::col(*column)), format!("group_{index}"))
+			.group_by(*column);
+	}
+
+	for (index, (_, column)) in numeric_columns.iter().enumerate() {
+		let aggregations: [(&str, SimpleExpr); 4] = [
+			("sum", Func::sum(Expr::col(*column)).into()),
+			("avg", Func::avg(Expr::col(*column)).into()),
+			("min", Func::min(Expr::col(*column)).into()),
+			("max", Func::max(Expr::col(*column)).into()),
+		];
+
+		for (prefix, expr) in aggregations {
+			stmt = stmt
+				.expr_as(Func::cast_as(expr, Alias::new(double_type)), format!("{prefix}_{index}"));
+		}
+	}
+
+	stmt
+}
+
+/// used to convert a grouped column from the json row into an output value
+fn json_to_value(value: &JsonValue, column_type: &ColumnType) -> Option<Value> {
+	match value {
+		JsonValue::Null => None,
+		JsonValue::Bool(value) => Some(Value::from(*value)),
+		JsonValue::Number(number) => match column_type {
+			ColumnType::Float
+			| ColumnType::Double
+			| ColumnType::Decimal(_)
+			| ColumnType::Money(_) => number.as_f64().map(Value::from),
+			_ => number.as_i64().map(Value::from).or_else(|| number.as_f64().map(Value::from)),
+		},
+		JsonValue::String(string) => match column_type {
+			ColumnType::Enum {
+				..
+			} => Some(Value::from(string.as_str().to_upper_camel_case().to_ascii_uppercase())),
+			_ => Some(Value::from(string.as_str())),
+		},
+		value => Some(Value::from(value.to_string())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::{film, with_context};
+
+	fn guarded_context() -> &'static BuilderContext {
+		let mut context = BuilderContext::default();
+		for field in ["Film.releaseYear", "Film.version"] {
+			context
+				.guards
+				.field_guards
+				.insert(field.into(), Box::new(|_| GuardAction::Block(None)));
+		}
+		Box::leak(Box::new(context))
+	}
+
+	fn sql(backend: DatabaseBackend) -> String {
+		let group_by = [("releaseYear".to_string(), film::Column::ReleaseYear)];
+		let numeric_columns = [("version".to_string(), film::Column::Version)];
+		let filters = sea_orm::Condition::all().add(film::Column::Title.eq("Alien"));
+
+		let stmt = aggregate_query::<film::Entity>(backend, filters, &group_by, &numeric_columns);
+		backend.build(&stmt.into_query()).to_string()
+	}
+
+	#[test]
+	fn aggregate_sql() {
+		assert_eq!(
+			sql(DatabaseBackend::Sqlite),
+			concat!(
+				r#"SELECT COUNT(*) AS "count", "release_year" AS "group_0", "#,
+				r#"CAST(SUM("version") AS REAL) AS "sum_0", "#,
+				r#"CAST(AVG("version") AS REAL) AS "avg_0", "#,
+				r#"CAST(MIN("version") AS REAL) AS "min_0", "#,
+				r#"CAST(MAX("version") AS REAL) AS "max_0" "#,
+				r#"FROM "film" WHERE "film"."title" = 'Alien' GROUP BY "film"."release_year""#,
+			)
+		);
+		assert_eq!(
+			sql(DatabaseBackend::Postgres),
+			concat!(
+				r#"SELECT COUNT(*) AS "count", "release_year" AS "group_0", "#,
+				r#"CAST(SUM("version") AS DOUBLE PRECISION) AS "sum_0", "#,
+				r#"CAST(AVG("version") AS DOUBLE PRECISION) AS "avg_0", "#,
+				r#"CAST(MIN("version") AS DOUBLE PRECISION) AS "min_0", "#,
+				r#"CAST(MAX("version") AS DOUBLE PRECISION) AS "max_0" "#,
+				r#"FROM "film" WHERE "film"."title" = 'Alien' GROUP BY "film"."release_year""#,
+			)
+		);
+	}
+
+	/// Should forbid grouping by guarded fields and leave them out of the aggregations.
+	#[test]
+	fn field_guards() {
+		let context = guarded_context();
+		with_context(|ctx| {
+			let group_by = [("releaseYear".to_string(), film::Column::ReleaseYear)];
+			assert!(matches!(
+				check_group_by::<film::Entity>(context, ctx, &group_by),
+				Err(SeaographyError::Forbidden(_))
+			));
+
+			let group_by = [("title".to_string(), film::Column::Title)];
+			assert!(check_group_by::<film::Entity>(context, ctx, &group_by).is_ok());
+
+			let names = |context| {
+				numeric_columns::<film::Entity>(context, ctx)
+					.into_iter()
+					.map(|(name, _)| name)
+					.collect::<Vec<_>>()
+			};
+			assert_eq!(names(Box::leak(Box::default())), vec!["id", "releaseYear", "version"]);
+			assert_eq!(names(context), vec!["id"]);
+		});
+	}
+}
@@ -1,12 +1,17 @@
 use crate::{
-	BuilderContext, Connection, ConnectionObjectBuilder, EntityObjectBuilder, FilterInputBuilder,
-	FilterTypeTrait, GuardAction, HashableGroupKey, KeyComplex, OneToManyLoader, OneToOneLoader,
-	OrderInputBuilder, PaginationInputBuilder, apply_memory_pagination, get_filter_conditions,
+	BuilderContext, Connection, ConnectionObjectBuilder, EntityInputBuilder, EntityObjectBuilder,
+	FilterInputBuilder, FilterTypeTrait, GuardAction, HashableGroupKey, KeyComplex,
+	NestedInsertRelation, OneToManyLoader, OneToOneLoader, OrderInputBuilder,
+	PaginationInputBuilder, apply_memory_pagination, apply_policy, apply_soft_delete, cursor_order,
+	get_filter_conditions, insert_nested_related, soft_delete_argument, soft_delete_column,
 };
 use dataloader::BatchFn;
 use dynamic::prelude::*;
 use heck::ToSnakeCase;
-use sea_orm::{EntityTrait, Iden, ModelTrait, RelationDef};
+use sea_orm::{
+	ConnectionTrait, DatabaseConnection, EntityTrait, Iden, IntoActiveModel, ModelTrait,
+	RelationDef, RelationType,
+};
 
 /// This builder produces a GraphQL field for an SeaORM entity relationship
 /// that can be added to the entity object
@@ -47,6 +52,8 @@ impl EntityObjectRelationBuilder {
 
 		let object_name: String = entity_object_builder.type_name::<R>();
 		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<R>(context);
 
 		let from_col = <T::Column as std::str::FromStr>::from_str(
 			relation_definition.from_col.to_string().to_snake_case().as_str(),
@@ -69,11 +76,11 @@ impl EntityObjectRelationBuilder {
 
 					if let GuardAction::Block(reason) = guard_flag {
 						return match reason {
-							Some(reason) => {
-								Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-								"Entity guard triggered.",
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
 							)),
 						};
 					}
@@ -87,12 +94,15 @@ impl EntityObjectRelationBuilder {
 
 					let stmt = R::find();
 					let filters = ctx.args.get(&context.entity_query_field.filters);
-					let filters = get_filter_conditions::<R, F>(context, filters)?;
+					let backend = ctx.data::<DatabaseConnection>()?.get_database_backend();
+					let filters = get_filter_conditions::<R, F>(context, backend, filters)?;
+					let filters = apply_policy(policy, &ctx, filters);
+					let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
 					let order_by = ctx.args.get(&context.entity_query_field.order_by);
 					let order_by = OrderInputBuilder {
 						context,
 					}
-					.parse_object::<R>(order_by)?;
+					.parse_unranked_object::<R>(order_by)?;
 					let key = KeyComplex::<R> {
 						key: vec![parent.get(from_col)],
 						meta: HashableGroupKey::<R> {
@@ -128,12 +138,12 @@ impl EntityObjectRelationBuilder {
 
 						if let GuardAction::Block(reason) = guard_flag {
 							return match reason {
-								Some(reason) => {
-									Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-								}
-								None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-									"Entity guard triggered.",
-								)),
+								Some(reason) => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden(reason),
+								),
+								None => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden("Entity guard triggered.".into()),
+								),
 							};
 						}
 
@@ -146,19 +156,23 @@ impl EntityObjectRelationBuilder {
 
 						let stmt = R::find();
 						let filters = ctx.args.get(&context.entity_query_field.filters);
-						let filters = get_filter_conditions::<R, F>(context, filters)?;
+						let backend = ctx.data::<DatabaseConnection>()?.get_database_backend();
+						let filters = get_filter_conditions::<R, F>(context, backend, filters)?;
+						let filters = apply_policy(policy, &ctx, filters);
+						let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
 						let order_by = ctx.args.get(&context.entity_query_field.order_by);
 						let order_by = OrderInputBuilder {
 							context,
 						}
-						.parse_object::<R>(order_by)?;
+						.parse_unranked_object::<R>(order_by)?;
+						let order_by = cursor_order::<R>(order_by);
 						let key = KeyComplex::<R> {
 							key: vec![parent.get(from_col)],
 							meta: HashableGroupKey::<R> {
 								stmt,
 								columns: vec![to_col],
 								filters: Some(filters),
-								order_by,
+								order_by: order_by.clone(),
 							},
 						};
 
@@ -173,7 +187,7 @@ impl EntityObjectRelationBuilder {
 						.parse_object(pagination)?;
 
 						let connection: Connection<R> =
-							apply_memory_pagination(values.remove(&keys[0]), pagination)?;
+							apply_memory_pagination(values.remove(&keys[0]), order_by, pagination)?;
 
 						Ok(Some(FieldValue::owned_any(connection)))
 					})
@@ -181,6 +195,8 @@ impl EntityObjectRelationBuilder {
 			),
 		};
 
+		let field = soft_delete_argument(context, soft_delete, field);
+
 		Ok(match relation_definition.is_owner {
 			false => field,
 			true => field
@@ -195,7 +211,57 @@ impl EntityObjectRelationBuilder {
 				.argument(Field::input(
 					&context.entity_query_field.pagination,
 					Ty::named(&context.pagination_input.type_name),
-				)),
+				))
+				.compute_complexity(move |args, child_complexity| {
+					PaginationInputBuilder {
+						context,
+					}
+					.complexity(args.get(&context.entity_query_field.pagination), child_complexity)
+				}),
+		})
+	}
+
+	/// used to describe an SeaORM entity relationship for nested inserts
+	pub fn get_nested_insert<T, R>(
+		&self,
+		name: &str,
+		relation_definition: RelationDef,
+	) -> SeaResult<NestedInsertRelation>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		<<T as sea_orm::EntityTrait>::Column as std::str::FromStr>::Err: core::fmt::Debug,
+		R: EntityTrait,
+		<R as sea_orm::EntityTrait>::Model: Sync,
+		<R as sea_orm::EntityTrait>::Model: IntoActiveModel<R::ActiveModel>,
+		<R as sea_orm::EntityTrait>::ActiveModel: std::marker::Send,
+		<<R as sea_orm::EntityTrait>::Column as std::str::FromStr>::Err: core::fmt::Debug,
+	{
+		let entity_object_builder = EntityObjectBuilder {
+			context: self.context,
+		};
+		let entity_input_builder = EntityInputBuilder {
+			context: self.context,
+		};
+
+		let from_col = <T::Column as std::str::FromStr>::from_str(
+			relation_definition.from_col.to_string().to_snake_case().as_str(),
+		)
+		.map_err(|err| SeaographyError::new(format!("{:#?}", err)))?;
+
+		let to_col = <R::Column as std::str::FromStr>::from_str(
+			relation_definition.to_col.to_string().to_snake_case().as_str(),
+		)
+		.map_err(|err| SeaographyError::new(format!("{:#?}", err)))?;
+
+		Ok(NestedInsertRelation {
+			name: name.into(),
+			input_type_name: entity_input_builder.insert_type_name::<R>(),
+			is_owner: relation_definition.is_owner,
+			is_list: matches!(relation_definition.rel_type, RelationType::HasMany),
+			from_column: entity_object_builder.column_name::<T>(&from_col),
+			to_column: entity_object_builder.column_name::<R>(&to_col),
+			insert: insert_nested_related::<R>,
 		})
 	}
 }
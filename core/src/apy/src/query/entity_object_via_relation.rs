@@ -1,14 +1,17 @@
 use crate::{
-	BuilderContext, ConnectionObjectBuilder, EntityObjectBuilder, FilterInputBuilder,
-	FilterTypeTrait, GuardAction, HashableGroupKey, KeyComplex, OneToManyLoader, OneToOneLoader,
-	OrderInputBuilder, PaginationInputBuilder, apply_memory_pagination, apply_order,
-	apply_pagination, get_filter_conditions,
+	BuilderContext, ConnectionObjectBuilder, EntityObjectBuilder, EntityObjectRelationBuilder,
+	FilterInputBuilder, FilterTypeTrait, GuardAction, HashableGroupKey, KeyComplex,
+	NestedInsertRelation, OneToManyLoader, OneToOneLoader, OrderInputBuilder,
+	PaginationInputBuilder, apply_memory_pagination, apply_pagination, apply_policy,
+	apply_soft_delete, cursor_order, get_filter_conditions, soft_delete_argument,
+	soft_delete_column,
 };
 use dataloader::BatchFn;
 use dynamic::prelude::*;
 use heck::ToSnakeCase;
 use sea_orm::{
-	ColumnTrait, Condition, DatabaseConnection, EntityTrait, Iden, ModelTrait, QueryFilter, Related,
+	ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, Iden,
+	IntoActiveModel, ModelTrait, QueryFilter, Related,
 };
 
 /// This builder produces a GraphQL field for an SeaORM entity related trait
@@ -53,6 +56,8 @@ impl EntityObjectViaRelationBuilder {
 
 		let object_name: String = entity_object_builder.type_name::<R>();
 		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<R>(context);
 
 		let from_col = <T::Column as std::str::FromStr>::from_str(
 			via_relation_definition.from_col.to_string().to_snake_case().as_str(),
@@ -75,11 +80,11 @@ impl EntityObjectViaRelationBuilder {
 
 					if let GuardAction::Block(reason) = guard_flag {
 						return match reason {
-							Some(reason) => {
-								Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-							}
-							None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-								"Entity guard triggered.",
+							Some(reason) => Err::<Option<_>, SeaographyError>(
+								SeaographyError::Forbidden(reason),
+							),
+							None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+								"Entity guard triggered.".into(),
 							)),
 						};
 					}
@@ -98,12 +103,15 @@ impl EntityObjectViaRelationBuilder {
 					};
 
 					let filters = ctx.args.get(&context.entity_query_field.filters);
-					let filters = get_filter_conditions::<R, F>(context, filters)?;
+					let backend = ctx.data::<DatabaseConnection>()?.get_database_backend();
+					let filters = get_filter_conditions::<R, F>(context, backend, filters)?;
+					let filters = apply_policy(policy, &ctx, filters);
+					let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
 					let order_by = ctx.args.get(&context.entity_query_field.order_by);
 					let order_by = OrderInputBuilder {
 						context,
 					}
-					.parse_object::<R>(order_by)?;
+					.parse_unranked_object::<R>(order_by)?;
 					let key = KeyComplex::<R> {
 						key: vec![parent.get(from_col)],
 						meta: HashableGroupKey::<R> {
@@ -139,12 +147,12 @@ impl EntityObjectViaRelationBuilder {
 
 						if let GuardAction::Block(reason) = guard_flag {
 							return match reason {
-								Some(reason) => {
-									Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
-								}
-								None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-									"Entity guard triggered.",
-								)),
+								Some(reason) => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden(reason),
+								),
+								None => Err::<Option<_>, SeaographyError>(
+									SeaographyError::Forbidden("Entity guard triggered.".into()),
+								),
 							};
 						}
 
@@ -155,13 +163,21 @@ impl EntityObjectViaRelationBuilder {
 						};
 
 						let filters = ctx.args.get(&context.entity_query_field.filters);
-						let filters = get_filter_conditions::<R, F>(context, filters)?;
+						let db = ctx.data::<DatabaseConnection>()?;
+						let filters = get_filter_conditions::<R, F>(
+							context,
+							db.get_database_backend(),
+							filters,
+						)?;
+						let filters = apply_policy(policy, &ctx, filters);
+						let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
 
 						let order_by = ctx.args.get(&context.entity_query_field.order_by);
 						let order_by = OrderInputBuilder {
 							context,
 						}
-						.parse_object::<R>(order_by)?;
+						.parse_unranked_object::<R>(order_by)?;
+						let order_by = cursor_order::<R>(order_by);
 
 						let pagination = ctx.args.get(&context.entity_query_field.pagination);
 						let pagination = PaginationInputBuilder {
@@ -169,8 +185,6 @@ impl EntityObjectViaRelationBuilder {
 						}
 						.parse_object(pagination)?;
 
-						let db = ctx.data::<DatabaseConnection>()?;
-
 						let connection = if is_via_relation {
 							// FIXME: optimize union queries
 							// NOTE: each has unique query in order to apply pagination...
@@ -183,8 +197,7 @@ impl EntityObjectViaRelationBuilder {
 							let condition = Condition::all().add(from_col.eq(parent.get(from_col)));
 
 							let stmt = stmt.filter(condition.add(filters));
-							let stmt = apply_order(stmt, order_by);
-							apply_pagination::<R>(db, stmt, pagination).await?
+							apply_pagination::<R>(db, stmt, order_by, pagination).await?
 						} else {
 							let loader = ctx.data_unchecked::<OneToManyLoader<R>>();
 
@@ -201,7 +214,7 @@ impl EntityObjectViaRelationBuilder {
 									stmt,
 									columns: vec![to_col],
 									filters: Some(filters),
-									order_by,
+									order_by: order_by.clone(),
 								},
 							};
 
@@ -209,7 +222,7 @@ impl EntityObjectViaRelationBuilder {
 
 							let mut values = loader.clone().load(&keys).await;
 
-							apply_memory_pagination(values.remove(&keys[0]), pagination)?
+							apply_memory_pagination(values.remove(&keys[0]), order_by, pagination)?
 						};
 
 						Ok(Some(FieldValue::owned_any(connection)))
@@ -218,6 +231,8 @@ impl EntityObjectViaRelationBuilder {
 			),
 		};
 
+		let field = soft_delete_argument(context, soft_delete, field);
+
 		Ok(match via_relation_definition.is_owner {
 			false => field,
 			true => field
@@ -232,7 +247,38 @@ impl EntityObjectViaRelationBuilder {
 				.argument(Field::input(
 					&context.entity_query_field.pagination,
 					Ty::named(&context.pagination_input.type_name),
-				)),
+				))
+				.compute_complexity(move |args, child_complexity| {
+					PaginationInputBuilder {
+						context,
+					}
+					.complexity(args.get(&context.entity_query_field.pagination), child_complexity)
+				}),
 		})
 	}
+
+	/// used to describe an SeaORM entity related trait for nested inserts,
+	/// relations through a junction entity are skipped
+	pub fn get_nested_insert<T, R>(&self, name: &str) -> SeaResult<Option<NestedInsertRelation>>
+	where
+		T: Related<R>,
+		T: EntityTrait,
+		R: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		<R as sea_orm::EntityTrait>::Model: Sync,
+		<R as sea_orm::EntityTrait>::Model: IntoActiveModel<R::ActiveModel>,
+		<R as sea_orm::EntityTrait>::ActiveModel: std::marker::Send,
+		<<T as sea_orm::EntityTrait>::Column as std::str::FromStr>::Err: core::fmt::Debug,
+		<<R as sea_orm::EntityTrait>::Column as std::str::FromStr>::Err: core::fmt::Debug,
+	{
+		if <T as Related<R>>::via().is_some() {
+			return Ok(None);
+		}
+
+		let relation_builder = EntityObjectRelationBuilder {
+			context: self.context,
+		};
+
+		relation_builder.get_nested_insert::<T, R>(name, <T as Related<R>>::to()).map(Some)
+	}
 }
@@ -1,11 +1,12 @@
 use crate::{
 	BuilderContext, ConnectionObjectBuilder, EntityObjectBuilder, FilterInputBuilder,
-	FilterTypeTrait, GuardAction, OrderInputBuilder, PaginationInputBuilder, apply_order,
-	apply_pagination, get_filter_conditions,
+	FilterTypeTrait, GuardAction, OrderInputBuilder, PaginationInputBuilder, apply_pagination,
+	apply_policy, apply_read_mask, apply_soft_delete, field_mask_argument, field_mask_paths,
+	get_filter_conditions, search_rank, soft_delete_argument, soft_delete_column,
 };
 use dynamic::prelude::*;
 use heck::ToLowerCamelCase;
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
 
 /// The configuration structure for EntityQueryFieldBuilder
 pub struct EntityQueryFieldConfig {
@@ -79,9 +80,11 @@ impl EntityQueryFieldBuilder {
 		let type_name = connection_object_builder.type_name(&object_name);
 
 		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<T>(self.context);
 
 		let context: &'static BuilderContext = self.context;
-		Field::output(self.type_name::<T>(), Ty::named_nn(&type_name), move |ctx| {
+		let field = Field::output(self.type_name::<T>(), Ty::named_nn(&type_name), move |ctx| {
 			let context: &'static BuilderContext = context;
 			FieldFuture::new(async move {
 				let guard_flag = if let Some(guard) = guard {
@@ -93,21 +96,27 @@ impl EntityQueryFieldBuilder {
 				if let GuardAction::Block(reason) = guard_flag {
 					return match reason {
 						Some(reason) => {
-							Err::<Option<_>, SeaographyError>(SeaographyError::new(reason))
+							Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(reason))
 						}
-						None => Err::<Option<_>, SeaographyError>(SeaographyError::new(
-							"Entity guard triggered.",
+						None => Err::<Option<_>, SeaographyError>(SeaographyError::Forbidden(
+							"Entity guard triggered.".into(),
 						)),
 					};
 				}
 
+				let db = ctx.data::<DatabaseConnection>()?;
+				let backend = db.get_database_backend();
+
 				let filters = ctx.args.get(&context.entity_query_field.filters);
-				let filters = get_filter_conditions::<T, F>(context, filters)?;
-				let order_by = ctx.args.get(&context.entity_query_field.order_by);
-				let order_by = OrderInputBuilder {
+				let filters = get_filter_conditions::<T, F>(context, backend, filters)?;
+				let filters = apply_policy(policy, &ctx, filters);
+				let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
+				let order_input_builder = OrderInputBuilder {
 					context,
-				}
-				.parse_object::<T>(order_by)?;
+				};
+				let order_by = ctx.args.get(&context.entity_query_field.order_by);
+				let rank_order = order_input_builder.parse_search_rank::<T>(order_by.clone())?;
+				let order_by = order_input_builder.parse_object::<T>(order_by)?;
 				let pagination = ctx.args.get(&context.entity_query_field.pagination);
 				let pagination = PaginationInputBuilder {
 					context,
@@ -115,12 +124,31 @@ impl EntityQueryFieldBuilder {
 				.parse_object(pagination)?;
 
 				let stmt = T::find();
-				let stmt = stmt.filter(filters);
-				let stmt = apply_order(stmt, order_by);
+				let mut stmt = stmt.filter(filters);
 
-				let db = ctx.data::<DatabaseConnection>()?;
+				// the columns outside the read mask of a grpc request are not read
+				let read_mask = field_mask_paths(&ctx.args, &context.field_masks.read_mask)?;
+				if let Some(paths) = read_mask {
+					stmt = apply_read_mask::<T>(context, stmt, &paths, &order_by)?;
+				}
+
+				// the rank precedes the column ordering, it is not part of the cursors
+				if let Some(rank_order) = rank_order {
+					if pagination.cursor.is_some() {
+						return Err(SeaographyError::new(format!(
+							"{} cannot be combined with cursor pagination.",
+							context.order_input.search_rank
+						)));
+					}
+
+					if let Some(rank) =
+						search_rank::<T>(context, backend, &rank_order.column, &rank_order.query)
+					{
+						stmt = stmt.order_by(rank, rank_order.order);
+					}
+				}
 
-				let connection = apply_pagination::<T>(db, stmt, pagination).await?;
+				let connection = apply_pagination::<T>(db, stmt, order_by, pagination).await?;
 
 				Ok(Some(FieldValue::owned_any(connection)))
 			})
@@ -137,5 +165,15 @@ impl EntityQueryFieldBuilder {
 			&self.context.entity_query_field.pagination,
 			Ty::named(pagination_input_builder.type_name()),
 		))
+		.compute_complexity(move |args, child_complexity| {
+			PaginationInputBuilder {
+				context,
+			}
+			.complexity(args.get(&context.entity_query_field.pagination), child_complexity)
+		});
+
+		let field = field_mask_argument(&context.field_masks.read_mask, field);
+
+		soft_delete_argument(context, soft_delete, field)
 	}
 }
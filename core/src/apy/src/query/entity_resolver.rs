@@ -0,0 +1,294 @@
+use crate::{
+	BuilderContext, EntityObjectBuilder, GuardAction, TypesMapHelper, apply_policy,
+	apply_soft_delete, soft_delete_column,
+};
+use dynamic::prelude::*;
+use futures::future::BoxFuture;
+use sea_orm::{
+	ColumnTrait, Condition, DatabaseConnection, EntityTrait, Iterable, ModelTrait,
+	PrimaryKeyToColumn, QueryFilter,
+	sea_query::{Expr, ValueTuple},
+};
+use std::collections::BTreeMap;
+
+/// name of the argument holding the representations of the `_entities` field
+const REPRESENTATIONS: &str = "representations";
+
+/// used to resolve the entities of a type from their federation representations with a
+/// single query, returns an item per representation in order, `None` when the entity does
+/// not exist
+pub type EntityResolverFn =
+	for<'a, 'b> fn(
+		&'static BuilderContext,
+		&'a ResolverContext<'b>,
+		Vec<ObjectAccessor<'a>>,
+	) -> BoxFuture<'a, SeaResult<Vec<Option<FieldValue<'static>>>>>;
+
+/// holds the entity resolvers of every registered entity, keyed by entity type name
+#[derive(Default)]
+pub struct EntityResolvers(pub BTreeMap<String, EntityResolverFn>);
+
+/// used as EntityResolverFn of an entity, finds the entities by the primary keys of the
+/// representations and applies their guard, policy and soft delete
+pub fn resolve_entity<'a, 'b, T>(
+	context: &'static BuilderContext,
+	ctx: &'a ResolverContext<'b>,
+	representations: Vec<ObjectAccessor<'a>>,
+) -> BoxFuture<'a, SeaResult<Vec<Option<FieldValue<'static>>>>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	Box::pin(async move {
+		let object_name = EntityObjectBuilder {
+			context,
+		}
+		.type_name::<T>();
+
+		let models = find_entities::<T>(context, ctx, &representations).await?;
+
+		Ok(models
+			.into_iter()
+			.map(|model| {
+				model.map(|model| FieldValue::owned_any(model).with_type(object_name.clone()))
+			})
+			.collect())
+	})
+}
+
+/// used to find the entities of the representations with one `IN` query on their primary
+/// keys, the models are matched back to the representations by their primary key
+async fn find_entities<T>(
+	context: &'static BuilderContext,
+	ctx: &ResolverContext<'_>,
+	representations: &[ObjectAccessor<'_>],
+) -> SeaResult<Vec<Option<T::Model>>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+	let types_map_helper = TypesMapHelper {
+		context,
+	};
+	let object_name = entity_object_builder.type_name::<T>();
+
+	let guard_flag = match context.guards.entity_guards.get(&object_name) {
+		Some(guard) => (*guard)(ctx),
+		None => GuardAction::Allow,
+	};
+
+	if let GuardAction::Block(reason) = guard_flag {
+		return Err(SeaographyError::Forbidden(
+			reason.unwrap_or_else(|| "Entity guard triggered.".into()),
+		));
+	}
+
+	let columns: Vec<T::Column> = T::PrimaryKey::iter().map(|key| key.into_column()).collect();
+
+	let keys = representations
+		.iter()
+		.map(|representation| {
+			columns
+				.iter()
+				.map(|column| {
+					let value =
+						representation.try_get(entity_object_builder.column_name::<T>(column))?;
+					types_map_helper.value_to_sea_orm_value::<T>(column, &value)
+				})
+				.collect::<SeaResult<Vec<_>>>()
+		})
+		.collect::<SeaResult<Vec<_>>>()?;
+
+	// composite keys are matched as tuples
+	let condition = match columns.as_slice() {
+		[column] => Condition::all().add(column.is_in(keys.iter().map(|key| key[0].clone()))),
+		_ => Condition::all().add(
+			Expr::tuple(columns.iter().map(|column| Expr::col(*column).into()))
+				.in_tuples(keys.iter().map(|key| ValueTuple::Many(key.clone()))),
+		),
+	};
+
+	let policy = context.policies.entity_policies.get(&object_name);
+	let condition = apply_policy(policy, ctx, condition);
+	let condition =
+		apply_soft_delete(context, soft_delete_column::<T>(context), &ctx.args, condition)?;
+
+	let db = ctx.data::<DatabaseConnection>()?;
+	let models = T::find().filter(condition).all(db).await?;
+
+	Ok(keys
+		.iter()
+		.map(|key| {
+			models
+				.iter()
+				.find(|model| {
+					columns.iter().zip(key).all(|(column, value)| &model.get(*column) == value)
+				})
+				.cloned()
+		})
+		.collect())
+}
+
+/// used to get the entity resolver of the schema, the representations are grouped by their
+/// `__typename` and every group is resolved at once by the resolver of its entity, the
+/// entities are returned in the order of the representations
+pub fn entity_resolver(
+	context: &'static BuilderContext,
+) -> impl for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync + 'static {
+	move |ctx| {
+		FieldFuture::new(async move {
+			let resolvers = ctx.data::<EntityResolvers>()?;
+
+			let representations = ctx.args.try_get(REPRESENTATIONS)?.list()?;
+			let mut groups = BTreeMap::<String, Vec<(usize, ObjectAccessor)>>::new();
+			for index in 0..representations.len() {
+				let representation = representations.try_get(index)?.object()?;
+				let type_name = representation.try_get("__typename")?.string()?.to_string();
+
+				groups.entry(type_name).or_default().push((index, representation));
+			}
+
+			let mut entities: Vec<FieldValue> =
+				(0..representations.len()).map(|_| FieldValue::NULL).collect();
+			for (type_name, group) in groups {
+				let resolver = resolvers.0.get(&type_name).ok_or_else(|| {
+					SeaographyError::new(format!("Entity `{}` is not resolvable.", type_name))
+				})?;
+
+				let (indexes, group): (Vec<_>, Vec<_>) = group.into_iter().unzip();
+				let resolved = resolver(context, &ctx, group).await?;
+				for (index, entity) in indexes.into_iter().zip(resolved) {
+					if let Some(entity) = entity {
+						entities[index] = entity;
+					}
+				}
+			}
+
+			Ok(Some(FieldValue::list(entities)))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::{author, post};
+	use sea_orm::{ActiveModelTrait, ConnectionTrait, Database, DbBackend, Schema, Set};
+	use std::sync::Arc;
+
+	async fn database() -> DatabaseConnection {
+		let db = Database::connect("sqlite::memory:").await.unwrap();
+		let schema = Schema::new(DbBackend::Sqlite);
+		for stmt in [
+			schema.create_table_from_entity(author::Entity),
+			schema.create_table_from_entity(post::Entity),
+		] {
+			db.execute(db.get_database_backend().build(&stmt)).await.unwrap();
+		}
+
+		for (id, name) in [(1, "Ann"), (2, "Bob")] {
+			let author = author::ActiveModel {
+				id: Set(id),
+				name: Set(name.into()),
+			};
+			author.insert(&db).await.unwrap();
+		}
+		let post = post::ActiveModel {
+			id: Set(1),
+			author_id: Set(1),
+			title: Set("First".into()),
+		};
+		post.insert(&db).await.unwrap();
+
+		db
+	}
+
+	fn representation(type_name: &str, id: i64) -> Value {
+		Value::Map(
+			[
+				(Value::from("__typename"), Value::from(type_name)),
+				(Value::from("id"), Value::from(id)),
+			]
+			.into_iter()
+			.collect(),
+		)
+	}
+
+	async fn base() -> ContextBase {
+		let mut resolvers = EntityResolvers::default();
+		resolvers.0.insert("Author".into(), resolve_entity::<author::Entity>);
+		resolvers.0.insert("Post".into(), resolve_entity::<post::Entity>);
+
+		let mut data = Data::default();
+		data.insert(database().await);
+		data.insert(resolvers);
+
+		ContextBase {
+			r#type: ApiType::GraphQL,
+			execute_data: Some(Arc::new(data)),
+		}
+	}
+
+	/// Should find the entities of every representation in order.
+	#[tokio::test]
+	async fn find() {
+		let context: &'static BuilderContext = Box::leak(Box::default());
+		let base = base().await;
+		let ctx = ResolverContext {
+			ctx: &base,
+			args: ObjectAccessor::default(),
+			parent_value: &FieldValue::NULL,
+		};
+
+		let representations: Vec<ObjectAccessor> = [2i64, 1, 3, 2]
+			.into_iter()
+			.map(|id| {
+				let mut representation = ObjectAccessor::default();
+				representation.insert(Value::from("__typename"), Value::from("Author"));
+				representation.insert(Value::from("id"), Value::from(id));
+				representation
+			})
+			.collect();
+
+		let models = find_entities::<author::Entity>(context, &ctx, &representations).await;
+		let names: Vec<Option<String>> =
+			models.unwrap().into_iter().map(|model| model.map(|model| model.name)).collect();
+
+		assert_eq!(names, [Some("Bob".into()), Some("Ann".into()), None, Some("Bob".into())]);
+	}
+
+	/// Should resolve the representations of every type and keep their order.
+	#[tokio::test]
+	async fn resolve() {
+		let context: &'static BuilderContext = Box::leak(Box::default());
+		let base = base().await;
+
+		let mut args = ObjectAccessor::default();
+		args.insert(
+			Value::from(REPRESENTATIONS),
+			Value::List(vec![
+				representation("Post", 1),
+				representation("Author", 2),
+				representation("Post", 9),
+				representation("Author", 1),
+			]),
+		);
+		let ctx = ResolverContext {
+			ctx: &base,
+			args,
+			parent_value: &FieldValue::NULL,
+		};
+
+		let entities = match entity_resolver(context)(ctx) {
+			FieldFuture::Future(future) => future.await.unwrap().unwrap(),
+			FieldFuture::Value(value) => value.unwrap(),
+		};
+		let types: Vec<String> =
+			entities.try_to_list().unwrap().iter().map(|entity| format!("{:?}", entity)).collect();
+
+		assert_eq!(types, ["Post", "Author", "null", "Author"]);
+	}
+}
@@ -0,0 +1,141 @@
+use crate::{
+	BuilderContext, EntityObjectBuilder, FilterInputBuilder, FilterTypeTrait, GuardAction,
+	OrderInputBuilder, apply_order, apply_policy, apply_read_mask, apply_soft_delete,
+	field_mask_argument, field_mask_paths, get_filter_conditions, search_rank,
+	soft_delete_argument, soft_delete_column,
+};
+use dynamic::prelude::*;
+use futures::TryStreamExt;
+use heck::ToLowerCamelCase;
+use sea_orm::{ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+/// The configuration structure for EntityStreamQueryFieldBuilder
+pub struct EntityStreamQueryFieldConfig {
+	/// used to format entity stream field name
+	pub type_name: crate::SimpleNamingFn,
+}
+
+impl std::default::Default for EntityStreamQueryFieldConfig {
+	fn default() -> Self {
+		EntityStreamQueryFieldConfig {
+			type_name: Box::new(|object_name: &str| -> String {
+				format!("{}Stream", object_name.to_lower_camel_case())
+			}),
+		}
+	}
+}
+
+/// This builder produces a server streaming field for the Query message that streams the rows
+/// of a SeaORM entity, it is only served over grpc
+pub struct EntityStreamQueryFieldBuilder {
+	pub context: &'static BuilderContext,
+}
+
+impl EntityStreamQueryFieldBuilder {
+	/// used to get field name for a SeaORM entity
+	pub fn type_name<T>(&self) -> String
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+	{
+		let entity_object = EntityObjectBuilder {
+			context: self.context,
+		};
+		let object_name = entity_object.type_name::<T>();
+		self.context.entity_stream_query_field.type_name.as_ref()(&object_name)
+	}
+
+	/// used to get the Query message field streaming a SeaORM entity, every row is sent as
+	/// soon as the database cursor yields it
+	pub fn to_field<T, Ty, F>(&self) -> Field<Ty>
+	where
+		T: EntityTrait,
+		<T as EntityTrait>::Model: Sync,
+		Ty: TypeRefTrait,
+		F: FilterTypeTrait,
+	{
+		let filter_input_builder = FilterInputBuilder {
+			context: self.context,
+		};
+		let order_input_builder = OrderInputBuilder {
+			context: self.context,
+		};
+		let entity_object = EntityObjectBuilder {
+			context: self.context,
+		};
+
+		let object_name = entity_object.type_name::<T>();
+
+		let guard = self.context.guards.entity_guards.get(&object_name);
+		let policy = self.context.policies.entity_policies.get(&object_name);
+		let soft_delete = soft_delete_column::<T>(self.context);
+
+		let context: &'static BuilderContext = self.context;
+		let field = Field::stream(self.type_name::<T>(), Ty::named_nn(&object_name), move |ctx| {
+			let context: &'static BuilderContext = context;
+			SubscriptionFieldFuture::new(async move {
+				let guard_flag = if let Some(guard) = guard {
+					(*guard)(&ctx)
+				} else {
+					GuardAction::Allow
+				};
+
+				if let GuardAction::Block(reason) = guard_flag {
+					return match reason {
+						Some(reason) => Err(SeaographyError::Forbidden(reason)),
+						None => Err(SeaographyError::Forbidden("Entity guard triggered.".into())),
+					};
+				}
+
+				// the stream outlives the resolver context, the connection is borrowed from the
+				// context base instead
+				let db = ctx.ctx.data::<DatabaseConnection>()?;
+				let backend = db.get_database_backend();
+
+				let filters = ctx.args.get(&context.entity_query_field.filters);
+				let filters = get_filter_conditions::<T, F>(context, backend, filters)?;
+				let filters = apply_policy(policy, &ctx, filters);
+				let filters = apply_soft_delete(context, soft_delete, &ctx.args, filters)?;
+				let order_input_builder = OrderInputBuilder {
+					context,
+				};
+				let order_by = ctx.args.get(&context.entity_query_field.order_by);
+				let rank_order = order_input_builder.parse_search_rank::<T>(order_by.clone())?;
+				let order_by = order_input_builder.parse_object::<T>(order_by)?;
+
+				// the columns outside the read mask are not read
+				let stmt = T::find().filter(filters);
+				let stmt = match field_mask_paths(&ctx.args, &context.field_masks.read_mask)? {
+					Some(paths) => apply_read_mask::<T>(context, stmt, &paths, &order_by)?,
+					None => stmt,
+				};
+
+				// the rank precedes the column ordering
+				let rank = rank_order.and_then(|rank_order| {
+					search_rank::<T>(context, backend, &rank_order.column, &rank_order.query)
+						.map(|rank| (rank, rank_order.order))
+				});
+				let stmt = match rank {
+					Some((rank, order)) => stmt.order_by(rank, order),
+					None => stmt,
+				};
+				let stmt = apply_order(stmt, order_by);
+				let stream = stmt.stream(db).await?;
+
+				Ok(stream.map_ok(FieldValue::owned_any).map_err(SeaographyError::from))
+			})
+		})
+		.argument(Field::input(
+			&self.context.entity_query_field.filters,
+			Ty::named(filter_input_builder.type_name(&object_name)),
+		))
+		.argument(Field::input(
+			&self.context.entity_query_field.order_by,
+			Ty::named(order_input_builder.type_name(&object_name)),
+		));
+
+		let field = field_mask_argument(&context.field_masks.read_mask, field);
+
+		soft_delete_argument(context, soft_delete, field)
+	}
+}
@@ -1,11 +1,12 @@
 use crate::{BuilderContext, EntityObjectBuilder, FilterTypeTrait, FilterTypesMapHelper};
 use dynamic::prelude::*;
-use sea_orm::{Condition, EntityTrait, Iterable};
+use sea_orm::{Condition, DbBackend, EntityTrait, Iterable};
 
 /// utility function used to create the query filter condition
 /// for a SeaORM entity using query filter inputs
 pub fn get_filter_conditions<'a, T, F>(
 	context: &'static BuilderContext,
+	backend: DbBackend,
 	filters: Option<ValueAccessor<'a>>,
 ) -> SeaResult<Condition>
 where
@@ -16,7 +17,7 @@ where
 	if let Some(filters) = filters {
 		let filters = filters.object()?;
 
-		recursive_prepare_condition::<T, F>(context, &filters)
+		recursive_prepare_condition::<T, F>(context, backend, &filters)
 	} else {
 		Ok(Condition::all())
 	}
@@ -25,6 +26,7 @@ where
 /// used to prepare recursively the query filtering condition
 pub fn recursive_prepare_condition<'a, T, F>(
 	context: &'static BuilderContext,
+	backend: DbBackend,
 	filters: &'a ObjectAccessor,
 ) -> SeaResult<Condition>
 where
@@ -50,7 +52,7 @@ where
 				let filter = filter.object()?;
 
 				filter_types_map_helper
-					.prepare_column_condition::<T, F>(condition, &filter, &column)
+					.prepare_column_condition::<T, F>(condition, &filter, backend, &column)
 			} else {
 				Ok(condition)
 			}
@@ -64,7 +66,7 @@ where
 			|condition, filters: ValueAccessor| {
 				let filters = filters.object()?;
 				Ok::<sea_orm::Condition, SeaographyError>(
-					condition.add(recursive_prepare_condition::<T, F>(context, &filters)?),
+					condition.add(recursive_prepare_condition::<T, F>(context, backend, &filters)?),
 				)
 			},
 		)?)
@@ -80,7 +82,7 @@ where
 			|condition, filters: ValueAccessor| {
 				let filters = filters.object()?;
 				Ok::<sea_orm::Condition, SeaographyError>(
-					condition.add(recursive_prepare_condition::<T, F>(context, &filters)?),
+					condition.add(recursive_prepare_condition::<T, F>(context, backend, &filters)?),
 				)
 			},
 		)?)
@@ -4,6 +4,17 @@ pub use loader::*;
 pub mod entity_query_field;
 pub use entity_query_field::*;
 
+pub mod entity_stream_query_field;
+pub use entity_stream_query_field::*;
+
+pub mod entity_resolver;
+pub use entity_resolver::*;
+
+#[cfg(feature = "with-json")]
+pub mod entity_aggregate_query_field;
+#[cfg(feature = "with-json")]
+pub use entity_aggregate_query_field::*;
+
 pub mod ordering;
 pub use ordering::*;
 
@@ -13,6 +24,9 @@ pub use pagination::*;
 pub mod filtering;
 pub use filtering::*;
 
+pub mod search;
+pub use search::*;
+
 pub mod entity_object_relation;
 pub use entity_object_relation::*;
 
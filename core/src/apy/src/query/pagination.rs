@@ -1,133 +1,88 @@
 use dynamic::{SeaResult, SeaographyError};
-use itertools::Itertools;
-#[allow(unused_imports)]
-use sea_orm::CursorTrait;
 use sea_orm::{
-	ConnectionTrait, DatabaseConnection, EntityTrait, Iterable, ModelTrait, PaginatorTrait,
-	PrimaryKeyToColumn, QuerySelect, QueryTrait, Select,
+	ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait,
+	IdenStatic, Iterable, ModelTrait, PaginatorTrait, PrimaryKeyToColumn, QueryFilter, QueryOrder,
+	QuerySelect, QueryTrait, Select,
+	sea_query::{Expr, Order},
 };
 
 use crate::{
-	Connection, Edge, PageInfo, PaginationInfo, PaginationInput, decode_cursor, encode_cursor,
-	map_cursor_values,
+	Connection, Edge, PageInfo, PaginationInfo, PaginationInput, apply_order, decode_cursor,
+	encode_cursor,
 };
 
 /// used to parse pagination input object and apply it to statement
 pub async fn apply_pagination<T>(
 	db: &DatabaseConnection,
 	stmt: Select<T>,
+	order_by: Vec<(T::Column, Order)>,
 	pagination: PaginationInput,
 ) -> SeaResult<Connection<T>>
 where
 	T: EntityTrait,
 	<T as EntityTrait>::Model: Sync,
 {
+	let order = cursor_order::<T>(order_by.clone());
+
 	if let Some(cursor_object) = pagination.cursor {
-		let next_stmt = stmt.clone();
-		let previous_stmt = stmt.clone();
-
-		fn apply_stmt_cursor_by<T>(
-			stmt: sea_orm::entity::prelude::Select<T>,
-		) -> SeaResult<sea_orm::Cursor<sea_orm::SelectModel<T::Model>>>
-		where
-			T: EntityTrait,
-			<T as EntityTrait>::Model: Sync,
-		{
-			let size = T::PrimaryKey::iter().fold(0, |acc, _| acc + 1);
-			if size == 1 {
-				let column = T::PrimaryKey::iter()
-					.map(|variant| variant.into_column())
-					.collect::<Vec<T::Column>>()[0];
-				Ok(stmt.cursor_by(column))
-			} else if size == 2 {
-				let columns = match T::PrimaryKey::iter()
-					.map(|variant| variant.into_column())
-					.collect_tuple::<(T::Column, T::Column)>()
-				{
-					Some(columns) => columns,
-					None => return Err(SeaographyError::new("cursors of size 2 not found")),
-				};
-				Ok(stmt.cursor_by(columns))
-			} else if size == 3 {
-				let columns = match T::PrimaryKey::iter()
-					.map(|variant| variant.into_column())
-					.collect_tuple::<(T::Column, T::Column, T::Column)>()
-				{
-					Some(columns) => columns,
-					None => return Err(SeaographyError::new("cursors of size 3 not found")),
-				};
-				Ok(stmt.cursor_by(columns))
-			} else {
-				return Err(SeaographyError::new(
-					"seaography does not support cursors with size greater than 3",
-				));
-			}
+		let mut window = Condition::all();
+
+		if let Some(cursor) = &cursor_object.cursor {
+			window = window.add(cursor_condition::<T>(
+				&order,
+				&decode_cursor(cursor)?,
+				true,
+				nulls_greatest(db),
+			)?);
 		}
 
-		let mut stmt = apply_stmt_cursor_by(stmt)?;
-
-		if let Some(cursor) = cursor_object.cursor {
-			let values = decode_cursor(&cursor)?;
-
-			let cursor_values: sea_orm::sea_query::value::ValueTuple = map_cursor_values(values);
-
-			stmt.after(cursor_values);
+		if let Some(before) = &cursor_object.before {
+			window = window.add(cursor_condition::<T>(
+				&order,
+				&decode_cursor(before)?,
+				false,
+				nulls_greatest(db),
+			)?);
 		}
 
-		let data = stmt.first(cursor_object.limit).all(db).await?;
-
-		let has_next_page: bool = {
-			let mut next_stmt = apply_stmt_cursor_by(next_stmt)?;
-
-			let last_node = data.last();
+		let window_stmt = stmt.clone().filter(window);
 
-			if let Some(node) = last_node {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
+		let data: Vec<T::Model> = match (cursor_object.limit, cursor_object.last) {
+			(Some(limit), last) => {
+				let mut data =
+					apply_cursor_order::<T>(window_stmt, &order, true).limit(limit).all(db).await?;
 
-				let values = map_cursor_values(values);
-
-				let next_data = next_stmt.first(1).after(values).all(db).await?;
+				if let Some(last) = last {
+					data = data.split_off(data.len().saturating_sub(last as usize));
+				}
 
-				!next_data.is_empty()
-			} else {
-				false
+				data
 			}
-		};
-
-		let has_previous_page: bool = {
-			let mut previous_stmt = apply_stmt_cursor_by(previous_stmt)?;
-
-			let first_node = data.first();
-
-			if let Some(node) = first_node {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
+			(None, Some(last)) => {
+				// the rows are read backwards from the end of the window
+				let mut data =
+					apply_cursor_order::<T>(window_stmt, &order, false).limit(last).all(db).await?;
 
-				let values = map_cursor_values(values);
+				data.reverse();
 
-				let previous_data = previous_stmt.first(1).before(values).all(db).await?;
-
-				!previous_data.is_empty()
-			} else {
-				false
+				data
+			}
+			(None, None) => {
+				return Err(SeaographyError::new("cursor pagination requires a limit"));
 			}
 		};
 
-		let edges: Vec<Edge<T>> = data
-			.into_iter()
-			.map(|node| {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
+		let has_next_page: bool = match data.last() {
+			Some(node) => has_rows::<T>(db, stmt.clone(), &order, node, true).await?,
+			None => false,
+		};
 
-				let cursor: String = encode_cursor(values);
+		let has_previous_page: bool = match data.first() {
+			Some(node) => has_rows::<T>(db, stmt, &order, node, false).await?,
+			None => false,
+		};
 
-				Edge {
-					cursor,
-					node,
-				}
-			})
-			.collect();
+		let edges = to_edges::<T>(data, &order)?;
 
 		let start_cursor = edges.first().map(|edge| edge.cursor.clone());
 		let end_cursor = edges.last().map(|edge| edge.cursor.clone());
@@ -143,26 +98,15 @@ where
 			pagination_info: None,
 		})
 	} else if let Some(page_object) = pagination.page {
+		let stmt = apply_order(stmt, order_by);
+
 		let paginator = stmt.paginate(db, page_object.limit);
 
 		let paginator_info = paginator.num_items_and_pages().await?;
 
 		let data = paginator.fetch_page(page_object.page).await?;
 
-		let edges: Vec<Edge<T>> = data
-			.into_iter()
-			.map(|node| {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
-
-				let cursor: String = encode_cursor(values);
-
-				Edge {
-					cursor,
-					node,
-				}
-			})
-			.collect();
+		let edges = to_edges::<T>(data, &order)?;
 
 		let start_cursor = edges.first().map(|edge| edge.cursor.clone());
 		let end_cursor = edges.last().map(|edge| edge.cursor.clone());
@@ -186,24 +130,13 @@ where
 		let offset = offset_object.offset;
 		let limit = offset_object.limit;
 
+		let stmt = apply_order(stmt, order_by);
+
 		let count_stmt = stmt.clone().as_query().to_owned();
 
 		let data = stmt.offset(offset).limit(limit).all(db).await?;
 
-		let edges: Vec<Edge<T>> = data
-			.into_iter()
-			.map(|node| {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
-
-				let cursor: String = encode_cursor(values);
-
-				Edge {
-					cursor,
-					node,
-				}
-			})
-			.collect();
+		let edges = to_edges::<T>(data, &order)?;
 
 		let start_cursor = edges.first().map(|edge| edge.cursor.clone());
 		let end_cursor = edges.last().map(|edge| edge.cursor.clone());
@@ -238,22 +171,9 @@ where
 			}),
 		})
 	} else {
-		let data = stmt.all(db).await?;
-
-		let edges: Vec<Edge<T>> = data
-			.into_iter()
-			.map(|node| {
-				let values: Vec<sea_orm::Value> =
-					T::PrimaryKey::iter().map(|variant| node.get(variant.into_column())).collect();
+		let data = apply_order(stmt, order_by).all(db).await?;
 
-				let cursor: String = encode_cursor(values);
-
-				Edge {
-					cursor,
-					node,
-				}
-			})
-			.collect();
+		let edges = to_edges::<T>(data, &order)?;
 
 		let start_cursor = edges.first().map(|edge| edge.cursor.clone());
 		let end_cursor = edges.last().map(|edge| edge.cursor.clone());
@@ -278,52 +198,62 @@ where
 	}
 }
 
+/// used to paginate rows that are already loaded and ordered by the requested ordering
 pub fn apply_memory_pagination<T>(
 	values: Option<Vec<T::Model>>,
+	order_by: Vec<(T::Column, Order)>,
 	pagination: PaginationInput,
 ) -> SeaResult<Connection<T>>
 where
 	T: EntityTrait,
 	T::Model: Sync,
 {
-	let edges: Vec<Edge<T>> = match values {
-		Some(data) => {
-			let edges: Vec<Edge<T>> = data
-				.into_iter()
-				.map(|node| {
-					let values: Vec<sea_orm::Value> = T::PrimaryKey::iter()
-						.map(|variant| node.get(variant.into_column()))
-						.collect();
-
-					let cursor: String = encode_cursor(values);
-
-					Edge {
-						cursor,
-						node,
-					}
-				})
-				.collect();
-			edges
-		}
-		None => Vec::new(),
-	};
+	let order = cursor_order::<T>(order_by);
+
+	let edges: Vec<Edge<T>> = to_edges::<T>(values.unwrap_or_default(), &order)?;
 
 	if let Some(cursor_object) = pagination.cursor {
 		let total: u64 = edges.len() as u64;
-		let pages = f64::ceil(total as f64 / cursor_object.limit as f64) as u64;
 
-		let first_cursor = edges.first().map(|edge| edge.cursor.clone());
-		let last_cursor = edges.last().map(|edge| edge.cursor.clone());
-
-		let edges: Vec<Edge<T>> = if let Some(cursor) = cursor_object.cursor {
-			edges.into_iter().filter(|edge: &Edge<T>| edge.cursor.gt(&cursor)).collect()
-		} else {
+		let position = |cursor: &str| -> SeaResult<usize> {
 			edges
+				.iter()
+				.position(|edge| edge.cursor.eq(cursor))
+				.ok_or_else(|| SeaographyError::new("Invalid cursor"))
 		};
 
-		let current = f64::ceil(total as f64 / edges.len() as f64 * pages as f64) as u64;
+		let start = match &cursor_object.cursor {
+			Some(cursor) => position(cursor)? + 1,
+			None => 0,
+		};
 
-		let edges: Vec<Edge<T>> = edges.into_iter().take(cursor_object.limit as usize).collect();
+		let end = match &cursor_object.before {
+			Some(before) => position(before)?,
+			None => edges.len(),
+		}
+		.max(start);
+
+		let (start, end) = match (cursor_object.limit, cursor_object.last) {
+			(Some(limit), last) => {
+				let end = end.min(start + limit as usize);
+				match last {
+					Some(last) => (end.saturating_sub(last as usize).max(start), end),
+					None => (start, end),
+				}
+			}
+			(None, Some(last)) => (end.saturating_sub(last as usize).max(start), end),
+			(None, None) => {
+				return Err(SeaographyError::new("cursor pagination requires a limit"));
+			}
+		};
+
+		let size = cursor_object.limit.or(cursor_object.last).unwrap_or(1).max(1);
+		let pages = f64::ceil(total as f64 / size as f64) as u64;
+
+		let has_previous_page = start > 0;
+		let has_next_page = end < edges.len();
+
+		let edges: Vec<Edge<T>> = edges.into_iter().skip(start).take(end - start).collect();
 
 		let start_cursor = edges.first().map(|edge| edge.cursor.clone());
 		let end_cursor = edges.last().map(|edge| edge.cursor.clone());
@@ -331,15 +261,15 @@ where
 		Ok(Connection {
 			edges,
 			page_info: PageInfo {
-				has_previous_page: !first_cursor.eq(&start_cursor),
-				has_next_page: !last_cursor.eq(&end_cursor),
+				has_previous_page,
+				has_next_page,
 				start_cursor,
 				end_cursor,
 			},
 			pagination_info: Some(PaginationInfo {
 				pages,
-				current,
-				offset: current * cursor_object.limit,
+				current: start as u64 / size,
+				offset: start as u64,
 				total,
 			}),
 		})
@@ -423,3 +353,257 @@ where
 		})
 	}
 }
+
+/// used to get the ordering the cursors are made of, the requested ordering
+/// followed by the primary key columns that are not part of it
+pub fn cursor_order<T>(order_by: Vec<(T::Column, Order)>) -> Vec<(T::Column, Order)>
+where
+	T: EntityTrait,
+{
+	T::PrimaryKey::iter().map(|key| key.into_column()).fold(order_by, |mut order, column| {
+		if !order.iter().any(|(item, _)| item.as_str().eq(column.as_str())) {
+			order.push((column, Order::Asc));
+		}
+		order
+	})
+}
+
+/// used to wrap the rows into edges holding their cursors
+fn to_edges<T>(data: Vec<T::Model>, order: &[(T::Column, Order)]) -> SeaResult<Vec<Edge<T>>>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	data.into_iter()
+		.map(|node| {
+			let values: Vec<sea_orm::Value> =
+				order.iter().map(|(column, _)| node.get(*column)).collect();
+
+			let cursor: String = encode_cursor(values)?;
+
+			Ok(Edge {
+				cursor,
+				node,
+			})
+		})
+		.collect()
+}
+
+/// used to apply the cursor ordering, reversed when not going forward
+fn apply_cursor_order<T>(stmt: Select<T>, order: &[(T::Column, Order)], forward: bool) -> Select<T>
+where
+	T: EntityTrait,
+{
+	order.iter().fold(stmt, |stmt, (column, ord)| {
+		if is_ascending(ord, forward) {
+			stmt.order_by(*column, Order::Asc)
+		} else {
+			stmt.order_by(*column, Order::Desc)
+		}
+	})
+}
+
+/// used to get the condition matching the rows that follow the cursor values,
+/// or precede them when not going forward
+///
+/// null values are placed the way the backend sorts them, as the greatest values
+/// on Postgres and as the smallest values on MySQL and SQLite
+fn cursor_condition<T>(
+	order: &[(T::Column, Order)],
+	values: &[sea_orm::Value],
+	forward: bool,
+	nulls_greatest: bool,
+) -> SeaResult<Condition>
+where
+	T: EntityTrait,
+{
+	if order.len() != values.len() {
+		return Err(SeaographyError::new("Invalid cursor"));
+	}
+
+	let condition = (0..order.len()).fold(Condition::any(), |condition, index| {
+		let (column, ord) = &order[index];
+		let value = &values[index];
+
+		let ascending = is_ascending(ord, forward);
+
+		let following = if is_null(value) {
+			// nothing follows null values sorted last
+			if ascending == nulls_greatest {
+				return condition;
+			}
+			Condition::all().add(column.is_not_null())
+		} else {
+			let following = if ascending {
+				column.gt(value.clone())
+			} else {
+				column.lt(value.clone())
+			};
+
+			if ascending == nulls_greatest {
+				Condition::any().add(following).add(column.is_null())
+			} else {
+				Condition::all().add(following)
+			}
+		};
+
+		let preceding_equal = order[..index].iter().zip(values).fold(
+			Condition::all(),
+			|condition, ((column, _), value)| {
+				if is_null(value) {
+					condition.add(column.is_null())
+				} else {
+					condition.add(column.eq(value.clone()))
+				}
+			},
+		);
+
+		condition.add(preceding_equal.add(following))
+	});
+
+	if condition.is_empty() {
+		return Ok(Condition::all().add(Expr::val(1).eq(0)));
+	}
+
+	Ok(condition)
+}
+
+/// used to check if rows follow the node, or precede it when not going forward
+async fn has_rows<T>(
+	db: &DatabaseConnection,
+	stmt: Select<T>,
+	order: &[(T::Column, Order)],
+	node: &T::Model,
+	forward: bool,
+) -> SeaResult<bool>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let values: Vec<sea_orm::Value> = order.iter().map(|(column, _)| node.get(*column)).collect();
+
+	let condition = cursor_condition::<T>(order, &values, forward, nulls_greatest(db))?;
+
+	let data = stmt.filter(condition).limit(1).all(db).await?;
+
+	Ok(!data.is_empty())
+}
+
+fn nulls_greatest(db: &DatabaseConnection) -> bool {
+	matches!(db.get_database_backend(), DbBackend::Postgres)
+}
+
+fn is_ascending(ord: &Order, forward: bool) -> bool {
+	!matches!(ord, Order::Desc) == forward
+}
+
+/// used to check if a value of a type supported by cursors is null
+fn is_null(value: &sea_orm::Value) -> bool {
+	match value {
+		sea_orm::Value::Bool(None)
+		| sea_orm::Value::TinyInt(None)
+		| sea_orm::Value::SmallInt(None)
+		| sea_orm::Value::Int(None)
+		| sea_orm::Value::BigInt(None)
+		| sea_orm::Value::TinyUnsigned(None)
+		| sea_orm::Value::SmallUnsigned(None)
+		| sea_orm::Value::Unsigned(None)
+		| sea_orm::Value::BigUnsigned(None)
+		| sea_orm::Value::Float(None)
+		| sea_orm::Value::Double(None)
+		| sea_orm::Value::Char(None)
+		| sea_orm::Value::String(None) => true,
+		#[cfg(feature = "with-chrono")]
+		sea_orm::Value::ChronoDate(None)
+		| sea_orm::Value::ChronoTime(None)
+		| sea_orm::Value::ChronoDateTime(None)
+		| sea_orm::Value::ChronoDateTimeUtc(None)
+		| sea_orm::Value::ChronoDateTimeLocal(None)
+		| sea_orm::Value::ChronoDateTimeWithTimeZone(None) => true,
+		#[cfg(feature = "with-uuid")]
+		sea_orm::Value::Uuid(None) => true,
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+
+	fn condition(values: Vec<sea_orm::Value>, forward: bool, nulls_greatest: bool) -> String {
+		let order = cursor_order::<film::Entity>(vec![(film::Column::ReleaseYear, Order::Asc)]);
+		let condition =
+			cursor_condition::<film::Entity>(&order, &values, forward, nulls_greatest).unwrap();
+
+		let sql = film::Entity::find()
+			.select_only()
+			.column(film::Column::Id)
+			.filter(condition)
+			.build(DbBackend::Postgres)
+			.to_string();
+
+		sql.trim_start_matches(r#"SELECT "film"."id" FROM "film" WHERE "#).to_string()
+	}
+
+	/// Should follow the ordering with the primary key as tie breaker.
+	#[test]
+	fn tie_breaker() {
+		let order = cursor_order::<film::Entity>(vec![(film::Column::ReleaseYear, Order::Desc)]);
+		assert_eq!(order.len(), 2);
+		assert!(matches!(order[1], (film::Column::Id, Order::Asc)));
+
+		// an ordering by the primary key is not extended
+		let order = cursor_order::<film::Entity>(vec![(film::Column::Id, Order::Desc)]);
+		assert_eq!(order.len(), 1);
+
+		let values = vec![sea_orm::Value::Int(Some(1999)), sea_orm::Value::Int(Some(3))];
+		assert_eq!(
+			condition(values.clone(), true, false),
+			r#""film"."release_year" > 1999 OR ("film"."release_year" = 1999 AND "film"."id" > 3)"#
+		);
+		assert_eq!(
+			condition(values, false, true),
+			r#""film"."release_year" < 1999 OR ("film"."release_year" = 1999 AND "film"."id" < 3)"#
+		);
+	}
+
+	/// Should place null values the way the backend sorts them.
+	#[test]
+	fn null_ordering() {
+		// nulls are the greatest values on Postgres, so they follow every value
+		let values = vec![sea_orm::Value::Int(Some(1999)), sea_orm::Value::Int(Some(3))];
+		assert_eq!(
+			condition(values, true, true),
+			concat!(
+				r#""film"."release_year" > 1999 OR "film"."release_year" IS NULL "#,
+				r#"OR ("film"."release_year" = 1999 AND ("film"."id" > 3 OR "film"."id" IS NULL))"#,
+			)
+		);
+
+		// only other nulls follow a null value sorted last
+		let values = vec![sea_orm::Value::Int(None), sea_orm::Value::Int(Some(3))];
+		assert_eq!(
+			condition(values.clone(), true, true),
+			r#""film"."release_year" IS NULL AND ("film"."id" > 3 OR "film"."id" IS NULL)"#
+		);
+
+		// every value follows a null value sorted first
+		assert_eq!(
+			condition(values, true, false),
+			concat!(
+				r#""film"."release_year" IS NOT NULL "#,
+				r#"OR ("film"."release_year" IS NULL AND "film"."id" > 3)"#,
+			)
+		);
+	}
+
+	/// Should reject cursors that do not match the ordering.
+	#[test]
+	fn invalid_cursor() {
+		let order = cursor_order::<film::Entity>(vec![(film::Column::ReleaseYear, Order::Asc)]);
+		let values = vec![sea_orm::Value::Int(Some(3))];
+
+		assert!(cursor_condition::<film::Entity>(&order, &values, true, true).is_err());
+	}
+}
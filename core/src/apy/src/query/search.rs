@@ -0,0 +1,193 @@
+use crate::{BuilderContext, EntityObjectBuilder};
+use sea_orm::{
+	ColumnTrait, Condition, DbBackend, EntityTrait,
+	sea_query::{Alias, BinOper, Expr, Func, Query, SimpleExpr},
+};
+
+/// used to get the condition of the search filter operator
+///
+/// Postgres matches the column text search vector against the web search query,
+/// SQLite matches the FTS5 table configured for the column, every other case
+/// requires the column to contain each word of the query
+pub fn search_condition<T>(
+	context: &'static BuilderContext,
+	backend: DbBackend,
+	column: &T::Column,
+	query: &str,
+) -> Condition
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let key = search_key::<T>(context, column);
+
+	match backend {
+		DbBackend::Postgres => {
+			let language = search_language(context, &key);
+
+			Condition::all().add(
+				to_tsvector(&language, column)
+					.binary(BinOper::Custom("@@"), websearch_to_tsquery(&language, query)),
+			)
+		}
+		DbBackend::Sqlite if context.filter_types.search_fts_tables.contains_key(&key) => {
+			let table = &context.filter_types.search_fts_tables[&key];
+
+			Condition::all().add(
+				Expr::col((column.entity_name(), Alias::new("rowid"))).in_subquery(
+					Query::select()
+						.column(Alias::new("rowid"))
+						.from(Alias::new(table))
+						.and_where(
+							Expr::col(Alias::new(table))
+								.binary(BinOper::Custom("MATCH"), fts5_query(query)),
+						)
+						.to_owned(),
+				),
+			)
+		}
+		_ => query
+			.split_whitespace()
+			.fold(Condition::all(), |condition, word| condition.add(column.contains(word))),
+	}
+}
+
+/// used to get the rank of the rows matching the search query,
+/// ranking is only supported on Postgres
+pub fn search_rank<T>(
+	context: &'static BuilderContext,
+	backend: DbBackend,
+	column: &T::Column,
+	query: &str,
+) -> Option<SimpleExpr>
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	match backend {
+		DbBackend::Postgres => {
+			let language = search_language(context, &search_key::<T>(context, column));
+
+			Some(
+				Func::cust(Alias::new("ts_rank"))
+					.arg(to_tsvector(&language, column))
+					.arg(websearch_to_tsquery(&language, query))
+					.into(),
+			)
+		}
+		_ => None,
+	}
+}
+
+/// used to get the entity_name.column_name key of the search configuration
+fn search_key<T>(context: &'static BuilderContext, column: &T::Column) -> String
+where
+	T: EntityTrait,
+	<T as EntityTrait>::Model: Sync,
+{
+	let entity_object_builder = EntityObjectBuilder {
+		context,
+	};
+
+	format!(
+		"{}.{}",
+		entity_object_builder.type_name::<T>(),
+		entity_object_builder.column_name::<T>(column)
+	)
+}
+
+/// used to get the text search configuration of a column
+fn search_language(context: &'static BuilderContext, key: &str) -> String {
+	context
+		.filter_types
+		.search_languages
+		.get(key)
+		.unwrap_or(&context.filter_types.default_search_language)
+		.clone()
+}
+
+fn to_tsvector<C>(language: &str, column: &C) -> SimpleExpr
+where
+	C: ColumnTrait,
+{
+	Func::cust(Alias::new("to_tsvector"))
+		.arg(Expr::val(language).cast_as(Alias::new("regconfig")))
+		.arg(Expr::col((column.entity_name(), *column)))
+		.into()
+}
+
+fn websearch_to_tsquery(language: &str, query: &str) -> SimpleExpr {
+	Func::cust(Alias::new("websearch_to_tsquery"))
+		.arg(Expr::val(language).cast_as(Alias::new("regconfig")))
+		.arg(query)
+		.into()
+}
+
+/// used to quote each word of the query, so that it is matched as is
+/// instead of being parsed as a FTS5 query expression
+fn fts5_query(query: &str) -> String {
+	query
+		.split_whitespace()
+		.map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests_cfg::film;
+	use sea_orm::{QueryFilter, QuerySelect, QueryTrait};
+
+	fn sql(context: &'static BuilderContext, backend: DbBackend) -> String {
+		let condition =
+			search_condition::<film::Entity>(context, backend, &film::Column::Title, "star wars");
+
+		film::Entity::find()
+			.select_only()
+			.column(film::Column::Id)
+			.filter(condition)
+			.build(backend)
+			.to_string()
+	}
+
+	/// Should match each word of the query as is.
+	#[test]
+	fn fts5_quoting() {
+		assert_eq!(fts5_query("star wars"), r#""star" "wars""#);
+		assert_eq!(fts5_query("  star \"wars\" "), r#""star" """wars""""#);
+		assert_eq!(fts5_query("NOT star*"), r#""NOT" "star*""#);
+		assert_eq!(fts5_query(""), "");
+	}
+
+	/// Should search the column the way the backend supports.
+	#[test]
+	fn condition() {
+		let mut context = BuilderContext::default();
+		context.filter_types.search_fts_tables.insert("Film.title".into(), "film_fts".into());
+		let context: &'static BuilderContext = Box::leak(Box::new(context));
+
+		assert_eq!(
+			sql(context, DbBackend::Postgres),
+			concat!(
+				r#"SELECT "film"."id" FROM "film" WHERE "#,
+				r#"to_tsvector(CAST('english' AS regconfig), "film"."title") "#,
+				r#"@@ websearch_to_tsquery(CAST('english' AS regconfig), 'star wars')"#,
+			)
+		);
+		assert_eq!(
+			sql(context, DbBackend::Sqlite),
+			concat!(
+				r#"SELECT "film"."id" FROM "film" WHERE "film"."rowid" IN "#,
+				r#"(SELECT "rowid" FROM "film_fts" WHERE "film_fts" MATCH '"star" "wars"')"#,
+			)
+		);
+		assert_eq!(
+			sql(context, DbBackend::MySql),
+			concat!(
+				"SELECT `film`.`id` FROM `film` WHERE ",
+				"`film`.`title` LIKE '%star%' AND `film`.`title` LIKE '%wars%'",
+			)
+		);
+	}
+}
@@ -0,0 +1,31 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "author")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub name: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(has_many = "super::post::Entity")]
+	Post,
+	#[sea_orm(has_one = "super::profile::Entity")]
+	Profile,
+}
+
+impl Related<super::post::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Post.def()
+	}
+}
+
+impl Related<super::profile::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Profile.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
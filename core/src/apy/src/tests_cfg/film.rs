@@ -0,0 +1,18 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "film")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub title: String,
+	pub release_year: Option<i32>,
+	pub metadata: Option<Json>,
+	pub version: i32,
+	pub deleted_at: Option<DateTimeUtc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
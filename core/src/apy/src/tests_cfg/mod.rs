@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use dynamic::prelude::{ApiType, ContextBase, FieldValue, ObjectAccessor, ResolverContext};
+
+pub mod author;
+pub mod film;
+#[cfg(feature = "with-postgres-array")]
+pub mod photo;
+pub mod post;
+pub mod profile;
+
+/// used to run a test with the context of a resolver that has no arguments and no parent
+pub fn with_context<R>(f: impl FnOnce(&ResolverContext) -> R) -> R {
+	let base = ContextBase::new(ApiType::GraphQL);
+	let ctx = ResolverContext {
+		ctx: &base,
+		args: ObjectAccessor::default(),
+		parent_value: &FieldValue::NULL,
+	};
+	f(&ctx)
+}
@@ -0,0 +1,28 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "post")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	pub author_id: i32,
+	pub title: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::author::Entity",
+		from = "Column::AuthorId",
+		to = "super::author::Column::Id"
+	)]
+	Author,
+}
+
+impl Related<super::author::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Author.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
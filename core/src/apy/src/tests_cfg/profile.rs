@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "profile")]
+pub struct Model {
+	#[sea_orm(primary_key)]
+	pub id: i32,
+	#[sea_orm(unique)]
+	pub author_id: i32,
+	pub bio: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::author::Entity",
+		from = "Column::AuthorId",
+		to = "super::author::Column::Id"
+	)]
+	Author,
+}
+
+impl Related<super::author::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Author.def()
+	}
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -1,109 +1,88 @@
-use dynamic::{SchemaError, SeaResult};
-use itertools::Itertools;
+use dynamic::{SchemaError, SeaResult, SeaographyError};
 use sea_orm::DatabaseConnection;
 
 pub trait QueryRoot<T> {
-	fn config_schema(&mut self, _depth: u16, _complexity: u16) {
+	fn config_schema(&mut self, _depth: Option<u16>, _complexity: Option<u16>) {
 		panic!("not implemented")
 	}
 
 	fn root(&self, database: &DatabaseConnection) -> Result<T, SchemaError>;
 }
 
-/// used to encode the primary key values of a SeaORM entity to a String
-pub fn encode_cursor(values: Vec<sea_orm::Value>) -> String {
+/// used to encode the cursor values of a SeaORM entity row to a String
+pub fn encode_cursor(values: Vec<sea_orm::Value>) -> SeaResult<String> {
 	values
 		.iter()
-		.map(|value| -> String {
-			match value {
-				sea_orm::Value::TinyInt(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("TinyInt[{}]:{}", value.len(), value)
-					} else {
-						"TinyInt[-1]:".into()
-					}
-				}
+		.map(|value| -> SeaResult<String> {
+			let (type_indicator, data): (&str, Option<String>) = match value {
+				sea_orm::Value::Bool(value) => ("Bool", value.map(|value| value.to_string())),
+				sea_orm::Value::TinyInt(value) => ("TinyInt", value.map(|value| value.to_string())),
 				sea_orm::Value::SmallInt(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("SmallInt[{}]:{}", value.len(), value)
-					} else {
-						"SmallInt[-1]:".into()
-					}
-				}
-				sea_orm::Value::Int(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("Int[{}]:{}", value.len(), value)
-					} else {
-						"Int[-1]:".into()
-					}
-				}
-				sea_orm::Value::BigInt(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("BigInt[{}]:{}", value.len(), value)
-					} else {
-						"BigInt[-1]:".into()
-					}
+					("SmallInt", value.map(|value| value.to_string()))
 				}
+				sea_orm::Value::Int(value) => ("Int", value.map(|value| value.to_string())),
+				sea_orm::Value::BigInt(value) => ("BigInt", value.map(|value| value.to_string())),
 				sea_orm::Value::TinyUnsigned(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("TinyUnsigned[{}]:{}", value.len(), value)
-					} else {
-						"TinyUnsigned[-1]:".into()
-					}
+					("TinyUnsigned", value.map(|value| value.to_string()))
 				}
 				sea_orm::Value::SmallUnsigned(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("SmallUnsigned[{}]:{}", value.len(), value)
-					} else {
-						"SmallUnsigned[-1]:".into()
-					}
+					("SmallUnsigned", value.map(|value| value.to_string()))
 				}
 				sea_orm::Value::Unsigned(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("Unsigned[{}]:{}", value.len(), value)
-					} else {
-						"Unsigned[-1]:".into()
-					}
+					("Unsigned", value.map(|value| value.to_string()))
 				}
 				sea_orm::Value::BigUnsigned(value) => {
-					if let Some(value) = value {
-						let value = value.to_string();
-						format!("BigUnsigned[{}]:{}", value.len(), value)
-					} else {
-						"BigUnsigned[-1]:".into()
-					}
+					("BigUnsigned", value.map(|value| value.to_string()))
 				}
+				sea_orm::Value::Float(value) => ("Float", value.map(|value| value.to_string())),
+				sea_orm::Value::Double(value) => ("Double", value.map(|value| value.to_string())),
+				sea_orm::Value::Char(value) => ("Char", value.map(|value| value.to_string())),
 				sea_orm::Value::String(value) => {
-					if let Some(value) = value {
-						let value = value.as_ref();
-						format!("String[{}]:{}", value.len(), value)
-					} else {
-						"String[-1]:".into()
-					}
+					("String", value.as_ref().map(|value| value.as_ref().clone()))
 				}
-				#[cfg(feature = "with-uuid")]
-				sea_orm::Value::Uuid(value) => {
-					if let Some(value) = value {
-						let value = value.as_ref().to_string();
-						format!("Uuid[{}]:{}", value.len(), value)
-					} else {
-						"Uuid[-1]:".into()
-					}
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoDate(value) => {
+					("ChronoDate", value.as_ref().map(|value| value.format("%Y-%m-%d").to_string()))
 				}
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoTime(value) => (
+					"ChronoTime",
+					value.as_ref().map(|value| value.format("%H:%M:%S%.f").to_string()),
+				),
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoDateTime(value) => (
+					"ChronoDateTime",
+					value.as_ref().map(|value| value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()),
+				),
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoDateTimeUtc(value) => {
+					("ChronoDateTimeUtc", value.as_ref().map(|value| value.to_rfc3339()))
+				}
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoDateTimeLocal(value) => {
+					("ChronoDateTimeLocal", value.as_ref().map(|value| value.to_rfc3339()))
+				}
+				#[cfg(feature = "with-chrono")]
+				sea_orm::Value::ChronoDateTimeWithTimeZone(value) => {
+					("ChronoDateTimeWithTimeZone", value.as_ref().map(|value| value.to_rfc3339()))
+				}
+				#[cfg(feature = "with-uuid")]
+				sea_orm::Value::Uuid(value) => ("Uuid", value.as_ref().map(|value| value.as_ref().to_string())),
 				_ => {
-					// FIXME: missing value types
-					panic!("Cannot convert type to cursor")
+					return Err(SeaographyError::new(format!(
+						"Cannot convert type to cursor: {value:?}"
+					)));
 				}
-			}
+			};
+
+			// the length counts chars, as the decoder reads the data char by char
+			Ok(match data {
+				Some(data) => format!("{}[{}]:{}", type_indicator, data.chars().count(), data),
+				None => format!("{}[-1]:", type_indicator),
+			})
 		})
-		.join(",")
+		.collect::<SeaResult<Vec<String>>>()
+		.map(|values| values.join(","))
 }
 
 #[derive(Debug)]
@@ -114,22 +93,6 @@ pub enum DecodeMode {
 	Data,
 }
 
-pub fn map_cursor_values(values: Vec<sea_orm::Value>) -> sea_orm::sea_query::value::ValueTuple {
-	if values.len() == 1 {
-		sea_orm::sea_query::value::ValueTuple::One(values[0].clone())
-	} else if values.len() == 2 {
-		sea_orm::sea_query::value::ValueTuple::Two(values[0].clone(), values[1].clone())
-	} else if values.len() == 3 {
-		sea_orm::sea_query::value::ValueTuple::Three(
-			values[0].clone(),
-			values[1].clone(),
-			values[2].clone(),
-		)
-	} else {
-		panic!("seaography does not support cursors values with size greater than 3")
-	}
-}
-
 /// used to decode a String to a vector of SeaORM values
 pub fn decode_cursor(s: &str) -> SeaResult<Vec<sea_orm::Value>> {
 	let chars = s.chars();
@@ -162,99 +125,31 @@ pub fn decode_cursor(s: &str) -> SeaResult<Vec<sea_orm::Value>> {
 				}
 			}
 			DecodeMode::ColonSkip => {
-				// skips ':' char
-				mode = DecodeMode::Data;
+				// skips ':' char, null and empty values carry no data
+				if length <= 0 {
+					let data = if length.eq(&-1) {
+						None
+					} else {
+						Some(data_buffer.as_str())
+					};
+					values.push(decode_value(&type_indicator, data)?);
+
+					type_indicator = String::new();
+					length_indicator = String::new();
+					data_buffer = String::new();
+					length = -1;
+
+					mode = DecodeMode::Type;
+				} else {
+					mode = DecodeMode::Data;
+				}
 			}
 			DecodeMode::Data => {
-				if length > 0 {
-					data_buffer.push(char);
-					length -= 1;
-				}
+				data_buffer.push(char);
+				length -= 1;
 
 				if length <= 0 {
-					let value: sea_orm::Value = match type_indicator.as_str() {
-						"TinyInt" => {
-							if length.eq(&-1) {
-								sea_orm::Value::TinyInt(None)
-							} else {
-								sea_orm::Value::TinyInt(Some(data_buffer.parse::<i8>()?))
-							}
-						}
-						"SmallInt" => {
-							if length.eq(&-1) {
-								sea_orm::Value::SmallInt(None)
-							} else {
-								sea_orm::Value::SmallInt(Some(data_buffer.parse::<i16>()?))
-							}
-						}
-						"Int" => {
-							if length.eq(&-1) {
-								sea_orm::Value::Int(None)
-							} else {
-								sea_orm::Value::Int(Some(data_buffer.parse::<i32>()?))
-							}
-						}
-						"BigInt" => {
-							if length.eq(&-1) {
-								sea_orm::Value::BigInt(None)
-							} else {
-								sea_orm::Value::BigInt(Some(data_buffer.parse::<i64>()?))
-							}
-						}
-						"TinyUnsigned" => {
-							if length.eq(&-1) {
-								sea_orm::Value::TinyUnsigned(None)
-							} else {
-								sea_orm::Value::TinyUnsigned(Some(data_buffer.parse::<u8>()?))
-							}
-						}
-						"SmallUnsigned" => {
-							if length.eq(&-1) {
-								sea_orm::Value::SmallUnsigned(None)
-							} else {
-								sea_orm::Value::SmallUnsigned(Some(data_buffer.parse::<u16>()?))
-							}
-						}
-						"Unsigned" => {
-							if length.eq(&-1) {
-								sea_orm::Value::Unsigned(None)
-							} else {
-								sea_orm::Value::Unsigned(Some(data_buffer.parse::<u32>()?))
-							}
-						}
-						"BigUnsigned" => {
-							if length.eq(&-1) {
-								sea_orm::Value::BigUnsigned(None)
-							} else {
-								sea_orm::Value::BigUnsigned(Some(data_buffer.parse::<u64>()?))
-							}
-						}
-						"String" => {
-							if length.eq(&-1) {
-								sea_orm::Value::String(None)
-							} else {
-								sea_orm::Value::String(Some(Box::new(
-									data_buffer.parse::<String>()?,
-								)))
-							}
-						}
-						#[cfg(feature = "with-uuid")]
-						"Uuid" => {
-							if length.eq(&-1) {
-								sea_orm::Value::Uuid(None)
-							} else {
-								sea_orm::Value::Uuid(Some(Box::new(
-									data_buffer.parse::<sea_orm::prelude::Uuid>()?,
-								)))
-							}
-						}
-						_ => {
-							// FIXME: missing value types
-							panic!("cannot encode current type")
-						}
-					};
-
-					values.push(value);
+					values.push(decode_value(&type_indicator, Some(data_buffer.as_str()))?);
 
 					type_indicator = String::new();
 					length_indicator = String::new();
@@ -267,5 +162,156 @@ pub fn decode_cursor(s: &str) -> SeaResult<Vec<sea_orm::Value>> {
 		}
 	}
 
+	if !matches!(mode, DecodeMode::Type) || !type_indicator.is_empty() {
+		return Err(SeaographyError::new("Invalid cursor"));
+	}
+
 	Ok(values)
 }
+
+/// used to decode the data of a single cursor value, None for null values
+fn decode_value(type_indicator: &str, data: Option<&str>) -> SeaResult<sea_orm::Value> {
+	let value = match type_indicator {
+		"Bool" => sea_orm::Value::Bool(
+			data.map(|data| data.parse::<bool>()).transpose().map_err(invalid_cursor)?,
+		),
+		"TinyInt" => sea_orm::Value::TinyInt(
+			data.map(|data| data.parse::<i8>()).transpose().map_err(invalid_cursor)?,
+		),
+		"SmallInt" => sea_orm::Value::SmallInt(
+			data.map(|data| data.parse::<i16>()).transpose().map_err(invalid_cursor)?,
+		),
+		"Int" => sea_orm::Value::Int(
+			data.map(|data| data.parse::<i32>()).transpose().map_err(invalid_cursor)?,
+		),
+		"BigInt" => sea_orm::Value::BigInt(
+			data.map(|data| data.parse::<i64>()).transpose().map_err(invalid_cursor)?,
+		),
+		"TinyUnsigned" => sea_orm::Value::TinyUnsigned(
+			data.map(|data| data.parse::<u8>()).transpose().map_err(invalid_cursor)?,
+		),
+		"SmallUnsigned" => sea_orm::Value::SmallUnsigned(
+			data.map(|data| data.parse::<u16>()).transpose().map_err(invalid_cursor)?,
+		),
+		"Unsigned" => sea_orm::Value::Unsigned(
+			data.map(|data| data.parse::<u32>()).transpose().map_err(invalid_cursor)?,
+		),
+		"BigUnsigned" => sea_orm::Value::BigUnsigned(
+			data.map(|data| data.parse::<u64>()).transpose().map_err(invalid_cursor)?,
+		),
+		"Float" => sea_orm::Value::Float(
+			data.map(|data| data.parse::<f32>()).transpose().map_err(invalid_cursor)?,
+		),
+		"Double" => sea_orm::Value::Double(
+			data.map(|data| data.parse::<f64>()).transpose().map_err(invalid_cursor)?,
+		),
+		"Char" => sea_orm::Value::Char(
+			data.map(|data| data.parse::<char>()).transpose().map_err(invalid_cursor)?,
+		),
+		"String" => sea_orm::Value::String(data.map(|data| Box::new(data.to_string()))),
+		#[cfg(feature = "with-chrono")]
+		"ChronoDate" => sea_orm::Value::ChronoDate(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoDate::parse_from_str(data, "%Y-%m-%d").map(Box::new)
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-chrono")]
+		"ChronoTime" => sea_orm::Value::ChronoTime(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoTime::parse_from_str(data, "%H:%M:%S%.f")
+					.map(Box::new)
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-chrono")]
+		"ChronoDateTime" => sea_orm::Value::ChronoDateTime(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoDateTime::parse_from_str(
+					data,
+					"%Y-%m-%dT%H:%M:%S%.f",
+				)
+				.map(Box::new)
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-chrono")]
+		"ChronoDateTimeUtc" => sea_orm::Value::ChronoDateTimeUtc(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoDateTimeWithTimeZone::parse_from_rfc3339(data)
+					.map(|value| Box::new(value.to_utc()))
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-chrono")]
+		"ChronoDateTimeLocal" => sea_orm::Value::ChronoDateTimeLocal(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoDateTimeWithTimeZone::parse_from_rfc3339(data)
+					.map(|value| Box::new(value.into()))
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-chrono")]
+		"ChronoDateTimeWithTimeZone" => sea_orm::Value::ChronoDateTimeWithTimeZone(
+			data.map(|data| {
+				sea_orm::entity::prelude::ChronoDateTimeWithTimeZone::parse_from_rfc3339(data)
+					.map(Box::new)
+			})
+			.transpose()
+			.map_err(invalid_cursor)?,
+		),
+		#[cfg(feature = "with-uuid")]
+		"Uuid" => sea_orm::Value::Uuid(
+			data.map(|data| data.parse::<sea_orm::prelude::Uuid>().map(Box::new))
+				.transpose()
+				.map_err(invalid_cursor)?,
+		),
+		_ => {
+			return Err(SeaographyError::new(format!(
+				"Cannot decode cursor type '{type_indicator}'"
+			)));
+		}
+	};
+
+	Ok(value)
+}
+
+fn invalid_cursor<E: std::fmt::Display>(err: E) -> SeaographyError {
+	SeaographyError::new(format!("Invalid cursor: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Should decode the values an encoded cursor was built from.
+	#[test]
+	fn cursor_round_trip() {
+		let values = vec![
+			sea_orm::Value::Int(Some(42)),
+			sea_orm::Value::String(Some(Box::new("a[3]:b,c".to_string()))),
+			sea_orm::Value::String(Some(Box::new("ünïcode".to_string()))),
+			sea_orm::Value::String(Some(Box::new(String::new()))),
+			sea_orm::Value::Int(None),
+			sea_orm::Value::Bool(Some(true)),
+			sea_orm::Value::BigInt(Some(-9_000_000_000)),
+		];
+
+		let cursor = encode_cursor(values.clone()).unwrap();
+		assert_eq!(decode_cursor(&cursor).unwrap(), values);
+	}
+
+	/// Should reject cursors that are malformed.
+	#[test]
+	fn invalid_cursor() {
+		assert!(decode_cursor("Int[2]:4").is_err());
+		assert!(decode_cursor("Int[x]:42").is_err());
+		assert!(decode_cursor("Int[2]:ab").is_err());
+		assert!(decode_cursor("Unknown[1]:a").is_err());
+	}
+}
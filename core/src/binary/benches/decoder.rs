@@ -0,0 +1,110 @@
+//! Compares the `Decoder` with the `Fields` iterator and the `BytesDecoder` on
+//! multi-megabyte messages.
+//!
+//! Run with `cargo bench -p binary`, the message size in megabytes can be
+//! passed as an argument, e.g. `cargo bench -p binary -- 16`.
+
+use binary::proto::{BytesDecoder, Decoder, Encoder, EncoderLit, Fields};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// The size of the chunks a message is fed in, like the frames of a stream.
+const CHUNK: usize = 16 * 1024;
+
+/// Builds a message of about `size` bytes of length-delimited fields with
+/// `len` bytes each, every field is followed by a varint and a fixed field.
+fn message(size: usize, len: usize) -> Vec<u8> {
+	let encoder = Encoder;
+	let value = vec![42u8; len];
+	let mut buf = Vec::with_capacity(size + size / len * 32);
+	while buf.len() < size {
+		encoder.encode((&1, &value), &mut buf).unwrap();
+		encoder.encode((&2, &(buf.len() as u64)), &mut buf).unwrap();
+		encoder.encode((&3, EncoderLit::Fixed64(&(buf.len() as u64))), &mut buf).unwrap();
+	}
+	buf
+}
+
+/// Runs `f` until it took at least a second and returns the mean duration.
+fn measure<F: FnMut() -> usize>(mut f: F) -> Duration {
+	let start = Instant::now();
+	let mut runs = 0;
+	while runs == 0 || start.elapsed() < Duration::from_secs(1) {
+		black_box(f());
+		runs += 1;
+	}
+	start.elapsed() / runs
+}
+
+fn report(name: &str, buf: &[u8], duration: Duration) {
+	let throughput = buf.len() as f64 / duration.as_secs_f64() / (1024.0 * 1024.0);
+	println!("  {:<28} {:>12.3?} {:>10.1} MiB/s", name, duration, throughput);
+}
+
+fn bench(name: &str, buf: &[u8]) {
+	println!("{} ({} bytes)", name, buf.len());
+
+	report(
+		"Decoder",
+		buf,
+		measure(|| {
+			let mut decoder = Decoder::default();
+			let mut src = buf.to_vec();
+			let mut dst = vec![];
+			decoder.decode(&mut src, &mut dst).unwrap();
+			dst.len()
+		}),
+	);
+
+	report(
+		"Decoder (chunked)",
+		buf,
+		measure(|| {
+			let mut decoder = Decoder::default();
+			let mut src = vec![];
+			let mut dst = vec![];
+			for chunk in buf.chunks(CHUNK) {
+				src.extend_from_slice(chunk);
+				decoder.decode(&mut src, &mut dst).unwrap();
+			}
+			dst.len()
+		}),
+	);
+
+	report("Fields", buf, measure(|| Fields::new(buf).map(|field| field.unwrap().2.len()).sum()));
+
+	report(
+		"BytesDecoder",
+		buf,
+		measure(|| {
+			let mut decoder = BytesDecoder::default();
+			let mut dst = vec![];
+			decoder.feed(buf);
+			decoder.decode(&mut dst).unwrap();
+			dst.len()
+		}),
+	);
+
+	report(
+		"BytesDecoder (chunked)",
+		buf,
+		measure(|| {
+			let mut decoder = BytesDecoder::default();
+			let mut dst = vec![];
+			for chunk in buf.chunks(CHUNK) {
+				decoder.feed(chunk);
+				decoder.decode(&mut dst).unwrap();
+			}
+			dst.len()
+		}),
+	);
+}
+
+fn main() {
+	let size = std::env::args().skip(1).find_map(|arg| arg.parse::<usize>().ok()).unwrap_or(4)
+		* 1024 * 1024;
+
+	bench("large fields", &message(size, 64 * 1024));
+	bench("small fields", &message(size, 256));
+	bench("single field", &message(size, size));
+}
@@ -21,11 +21,13 @@
 mod error;
 mod lit;
 mod primitives;
+mod stream;
 
 use super::Typ;
 pub use error::*;
 pub use lit::*;
-use primitives::*;
+pub use primitives::*;
+pub use stream::*;
 
 /// Provides the decoding engine for Protocol Buffers.
 pub struct Decoder {
@@ -92,6 +94,14 @@ impl Decoder {
 				Typ::LengthDelimited => {
 					_size = self.extract_ld(buf, dst)?;
 				}
+				Typ::StartGroup => {
+					_size = self.extract_group(buf, dst)?;
+				}
+				Typ::EndGroup => {
+					// an end group key is only valid inside of a group
+					self.reset();
+					return Err(DecoderError::InvalidInput);
+				}
 			}
 			if _size == 0 {
 				break;
@@ -266,6 +276,32 @@ impl Decoder {
 		Ok(size)
 	}
 
+	/// Reads bytes of a group with wire type `3` from the provided `buf` and
+	/// writes the resulting bytes into `dst`.
+	///
+	/// This function consumes the buffer only if the decoding succeeds. The
+	/// provided vector will stay untouched in case of an error or insufficient
+	/// data.
+	///
+	/// On success, the number of written bytes is returned otherwise an error
+	/// is thrown.
+	fn extract_group(
+		&mut self,
+		buf: &mut Vec<u8>,
+		dst: &mut Vec<(u32, Typ, Vec<u8>)>,
+	) -> Result<usize, DecoderError> {
+		let mut bytes = vec![];
+		let size = match extract_group(buf, self.key.0, &mut bytes) {
+			Ok(size) => size,
+			Err(DecoderError::InputUnderflow) => return Ok(0),
+			Err(e) => return Err(e),
+		};
+		dst.push((self.key.0, self.key.1, bytes));
+		buf.drain(..size);
+		self.reset();
+		Ok(size)
+	}
+
 	/// Resets the decoder and flushes all memoried data.
 	fn reset(&mut self) {
 		self.key = (0, Typ::Unknown);
@@ -400,4 +436,21 @@ mod test {
 		let mut src = vec![0];
 		assert!(decoder.decode(&mut src, &mut dst).is_err()); // handles errors
 	}
+
+	/// Should decode groups in chunks and reject unmatched end group keys.
+	#[test]
+	fn decodes_groups() {
+		let mut decoder = Decoder::default();
+		let mut dst = vec![];
+		let mut src = vec![0x0b, 0x10, 0x96];
+		assert_eq!(decoder.decode(&mut src, &mut dst).unwrap(), 1); // key only
+		assert!(dst.is_empty());
+		src.extend([0x01, 0x0c, 0x18, 0x01]);
+		assert_eq!(decoder.decode(&mut src, &mut dst).unwrap(), 6);
+		assert_eq!(dst[0], (1, Typ::StartGroup, vec![0x10, 0x96, 0x01]));
+		assert_eq!(dst[1], (3, Typ::Varint, vec![0x01]));
+		assert!(src.is_empty());
+		let mut src = vec![0x0c];
+		assert!(decoder.decode(&mut src, &mut dst).is_err());
+	}
 }
@@ -2,6 +2,7 @@ pub use super::DecoderError;
 use super::Typ;
 use crate::proto::Encoder;
 use core::convert::TryFrom;
+use core::ops::Range;
 
 /// Decodes a LEB128 encoded number from the provided `buf` and writes the
 /// resulting bytes into `dst`.
@@ -32,6 +33,10 @@ pub fn decode_varint(buf: &[u8], dst: &mut u64) -> Result<usize, DecoderError> {
 	let mut value: u64 = 0;
 	let mut count = 0;
 	loop {
+		// a 64-bit number takes at most `10` bytes on the wire
+		if count == 10 {
+			return Err(DecoderError::InvalidInput);
+		}
 		let byte = match buf.get(count) {
 			Some(b) => *b,
 			None => return Err(DecoderError::InputUnderflow),
@@ -82,6 +87,94 @@ pub fn decode_key(buf: &[u8], dst: &mut (u32, Typ)) -> Result<usize, DecoderErro
 	Ok(size)
 }
 
+/// The maximum depth of nested groups, deeper groups are rejected as invalid
+/// input.
+const GROUP_DEPTH_LIMIT: usize = 100;
+
+/// Locates the next field in the provided `buf` without copying it and writes
+/// its tag number, wire type and the range of its value bytes into `dst`.
+///
+/// The range of a length-delimited field excludes the length prefix and the
+/// range of a group excludes the end group key, so the value bytes are the same
+/// as returned by `extract_ld` and `extract_group`.
+///
+/// ```txt
+/// [key][len][value] ... length-delimited field
+///           ^^^^^^^ ... range written into `dst`
+/// ```
+///
+/// On success, the number of bytes of the whole field is returned otherwise an
+/// error is thrown. The error is `InputUnderflow` when `buf` holds only a part
+/// of the field.
+pub fn decode_field(buf: &[u8], dst: &mut (u32, Typ, Range<usize>)) -> Result<usize, DecoderError> {
+	locate_field(buf, dst, 0)
+}
+
+fn locate_field(
+	buf: &[u8],
+	dst: &mut (u32, Typ, Range<usize>),
+	depth: usize,
+) -> Result<usize, DecoderError> {
+	let mut key = (0, Typ::Unknown);
+	let mut start = decode_key(buf, &mut key)?;
+	let end = match key.1 {
+		Typ::Varint => {
+			let mut val = 0;
+			start + decode_varint(&buf[start..], &mut val)?
+		}
+		Typ::Bit32 => start + 4,
+		Typ::Bit64 => start + 8,
+		Typ::LengthDelimited => {
+			let mut len = 0;
+			start += decode_varint(&buf[start..], &mut len)?;
+			usize::try_from(len)
+				.ok()
+				.and_then(|len| start.checked_add(len))
+				.ok_or(DecoderError::InvalidInput)?
+		}
+		Typ::StartGroup => {
+			let mut len = 0;
+			let size = skip_group(&buf[start..], key.0, &mut len, depth + 1)?;
+			*dst = (key.0, key.1, start..start + len);
+			return Ok(start + size);
+		}
+		Typ::EndGroup | Typ::Unknown => return Err(DecoderError::InvalidInput),
+	};
+	if buf.len() < end {
+		return Err(DecoderError::InputUnderflow);
+	}
+
+	*dst = (key.0, key.1, start..end);
+	Ok(end)
+}
+
+/// Reads the fields of a group up to the end group key with the provided `tag`
+/// and writes the number of bytes of the fields into `dst`.
+///
+/// On success, the number of read bytes including the end group key is
+/// returned otherwise an error is thrown.
+fn skip_group(buf: &[u8], tag: u32, dst: &mut usize, depth: usize) -> Result<usize, DecoderError> {
+	if depth > GROUP_DEPTH_LIMIT {
+		return Err(DecoderError::InvalidInput);
+	}
+
+	let mut size = 0;
+	loop {
+		let mut key = (0, Typ::Unknown);
+		let key_size = decode_key(&buf[size..], &mut key)?;
+		if key.1 == Typ::EndGroup {
+			if key.0 != tag {
+				return Err(DecoderError::InvalidInput);
+			}
+			*dst = size;
+			return Ok(size + key_size);
+		}
+
+		let mut field = (0, Typ::Unknown, 0..0);
+		size += locate_field(&buf[size..], &mut field, depth)?;
+	}
+}
+
 /// Decodes an encoded `bool` value from the provided `buf` and writes the
 /// resulting bytes into `dst`.
 ///
@@ -619,6 +712,21 @@ pub fn extract_ld(buf: &[u8], size: u64, dst: &mut Vec<u8>) -> Result<usize, Dec
 	Ok(size)
 }
 
+/// Reads bytes for a group with the provided `tag` from the provided `buf`
+/// and writes the resulting bytes into `dst`.
+///
+/// The group starts after its start group key, the resulting bytes are the
+/// encoded fields of the group without the end group key.
+///
+/// On success, the number of read bytes including the end group key is
+/// returned otherwise an error is thrown.
+pub fn extract_group(buf: &[u8], tag: u32, dst: &mut Vec<u8>) -> Result<usize, DecoderError> {
+	let mut len = 0;
+	let size = skip_group(buf, tag, &mut len, 1)?;
+	dst.extend_from_slice(&buf[..len]);
+	Ok(size)
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -656,6 +764,53 @@ mod test {
 		assert_eq!(size, 3);
 	}
 
+	/// Should locate fields of every wire type without reading past them.
+	#[test]
+	fn decodes_field() {
+		let mut dst = (0, Typ::Unknown, 0..0);
+		let buf = vec![0x08, 0x96, 0x01, 0xff];
+		let size = decode_field(&buf, &mut dst).unwrap();
+		assert_eq!(dst, (1, Typ::Varint, 1..3));
+		assert_eq!(size, 3);
+		let buf = vec![0x12, 0x03, 0x66, 0x6f, 0x6f, 0xff];
+		let size = decode_field(&buf, &mut dst).unwrap();
+		assert_eq!(dst, (2, Typ::LengthDelimited, 2..5));
+		assert_eq!(size, 5);
+		let buf = vec![0x1d, 0x00, 0x00, 0x80, 0x3f];
+		let size = decode_field(&buf, &mut dst).unwrap();
+		assert_eq!(dst, (3, Typ::Bit32, 1..5));
+		assert_eq!(size, 5);
+		let buf = vec![0x12, 0x03, 0x66];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InputUnderflow));
+		let buf = vec![0x21, 0x00, 0x00];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InputUnderflow));
+		let buf = vec![0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InvalidInput));
+	}
+
+	/// Should read groups including nested groups up to their end group key.
+	#[test]
+	fn decodes_group() {
+		let mut dst = (0, Typ::Unknown, 0..0);
+		// group 1 { int32 2 = 150; group 3 { } }
+		let buf = vec![0x0b, 0x10, 0x96, 0x01, 0x1b, 0x1c, 0x0c, 0xff];
+		let size = decode_field(&buf, &mut dst).unwrap();
+		assert_eq!(dst, (1, Typ::StartGroup, 1..6));
+		assert_eq!(size, 7);
+		let mut bytes = vec![];
+		let size = extract_group(&buf[1..], 1, &mut bytes).unwrap();
+		assert_eq!(bytes, vec![0x10, 0x96, 0x01, 0x1b, 0x1c]);
+		assert_eq!(size, 6);
+		let buf = vec![0x0b, 0x10, 0x96, 0x01];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InputUnderflow));
+		let buf = vec![0x0b, 0x14];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InvalidInput));
+		let buf = vec![0x0c];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InvalidInput));
+		let buf = vec![0x0b; 200];
+		assert_eq!(decode_field(&buf, &mut dst), Err(DecoderError::InvalidInput));
+	}
+
 	/// Should decode an encoded numeric value into `bool` data type.
 	#[test]
 	fn decodes_bool() {
@@ -0,0 +1,238 @@
+use super::{DecoderError, Typ, primitives::decode_field};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Provides an iterator over the fields of a complete `proto3` encoded
+/// message.
+///
+/// The fields are tuples of format `(tag, type, bytes)` where the returned
+/// `bytes` borrow the encoded value from the message, nothing is copied. A
+/// message held in `Bytes` can turn a value into `Bytes` with `slice_ref`.
+///
+/// ```rust
+/// use binary::proto::{Fields, Typ, decode_int32};
+///
+/// let buf = [0x08, 0x96, 0x01, 0x12, 0x03, 0x66, 0x6f, 0x6f];
+///
+/// for field in Fields::new(&buf) {
+///     let (tag, typ, byt) = field.unwrap();
+///     if tag == 1 && typ == Typ::Varint {
+///         let mut val = 0;
+///         decode_int32(byt, &mut val).unwrap();
+///         assert_eq!(val, 150);
+///     }
+/// }
+/// ```
+///
+/// The iterator stops after the first error, a message ending in a partial
+/// field yields `InputUnderflow`.
+pub struct Fields<'a> {
+	/// The bytes of the message that are not read yet.
+	buf: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+	/// Returns an iterator over the fields of the provided message.
+	pub fn new(buf: &'a [u8]) -> Self {
+		Self {
+			buf,
+		}
+	}
+
+	/// Returns the bytes of the message that are not read yet.
+	pub fn remaining(&self) -> &'a [u8] {
+		self.buf
+	}
+}
+
+impl<'a> Iterator for Fields<'a> {
+	type Item = Result<(u32, Typ, &'a [u8]), DecoderError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.buf.is_empty() {
+			return None;
+		}
+
+		let mut field = (0, Typ::Unknown, 0..0);
+		match decode_field(self.buf, &mut field) {
+			Ok(size) => {
+				let value = &self.buf[field.2];
+				self.buf = &self.buf[size..];
+				Some(Ok((field.0, field.1, value)))
+			}
+			Err(e) => {
+				self.buf = &[];
+				Some(Err(e))
+			}
+		}
+	}
+}
+
+/// Provides an incremental decoding engine for Protocol Buffers which reads
+/// fields from `Bytes`.
+///
+/// Chunks of a message are fed in as they arrive and complete fields are
+/// split off the front of the buffer, the returned values share the memory of
+/// the buffer so neither the values are copied nor is the buffer shifted. A
+/// partial field stays buffered until the next chunk completes it.
+///
+/// ```rust
+/// use binary::proto::{BytesDecoder, Typ};
+///
+/// let mut decoder = BytesDecoder::default();
+///
+/// decoder.feed(&[0x12, 0x03, 0x66][..]);
+/// assert_eq!(decoder.decode_field().unwrap(), None);
+///
+/// decoder.feed(&[0x6f, 0x6f][..]);
+/// let (tag, typ, byt) = decoder.decode_field().unwrap().unwrap();
+/// assert_eq!((tag, typ, &byt[..]), (2, Typ::LengthDelimited, &b"foo"[..]));
+/// ```
+#[derive(Default)]
+pub struct BytesDecoder {
+	/// The fed bytes that are not decoded yet.
+	buf: BytesMut,
+}
+
+impl BytesDecoder {
+	/// Appends a chunk of the encoded message to the buffer.
+	pub fn feed<B: Buf>(&mut self, src: B) {
+		self.buf.put(src);
+	}
+
+	/// Decodes the next complete field from the buffer.
+	///
+	/// The returned field is a tuple of format `(tag, type, bytes)` where the
+	/// `bytes` are a slice of the buffer holding the encoded value.
+	///
+	/// This function consumes the buffer only if the decoding succeeds. `None`
+	/// is returned when the buffer does not hold a complete field.
+	pub fn decode_field(&mut self) -> Result<Option<(u32, Typ, Bytes)>, DecoderError> {
+		let mut field = (0, Typ::Unknown, 0..0);
+		let size = match decode_field(&self.buf, &mut field) {
+			Ok(size) => size,
+			Err(DecoderError::InputUnderflow) => return Ok(None),
+			Err(e) => return Err(e),
+		};
+
+		let mut bytes = self.buf.split_to(size).freeze();
+		bytes.truncate(field.2.end);
+		bytes.advance(field.2.start);
+
+		Ok(Some((field.0, field.1, bytes)))
+	}
+
+	/// Decodes all complete fields from the buffer and writes the result into
+	/// `dst`.
+	///
+	/// On success, the number of read bytes is returned otherwise an error is
+	/// thrown. Fields decoded before the error are kept in `dst`.
+	pub fn decode(&mut self, dst: &mut Vec<(u32, Typ, Bytes)>) -> Result<usize, DecoderError> {
+		let total = self.buf.len();
+		while let Some(field) = self.decode_field()? {
+			dst.push(field);
+		}
+		Ok(total - self.buf.len())
+	}
+
+	/// Returns the number of buffered bytes that are not decoded yet.
+	pub fn remaining(&self) -> usize {
+		self.buf.len()
+	}
+
+	/// Returns `true` if the buffer does not hold a partial field.
+	pub fn is_empty(&self) -> bool {
+		self.buf.is_empty()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::proto::{DecoderLit, Encoder, EncoderLit};
+
+	fn message() -> Vec<u8> {
+		let encoder = Encoder;
+		let mut buf = vec![];
+		encoder.encode((&1, &150i32), &mut buf).unwrap();
+		encoder.encode((&2, &b"foo".to_vec()), &mut buf).unwrap();
+		encoder.encode((&3, EncoderLit::Float(&1.0f32)), &mut buf).unwrap();
+		encoder.encode((&4, EncoderLit::SInt64(&-10i64)), &mut buf).unwrap();
+		encoder.encode((&5, &vec![7u8; 300]), &mut buf).unwrap();
+		buf
+	}
+
+	/// Should borrow the fields of a complete message.
+	#[test]
+	fn iterates_fields() {
+		let buf = message();
+		let fields = Fields::new(&buf).collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(fields.len(), 5);
+		assert_eq!(fields[0], (1, Typ::Varint, &[0x96, 0x01][..]));
+		assert_eq!(fields[1], (2, Typ::LengthDelimited, &b"foo"[..]));
+		assert_eq!(f32::from(DecoderLit::Float(fields[2].2.to_vec())), 1.0);
+		assert_eq!(i64::from(DecoderLit::SInt64(fields[3].2.to_vec())), -10);
+		assert_eq!(fields[4].2, &[7u8; 300][..]);
+
+		let mut fields = Fields::new(&buf[..buf.len() - 1]);
+		assert_eq!(fields.by_ref().filter(|field| field.is_ok()).count(), 4);
+		assert_eq!(fields.next(), None);
+		let mut fields = Fields::new(&buf[..buf.len() - 1]);
+		assert_eq!(fields.nth(4), Some(Err(DecoderError::InputUnderflow)));
+		assert!(Fields::new(&[0]).next().unwrap().is_err());
+	}
+
+	/// Should decode the same fields regardless of how the message is split
+	/// into chunks.
+	#[test]
+	fn decodes_chunks() {
+		let buf = message();
+		let expected = Fields::new(&buf)
+			.map(|field| field.map(|(tag, typ, byt)| (tag, typ, Bytes::copy_from_slice(byt))))
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+
+		for chunk in [1, 2, 3, 7, buf.len()] {
+			let mut decoder = BytesDecoder::default();
+			let mut dst = vec![];
+			let mut total = 0;
+			for src in buf.chunks(chunk) {
+				decoder.feed(src);
+				total += decoder.decode(&mut dst).unwrap();
+			}
+			assert_eq!(dst, expected);
+			assert_eq!(total, buf.len());
+			assert!(decoder.is_empty());
+		}
+
+		let mut decoder = BytesDecoder::default();
+		decoder.feed(&buf[..3]);
+		let mut dst = vec![];
+		assert_eq!(decoder.decode(&mut dst), Ok(3));
+		assert_eq!(decoder.remaining(), 0);
+		decoder.feed(&[0x12, 0x03][..]);
+		assert_eq!(decoder.decode_field(), Ok(None));
+		assert_eq!(decoder.remaining(), 2);
+		decoder.feed(&[0][..]);
+		assert_eq!(decoder.decode_field(), Ok(None));
+
+		let mut decoder = BytesDecoder::default();
+		decoder.feed(&[0][..]);
+		assert!(decoder.decode_field().is_err());
+	}
+
+	/// Should return values that share the memory of the buffer.
+	#[test]
+	fn shares_memory() {
+		let buf = message();
+		let mut decoder = BytesDecoder::default();
+		decoder.feed(&buf[..]);
+		let mut dst = vec![];
+		decoder.decode(&mut dst).unwrap();
+
+		let (_, _, first) = &dst[1];
+		let (_, _, last) = &dst[4];
+		let offset = last.as_ptr() as usize - first.as_ptr() as usize;
+		let start = buf.len() - 300 - offset;
+		assert_eq!(&buf[start..start + first.len()], &first[..]);
+	}
+}
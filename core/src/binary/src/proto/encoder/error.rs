@@ -18,6 +18,10 @@ pub enum EncoderError {
 	/// invalid tag number. A tag number must be unique per message and the
 	/// value can be between `1` and `2^29 - 1`.
 	InvalidTag,
+
+	/// Indicates that the encoder was unable to write a field with a wire type
+	/// that can not start a field, like the end group or an unknown wire type.
+	InvalidType,
 }
 
 impl From<io::Error> for EncoderError {
@@ -39,6 +43,7 @@ impl fmt::Display for EncoderError {
 			Self::DataOverflow => write!(fmt, "Available data type size exceeded."),
 			Self::Interrupted => write!(fmt, "Write operation interrupted."),
 			Self::InvalidTag => write!(fmt, "Found tag with invalid number."),
+			Self::InvalidType => write!(fmt, "Found field with invalid wire type."),
 		}
 	}
 }
@@ -35,10 +35,10 @@ mod error;
 mod lit;
 mod primitives;
 
-use super::Typ;
+use super::{Typ, UnknownFields};
 pub use error::*;
 pub use lit::*;
-use primitives::*;
+pub use primitives::*;
 use std::io;
 
 /// Provides the encoding engine for Protocol Buffers.
@@ -616,6 +616,47 @@ impl Encoder {
 		}
 		self.encode_bytes(tag, &data, dst)
 	}
+
+	/// Writes the provided unknown `fields` back into `dst`.
+	///
+	/// The values are written verbatim, length-delimited values are prefixed
+	/// with their length and groups are closed with an end group key.
+	///
+	/// On success the number of written bytes is returned otherwise an error is
+	/// thrown.
+	pub fn encode_unknown<W>(
+		&self,
+		fields: &UnknownFields,
+		dst: &mut W,
+	) -> Result<usize, EncoderError>
+	where
+		W: ?Sized + io::Write,
+	{
+		let mut size = 0;
+		for (tag, typ, val) in fields {
+			match typ {
+				Typ::Varint | Typ::Bit32 | Typ::Bit64 => {
+					size += encode_key(*tag, *typ, dst)?;
+					dst.write_all(val)?;
+					size += val.len();
+				}
+				Typ::LengthDelimited => {
+					size += encode_key(*tag, *typ, dst)?;
+					size += encode_varint(val.len() as u64, dst)?;
+					dst.write_all(val)?;
+					size += val.len();
+				}
+				Typ::StartGroup => {
+					size += encode_key(*tag, *typ, dst)?;
+					dst.write_all(val)?;
+					size += val.len();
+					size += encode_key(*tag, Typ::EndGroup, dst)?;
+				}
+				Typ::EndGroup | Typ::Unknown => return Err(EncoderError::InvalidType),
+			}
+		}
+		Ok(size)
+	}
 }
 
 impl Default for Encoder {
@@ -80,7 +80,9 @@
 pub mod decoder;
 pub mod encoder;
 mod typ;
+mod unknown;
 
 pub use decoder::*;
 pub use encoder::*;
 pub use typ::*;
+pub use unknown::*;
@@ -15,6 +15,13 @@ pub enum Typ {
     /// `string`, `bytes`, `embedded messages` and `packed repeated fields`.
     LengthDelimited = 2,
 
+    /// Represents the wire type `3` which starts a `group` of proto2. The
+    /// fields of the group follow until the matching end group key.
+    StartGroup = 3,
+
+    /// Represents the wire type `4` which ends a `group` of proto2.
+    EndGroup = 4,
+
     /// Represents the wire type `5` which allows for encoding of data formats
     /// `fixed32`, `sfixed32` and `float`.
     Bit32 = 5,
@@ -31,6 +38,8 @@ impl TryFrom<u64> for Typ {
             0 => Ok(Typ::Varint),
             1 => Ok(Typ::Bit64),
             2 => Ok(Typ::LengthDelimited),
+            3 => Ok(Typ::StartGroup),
+            4 => Ok(Typ::EndGroup),
             5 => Ok(Typ::Bit32),
             _ => Ok(Typ::Unknown),
         }
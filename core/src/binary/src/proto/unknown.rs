@@ -0,0 +1,283 @@
+use super::{DecoderError, Fields, Typ, decode_key};
+use bytes::Buf;
+
+/// The maximum depth of nested groups, deeper groups are rejected as invalid
+/// input.
+const GROUP_DEPTH_LIMIT: usize = 100;
+
+/// Provides a set of fields that a message does not know.
+///
+/// The fields are tuples of format `(tag, type, bytes)` as returned by the
+/// `Decoder`, the `bytes` are the encoded value which is kept untouched. The
+/// value of a length-delimited field excludes the length prefix and the value
+/// of a group excludes the end group key. The `Encoder` writes the fields back
+/// with `encode_unknown`.
+///
+/// ```rust
+/// use binary::proto::{Encoder, UnknownFields};
+///
+/// let buf = vec![0x08, 0x96, 0x01, 0x0b, 0x10, 0x01, 0x0c];
+///
+/// let fields = UnknownFields::decode(&buf).unwrap();
+/// assert_eq!(fields.len(), 2);
+///
+/// let mut dst = vec![];
+/// Encoder.encode_unknown(&fields, &mut dst).unwrap();
+/// assert_eq!(dst, buf);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnknownFields {
+	/// The unknown fields in the order they were read.
+	fields: Vec<(u32, Typ, Vec<u8>)>,
+}
+
+impl UnknownFields {
+	/// Returns an empty set.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Decodes all fields of the provided message into a set.
+	pub fn decode(buf: &[u8]) -> Result<Self, DecoderError> {
+		let mut fields = Self::new();
+		for field in Fields::new(buf) {
+			let (tag, typ, byt) = field?;
+			fields.push((tag, typ, byt.to_vec()));
+		}
+		Ok(fields)
+	}
+
+	/// Adds a field as returned by the `Decoder` to the set.
+	pub fn push(&mut self, field: (u32, Typ, Vec<u8>)) {
+		self.fields.push(field);
+	}
+
+	/// Reads the value of a field whose key has already been read from the
+	/// provided `buf` and adds the field to the set.
+	///
+	/// The value is copied byte by byte, so it is written back exactly as it was
+	/// received.
+	///
+	/// On success, the number of read bytes is returned otherwise an error is
+	/// thrown.
+	pub fn merge<B: Buf>(
+		&mut self,
+		tag: u32,
+		typ: Typ,
+		buf: &mut B,
+	) -> Result<usize, DecoderError> {
+		let mut bytes = vec![];
+		let size = match typ {
+			Typ::LengthDelimited => {
+				let mut prefix = vec![];
+				let len = read_varint(buf, &mut prefix)?;
+				prefix.len() + read_bytes(buf, len, &mut bytes)?
+			}
+			Typ::StartGroup => read_group(buf, tag, &mut bytes, 1)?,
+			typ => read_value(buf, typ, &mut bytes)?,
+		};
+		self.fields.push((tag, typ, bytes));
+		Ok(size)
+	}
+
+	/// Returns an iterator over the fields of the set.
+	pub fn iter(&self) -> std::slice::Iter<'_, (u32, Typ, Vec<u8>)> {
+		self.fields.iter()
+	}
+
+	/// Returns the number of fields in the set.
+	pub fn len(&self) -> usize {
+		self.fields.len()
+	}
+
+	/// Returns `true` if the set does not hold any field.
+	pub fn is_empty(&self) -> bool {
+		self.fields.is_empty()
+	}
+
+	/// Removes all fields from the set.
+	pub fn clear(&mut self) {
+		self.fields.clear();
+	}
+}
+
+impl<'a> IntoIterator for &'a UnknownFields {
+	type Item = &'a (u32, Typ, Vec<u8>);
+	type IntoIter = std::slice::Iter<'a, (u32, Typ, Vec<u8>)>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.fields.iter()
+	}
+}
+
+impl Extend<(u32, Typ, Vec<u8>)> for UnknownFields {
+	fn extend<T: IntoIterator<Item = (u32, Typ, Vec<u8>)>>(&mut self, iter: T) {
+		self.fields.extend(iter);
+	}
+}
+
+/// Reads a varint from the provided `buf`, the read bytes are written into
+/// `dst`.
+fn read_varint<B: Buf>(buf: &mut B, dst: &mut Vec<u8>) -> Result<u64, DecoderError> {
+	let mut value: u64 = 0;
+	for count in 0..10 {
+		if !buf.has_remaining() {
+			return Err(DecoderError::InputUnderflow);
+		}
+		let byte = buf.get_u8();
+		dst.push(byte);
+		value |= u64::from(byte & 0x7F) << (7 * count);
+		if byte <= 0x7F {
+			return Ok(value);
+		}
+	}
+	Err(DecoderError::InvalidInput)
+}
+
+/// Reads `len` bytes from the provided `buf` into `dst`.
+fn read_bytes<B: Buf>(buf: &mut B, len: u64, dst: &mut Vec<u8>) -> Result<usize, DecoderError> {
+	let len = usize::try_from(len).map_err(|_| DecoderError::InvalidInput)?;
+	if buf.remaining() < len {
+		return Err(DecoderError::InputUnderflow);
+	}
+	let start = dst.len();
+	dst.resize(start + len, 0);
+	buf.copy_to_slice(&mut dst[start..]);
+	Ok(len)
+}
+
+/// Reads the encoded value of a field with the provided wire type, a
+/// length-delimited value is read together with its length prefix.
+fn read_value<B: Buf>(buf: &mut B, typ: Typ, dst: &mut Vec<u8>) -> Result<usize, DecoderError> {
+	let start = dst.len();
+	match typ {
+		Typ::Varint => {
+			read_varint(buf, dst)?;
+		}
+		Typ::Bit32 => {
+			read_bytes(buf, 4, dst)?;
+		}
+		Typ::Bit64 => {
+			read_bytes(buf, 8, dst)?;
+		}
+		Typ::LengthDelimited => {
+			let len = read_varint(buf, dst)?;
+			read_bytes(buf, len, dst)?;
+		}
+		Typ::StartGroup | Typ::EndGroup | Typ::Unknown => {
+			return Err(DecoderError::InvalidInput);
+		}
+	}
+	Ok(dst.len() - start)
+}
+
+/// Reads the fields of a group up to the end group key with the provided
+/// `tag` and writes them into `dst`, the end group key is only written for
+/// nested groups.
+///
+/// On success, the number of read bytes including the end group key is
+/// returned otherwise an error is thrown.
+fn read_group<B: Buf>(
+	buf: &mut B,
+	tag: u32,
+	dst: &mut Vec<u8>,
+	depth: usize,
+) -> Result<usize, DecoderError> {
+	if depth > GROUP_DEPTH_LIMIT {
+		return Err(DecoderError::InvalidInput);
+	}
+
+	let start = dst.len();
+	loop {
+		let mut key = vec![];
+		read_varint(buf, &mut key)?;
+		let mut field = (0, Typ::Unknown);
+		decode_key(&key, &mut field)?;
+
+		match field.1 {
+			Typ::EndGroup if field.0 == tag => {
+				let size = dst.len() - start + key.len();
+				if depth > 1 {
+					dst.extend_from_slice(&key);
+				}
+				return Ok(size);
+			}
+			Typ::EndGroup => return Err(DecoderError::InvalidInput),
+			Typ::StartGroup => {
+				dst.extend_from_slice(&key);
+				read_group(buf, field.0, dst, depth + 1)?;
+			}
+			typ => {
+				dst.extend_from_slice(&key);
+				read_value(buf, typ, dst)?;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::proto::{Decoder, Encoder, EncoderError};
+
+	/// Should read the values of fields whose key has been read already.
+	#[test]
+	fn merges_fields() {
+		let mut fields = UnknownFields::new();
+		let mut buf = &[0x96, 0x01, 0x03, 0x66, 0x6f, 0x6f, 0x00, 0x00, 0x80, 0x3f][..];
+		assert_eq!(fields.merge(1, Typ::Varint, &mut buf), Ok(2));
+		assert_eq!(fields.merge(2, Typ::LengthDelimited, &mut buf), Ok(4));
+		assert_eq!(fields.merge(3, Typ::Bit32, &mut buf), Ok(4));
+		assert!(buf.is_empty());
+
+		// group 4 { int32 1 = 1; group 2 { bytes 3 = "a"; } }
+		let mut buf = &[0x08, 0x01, 0x13, 0x1a, 0x01, 0x61, 0x14, 0x24, 0xff][..];
+		assert_eq!(fields.merge(4, Typ::StartGroup, &mut buf), Ok(8));
+		assert_eq!(buf, &[0xff]);
+
+		let expected = vec![
+			(1, Typ::Varint, vec![0x96, 0x01]),
+			(2, Typ::LengthDelimited, b"foo".to_vec()),
+			(3, Typ::Bit32, vec![0x00, 0x00, 0x80, 0x3f]),
+			(4, Typ::StartGroup, vec![0x08, 0x01, 0x13, 0x1a, 0x01, 0x61, 0x14]),
+		];
+		assert_eq!(fields.iter().cloned().collect::<Vec<_>>(), expected);
+
+		let mut buf = &[0x08, 0x01, 0x2c][..];
+		assert_eq!(fields.merge(4, Typ::StartGroup, &mut buf), Err(DecoderError::InvalidInput));
+		let mut buf = &[0x03, 0x66][..];
+		assert_eq!(
+			fields.merge(2, Typ::LengthDelimited, &mut buf),
+			Err(DecoderError::InputUnderflow)
+		);
+		let mut buf = &[0x0b; 200][..];
+		assert_eq!(fields.merge(1, Typ::StartGroup, &mut buf), Err(DecoderError::InvalidInput));
+		assert_eq!(fields.merge(1, Typ::EndGroup, &mut &[][..]), Err(DecoderError::InvalidInput));
+	}
+
+	/// Should write the fields filled by the decoder back verbatim.
+	#[test]
+	fn round_trips() {
+		let buf = vec![
+			0x08, 0x96, 0x01, 0x12, 0x03, 0x66, 0x6f, 0x6f, 0x1d, 0x00, 0x00, 0x80, 0x3f, 0x23,
+			0x08, 0x01, 0x13, 0x1a, 0x01, 0x61, 0x14, 0x24, 0x29, 0x01, 0x00, 0x00, 0x00, 0x00,
+			0x00, 0x00, 0x00,
+		];
+
+		let mut decoder = Decoder::default();
+		let mut src = buf.clone();
+		let mut dst = vec![];
+		decoder.decode(&mut src, &mut dst).unwrap();
+		let mut fields = UnknownFields::new();
+		fields.extend(dst);
+		assert_eq!(fields, UnknownFields::decode(&buf).unwrap());
+
+		let mut out = vec![];
+		assert_eq!(Encoder.encode_unknown(&fields, &mut out), Ok(buf.len()));
+		assert_eq!(out, buf);
+
+		let mut fields = UnknownFields::new();
+		fields.push((1, Typ::EndGroup, vec![]));
+		assert_eq!(Encoder.encode_unknown(&fields, &mut out), Err(EncoderError::InvalidType));
+	}
+}
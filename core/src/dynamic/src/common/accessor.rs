@@ -143,6 +143,14 @@ impl<'a> ValueAccessor<'a> {
 		}
 	}
 
+	/// Returns the content of a binary value, like an uploaded file
+	pub fn bytes(&self) -> SeaResult<&'a [u8]> {
+		match self.0 {
+			Value::Bytes(b) => Ok(b),
+			_ => Err(SeaographyError::new("internal: not bytes")),
+		}
+	}
+
 	// Returns the object accessor
 	pub fn object(&self) -> SeaResult<ObjectAccessor<'a>> {
 		if let Value::Map(obj) = self.0 {
@@ -196,7 +204,7 @@ impl<'a> ValueAccessor<'a> {
 }
 
 /// A object accessor
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct ObjectAccessor<'a>(pub(crate) Cow<'a, BTreeMap<Value, Value>>);
 
 impl<'a> ObjectAccessor<'a> {
@@ -124,6 +124,7 @@ impl Debug for Data {
 pub enum ApiType {
 	GraphQL,
 	Proto,
+	Thrift,
 }
 
 /// Query context.
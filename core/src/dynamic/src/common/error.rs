@@ -5,7 +5,7 @@ use crate::{
 	prelude::{GraphQLServerError, ProtoError},
 };
 use binary::proto::{DecoderError, EncoderError};
-use juniper::{FieldError, IntoFieldError};
+use juniper::{FieldError, IntoFieldError, ScalarValue, Value as JuniperValue};
 use sea_orm::sqlx::types::uuid;
 use thiserror::Error;
 
@@ -42,11 +42,28 @@ pub enum SeaographyError {
 	EncoderError(EncoderError),
 	#[error("[db] {0}")]
 	DB(sea_orm::DbErr),
+	#[error("[conflict] {0}")]
+	Conflict(String),
+	#[error("[forbidden] {0}")]
+	Forbidden(String),
+	#[error("[not found] {0}")]
+	NotFound(String),
 }
 
-impl<S> IntoFieldError<S> for SeaographyError {
+impl<S> IntoFieldError<S> for SeaographyError
+where
+	S: ScalarValue,
+{
 	fn into_field_error(self) -> FieldError<S> {
-		FieldError::from(self.to_string())
+		match self.code() {
+			Some(code) => FieldError::new(
+				self.to_string(),
+				JuniperValue::object(
+					[("code", JuniperValue::scalar(code.to_string()))].into_iter().collect(),
+				),
+			),
+			None => FieldError::from(self.to_string()),
+		}
 	}
 }
 
@@ -54,6 +71,16 @@ impl SeaographyError {
 	pub fn new(message: impl Into<String>) -> Self {
 		Self::Custom(message.into())
 	}
+
+	/// used to get the code clients can match the error on
+	pub fn code(&self) -> Option<&'static str> {
+		match self {
+			Self::Conflict(_) => Some("CONFLICT"),
+			Self::Forbidden(_) => Some("FORBIDDEN"),
+			Self::NotFound(_) => Some("NOT_FOUND"),
+			_ => None,
+		}
+	}
 }
 
 impl From<GraphQLServerError> for SeaographyError {
@@ -1,6 +1,10 @@
 use crate::{ContextBase, ObjectAccessor, SeaResult, SeaographyError, Value};
 use bytes::BufMut;
-use futures::{FutureExt, future::BoxFuture};
+use futures::{
+	FutureExt, Stream, StreamExt, TryStreamExt,
+	future::BoxFuture,
+	stream::{BoxStream, once},
+};
 use std::{any::Any, borrow::Cow, ops::Deref, pin::Pin};
 
 //// A value returned from the resolver function
@@ -300,6 +304,37 @@ impl<'a> FieldFuture<'a> {
 pub(crate) type BoxResolverFn =
 	Box<(dyn for<'a> Fn(ResolverContext<'a>) -> FieldFuture<'a> + Send + Sync)>;
 
+type BoxFieldStream<'a> = BoxStream<'a, SeaResult<FieldValue<'static>>>;
+
+/// A stream future that returned from subscription field resolver
+pub struct SubscriptionFieldFuture<'a>(pub(crate) BoxFuture<'a, SeaResult<BoxFieldStream<'a>>>);
+
+impl<'a> SubscriptionFieldFuture<'a> {
+	/// Create a `SubscriptionFieldFuture` from a `Future` resolving into a `Stream`
+	pub fn new<Fut, S, T>(future: Fut) -> Self
+	where
+		Fut: Future<Output = SeaResult<S>> + Send + 'a,
+		S: Stream<Item = SeaResult<T>> + Send + 'a,
+		T: Into<FieldValue<'static>> + Send + 'a,
+	{
+		Self(
+			async move {
+				let stream = future.await?;
+				Ok(stream.map_ok(Into::into).boxed())
+			}
+			.boxed(),
+		)
+	}
+
+	/// Create a `SubscriptionFieldFuture` yielding a single `Value`
+	pub fn from_value(value: Value) -> Self {
+		Self(async move { Ok(once(async move { Ok(FieldValue::from(value)) }).boxed()) }.boxed())
+	}
+}
+
+pub(crate) type BoxSubscriptionResolverFn =
+	Box<dyn for<'a> Fn(ResolverContext<'a>) -> SubscriptionFieldFuture<'a> + Send + Sync>;
+
 pub(crate) type BoxFieldFutureJson<'a> =
 	Pin<Box<dyn Future<Output = SeaResult<(Value, Value)>> + 'a + Send>>;
 
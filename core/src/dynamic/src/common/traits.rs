@@ -69,7 +69,10 @@ pub trait TypeRefTrait: Sized {
 	const STRING: &'static str;
 	const BYTES: &'static str;
 	const ID: &'static str;
-	// const UPLOAD: &'static str;
+	const UPLOAD: &'static str;
+	const TIMESTAMP: &'static str;
+	const JSON: &'static str;
+	const FIELD_MASK: &'static str;
 }
 
 pub trait ErrorTrait {
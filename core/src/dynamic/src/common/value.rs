@@ -38,6 +38,9 @@ pub enum Value {
 	List(Vec<Value>),
 	Map(BTreeMap<Value, Value>),
 
+	// Binary, declared after the lists so arrays are never deserialized into bytes
+	Bytes(Vec<u8>),
+
 	// Optional
 	Option(Box<Option<Value>>),
 
@@ -69,6 +72,7 @@ impl Value {
 			Value::String(_) => "string",
 			Value::List(_) => "list",
 			Value::Map(_) => "map",
+			Value::Bytes(_) => "bytes",
 			Value::Option(_) => "option",
 			Value::Var(_) => "var",
 			Value::Null => "null",
@@ -167,6 +171,7 @@ impl fmt::Display for Value {
 			Value::String(data) => write!(fmt, "String: ({:?})", data),
 			Value::List(data) => write!(fmt, "list: ({:?})", data),
 			Value::Map(data) => write!(fmt, "map: ({:?})", data),
+			Value::Bytes(data) => write!(fmt, "bytes: ({} bytes)", data.len()),
 			Value::Option(data) => write!(fmt, "option: ({:?})", data),
 			Value::Var(data) => write!(fmt, "var: ({:?})", data),
 			Value::Null => write!(fmt, "null"),
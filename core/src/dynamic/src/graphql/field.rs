@@ -1,15 +1,17 @@
 use super::{
-	Argument, DeprecationStatus, JuniperField, Registry, SelectionSet, TYPE_REGISTRY, TypeRef,
-	TypeRefToMeta,
+	Argument, DeprecationStatus, JuniperField, Registry, SelectionSet, TypeRef, TypeRefToMeta,
+	TypeRegistry,
 };
 use crate::{
-	BoxFieldFutureJson, BoxResolverFn, ContextBase, FieldFuture, FieldValue, FieldValueInner,
-	ObjectAccessor, ResolverContext, SeaResult, SeaographyError, Value,
+	BoxFieldFutureJson, BoxResolverFn, ComputeComplexityFn, ContextBase, FieldFuture, FieldValue,
+	FieldValueInner, ObjectAccessor, ResolverContext, SeaResult, SeaographyError, Value,
 };
 use futures::FutureExt;
 use std::{
+	borrow::Cow,
 	collections::BTreeMap,
 	fmt::{self, Debug},
+	sync::Arc,
 };
 
 /// A GraphQL field
@@ -19,7 +21,13 @@ pub struct Field {
 	pub(crate) arguments: BTreeMap<String, Field>,
 	pub(crate) ty: TypeRef,
 	pub(crate) resolver_fn: Option<BoxResolverFn>,
+	pub(crate) compute_complexity: Option<ComputeComplexityFn>,
 	pub(crate) deprecation: DeprecationStatus,
+	pub(crate) external: bool,
+	pub(crate) requires: Option<String>,
+	pub(crate) provides: Option<String>,
+	pub(crate) shareable: bool,
+	pub(crate) override_from: Option<String>,
 	pub(crate) inaccessible: bool,
 	pub(crate) default_value: Option<Value>,
 }
@@ -50,10 +58,16 @@ impl Field {
 			description: None,
 			ty,
 			default_value: None,
+			external: false,
+			requires: None,
+			provides: None,
+			shareable: false,
+			override_from: None,
 			inaccessible: false,
 			deprecation: DeprecationStatus::Current,
 			arguments: Default::default(),
 			resolver_fn: None,
+			compute_complexity: None,
 		}
 	}
 
@@ -71,7 +85,13 @@ impl Field {
 			arguments: Default::default(),
 			ty,
 			resolver_fn: Some(Box::new(resolver_fn)),
+			compute_complexity: None,
 			deprecation: DeprecationStatus::Current,
+			external: false,
+			requires: None,
+			provides: None,
+			shareable: false,
+			override_from: None,
 			inaccessible: false,
 			default_value: None,
 		}
@@ -79,8 +99,46 @@ impl Field {
 
 	impl_set_description!();
 	impl_set_deprecation!();
+	impl_set_external!();
+	impl_set_shareable!();
 	impl_set_inaccessible!();
 
+	/// Indicate the fields of the entity that are required to resolve this field when using
+	/// Apollo Federation
+	///
+	/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#requires>
+	#[inline]
+	pub fn requires(self, fields: impl Into<String>) -> Self {
+		Self {
+			requires: Some(fields.into()),
+			..self
+		}
+	}
+
+	/// Indicate the fields of the returned entity this subgraph can resolve when using Apollo
+	/// Federation
+	///
+	/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#provides>
+	#[inline]
+	pub fn provides(self, fields: impl Into<String>) -> Self {
+		Self {
+			provides: Some(fields.into()),
+			..self
+		}
+	}
+
+	/// Indicate that this subgraph resolves the field instead of the given subgraph when using
+	/// Apollo Federation
+	///
+	/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#override>
+	#[inline]
+	pub fn override_from(self, name: impl Into<String>) -> Self {
+		Self {
+			override_from: Some(name.into()),
+			..self
+		}
+	}
+
 	/// Set the default value
 	#[inline]
 	pub fn default_value(self, value: impl Into<Value>) -> Self {
@@ -90,6 +148,20 @@ impl Field {
 		}
 	}
 
+	/// Set the complexity of the field, the function receives the arguments of the field and the
+	/// complexity of its selection set
+	///
+	/// By default a field costs `1 + child complexity`.
+	pub fn compute_complexity<F>(self, complexity_fn: F) -> Self
+	where
+		F: Fn(&ObjectAccessor<'_>, usize) -> SeaResult<usize> + Send + Sync + 'static,
+	{
+		Self {
+			compute_complexity: Some(Arc::new(complexity_fn)),
+			..self
+		}
+	}
+
 	/// Returns the type name
 	#[inline]
 	pub fn type_name(&self) -> &str {
@@ -105,88 +177,20 @@ impl Field {
 
 	pub(crate) async fn to_value<'a>(
 		&self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		selection_set: &'a SelectionSet,
 		val: &'a FieldValue<'a>,
-		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
 	) -> SeaResult<Value> {
-		match &val.0 {
-			FieldValueInner::Value(val) => match self.ty.type_name() {
-				TypeRef::INT
-				| TypeRef::FLOAT
-				| TypeRef::STRING
-				| TypeRef::BOOLEAN
-				| TypeRef::ID => Ok(val.to_owned()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.to_value(val),
-					None => {
-						Err(SeaographyError::new(format!("Unsupported type for field `{}`", name)))
-					}
-				},
-			},
-			FieldValueInner::List(values) => {
-				let mut list = Vec::new();
-				for value in values.iter() {
-					list.push(
-						Box::pin(self.to_value(ctx, selection_set, value, arguments, parent_value))
-							.await?,
-					);
-				}
-
-				Ok(Value::List(list))
-			}
-			FieldValueInner::OwnedAny(..) => match TYPE_REGISTRY.get(self.ty.type_name()) {
-				Some(inner) => {
-					let mut data = BTreeMap::new();
-					for field in inner.collect(ctx, selection_set, arguments, Some(val)) {
-						let (name, res) = field.await?;
-
-						data.insert(name, res);
-					}
-
-					Ok(Value::Map(data))
-				}
-				None => Ok(Value::Null),
-			},
-			FieldValueInner::BorrowedAny(..) => match TYPE_REGISTRY.get(self.ty.type_name()) {
-				Some(inner) => {
-					let mut data = BTreeMap::new();
-					for field in inner.collect(ctx, selection_set, arguments, Some(val)) {
-						let (name, res) = field.await?;
-
-						data.insert(name, res);
-					}
-
-					Ok(Value::Map(data))
-				}
-				None => Ok(Value::Null),
-			},
-			FieldValueInner::WithType {
-				value,
-				ty,
-			} => match TYPE_REGISTRY.get(ty) {
-				Some(inner) => {
-					inner.check(self.ty.type_name())?;
-					let mut data = BTreeMap::new();
-					for field in inner.collect(ctx, selection_set, arguments, Some(value)) {
-						let (name, res) = field.await?;
-
-						data.insert(name, res);
-					}
-
-					Ok(Value::Map(data))
-				}
-				None => Ok(Value::Null),
-			},
-		}
+		type_ref_to_value(&self.ty, types, ctx, selection_set, val, parent_value).await
 	}
 
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		selection_set: &'a SelectionSet,
-		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
 	) -> BoxFieldFutureJson<'a> {
 		async move {
@@ -198,7 +202,7 @@ impl Field {
 				let field_future = match &self.resolver_fn {
 					Some(resolver_fn) => (resolver_fn)(ResolverContext {
 						ctx,
-						args: arguments.clone(),
+						args: ObjectAccessor(Cow::Borrowed(&selection_set.arguments)),
 						parent_value: parent_val,
 					}),
 					None => FieldFuture::Value(FieldValue::NONE),
@@ -211,7 +215,7 @@ impl Field {
 
 				let value = match field_value {
 					Some(val) => {
-						self.to_value(ctx, selection_set, &val, arguments, parent_value).await?
+						self.to_value(types, ctx, selection_set, &val, parent_value).await?
 					}
 					None => match &self.default_value {
 						Some(value) => value.clone(),
@@ -234,10 +238,11 @@ impl Field {
 
 	pub fn meta_input<'r>(
 		&self,
+		types: &Arc<TypeRegistry>,
 		registry: &mut Registry<'r, Value>,
 	) -> Option<Argument<'r, Value>> {
 		if self.resolver_fn.is_none() {
-			let mut type_ref = TypeRefToMeta::new(self.type_name());
+			let mut type_ref = TypeRefToMeta::new(self.type_name(), types);
 			type_ref.from_type_ref(&self.ty);
 			Some(type_ref.to_input_meta(registry))
 		} else {
@@ -247,15 +252,16 @@ impl Field {
 
 	pub fn meta_output<'r>(
 		&self,
+		types: &Arc<TypeRegistry>,
 		registry: &mut Registry<'r, Value>,
 	) -> Option<JuniperField<'r, Value>> {
 		if self.resolver_fn.is_some() {
-			let mut type_ref = TypeRefToMeta::new(self.type_name());
+			let mut type_ref = TypeRefToMeta::new(self.type_name(), types);
 			type_ref.from_type_ref(&self.ty);
 			let mut field = type_ref.to_ouput_meta(registry);
 
 			for (_, arg) in self.arguments.iter().filter(|(_, p)| !p.inaccessible) {
-				let mut type_ref = TypeRefToMeta::new(arg.type_name());
+				let mut type_ref = TypeRefToMeta::new(arg.type_name(), types);
 				type_ref.from_type_ref(&arg.ty);
 				let arg = type_ref.to_input_meta(registry);
 				field = field.argument(arg);
@@ -267,3 +273,87 @@ impl Field {
 		}
 	}
 }
+
+/// used to convert a resolved field value into the value of the given type
+pub(crate) async fn type_ref_to_value<'a>(
+	type_ref: &TypeRef,
+	types: &'a TypeRegistry,
+	ctx: &'a ContextBase,
+	selection_set: &'a SelectionSet,
+	val: &'a FieldValue<'a>,
+	parent_value: Option<&'a FieldValue<'a>>,
+) -> SeaResult<Value> {
+	match &val.0 {
+		FieldValueInner::Value(Value::Null) => Ok(Value::Null),
+		FieldValueInner::Value(val) => match type_ref.type_name() {
+			TypeRef::INT | TypeRef::FLOAT | TypeRef::STRING | TypeRef::BOOLEAN | TypeRef::ID => {
+				Ok(val.to_owned())
+			}
+			name => match types.get(name) {
+				Some(ty) => ty.to_value(val),
+				None => Err(SeaographyError::new(format!("Unsupported type for field `{}`", name))),
+			},
+		},
+		FieldValueInner::List(values) => {
+			let mut list = Vec::new();
+			for value in values.iter() {
+				list.push(
+					Box::pin(type_ref_to_value(
+						type_ref,
+						types,
+						ctx,
+						selection_set,
+						value,
+						parent_value,
+					))
+					.await?,
+				);
+			}
+
+			Ok(Value::List(list))
+		}
+		FieldValueInner::OwnedAny(..) => match types.get(type_ref.type_name()) {
+			Some(inner) => {
+				let mut data = BTreeMap::new();
+				for field in inner.collect(types, ctx, selection_set, Some(val)) {
+					let (name, res) = field.await?;
+
+					data.insert(name, res);
+				}
+
+				Ok(Value::Map(data))
+			}
+			None => Ok(Value::Null),
+		},
+		FieldValueInner::BorrowedAny(..) => match types.get(type_ref.type_name()) {
+			Some(inner) => {
+				let mut data = BTreeMap::new();
+				for field in inner.collect(types, ctx, selection_set, Some(val)) {
+					let (name, res) = field.await?;
+
+					data.insert(name, res);
+				}
+
+				Ok(Value::Map(data))
+			}
+			None => Ok(Value::Null),
+		},
+		FieldValueInner::WithType {
+			value,
+			ty,
+		} => match types.get(ty) {
+			Some(inner) => {
+				inner.check(types, type_ref.type_name())?;
+				let mut data = BTreeMap::new();
+				for field in inner.collect(types, ctx, selection_set, Some(value)) {
+					let (name, res) = field.await?;
+
+					data.insert(name, res);
+				}
+
+				Ok(Value::Map(data))
+			}
+			None => Ok(Value::Null),
+		},
+	}
+}
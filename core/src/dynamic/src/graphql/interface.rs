@@ -1,10 +1,11 @@
 use super::{
 	DeprecationStatus, Directive, Field, GraphQLType, GraphQLValue, GraphQLValueAsync,
-	JuniperField, MetaType, Registry, TYPE_REGISTRY, Type, TypeRef, TypeRefToMeta,
+	JuniperField, MetaType, Registry, Type, TypeInfo, TypeRef, TypeRefToMeta, TypeRegistry,
 };
 use crate::{BoxFieldFutureJson, ContextBase, SeaResult, SeaographyError, Value};
 use futures::FutureExt;
 use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
 
 /// A GraphQL interface field type
 ///
@@ -125,13 +126,17 @@ impl InterfaceField {
 		self
 	}
 
-	pub fn meta<'r>(&self, registry: &mut Registry<'r, Value>) -> JuniperField<'r, Value> {
-		let mut type_ref = TypeRefToMeta::new(self.type_name());
+	pub fn meta<'r>(
+		&self,
+		types: &Arc<TypeRegistry>,
+		registry: &mut Registry<'r, Value>,
+	) -> JuniperField<'r, Value> {
+		let mut type_ref = TypeRefToMeta::new(self.type_name(), types);
 		type_ref.from_type_ref(&self.ty);
 		let mut field = type_ref.to_ouput_meta(registry);
 
 		for (_, arg) in self.arguments.iter().filter(|(_, p)| !p.inaccessible) {
-			let mut type_ref = TypeRefToMeta::new(arg.type_name());
+			let mut type_ref = TypeRefToMeta::new(arg.type_name(), types);
 			type_ref.from_type_ref(&arg.ty);
 			let arg = type_ref.to_input_meta(registry);
 			field = field.argument(arg);
@@ -193,14 +198,14 @@ impl Interface {
 		self
 	}
 
-	pub(crate) fn check(&self, type_name: &str) -> SeaResult<()> {
+	pub(crate) fn check(&self, types: &TypeRegistry, type_name: &str) -> SeaResult<()> {
 		if self.inaccessible {
 			return Err(SeaographyError::new(format!(
 				"interface `{}` is inaccessible",
 				self.type_name()
 			)));
 		}
-		if let Some(ty) = TYPE_REGISTRY.get(type_name) {
+		if let Some(ty) = types.get(type_name) {
 			if let Some(interface) = ty.as_interface() {
 				if self.implements.get(interface.type_name()).is_some() {
 					for interface_field in interface.fields.values() {
@@ -271,20 +276,25 @@ impl GraphQLType<Value> for Interface {
 	where
 		Value: 'r,
 	{
-		let implements = TYPE_REGISTRY.get_filtered(|_, ty| match &**ty {
-			Type::Object(obj) => obj.implements.contains(&info.name),
-			Type::Interface(interface) => interface.implements.contains(&info.name),
+		let interface = match info.ty.as_interface() {
+			Some(interface) => interface,
+			None => panic!("Type `{}` is not an interface", info.type_name()),
+		};
+
+		let implements = info.types.get_filtered(|_, ty| match &**ty {
+			Type::Object(obj) => obj.implements.contains(&interface.name),
+			Type::Interface(other) => other.implements.contains(&interface.name),
 			_ => false,
 		});
 
-		for ty in implements.values() {
-			ty.get_type(registry);
+		for ty in implements.into_values() {
+			TypeInfo::new(ty, info.types.clone()).get_type(registry);
 		}
 
 		let mut fields = vec![];
 
-		for (_, field) in info.fields.iter().filter(|(_, p)| !p.inaccessible) {
-			let mut interface_field = field.meta(registry);
+		for (_, field) in interface.fields.iter().filter(|(_, p)| !p.inaccessible) {
+			let mut interface_field = field.meta(&info.types, registry);
 			if let Some(description) = &field.description {
 				interface_field = interface_field.description(description);
 			}
@@ -299,9 +309,9 @@ impl GraphQLType<Value> for Interface {
 
 		let mut types = vec![];
 
-		for name in &info.implements {
-			let ty = match TYPE_REGISTRY.get(name) {
-				Some(ty) => ty.get_type(registry),
+		for name in &interface.implements {
+			let ty = match info.types.get(name) {
+				Some(ty) => TypeInfo::new(ty, info.types.clone()).get_type(registry),
 				None => panic!("Type {} not found", name),
 			};
 			types.push(ty);
@@ -309,7 +319,7 @@ impl GraphQLType<Value> for Interface {
 
 		let mut meta_type = registry.build_interface_type::<Self>(info, &fields).interfaces(&types);
 
-		if let Some(description) = &info.description {
+		if let Some(description) = &interface.description {
 			meta_type = meta_type.description(description);
 		}
 
@@ -319,7 +329,7 @@ impl GraphQLType<Value> for Interface {
 
 impl GraphQLValue<Value> for Interface {
 	type Context = ContextBase;
-	type TypeInfo = Self;
+	type TypeInfo = TypeInfo;
 	fn type_name<'i>(&self, info: &'i Self::TypeInfo) -> Option<&'i str> {
 		Some(info.type_name())
 	}
@@ -0,0 +1,412 @@
+use super::{Selection, Type, TypeRegistry, select_operation, to_value};
+use crate::{GraphQLRegistry, ObjectAccessor, Value};
+use juniper::{
+	Definition, Document, GraphQLError, OperationType, Variables, parser::SourcePosition,
+	validation::RuleError,
+};
+use std::{
+	borrow::Cow,
+	collections::{BTreeMap, HashMap},
+};
+
+/// The limits a document has to respect before it is executed
+#[derive(Clone, Debug)]
+pub(crate) struct Limits {
+	pub(crate) depth: Option<u16>,
+	pub(crate) complexity: Option<u16>,
+	pub(crate) recursive_depth: usize,
+	pub(crate) max_directives: Option<usize>,
+	pub(crate) batch_size: Option<usize>,
+}
+
+impl Limits {
+	fn is_complexity_limited(&self) -> bool {
+		self.complexity.is_some_and(|limit| limit > 0)
+	}
+
+	/// used to validate the operation of a request against the limits, documents juniper cannot
+	/// select an operation from are left for juniper to report
+	pub(crate) fn check(
+		&self,
+		types: &TypeRegistry,
+		registry: &GraphQLRegistry,
+		roots: &[Option<String>; 3],
+		document: &Document<'_, Value>,
+		operation_name: Option<&str>,
+		variables: &Variables<Value>,
+	) -> Result<(), GraphQLError> {
+		let mut fragments = HashMap::new();
+		for definition in document.iter() {
+			if let Definition::Fragment(fragment) = definition {
+				fragments.insert(
+					fragment.item.name.item,
+					(fragment.item.type_condition.item, fragment.item.selection_set.as_slice()),
+				);
+			}
+		}
+
+		let operation = match select_operation(document, operation_name) {
+			Some(operation) => operation,
+			None => return Ok(()),
+		};
+
+		let root = match operation.item.operation_type {
+			OperationType::Query => &roots[0],
+			OperationType::Mutation => &roots[1],
+			OperationType::Subscription => &roots[2],
+		};
+		let root = match root {
+			Some(root) => root,
+			None => return Ok(()),
+		};
+
+		let mut variables = variables.clone();
+		if let Some(definitions) = &operation.item.variable_definitions {
+			for (name, definition) in definitions.item.items.iter() {
+				if let Some(default_value) = &definition.default_value {
+					variables
+						.entry(name.item.to_string())
+						.or_insert_with(|| default_value.item.clone());
+				}
+			}
+		}
+
+		let mut visitor = Visitor {
+			types,
+			registry,
+			limits: self,
+			fragments,
+			variables,
+			visiting: Vec::new(),
+		};
+
+		let position = operation.start();
+		let (depth, complexity) =
+			visitor.visit(root, &operation.item.selection_set, 1, &position)?;
+
+		if let Some(limit) = self.depth.filter(|limit| *limit > 0) {
+			if depth > limit as usize {
+				return Err(error("Query is nested too deep.", &position));
+			}
+		}
+
+		if let Some(limit) = self.complexity.filter(|limit| *limit > 0) {
+			if complexity > limit as usize {
+				return Err(error("Query is too complex.", &position));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn error(message: &str, position: &SourcePosition) -> GraphQLError {
+	GraphQLError::ValidationError(vec![RuleError::new(message, &[*position])])
+}
+
+type Fragments<'a> = HashMap<&'a str, (&'a str, &'a [Selection<'a, Value>])>;
+
+/// used to walk the selections of an operation, returns the depth and the complexity of every
+/// selection set
+struct Visitor<'a> {
+	types: &'a TypeRegistry,
+	registry: &'a GraphQLRegistry,
+	limits: &'a Limits,
+	fragments: Fragments<'a>,
+	variables: Variables<Value>,
+	visiting: Vec<&'a str>,
+}
+
+impl<'a> Visitor<'a> {
+	fn visit(
+		&mut self,
+		type_name: &str,
+		selection_set: &'a [Selection<'a, Value>],
+		recursion: usize,
+		position: &SourcePosition,
+	) -> Result<(usize, usize), GraphQLError> {
+		if recursion > self.limits.recursive_depth {
+			return Err(error(
+				&format!(
+					"The recursion depth of the query cannot be greater than `{}`",
+					self.limits.recursive_depth
+				),
+				position,
+			));
+		}
+
+		let mut depth = 0;
+		let mut complexity = 0usize;
+
+		for selection in selection_set {
+			let (child_depth, child_complexity) = match selection {
+				Selection::Field(field) => {
+					let name = field.item.name.item;
+					let position = field.start();
+
+					if let Some(max_directives) = self.limits.max_directives {
+						let directives = field.item.directives.as_ref().map_or(0, Vec::len);
+						if directives > max_directives {
+							return Err(error(
+								&format!(
+									"The number of directives on the field `{}` cannot be greater than `{}`",
+									name, max_directives
+								),
+								&position,
+							));
+						}
+					}
+
+					// introspection is not limited
+					if name.starts_with("__") {
+						continue;
+					}
+
+					let ty = match self.types.get(type_name) {
+						Some(ty) => ty,
+						None => continue,
+					};
+					let (field_type, definition) = match &*ty {
+						Type::Object(object) => match object.fields.get(name) {
+							Some(field) => (field.ty.type_name().to_string(), Some(field)),
+							None => continue,
+						},
+						Type::Interface(interface) => match interface.fields.get(name) {
+							Some(field) => (field.ty.type_name().to_string(), None),
+							None => continue,
+						},
+						Type::Subscription(subscription) => match subscription.fields.get(name) {
+							Some(field) => (field.ty.type_name().to_string(), None),
+							None => continue,
+						},
+						_ => continue,
+					};
+
+					let (child_depth, child_complexity) = match &field.item.selection_set {
+						Some(selection_set) => {
+							self.visit(&field_type, selection_set, recursion + 1, &position)?
+						}
+						None => (0, 0),
+					};
+
+					// the complexity functions only run when the complexity is limited
+					let compute_complexity = self
+						.registry
+						.types
+						.get(type_name)
+						.and_then(|ty| ty.field_by_name(name))
+						.and_then(|field| field.compute_complexity.as_ref())
+						.filter(|_| self.limits.is_complexity_limited());
+					let complexity = match definition.zip(compute_complexity) {
+						Some((definition, compute_complexity)) => {
+							let mut arguments = BTreeMap::new();
+							for (name, argument) in definition.arguments.iter() {
+								if let Some(default_value) = &argument.default_value {
+									arguments
+										.insert(Value::from(name.as_str()), default_value.clone());
+								}
+							}
+							if let Some(items) = &field.item.arguments {
+								for (name, value) in items.item.items.iter() {
+									arguments.insert(
+										Value::from(name.item),
+										to_value(&value.item, &self.variables),
+									);
+								}
+							}
+
+							compute_complexity(
+								&ObjectAccessor(Cow::Owned(arguments)),
+								child_complexity,
+							)
+							.map_err(|err| error(&err.to_string(), &position))?
+						}
+						None => child_complexity.saturating_add(1),
+					};
+
+					(child_depth + 1, complexity)
+				}
+				Selection::InlineFragment(fragment) => {
+					let type_name = match &fragment.item.type_condition {
+						Some(type_condition) => type_condition.item,
+						None => type_name,
+					};
+
+					self.visit(
+						type_name,
+						&fragment.item.selection_set,
+						recursion + 1,
+						&fragment.start(),
+					)?
+				}
+				Selection::FragmentSpread(spread) => {
+					let name = spread.item.name.item;
+					let (type_name, selection_set) = match self.fragments.get(name) {
+						Some(fragment) => *fragment,
+						None => continue,
+					};
+
+					// cycles are reported by juniper
+					if self.visiting.contains(&name) {
+						continue;
+					}
+
+					self.visiting.push(name);
+					let res = self.visit(type_name, selection_set, recursion + 1, &spread.start());
+					self.visiting.pop();
+					res?
+				}
+			};
+
+			depth = depth.max(child_depth);
+			complexity = complexity.saturating_add(child_complexity);
+		}
+
+		Ok((depth, complexity))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use juniper::{
+		InputValue, Spanning,
+		http::{GraphQLRequest, GraphQLResponse},
+	};
+
+	use crate::{
+		FieldFuture, FieldValue, Value,
+		graphql::{Field, Object, Schema, SchemaBuilder, TypeRef},
+	};
+
+	fn schema(limit: fn(SchemaBuilder) -> SchemaBuilder) -> Schema {
+		let node = Object::new("Node")
+			.field(Field::output("value", TypeRef::named_nn(TypeRef::INT), |_| {
+				FieldFuture::new(async move { Ok(Some(Value::from(1))) })
+			}))
+			.field(Field::output("child", TypeRef::named_nn("Node"), |_| {
+				FieldFuture::new(async move { Ok(Some(FieldValue::owned_any(()))) })
+			}))
+			.field(
+				Field::output("children", TypeRef::named_nn_list_nn("Node"), |_| {
+					FieldFuture::new(async move { Ok(Some(FieldValue::list(Vec::<Value>::new()))) })
+				})
+				.argument(Field::input("limit", TypeRef::named_nn(TypeRef::INT)))
+				.compute_complexity(|args, child_complexity| {
+					Ok(args.try_get("limit")?.int32()? as usize * child_complexity)
+				}),
+			);
+
+		let query = Object::new("Query").field(Field::output(
+			"node",
+			TypeRef::named_nn(node.type_name()),
+			|_| FieldFuture::new(async move { Ok(Some(FieldValue::owned_any(()))) }),
+		));
+
+		limit(Schema::build(query.type_name(), None, None).register(query).register(node))
+			.finish()
+			.unwrap()
+	}
+
+	fn message(res: &GraphQLResponse<Value>) -> Option<String> {
+		let res = serde_json::to_value(res).unwrap();
+
+		res["errors"][0]["message"].as_str().map(ToString::to_string)
+	}
+
+	#[tokio::test]
+	async fn depth() {
+		let schema = schema(|builder| builder.limit_depth(2));
+
+		let res = schema
+			.executer(GraphQLRequest::new("{ node { value } }".to_string(), None, None))
+			.await;
+		assert!(res.is_ok());
+
+		let res = schema
+			.executer(GraphQLRequest::new(
+				"{ node { ...Child } } fragment Child on Node { child { value } }".to_string(),
+				None,
+				None,
+			))
+			.await;
+		assert_eq!(message(&res).as_deref(), Some("Query is nested too deep."));
+	}
+
+	#[tokio::test]
+	async fn complexity() {
+		let schema = schema(|builder| builder.limit_complexity(5));
+
+		// the complexity of the fields is kept in the registry
+		let children = schema.0.registry.types["Node"].field_by_name("children").unwrap();
+		assert!(children.compute_complexity.is_some());
+		let value = schema.0.registry.types["Node"].field_by_name("value").unwrap();
+		assert!(value.compute_complexity.is_none());
+
+		let res = schema
+			.executer(GraphQLRequest::new(
+				"{ node { children(limit: 2) { value } } }".to_string(),
+				None,
+				None,
+			))
+			.await;
+		assert!(res.is_ok());
+
+		let res = schema
+			.executer(GraphQLRequest::new(
+				"query($limit: Int!) { node { children(limit: $limit) { value } } }".to_string(),
+				None,
+				Some(InputValue::Object(vec![(
+					Spanning::unlocated("limit".to_string()),
+					Spanning::unlocated(InputValue::scalar(10)),
+				)])),
+			))
+			.await;
+		assert_eq!(message(&res).as_deref(), Some("Query is too complex."));
+	}
+
+	#[tokio::test]
+	async fn recursive_depth() {
+		let schema = schema(|builder| builder.limit_recursive_depth(2));
+
+		let res = schema
+			.executer(GraphQLRequest::new("{ node { child { value } } }".to_string(), None, None))
+			.await;
+		assert_eq!(
+			message(&res).as_deref(),
+			Some("The recursion depth of the query cannot be greater than `2`")
+		);
+	}
+
+	#[tokio::test]
+	async fn fragment_cycles() {
+		let schema = schema(|builder| builder.limit_depth(8));
+
+		// the cycle is reported by the validation of juniper
+
+		let res = schema
+			.executer(GraphQLRequest::new(
+				"{ node { ...Child } } fragment Child on Node { child { ...Child } }".to_string(),
+				None,
+				None,
+			))
+			.await;
+		assert!(!res.is_ok());
+	}
+
+	#[tokio::test]
+	async fn directives() {
+		let schema = schema(|builder| builder.limit_directives(1));
+
+		let res = schema
+			.executer(GraphQLRequest::new(
+				"{ node { value @skip(if: false) @include(if: true) } }".to_string(),
+				None,
+				None,
+			))
+			.await;
+		assert_eq!(
+			message(&res).as_deref(),
+			Some("The number of directives on the field `value` cannot be greater than `1`")
+		);
+	}
+}
@@ -2,9 +2,13 @@ mod directive;
 mod r#enum;
 mod field;
 mod interface;
+mod limit;
 mod object;
+mod persisted;
+mod request;
 mod scalar;
 mod schema;
+mod sdl;
 mod subscription;
 mod r#type;
 mod type_ref;
@@ -16,9 +20,13 @@ pub use directive::*;
 pub use r#enum::*;
 pub use field::*;
 pub use interface::*;
+pub(crate) use limit::*;
 pub use object::*;
+pub use persisted::*;
+pub use request::*;
 pub use scalar::*;
 pub use schema::*;
+pub use sdl::*;
 pub use subscription::*;
 pub use r#type::*;
 pub use type_ref::*;
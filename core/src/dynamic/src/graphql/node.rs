@@ -1,6 +1,6 @@
 use super::{
 	Arguments, ExecutionResult, Executor, FieldError, GraphQLType, GraphQLValue, GraphQLValueAsync,
-	MetaType, Registry, TYPE_REGISTRY,
+	MetaType, Registry,
 };
 use crate::{ContextBase, Value};
 use futures::{FutureExt, future::BoxFuture};
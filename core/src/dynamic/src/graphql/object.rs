@@ -1,10 +1,10 @@
 use super::{
 	Arguments, DeprecationStatus, Directive, ExecutionResult, Executor, Field, FieldError,
 	GraphQLType, GraphQLValue, GraphQLValueAsync, JuniperValue, MetaType, Registry, SelectionSet,
-	TYPE_REGISTRY, to_object_accessor, type_name,
+	TypeInfo, TypeRegistry, type_name,
 };
 use crate::SeaResult;
-use crate::{BoxFieldFutureJson, ContextBase, FieldValue, ObjectAccessor, SeaographyError, Value};
+use crate::{BoxFieldFutureJson, ContextBase, FieldValue, SeaographyError, Value};
 use futures::{FutureExt, future::BoxFuture};
 use juniper::FromInputValue;
 use juniper::IntoFieldError;
@@ -46,6 +46,10 @@ pub struct Object {
 	pub(crate) description: Option<String>,
 	pub(crate) fields: BTreeMap<String, Field>,
 	pub(crate) implements: BTreeSet<String>,
+	pub(crate) keys: Vec<String>,
+	pub(crate) resolvable: bool,
+	pub(crate) extends: bool,
+	pub(crate) shareable: bool,
 	pub(crate) inaccessible: bool,
 	pub(crate) directives: Vec<Directive>,
 }
@@ -60,15 +64,48 @@ impl Object {
 			description: None,
 			fields: BTreeMap::from_iter(vec![type_name(name)]),
 			implements: Default::default(),
+			keys: Vec::new(),
+			resolvable: true,
+			extends: false,
+			shareable: false,
 			inaccessible: false,
 			directives: Vec::new(),
 		}
 	}
 
 	impl_set_description!();
+	impl_set_extends!();
+	impl_set_shareable!();
 	impl_set_inaccessible!();
 	impl_directive!();
 
+	/// Add an entity key to the object when using Apollo Federation, entities are resolved
+	/// through the entity resolver of the schema
+	///
+	/// Reference: <https://www.apollographql.com/docs/federation/entities>
+	#[inline]
+	pub fn key(mut self, fields: impl Into<String>) -> Self {
+		self.keys.push(fields.into());
+		self
+	}
+
+	/// Add an entity key to the object that this subgraph cannot resolve, used to reference
+	/// entities of other subgraphs
+	///
+	/// Reference: <https://www.apollographql.com/docs/federation/entities/contribute-fields#referencing-an-entity-without-contributing-fields>
+	#[inline]
+	pub fn unresolvable(mut self, fields: impl Into<String>) -> Self {
+		self.resolvable = false;
+		self.keys.push(fields.into());
+		self
+	}
+
+	/// Returns whether the object is an entity that can be resolved by this subgraph
+	#[inline]
+	pub(crate) fn is_entity(&self) -> bool {
+		!self.keys.is_empty() && self.resolvable
+	}
+
 	/// Add an field to the object
 	#[inline]
 	pub fn field(mut self, field: Field) -> Self {
@@ -98,14 +135,14 @@ impl Object {
 		&self.name
 	}
 
-	pub(crate) fn check(&self, type_name: &str) -> SeaResult<()> {
+	pub(crate) fn check(&self, types: &TypeRegistry, type_name: &str) -> SeaResult<()> {
 		if self.inaccessible {
 			return Err(SeaographyError::new(format!(
 				"object `{}` is inaccessible",
 				self.type_name()
 			)));
 		}
-		if let Some(ty) = TYPE_REGISTRY.get(type_name) {
+		if let Some(ty) = types.get(type_name) {
 			if let Some(interface) = ty.as_interface() {
 				if self.implements.get(interface.type_name()).is_some() {
 					for interface_field in interface.fields.values() {
@@ -158,9 +195,9 @@ impl Object {
 
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		selection_set: &'a SelectionSet,
-		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
 	) -> Vec<BoxFieldFutureJson<'a>> {
 		if self.inaccessible {
@@ -174,11 +211,11 @@ impl Object {
 				.boxed(),
 			];
 		}
+		// every selection is collected, a field can be selected several times under aliases
 		let mut futures = Vec::new();
-		for field in self.fields.values() {
-			if let Some(child) = selection_set.childs.iter().find(|child| child.name == field.name)
-			{
-				futures.push(field.collect(ctx, child, arguments, parent_value));
+		for child in selection_set.childs.iter() {
+			if let Some(field) = self.fields.values().find(|field| field.name == child.name) {
+				futures.push(field.collect(types, ctx, child, parent_value));
 			}
 		}
 		futures
@@ -194,11 +231,16 @@ impl GraphQLType<Value> for Object {
 	where
 		Value: 'r,
 	{
+		let object = match info.ty.as_object() {
+			Some(object) => object,
+			None => panic!("Type `{}` is not an object", info.type_name()),
+		};
+
 		let mut output_fields = vec![];
 		let mut input_fields = vec![];
 
-		for (_, field) in &info.fields {
-			if let Some(mut meta) = field.meta_output(registry) {
+		for field in object.fields.values() {
+			if let Some(mut meta) = field.meta_output(&info.types, registry) {
 				if let Some(description) = &field.description {
 					meta = meta.description(description);
 				}
@@ -210,7 +252,7 @@ impl GraphQLType<Value> for Object {
 				}
 				output_fields.push(meta);
 			}
-			if let Some(mut meta) = field.meta_input(registry) {
+			if let Some(mut meta) = field.meta_input(&info.types, registry) {
 				if let Some(description) = &field.description {
 					meta = meta.description(description);
 				}
@@ -222,7 +264,7 @@ impl GraphQLType<Value> for Object {
 		if !input_fields.is_empty() {
 			let mut meta_type = registry.build_input_object_type::<Self>(info, &input_fields);
 
-			if let Some(description) = &info.description {
+			if let Some(description) = &object.description {
 				meta_type = meta_type.description(description);
 			}
 
@@ -230,9 +272,9 @@ impl GraphQLType<Value> for Object {
 		} else if !output_fields.is_empty() {
 			let mut types = vec![];
 
-			for name in &info.implements {
-				let ty = match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.get_type(registry),
+			for name in &object.implements {
+				let ty = match info.types.get(name) {
+					Some(ty) => TypeInfo::new(ty, info.types.clone()).get_type(registry),
 					None => panic!("Type `{}` not found", name),
 				};
 				types.push(ty);
@@ -241,7 +283,7 @@ impl GraphQLType<Value> for Object {
 			let mut meta_type =
 				registry.build_object_type::<Self>(info, &output_fields).interfaces(&types);
 
-			if let Some(description) = &info.description {
+			if let Some(description) = &object.description {
 				meta_type = meta_type.description(description);
 			}
 
@@ -249,7 +291,7 @@ impl GraphQLType<Value> for Object {
 		} else {
 			let mut meta_type = registry.build_object_type::<Self>(info, &vec![]);
 
-			if let Some(description) = &info.description {
+			if let Some(description) = &object.description {
 				meta_type = meta_type.description(description);
 			}
 
@@ -269,7 +311,7 @@ impl FromInputValue<Value> for Object {
 
 impl GraphQLValue<Value> for Object {
 	type Context = ContextBase;
-	type TypeInfo = Self;
+	type TypeInfo = TypeInfo;
 	fn type_name<'i>(&self, info: &'i Self::TypeInfo) -> Option<&'i str> {
 		Some(info.type_name())
 	}
@@ -298,12 +340,7 @@ where
 			match self.get_field(field_name) {
 				Some(field) => {
 					let res = field
-						.collect(
-							executor.context(),
-							&selection_set,
-							&to_object_accessor(look_ahead),
-							None,
-						)
+						.collect(&info.types, executor.context(), &selection_set, None)
 						.await
 						.map_err(|err| err.into_field_error())?;
 
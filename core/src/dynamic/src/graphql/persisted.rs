@@ -0,0 +1,169 @@
+use super::{Request, Root};
+use crate::Value;
+use juniper::{
+	Definition, Document, GraphQLError,
+	parser::parse_document_source,
+	validation::{MultiVisitorNil, RuleError, ValidatorContext, rules, visit, visit_all_rules},
+};
+use lru::LruCache;
+use self_cell::self_cell;
+use sha2::{Digest, Sha256};
+use std::{
+	collections::HashMap,
+	num::NonZeroUsize,
+	sync::{Arc, Mutex},
+};
+
+/// Returns the lowercase hex encoded sha256 hash of a document
+pub fn sha256_hash(query: &str) -> String {
+	hex::encode(Sha256::digest(query.as_bytes()))
+}
+
+type Definitions<'a> = Vec<Definition<'a, Value>>;
+
+self_cell!(
+	/// A parsed and validated document, its definitions borrow the query that is owned by the
+	/// document itself
+	pub(crate) struct CachedDocument {
+		owner: Box<str>,
+
+		#[covariant]
+		dependent: Definitions,
+	}
+);
+
+impl CachedDocument {
+	/// used to parse a document and to validate it against the schema
+	fn parse(root_node: &Root, query: &str) -> Result<Self, GraphQLError> {
+		Self::try_new(query.into(), |query| {
+			let definitions = parse_document_source(query, &root_node.schema)
+				.map_err(GraphQLError::ParseError)?;
+
+			let mut ctx = ValidatorContext::new(&root_node.schema, &definitions);
+			visit_all_rules(&mut ctx, &definitions);
+			if root_node.introspection_disabled {
+				visit(
+					&mut MultiVisitorNil.with(rules::disable_introspection::factory()),
+					&mut ctx,
+					&definitions,
+				);
+			}
+
+			let errors = ctx.into_errors();
+			if !errors.is_empty() {
+				return Err(GraphQLError::ValidationError(errors));
+			}
+
+			Ok(definitions)
+		})
+	}
+
+	/// Returns the definitions of the document
+	pub(crate) fn document(&self) -> &Document<'_, Value> {
+		self.borrow_dependent()
+	}
+}
+
+/// The configuration of persisted queries and of the document cache
+#[derive(Clone, Debug)]
+pub(crate) struct PersistedQueries {
+	/// used to register the documents of automatic persisted queries on a miss
+	pub(crate) automatic: bool,
+	/// used to reject every document that is not in the allow list
+	pub(crate) allow_list: bool,
+	/// the documents registered on the schema, keyed by their hash
+	pub(crate) documents: HashMap<String, String>,
+	/// the number of parsed documents kept in the cache
+	pub(crate) cache_size: usize,
+}
+
+impl Default for PersistedQueries {
+	fn default() -> Self {
+		Self {
+			automatic: false,
+			allow_list: false,
+			documents: HashMap::new(),
+			cache_size: 1000,
+		}
+	}
+}
+
+/// Resolves the documents of requests, parsed and validated documents are kept in a lru cache
+/// keyed by the hash of their query
+pub(crate) struct Documents {
+	config: PersistedQueries,
+	cache: Option<Mutex<LruCache<String, Arc<CachedDocument>>>>,
+}
+
+impl Documents {
+	pub(crate) fn new(config: PersistedQueries) -> Self {
+		Self {
+			cache: NonZeroUsize::new(config.cache_size)
+				.map(|capacity| Mutex::new(LruCache::new(capacity))),
+			config,
+		}
+	}
+
+	/// used to get the document of a request, unknown hashes of automatic persisted queries
+	/// are reported with `PersistedQueryNotFound` so the client sends the query along
+	pub(crate) fn load(
+		&self,
+		root_node: &Root,
+		request: &Request,
+	) -> Result<Arc<CachedDocument>, GraphQLError> {
+		let persisted_query =
+			request.extensions.as_ref().and_then(|extensions| extensions.persisted_query.as_ref());
+
+		let (hash, query) = match (persisted_query, request.query.as_deref()) {
+			(Some(_), _) if !self.config.automatic && !self.config.allow_list => {
+				return Err(error("PersistedQueryNotSupported"));
+			}
+			(Some(persisted_query), _) if persisted_query.version != 1 => {
+				return Err(error("Unsupported persisted query version"));
+			}
+			(Some(persisted_query), Some(query)) => {
+				let hash = sha256_hash(query);
+				if !hash.eq_ignore_ascii_case(&persisted_query.sha256_hash) {
+					return Err(error("provided sha does not match query"));
+				}
+				(hash, Some(query))
+			}
+			(Some(persisted_query), None) => (persisted_query.sha256_hash.to_lowercase(), None),
+			(None, Some(query)) => (sha256_hash(query), Some(query)),
+			(None, None) => return Err(error("Must provide a query string")),
+		};
+
+		if self.config.allow_list && !self.config.documents.contains_key(&hash) {
+			return Err(error("PersistedQueryNotInAllowList"));
+		}
+
+		if let Some(document) = self.cached(&hash) {
+			return Ok(document);
+		}
+
+		let query = match query.or_else(|| self.config.documents.get(&hash).map(String::as_str)) {
+			Some(query) => query,
+			None => return Err(error("PersistedQueryNotFound")),
+		};
+
+		let document = Arc::new(CachedDocument::parse(root_node, query)?);
+		if let Some(cache) = &self.cache {
+			cache.lock().unwrap().put(hash, document.clone());
+		}
+
+		Ok(document)
+	}
+
+	fn cached(&self, hash: &str) -> Option<Arc<CachedDocument>> {
+		self.cache.as_ref().and_then(|cache| cache.lock().unwrap().get(hash).cloned())
+	}
+
+	#[cfg(test)]
+	pub(crate) fn len(&self) -> usize {
+		self.cache.as_ref().map_or(0, |cache| cache.lock().unwrap().len())
+	}
+}
+
+fn error(message: &str) -> GraphQLError {
+	GraphQLError::ValidationError(vec![RuleError::new(message, &[])])
+}
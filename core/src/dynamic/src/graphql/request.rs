@@ -0,0 +1,240 @@
+use super::InputValue;
+use crate::{SeaResult, SeaographyError, Value};
+use juniper::{Variables, http::GraphQLRequest};
+use serde::Deserialize;
+
+/// The `persistedQuery` extension of an automatic persisted query
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+	pub version: u32,
+	pub sha256_hash: String,
+}
+
+/// The extensions sent along a GraphQL request
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+	pub persisted_query: Option<PersistedQuery>,
+}
+
+/// A GraphQL request, the document is either sent as `query` or referenced by the hash of a
+/// persisted query
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+	pub query: Option<String>,
+	pub operation_name: Option<String>,
+	pub variables: Option<InputValue<Value>>,
+	pub extensions: Option<Extensions>,
+	/// used to reject mutations and subscriptions, requests sent over GET are not allowed to
+	/// change data
+	#[serde(skip)]
+	pub(crate) queries_only: bool,
+}
+
+impl Request {
+	/// Create a request executing the given document
+	pub fn new(query: impl Into<String>) -> Self {
+		Self {
+			query: Some(query.into()),
+			operation_name: None,
+			variables: None,
+			extensions: None,
+			queries_only: false,
+		}
+	}
+
+	/// Create a request executing the persisted query with the given hash
+	pub fn persisted(sha256_hash: impl Into<String>) -> Self {
+		Self {
+			query: None,
+			operation_name: None,
+			variables: None,
+			extensions: Some(Extensions {
+				persisted_query: Some(PersistedQuery {
+					version: 1,
+					sha256_hash: sha256_hash.into(),
+				}),
+			}),
+			queries_only: false,
+		}
+	}
+
+	/// Set the name of the operation to execute
+	#[must_use]
+	pub fn operation_name(self, operation_name: impl Into<String>) -> Self {
+		Self {
+			operation_name: Some(operation_name.into()),
+			..self
+		}
+	}
+
+	/// Set the variables of the request
+	#[must_use]
+	pub fn variables(self, variables: InputValue<Value>) -> Self {
+		Self {
+			variables: Some(variables),
+			..self
+		}
+	}
+
+	/// Only allow the request to execute queries, used for requests sent over GET
+	#[must_use]
+	pub fn queries_only(self) -> Self {
+		Self {
+			queries_only: true,
+			..self
+		}
+	}
+
+	/// Set the content of an uploaded file at a path of the variables, e.g.
+	/// `variables.files.0`, as described by the GraphQL multipart request specification
+	pub fn set_upload(&mut self, path: &str, content: Vec<u8>) -> SeaResult<()> {
+		let error = || SeaographyError::new(format!("invalid upload path `{}`", path));
+
+		let mut segments = path.split('.');
+		if segments.next() != Some("variables") {
+			return Err(error());
+		}
+
+		let mut value = self.variables.as_mut().ok_or_else(error)?;
+		for segment in segments {
+			value = match value {
+				InputValue::Object(fields) => fields
+					.iter_mut()
+					.find(|(name, _)| name.item == segment)
+					.map(|(_, value)| &mut value.item),
+				InputValue::List(values) => segment
+					.parse::<usize>()
+					.ok()
+					.and_then(|index| values.get_mut(index))
+					.map(|value| &mut value.item),
+				_ => None,
+			}
+			.ok_or_else(error)?;
+		}
+
+		*value = InputValue::Scalar(Value::Bytes(content));
+
+		Ok(())
+	}
+
+	/// used to get the variables of the request as a map
+	pub(crate) fn to_variables(&self) -> Variables<Value> {
+		self.variables
+			.as_ref()
+			.and_then(|variables| variables.to_object_value())
+			.map(|object| {
+				object.into_iter().map(|(name, value)| (name.to_string(), value.clone())).collect()
+			})
+			.unwrap_or_default()
+	}
+}
+
+impl From<GraphQLRequest<Value>> for Request {
+	fn from(request: GraphQLRequest<Value>) -> Self {
+		Self {
+			query: Some(request.query),
+			operation_name: request.operation_name,
+			variables: request.variables,
+			extensions: None,
+			queries_only: false,
+		}
+	}
+}
+
+/// A single request or a batch of requests sent as an array
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum BatchRequest {
+	Single(Request),
+	Batch(Vec<Request>),
+}
+
+impl BatchRequest {
+	/// Set the content of an uploaded file at a path of the operations, the paths of batches
+	/// start with the index of the request, e.g. `0.variables.file`
+	pub fn set_upload(&mut self, path: &str, content: Vec<u8>) -> SeaResult<()> {
+		match self {
+			BatchRequest::Single(request) => request.set_upload(path, content),
+			BatchRequest::Batch(requests) => {
+				let request = path
+					.split_once('.')
+					.and_then(|(index, path)| Some((index.parse::<usize>().ok()?, path)))
+					.and_then(|(index, path)| Some((requests.get_mut(index)?, path)));
+
+				match request {
+					Some((request, path)) => request.set_upload(path, content),
+					None => Err(SeaographyError::new(format!("invalid upload path `{}`", path))),
+				}
+			}
+		}
+	}
+}
+
+impl From<Request> for BatchRequest {
+	fn from(request: Request) -> Self {
+		BatchRequest::Single(request)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{BatchRequest, InputValue, Request};
+	use crate::Value;
+
+	#[test]
+	fn batch() {
+		let request: BatchRequest = serde_json::from_str(r#"{ "query": "{ value }" }"#).unwrap();
+		assert!(matches!(request, BatchRequest::Single(_)));
+
+		let request: BatchRequest = serde_json::from_str(
+			r#"[{ "query": "{ value }" }, { "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "abc" } } }]"#,
+		)
+		.unwrap();
+		match request {
+			BatchRequest::Batch(requests) => {
+				assert_eq!(requests.len(), 2);
+				assert_eq!(requests[0].query.as_deref(), Some("{ value }"));
+				let persisted_query =
+					requests[1].extensions.as_ref().unwrap().persisted_query.as_ref();
+				assert_eq!(persisted_query.unwrap().sha256_hash, "abc");
+			}
+			BatchRequest::Single(_) => panic!("expected a batch"),
+		}
+	}
+
+	#[test]
+	fn upload() {
+		let mut request: BatchRequest = serde_json::from_str(
+			r#"[{ "query": "", "variables": { "file": null } }, { "query": "", "variables": { "files": [null, null] } }]"#,
+		)
+		.unwrap();
+
+		request.set_upload("0.variables.file", vec![1]).unwrap();
+		request.set_upload("1.variables.files.1", vec![2, 3]).unwrap();
+		assert!(request.set_upload("1.variables.files.2", vec![]).is_err());
+		assert!(request.set_upload("2.variables.file", vec![]).is_err());
+		assert!(request.set_upload("0.query", vec![]).is_err());
+
+		let requests = match request {
+			BatchRequest::Batch(requests) => requests,
+			BatchRequest::Single(_) => panic!("expected a batch"),
+		};
+		assert_eq!(
+			requests[0].to_variables().get("file"),
+			Some(&InputValue::scalar(Value::Bytes(vec![1])))
+		);
+		assert_eq!(
+			requests[1].to_variables().get("files"),
+			Some(&InputValue::list(vec![
+				InputValue::null(),
+				InputValue::scalar(Value::Bytes(vec![2, 3]))
+			]))
+		);
+
+		let mut request = Request::new("");
+		assert!(request.set_upload("variables.file", vec![]).is_err());
+	}
+}
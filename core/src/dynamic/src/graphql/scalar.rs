@@ -193,11 +193,11 @@ impl ToInputValue<Value> for Scalar {
 impl FromInputValue<Value> for Scalar {
 	type Error = FieldError<Value>;
 	fn from_input_value(input: &InputValue<Value>) -> Result<Self, Self::Error> {
-		println!("input: {:?}", input);
+		// custom scalars accept any scalar value, lists and objects are coerced before execution
 		input
-			.as_string_value()
-			.map(|s| Scalar::new(s))
-			.ok_or_else(|| format!("Expected `String` or `Int`, found: {input}"))
+			.as_scalar()
+			.map(|value| Scalar::new(value.to_string()))
+			.ok_or_else(|| format!("Expected a scalar, found: {input}"))
 			.map_err(IntoFieldError::<Value>::into_field_error)
 		// 	.as_string_value()
 		// 	.map(|s| Self::String(s.into()))
@@ -1,14 +1,28 @@
 use super::{
-	Arguments, ExecutionResult, Executor, FieldError, GraphQLType, GraphQLValue, GraphQLValueAsync,
-	IntrospectionMode, MetaType, NodeInfo, NodeType, Registry, Scalar, TYPE_REGISTRY, Type,
-	ValidationMode,
+	Arguments, BatchRequest, DeprecationStatus, ExecutionResult, Executor, Field, FieldError,
+	GraphQLType, GraphQLValue, GraphQLValueAsync, IntrospectionMode, JuniperValue, Limits,
+	MetaType, NodeInfo, NodeType, Object, PersistedQueries, Registry, Request, SYSTEM_SCALARS,
+	Scalar, SdlPrinter, Subscription, Type, TypeInfo, TypeRef, TypeRegistry, Union, ValidationMode,
+	coerce_variables, collect_stream,
+	persisted::{CachedDocument, Documents},
+	sha256_hash,
+};
+use crate::{
+	BoxResolverFn, ContextBase, Data, Deprecation, FieldFuture, FieldValue, GraphQLRegistry,
+	MetaField, MetaInputValue, ResolverContext, SchemaError, SeaographyError, Value,
 };
-use crate::{BoxResolverFn, ContextBase, Data, FieldFuture, ResolverContext, SchemaError, Value};
 use actix_web::HttpResponse;
-use futures::{FutureExt, future::BoxFuture};
+use futures::{
+	FutureExt, Stream, StreamExt,
+	future::{BoxFuture, join_all},
+	stream::select_all,
+};
 use juniper::{
-	EmptySubscription, RootNode,
-	http::{GraphQLRequest, GraphQLResponse},
+	ExecutionError, GraphQLError, GraphQLSubscriptionValue, OperationType, RootNode, ValuesStream,
+	Variables,
+	executor::{execute_validated_query_async, get_operation, resolve_validated_subscription},
+	http::GraphQLResponse,
+	validation::{RuleError, validate_input_values},
 };
 use std::{any::Any, collections::BTreeMap, fmt::Debug, sync::Arc};
 
@@ -33,8 +47,8 @@ impl GraphQLType<Value> for NodeWrapper {
 		if info.type_name().contains("Empty") {
 			return registry.build_object_type::<Self>(info, &vec![]).into_meta();
 		}
-		match TYPE_REGISTRY.get(info.type_name()) {
-			Some(obj) => obj.meta(registry),
+		match info.types.get(info.type_name()) {
+			Some(obj) => TypeInfo::new(obj, info.types.clone()).meta(registry),
 			None => {
 				panic!(
 					"`{}` with name `{}` not found",
@@ -71,8 +85,12 @@ where
 		executor: &'a Executor<Self::Context, Value>,
 	) -> BoxFuture<'a, ExecutionResult<Value>> {
 		async move {
-			match TYPE_REGISTRY.get(info.type_name()) {
-				Some(obj) => obj.resolve(field_name, arguments, executor).await,
+			match info.types.get(info.type_name()) {
+				Some(obj) => {
+					TypeInfo::new(obj, info.types.clone())
+						.resolve(field_name, arguments, executor)
+						.await
+				}
 				None => Err(FieldError::from(format!(
 					"`{}` with name `{}` not found",
 					info.node_type.type_name(),
@@ -84,10 +102,46 @@ where
 	}
 }
 
-pub type Root = RootNode<'static, NodeWrapper, NodeWrapper, EmptySubscription<ContextBase>, Value>;
+impl GraphQLSubscriptionValue<Value> for NodeWrapper {
+	fn resolve_field_into_stream<'s, 'i, 'ft, 'args, 'e, 'ref_e, 'res, 'f>(
+		&'s self,
+		info: &'i Self::TypeInfo,
+		field_name: &'ft str,
+		_arguments: Arguments<'args, Value>,
+		executor: &'ref_e Executor<'ref_e, 'e, Self::Context, Value>,
+	) -> BoxFuture<'f, Result<JuniperValue<ValuesStream<'res, Value>>, FieldError<Value>>>
+	where
+		's: 'f,
+		'i: 'res,
+		'ft: 'f,
+		'args: 'f,
+		'ref_e: 'f,
+		'res: 'f,
+		'e: 'res,
+	{
+		async move {
+			match info.types.get(info.type_name()) {
+				Some(ty) => Ok(JuniperValue::Scalar(collect_stream(
+					TypeInfo::new(ty, info.types.clone()),
+					field_name,
+					executor,
+				))),
+				None => Err(FieldError::from(format!(
+					"`{}` with name `{}` not found",
+					info.node_type.type_name(),
+					info.type_name()
+				))),
+			}
+		}
+		.boxed()
+	}
+}
+
+pub type Root = RootNode<'static, NodeWrapper, NodeWrapper, NodeWrapper, Value>;
 
 /// Dynamic schema builder
 pub struct SchemaBuilder {
+	types: Arc<TypeRegistry>,
 	query_type: NodeInfo,
 	mutation_type: Option<NodeInfo>,
 	subscription_type: Option<NodeInfo>,
@@ -95,12 +149,14 @@ pub struct SchemaBuilder {
 	validation_mode: ValidationMode,
 	recursive_depth: usize,
 	max_directives: Option<usize>,
+	batch_size: Option<usize>,
 	complexity: Option<u16>,
 	depth: Option<u16>,
 	enable_suggestions: bool,
 	introspection_mode: IntrospectionMode,
 	enable_federation: bool,
 	entity_resolver: Option<BoxResolverFn>,
+	persisted_queries: PersistedQueries,
 }
 
 impl SchemaBuilder {
@@ -108,10 +164,23 @@ impl SchemaBuilder {
 	#[must_use]
 	pub fn register(self, ty: impl Into<Type>) -> Self {
 		let ty = ty.into();
-		TYPE_REGISTRY.add(ty.name().to_owned(), ty);
+		self.types.add(ty.name().to_owned(), ty);
 		self
 	}
 
+	/// Register the subscription root type, its fields are resolved into streams
+	#[must_use]
+	pub fn subscription(self, subscription: Subscription) -> Self {
+		Self {
+			subscription_type: Some(NodeInfo::new(
+				subscription.type_name().to_string(),
+				NodeType::Subscription,
+				self.types.clone(),
+			)),
+			..self.register(subscription)
+		}
+	}
+
 	/// Add a global data that can be accessed in the `Schema`. You access it
 	/// with `Context::data`.
 	#[must_use]
@@ -152,6 +221,14 @@ impl SchemaBuilder {
 		self
 	}
 
+	/// Set the maximum number of requests a batch can have, larger batches are rejected
+	/// before any of their requests is executed. (default: no limit)
+	#[must_use]
+	pub fn limit_batch_size(mut self, batch_size: usize) -> Self {
+		self.batch_size = Some(batch_size);
+		self
+	}
+
 	/// Set the validation mode, default is `ValidationMode::Strict`.
 	#[must_use]
 	pub fn validation_mode(mut self, validation_mode: ValidationMode) -> Self {
@@ -200,31 +277,373 @@ impl SchemaBuilder {
 		}
 	}
 
+	/// Enable automatic persisted queries, a request only sending the
+	/// `extensions.persistedQuery.sha256Hash` of a document is answered with
+	/// `PersistedQueryNotFound` until the document is sent along its hash.
+	#[must_use]
+	pub fn enable_persisted_queries(mut self) -> Self {
+		self.persisted_queries.automatic = true;
+		self
+	}
+
+	/// Register a persisted query, it can be executed by the sha256 hash of
+	/// the document.
+	#[must_use]
+	pub fn persisted_query(mut self, query: impl Into<String>) -> Self {
+		let query = query.into();
+		self.persisted_queries.documents.insert(sha256_hash(&query), query);
+		self
+	}
+
+	/// Only execute the registered persisted queries, every other document is
+	/// rejected.
+	#[must_use]
+	pub fn persisted_queries_only(mut self) -> Self {
+		self.persisted_queries.allow_list = true;
+		self
+	}
+
+	/// Set the number of parsed and validated documents that are cached.
+	/// (default: 1000)
+	///
+	/// Documents of automatic persisted queries are only kept in this cache,
+	/// `0` disables the cache.
+	#[must_use]
+	pub fn document_cache_size(mut self, size: usize) -> Self {
+		self.persisted_queries.cache_size = size;
+		self
+	}
+
 	/// Consumes this builder and returns a schema.
 	pub fn finish(self) -> Result<Schema, SchemaError> {
 		// create system scalars
-		for ty in ["Int", "Float", "Boolean", "String", "ID"] {
-			TYPE_REGISTRY.add(ty.to_string(), Scalar::new(ty).into());
+		for ty in SYSTEM_SCALARS {
+			self.types.add(ty.to_string(), Scalar::new(ty).into());
+		}
+
+		let roots = [
+			Some(self.query_type.type_name().to_string()),
+			self.mutation_type.as_ref().map(|ty| ty.type_name().to_string()),
+			self.subscription_type.as_ref().map(|ty| ty.type_name().to_string()),
+		];
+
+		let federation = self.enable_federation
+			|| !self
+				.types
+				.get_filtered(|_, ty| ty.as_object().is_some_and(|object| !object.keys.is_empty()))
+				.is_empty();
+
+		// the sdl of a subgraph does not contain the types added for federation
+		let sdl = SdlPrinter::new(&roots, federation).print(&self.types);
+
+		if federation {
+			register_federation(
+				&self.types,
+				self.query_type.type_name(),
+				&sdl,
+				self.entity_resolver,
+			)?;
 		}
 
+		let registry = graphql_registry(&self.types, &roots);
+
 		let inner = SchemaInner {
 			root_node: Root::new_with_info(
 				NodeWrapper::new(),
 				NodeWrapper::new(),
-				EmptySubscription::new(),
+				NodeWrapper::new(),
 				self.query_type,
 				match self.mutation_type {
 					Some(ty) => ty,
-					None => NodeInfo::new("EmptyMutation".to_string(), NodeType::Mutation),
+					None => NodeInfo::new(
+						"EmptyMutation".to_string(),
+						NodeType::Mutation,
+						self.types.clone(),
+					),
+				},
+				match self.subscription_type {
+					Some(ty) => ty,
+					None => NodeInfo::new(
+						"_EmptySubscription".to_string(),
+						NodeType::Subscription,
+						self.types.clone(),
+					),
 				},
-				(),
 			),
+			types: self.types,
+			registry,
+			roots,
+			sdl,
+			limits: Limits {
+				depth: self.depth,
+				complexity: self.complexity,
+				recursive_depth: self.recursive_depth,
+				max_directives: self.max_directives,
+				batch_size: self.batch_size,
+			},
+			documents: Documents::new(self.persisted_queries),
 			data: Arc::new(self.data),
 		};
 		Ok(Schema(Arc::new(inner)))
 	}
 }
 
+/// used to add the types and root fields of the federation subgraph specification, `_service`
+/// returns the sdl of the schema and `_entities` resolves representations through the entity
+/// resolver
+fn register_federation(
+	types: &TypeRegistry,
+	query: &str,
+	sdl: &str,
+	entity_resolver: Option<BoxResolverFn>,
+) -> Result<(), SchemaError> {
+	let mut query = match types.remove(query).map(Arc::try_unwrap) {
+		Some(Ok(Type::Object(object))) => object,
+		Some(Ok(ty)) => {
+			let name = ty.name().to_string();
+			types.add(name.clone(), ty);
+			return Err(SchemaError(format!("Query `{}` is not an object", name)));
+		}
+		Some(Err(ty)) => {
+			return Err(SchemaError(format!("Query `{}` is already in use", ty.name())));
+		}
+		None => return Err(SchemaError(format!("Query `{}` is not registered", query))),
+	};
+
+	types.add("_Any".to_string(), Scalar::new("_Any").into());
+
+	let sdl = sdl.to_string();
+	types.add(
+		"_Service".to_string(),
+		Object::new("_Service")
+			.field(Field::output("sdl", TypeRef::named_nn(TypeRef::STRING), move |_| {
+				FieldFuture::from_value(Some(Value::from(sdl.clone())))
+			}))
+			.into(),
+	);
+
+	query = query.field(Field::output("_service", TypeRef::named_nn("_Service"), |_| {
+		FieldFuture::Value(Some(FieldValue::owned_any(())))
+	}));
+
+	let entities = types.get_filtered(|_, ty| ty.as_object().is_some_and(Object::is_entity));
+	if !entities.is_empty() {
+		let mut entity = Union::new("_Entity");
+		for name in entities.into_keys() {
+			entity = entity.possible_type(name);
+		}
+		types.add("_Entity".to_string(), entity.into());
+
+		query = query.field(
+			Field::output("_entities", TypeRef::named_list_nn("_Entity"), move |ctx| {
+				match &entity_resolver {
+					Some(entity_resolver) => (entity_resolver)(ctx),
+					None => FieldFuture::new(async move {
+						Err::<Option<FieldValue>, _>(SeaographyError::new(
+							"No entity resolver is registered",
+						))
+					}),
+				}
+			})
+			.argument(Field::input("representations", TypeRef::named_nn_list_nn("_Any"))),
+		);
+	}
+
+	types.add(query.type_name().to_string(), query.into());
+
+	Ok(())
+}
+
+/// used to build the registry of the object, interface and subscription fields, the limits read
+/// the complexity of the fields from it
+fn graphql_registry(types: &TypeRegistry, roots: &[Option<String>; 3]) -> GraphQLRegistry {
+	let mut registry = GraphQLRegistry {
+		query_type: roots[0].clone().unwrap_or_default(),
+		mutation_type: roots[1].clone(),
+		subscription_type: roots[2].clone(),
+		..Default::default()
+	};
+
+	for (name, ty) in types.get_filtered(|_, _| true) {
+		let meta_type = match &*ty {
+			Type::Object(object) => {
+				for interface in &object.implements {
+					registry.add_implements(&name, interface);
+				}
+
+				crate::MetaType::Object {
+					name: name.clone(),
+					description: object.description.clone(),
+					fields: object
+						.fields
+						.values()
+						.map(|field| {
+							let meta_field = MetaField {
+								external: field.external,
+								requires: field.requires.clone(),
+								provides: field.provides.clone(),
+								shareable: field.shareable,
+								inaccessible: field.inaccessible,
+								override_from: field.override_from.clone(),
+								compute_complexity: field.compute_complexity.clone(),
+								..meta_field(
+									&field.name,
+									&field.description,
+									&field.arguments,
+									&field.ty,
+									&field.deprecation,
+								)
+							};
+							(field.name.clone(), meta_field)
+						})
+						.collect(),
+					cache_control: Default::default(),
+					extends: object.extends,
+					shareable: object.shareable,
+					resolvable: object.resolvable,
+					keys: (!object.keys.is_empty()).then(|| object.keys.clone()),
+					visible: None,
+					inaccessible: object.inaccessible,
+					interface_object: false,
+					tags: Vec::new(),
+					is_subscription: false,
+					rust_typename: None,
+					directive_invocations: Vec::new(),
+					requires_scopes: Vec::new(),
+				}
+			}
+			Type::Interface(interface) => crate::MetaType::Interface {
+				name: name.clone(),
+				description: interface.description.clone(),
+				fields: interface
+					.fields
+					.values()
+					.map(|field| {
+						let meta_field = MetaField {
+							inaccessible: field.inaccessible,
+							..meta_field(
+								&field.name,
+								&field.description,
+								&field.arguments,
+								&field.ty,
+								&field.deprecation,
+							)
+						};
+						(field.name.clone(), meta_field)
+					})
+					.collect(),
+				possible_types: Default::default(),
+				extends: false,
+				keys: None,
+				visible: None,
+				inaccessible: interface.inaccessible,
+				tags: Vec::new(),
+				rust_typename: None,
+				directive_invocations: Vec::new(),
+				requires_scopes: Vec::new(),
+			},
+			Type::Subscription(subscription) => crate::MetaType::Object {
+				name: name.clone(),
+				description: subscription.description.clone(),
+				fields: subscription
+					.fields
+					.values()
+					.map(|field| {
+						let meta_field = meta_field(
+							&field.name,
+							&field.description,
+							&field.arguments,
+							&field.ty,
+							&field.deprecation,
+						);
+						(field.name.clone(), meta_field)
+					})
+					.collect(),
+				cache_control: Default::default(),
+				extends: false,
+				shareable: false,
+				resolvable: true,
+				keys: None,
+				visible: None,
+				inaccessible: false,
+				interface_object: false,
+				tags: Vec::new(),
+				is_subscription: true,
+				rust_typename: None,
+				directive_invocations: Vec::new(),
+				requires_scopes: Vec::new(),
+			},
+			_ => continue,
+		};
+
+		registry.types.insert(name, meta_type);
+	}
+
+	for (name, interfaces) in &registry.implements {
+		for interface in interfaces {
+			if let Some(crate::MetaType::Interface {
+				possible_types,
+				..
+			}) = registry.types.get_mut(interface)
+			{
+				possible_types.insert(name.clone());
+			}
+		}
+	}
+
+	registry
+}
+
+fn meta_field(
+	name: &str,
+	description: &Option<String>,
+	arguments: &BTreeMap<String, Field>,
+	ty: &TypeRef,
+	deprecation: &DeprecationStatus,
+) -> MetaField {
+	MetaField {
+		name: name.to_string(),
+		description: description.clone(),
+		args: arguments
+			.values()
+			.map(|argument| {
+				let meta_input_value = MetaInputValue {
+					name: argument.name.clone(),
+					description: argument.description.clone(),
+					ty: argument.ty.to_string(),
+					deprecation: Deprecation::NoDeprecated,
+					default_value: argument.default_value.as_ref().map(ToString::to_string),
+					visible: None,
+					inaccessible: argument.inaccessible,
+					tags: Vec::new(),
+					is_secret: false,
+					directive_invocations: Vec::new(),
+				};
+				(argument.name.clone(), meta_input_value)
+			})
+			.collect(),
+		ty: ty.to_string(),
+		deprecation: match deprecation {
+			DeprecationStatus::Current => Deprecation::NoDeprecated,
+			DeprecationStatus::Deprecated(reason) => Deprecation::Deprecated {
+				reason: reason.clone(),
+			},
+		},
+		cache_control: Default::default(),
+		external: false,
+		requires: None,
+		provides: None,
+		visible: None,
+		shareable: false,
+		inaccessible: false,
+		tags: Vec::new(),
+		override_from: None,
+		compute_complexity: None,
+		directive_invocations: Vec::new(),
+		requires_scopes: Vec::new(),
+	}
+}
+
 /// Dynamic GraphQL schema.
 ///
 /// Cloning a schema is cheap, so it can be easily shared.
@@ -239,129 +658,378 @@ impl Debug for Schema {
 
 pub struct SchemaInner {
 	root_node: Root,
+	pub(crate) types: Arc<TypeRegistry>,
+	pub(crate) registry: GraphQLRegistry,
+	roots: [Option<String>; 3],
+	sdl: String,
+	limits: Limits,
+	documents: Documents,
 	pub(crate) data: Arc<Data>,
 }
 
+/// A request whose document is parsed and validated, only the variables are left to check
+struct Prepared {
+	document: Arc<CachedDocument>,
+	operation_name: Option<String>,
+	variables: Variables<Value>,
+}
+
 impl Schema {
 	/// Create a schema builder
 	pub fn build(query: &str, mutation: Option<&str>, subscription: Option<&str>) -> SchemaBuilder {
-		#[cfg(test)]
-		TYPE_REGISTRY.clear();
+		let types = Arc::new(TypeRegistry::new());
 
 		SchemaBuilder {
-			query_type: NodeInfo::new(query.to_string(), NodeType::Query),
-			mutation_type: mutation
-				.map(|mutation| NodeInfo::new(mutation.to_string(), NodeType::Mutation)),
+			query_type: NodeInfo::new(query.to_string(), NodeType::Query, types.clone()),
+			mutation_type: mutation.map(|mutation| {
+				NodeInfo::new(mutation.to_string(), NodeType::Mutation, types.clone())
+			}),
 			subscription_type: subscription.map(|subscription| {
-				NodeInfo::new(subscription.to_string(), NodeType::Subscription)
+				NodeInfo::new(subscription.to_string(), NodeType::Subscription, types.clone())
 			}),
+			types,
 			data: Default::default(),
 			validation_mode: ValidationMode::Strict,
 			recursive_depth: 32,
 			max_directives: None,
+			batch_size: None,
 			complexity: None,
 			depth: None,
 			enable_suggestions: true,
 			introspection_mode: IntrospectionMode::Enabled,
 			entity_resolver: None,
 			enable_federation: false,
+			persisted_queries: Default::default(),
 		}
 	}
 
 	/// Execute a GraphQL query.
-	pub async fn execute(&self, request: GraphQLRequest<Value>) -> HttpResponse {
-		let mut ctx = ContextBase::new(crate::ApiType::GraphQL);
+	pub async fn execute(&self, request: impl Into<Request>) -> HttpResponse {
+		HttpResponse::Ok().json(self.response(request.into()).await)
+	}
 
-		ctx.execute_data = Some(self.0.data.clone());
+	/// Execute a single GraphQL request or a batch of requests, batches are answered with the
+	/// list of their responses. Batches larger than the batch size limit are rejected.
+	pub async fn execute_batch(&self, request: impl Into<BatchRequest>) -> HttpResponse {
+		match request.into() {
+			BatchRequest::Single(request) => self.execute(request).await,
+			BatchRequest::Batch(requests) => {
+				// the whole batch is rejected before any of its requests is executed
+				let limit = self.0.limits.batch_size.filter(|limit| requests.len() > *limit);
+				if let Some(limit) = limit {
+					let message = format!(
+						"Batch of {} requests exceeds the limit of {} requests",
+						requests.len(),
+						limit
+					);
+					let err = GraphQLError::ValidationError(vec![RuleError::new(&message, &[])]);
+
+					return HttpResponse::BadRequest()
+						.json(GraphQLResponse::<Value>::from_result(Err(err)));
+				}
 
-		let res = request.execute(&self.0.root_node, &ctx).await;
+				let responses =
+					join_all(requests.into_iter().map(|request| self.response(request))).await;
 
-		HttpResponse::Ok().json(res)
+				HttpResponse::Ok().json(responses)
+			}
+		}
 	}
 
 	#[cfg(test)]
-	pub(crate) async fn executer(&self, request: GraphQLRequest<Value>) -> GraphQLResponse<Value> {
+	pub(crate) async fn executer(&self, request: impl Into<Request>) -> GraphQLResponse<Value> {
+		self.response(request.into()).await
+	}
+
+	/// used to execute a query or mutation into its response
+	async fn response(&self, request: Request) -> GraphQLResponse<Value> {
 		let mut ctx = ContextBase::new(crate::ApiType::GraphQL);
 
 		ctx.execute_data = Some(self.0.data.clone());
 
-		request.execute(&self.0.root_node, &ctx).await
-	}
-
-	// /// Execute a GraphQL subscription with session data.
-	// pub fn execute_stream_with_session_data(
-	// 	&self,
-	// 	request: impl Into<DynamicRequest>,
-	// 	session_data: Arc<Data>,
-	// ) -> impl Stream<Item = Response> + Send + Unpin + 'static {
-	// 	let schema = self.clone();
-	// 	let request = request.into();
-	// 	let extensions = self.create_extensions(session_data.clone());
-
-	// 	let stream = {
-	// 		let extensions = extensions.clone();
-
-	// 		async_stream::stream! {
-	// 			let subscription = match schema.subscription_root() {
-	// 				Ok(subscription) => subscription,
-	// 				Err(err) => {
-	// 					yield Response::from_errors(vec![err]);
-	// 					return;
-	// 				}
-	// 			};
-
-	// 			let (env, _) = match prepare_request(
-	// 				extensions,
-	// 				request.inner,
-	// 				session_data,
-	// 				&schema.0.env.registry,
-	// 				schema.0.validation_mode,
-	// 				schema.0.recursive_depth,
-	// 				schema.0.max_directives,
-	// 				schema.0.complexity,
-	// 				schema.0.depth,
-	// 			)
-	// 			.await {
-	// 				Ok(res) => res,
-	// 				Err(errors) => {
-	// 					yield Response::from_errors(errors);
-	// 					return;
-	// 				}
-	// 			};
-
-	// 			if env.operation.node.ty != OperationType::Subscription {
-	// 				yield schema.execute_once(env, &request.root_value, None).await;
-	// 				return;
-	// 			}
-
-	// 			let ctx = env.create_context(
-	// 				&schema.0.env,
-	// 				None,
-	// 				&env.operation.node.selection_set,
-	// 				None,
-	// 			);
-	// 			let mut streams = Vec::new();
-	// 			subscription.collect_streams(&schema, &ctx, &mut streams, &request.root_value);
-
-	// 			let mut stream = futures_util::stream::select_all(streams);
-	// 			while let Some(resp) = stream.next().await {
-	// 				yield resp;
-	// 			}
-	// 		}
-	// 	};
-	// 	extensions.subscribe(stream.boxed())
-	// }
-
-	// /// Execute a GraphQL subscription.
-	// pub fn execute_stream(
-	// 	&self,
-	// 	request: impl Into<DynamicRequest>,
-	// ) -> impl Stream<Item = Response> + Send + Unpin {
-	// 	self.execute_stream_with_session_data(request, Default::default())
-	// }
-
-	// /// Returns the registry of this schema.
-	// pub fn registry(&self) -> &Registry {
-	// 	&self.0.registry
-	// }
+		let res = match self.prepare(request) {
+			Ok(prepared) => self.resolve(&prepared, &ctx).await,
+			Err(err) => Err(err),
+		};
+
+		GraphQLResponse::from_result(res)
+	}
+
+	/// Execute a GraphQL subscription, every event of the subscription yields a response.
+	///
+	/// Queries and mutations yield a single response.
+	pub fn execute_stream(
+		&self,
+		request: impl Into<Request>,
+	) -> impl Stream<Item = GraphQLResponse<Value>> + Send + 'static {
+		let schema = self.clone();
+		let request = request.into();
+
+		async_stream::stream! {
+			let mut ctx = ContextBase::new(crate::ApiType::GraphQL);
+
+			ctx.execute_data = Some(schema.0.data.clone());
+
+			let prepared = match schema.prepare(request) {
+				Ok(prepared) => prepared,
+				Err(err) => {
+					yield GraphQLResponse::from_result(Err(err));
+					return;
+				}
+			};
+
+			let document = prepared.document.document();
+			let res = match get_operation(document, prepared.operation_name.as_deref()) {
+				Ok(operation) => {
+					resolve_validated_subscription(
+						document,
+						operation,
+						&schema.0.root_node,
+						&prepared.variables,
+						&ctx,
+					)
+					.await
+				}
+				Err(err) => Err(err),
+			};
+
+			let (value, errors) = match res {
+				Ok(res) => res,
+				Err(GraphQLError::NotSubscription) => {
+					yield GraphQLResponse::from_result(schema.resolve(&prepared, &ctx).await);
+					return;
+				}
+				Err(err) => {
+					yield GraphQLResponse::from_result(Err(err));
+					return;
+				}
+			};
+
+			if !errors.is_empty() {
+				yield GraphQLResponse::from_result(Ok((JuniperValue::null(), errors)));
+				return;
+			}
+
+			let streams: Vec<_> = match value {
+				JuniperValue::Object(object) => object
+					.into_iter()
+					.filter_map(|(name, value)| match value {
+						JuniperValue::Scalar(stream) => {
+							Some(stream.map(move |res| (name.clone(), res)))
+						}
+						_ => None,
+					})
+					.collect(),
+				_ => Vec::new(),
+			};
+
+			let mut stream = select_all(streams);
+
+			while let Some((name, res)) = stream.next().await {
+				yield match res {
+					Ok(value) => GraphQLResponse::from_result(Ok((
+						JuniperValue::object(juniper::Object::from_iter([(name, value)])),
+						vec![],
+					))),
+					Err(err) => GraphQLResponse::from_result(Ok((JuniperValue::null(), vec![err]))),
+				};
+			}
+		}
+	}
+
+	/// used to load the parsed and validated document of a request, to coerce the variables of
+	/// custom scalars and to reject operations exceeding the depth, complexity, recursion or
+	/// directive limits
+	fn prepare(&self, mut request: Request) -> Result<Prepared, GraphQLError> {
+		let document = self.0.documents.load(&self.0.root_node, &request)?;
+		let operation_name = request.operation_name.take();
+
+		coerce_variables(
+			&self.0.types,
+			document.document(),
+			operation_name.as_deref(),
+			&mut request.variables,
+		);
+		let variables = request.to_variables();
+
+		let operation = get_operation(document.document(), operation_name.as_deref())?;
+		if request.queries_only && operation.item.operation_type != OperationType::Query {
+			return Err(GraphQLError::ValidationError(vec![RuleError::new(
+				"Only queries can be executed over GET",
+				&[operation.start()],
+			)]));
+		}
+
+		let errors = validate_input_values(&variables, operation, &self.0.root_node.schema);
+		if !errors.is_empty() {
+			return Err(GraphQLError::ValidationError(errors));
+		}
+
+		self.0.limits.check(
+			&self.0.types,
+			&self.0.registry,
+			&self.0.roots,
+			document.document(),
+			operation_name.as_deref(),
+			&variables,
+		)?;
+
+		Ok(Prepared {
+			document,
+			operation_name,
+			variables,
+		})
+	}
+
+	/// used to execute the query or mutation of a prepared request
+	async fn resolve(
+		&self,
+		prepared: &Prepared,
+		ctx: &ContextBase,
+	) -> Result<(JuniperValue<Value>, Vec<ExecutionError<Value>>), GraphQLError> {
+		let document = prepared.document.document();
+		let operation = get_operation(document, prepared.operation_name.as_deref())?;
+
+		execute_validated_query_async(
+			document,
+			operation,
+			&self.0.root_node,
+			&prepared.variables,
+			ctx,
+		)
+		.await
+	}
+
+	/// Returns the schema definition language of this schema, federation directives are
+	/// included for subgraphs.
+	pub fn sdl(&self) -> &str {
+		&self.0.sdl
+	}
+
+	/// Returns the type registry of this schema.
+	pub fn registry(&self) -> &TypeRegistry {
+		&self.0.types
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use juniper::http::{GraphQLRequest, GraphQLResponse};
+
+	use crate::{
+		FieldFuture, Value,
+		graphql::{
+			Field, JuniperValue, Object, Request, Schema, SchemaBuilder, TypeRef, sha256_hash,
+		},
+	};
+
+	fn builder(value: &'static str) -> SchemaBuilder {
+		let query = Object::new("Query").field(Field::output(
+			"value",
+			TypeRef::named_nn(TypeRef::STRING),
+			move |_| FieldFuture::new(async move { Ok(Some(Value::from(value))) }),
+		));
+
+		Schema::build(query.type_name(), None, None).register(query)
+	}
+
+	fn schema(value: &'static str) -> Schema {
+		builder(value).finish().unwrap()
+	}
+
+	fn message(res: &GraphQLResponse<Value>) -> Option<String> {
+		let res = serde_json::to_value(res).unwrap();
+
+		res["errors"][0]["message"].as_str().map(ToString::to_string)
+	}
+
+	#[tokio::test]
+	async fn schemas_side_by_side() {
+		let first = schema("first");
+		let second = schema("second");
+
+		for (schema, value) in [(first, "first"), (second, "second")] {
+			let res =
+				schema.executer(GraphQLRequest::new("{ value }".to_string(), None, None)).await;
+
+			assert_eq!(
+				res,
+				GraphQLResponse::from_result(Ok((
+					JuniperValue::object(juniper::Object::from_iter(vec![(
+						"value",
+						JuniperValue::scalar(Value::from(value))
+					)])),
+					vec![]
+				)))
+			);
+		}
+	}
+
+	#[tokio::test]
+	async fn persisted_queries() {
+		let schema = builder("value").enable_persisted_queries().finish().unwrap();
+		let hash = sha256_hash("{ value }");
+
+		let res = schema.executer(Request::persisted(&hash)).await;
+		assert_eq!(message(&res).as_deref(), Some("PersistedQueryNotFound"));
+
+		let mut request = Request::persisted(&hash);
+		request.query = Some("{ value }".to_string());
+		let res = schema.executer(request).await;
+		assert!(res.is_ok());
+
+		let res = schema.executer(Request::persisted(&hash)).await;
+		assert!(res.is_ok());
+		assert_eq!(schema.0.documents.len(), 1);
+
+		let mut request = Request::persisted(&hash);
+		request.query = Some("{ __typename }".to_string());
+		let res = schema.executer(request).await;
+		assert_eq!(message(&res).as_deref(), Some("provided sha does not match query"));
+
+		let res = schema.executer(Request::persisted(&hash)).await;
+		assert!(res.is_ok());
+
+		let res = self::schema("value").executer(Request::persisted(&hash)).await;
+		assert_eq!(message(&res).as_deref(), Some("PersistedQueryNotSupported"));
+	}
+
+	#[tokio::test]
+	async fn persisted_queries_only() {
+		let schema = builder("value")
+			.persisted_query("{ value }")
+			.persisted_queries_only()
+			.finish()
+			.unwrap();
+
+		let res = schema.executer(Request::persisted(sha256_hash("{ value }"))).await;
+		assert!(res.is_ok());
+
+		let res = schema.executer(Request::new("{ value }")).await;
+		assert!(res.is_ok());
+
+		let res = schema.executer(Request::new("{ __typename }")).await;
+		assert_eq!(message(&res).as_deref(), Some("PersistedQueryNotInAllowList"));
+	}
+
+	#[tokio::test]
+	async fn document_cache() {
+		let schema = schema("value");
+
+		for _ in 0..2 {
+			let res = schema.executer(Request::new("{ value }")).await;
+			assert!(res.is_ok());
+		}
+		assert_eq!(schema.0.documents.len(), 1);
+
+		// documents failing the validation are not cached
+		let res = schema.executer(Request::new("{ missing }")).await;
+		assert!(!res.is_ok());
+		assert_eq!(schema.0.documents.len(), 1);
+
+		let schema = builder("value").document_cache_size(0).finish().unwrap();
+		let res = schema.executer(Request::new("{ value }")).await;
+		assert!(res.is_ok());
+		assert_eq!(schema.0.documents.len(), 0);
+	}
 }
@@ -0,0 +1,567 @@
+use super::{
+	DeprecationStatus, Directive, Enum, Field, Interface, InterfaceField, Object, Scalar,
+	Subscription, SubscriptionField, Type, TypeRef, TypeRegistry, Union,
+};
+use crate::Value;
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The federation specification a subgraph links to
+pub const FEDERATION_SPEC: &str = "https://specs.apollo.dev/federation/v2.3";
+
+/// The directives a subgraph imports from the federation specification
+const FEDERATION_DIRECTIVES: [&str; 7] =
+	["@key", "@requires", "@provides", "@external", "@shareable", "@override", "@inaccessible"];
+
+/// The types every GraphQL schema provides
+const SYSTEM_TYPES: [&str; 5] = ["Int", "Float", "String", "Boolean", "ID"];
+
+/// used to print the types of a schema in the schema definition language, the federation
+/// directives are only printed for subgraphs
+pub(crate) struct SdlPrinter<'a> {
+	roots: &'a [Option<String>; 3],
+	federation: bool,
+	sdl: String,
+}
+
+impl<'a> SdlPrinter<'a> {
+	pub(crate) fn new(roots: &'a [Option<String>; 3], federation: bool) -> Self {
+		Self {
+			roots,
+			federation,
+			sdl: String::new(),
+		}
+	}
+
+	pub(crate) fn print(mut self, types: &TypeRegistry) -> String {
+		self.schema();
+
+		for ty in types.get_filtered(|name, _| !SYSTEM_TYPES.contains(&name)).values() {
+			match &**ty {
+				Type::Scalar(scalar) => self.scalar(scalar),
+				Type::Object(object) => self.object(object),
+				Type::Enum(en) => self.r#enum(en),
+				Type::Interface(interface) => self.interface(interface),
+				Type::Union(union) => self.union(union),
+				Type::Subscription(subscription) => self.subscription(subscription),
+			}
+		}
+
+		self.sdl
+	}
+
+	fn schema(&mut self) {
+		if self.federation {
+			let imports: Vec<String> = FEDERATION_DIRECTIVES
+				.iter()
+				.map(|directive| format!("\"{}\"", directive))
+				.collect();
+
+			let _ = writeln!(
+				self.sdl,
+				"extend schema @link(url: \"{}\", import: [{}])\n",
+				FEDERATION_SPEC,
+				imports.join(", ")
+			);
+		}
+
+		let roots: Vec<(&str, &String)> = ["query", "mutation", "subscription"]
+			.into_iter()
+			.zip(self.roots.iter())
+			.filter_map(|(operation, root)| root.as_ref().map(|root| (operation, root)))
+			.collect();
+
+		let is_default = roots.iter().all(|(operation, root)| {
+			root.as_str()
+				== match *operation {
+					"query" => "Query",
+					"mutation" => "Mutation",
+					_ => "Subscription",
+				}
+		});
+
+		if !is_default {
+			self.sdl.push_str("schema {\n");
+			for (operation, root) in roots {
+				let _ = writeln!(self.sdl, "\t{}: {}", operation, root);
+			}
+			self.sdl.push_str("}\n\n");
+		}
+	}
+
+	fn scalar(&mut self, scalar: &Scalar) {
+		if scalar.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&scalar.description, "");
+		let _ = write!(self.sdl, "scalar {}", scalar.name);
+		if let Some(url) = &scalar.specified_by_url {
+			let _ = write!(self.sdl, " @specifiedBy(url: {})", string(url));
+		}
+		self.inaccessible(scalar.inaccessible);
+		self.directives(&scalar.directives);
+		self.sdl.push_str("\n\n");
+	}
+
+	fn object(&mut self, object: &Object) {
+		if object.inaccessible && !self.federation {
+			return;
+		}
+
+		let fields = object.fields.values().filter(|field| !field.name.starts_with("__"));
+
+		// objects without resolvers are input objects
+		if object.fields.values().any(|field| field.resolver_fn.is_none()) {
+			self.description(&object.description, "");
+			let _ = write!(self.sdl, "input {}", object.name);
+			self.inaccessible(object.inaccessible);
+			self.directives(&object.directives);
+			self.sdl.push_str(" {\n");
+			for field in fields {
+				self.input_value(field, "\t");
+				self.sdl.push('\n');
+			}
+			self.sdl.push_str("}\n\n");
+			return;
+		}
+
+		self.description(&object.description, "");
+		if object.extends && self.federation {
+			self.sdl.push_str("extend ");
+		}
+		let _ = write!(self.sdl, "type {}", object.name);
+		self.implements(object.implements.iter());
+		if self.federation {
+			for key in &object.keys {
+				let _ = write!(self.sdl, " @key(fields: {}", string(key));
+				if !object.resolvable {
+					self.sdl.push_str(", resolvable: false");
+				}
+				self.sdl.push(')');
+			}
+			if object.shareable {
+				self.sdl.push_str(" @shareable");
+			}
+		}
+		self.inaccessible(object.inaccessible);
+		self.directives(&object.directives);
+		self.sdl.push_str(" {\n");
+		for field in fields {
+			self.field(field);
+		}
+		self.sdl.push_str("}\n\n");
+	}
+
+	fn field(&mut self, field: &Field) {
+		if field.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&field.description, "\t");
+		let _ = write!(self.sdl, "\t{}", field.name);
+		self.arguments(&field.arguments);
+		let _ = write!(self.sdl, ": {}", type_ref(&field.ty));
+		self.deprecation(&field.deprecation);
+		if self.federation {
+			if field.external {
+				self.sdl.push_str(" @external");
+			}
+			if let Some(fields) = &field.requires {
+				let _ = write!(self.sdl, " @requires(fields: {})", string(fields));
+			}
+			if let Some(fields) = &field.provides {
+				let _ = write!(self.sdl, " @provides(fields: {})", string(fields));
+			}
+			if field.shareable {
+				self.sdl.push_str(" @shareable");
+			}
+			if let Some(from) = &field.override_from {
+				let _ = write!(self.sdl, " @override(from: {})", string(from));
+			}
+		}
+		self.inaccessible(field.inaccessible);
+		self.sdl.push('\n');
+	}
+
+	fn r#enum(&mut self, en: &Enum) {
+		if en.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&en.description, "");
+		let _ = write!(self.sdl, "enum {}", en.name);
+		self.inaccessible(en.inaccessible);
+		self.directives(&en.directives);
+		self.sdl.push_str(" {\n");
+		for item in en.enum_values.values() {
+			if item.inaccessible && !self.federation {
+				continue;
+			}
+
+			self.description(&item.description, "\t");
+			let _ = write!(self.sdl, "\t{}", item.name);
+			self.deprecation(&item.deprecation);
+			self.inaccessible(item.inaccessible);
+			self.directives(&item.directives);
+			self.sdl.push('\n');
+		}
+		self.sdl.push_str("}\n\n");
+	}
+
+	fn interface(&mut self, interface: &Interface) {
+		if interface.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&interface.description, "");
+		let _ = write!(self.sdl, "interface {}", interface.name);
+		self.implements(interface.implements.iter());
+		self.inaccessible(interface.inaccessible);
+		self.directives(&interface.directives);
+		self.sdl.push_str(" {\n");
+		for field in interface.fields.values() {
+			self.interface_field(field);
+		}
+		self.sdl.push_str("}\n\n");
+	}
+
+	fn interface_field(&mut self, field: &InterfaceField) {
+		if field.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&field.description, "\t");
+		let _ = write!(self.sdl, "\t{}", field.name);
+		self.arguments(&field.arguments);
+		let _ = write!(self.sdl, ": {}", type_ref(&field.ty));
+		self.deprecation(&field.deprecation);
+		self.inaccessible(field.inaccessible);
+		self.directives(&field.directives);
+		self.sdl.push('\n');
+	}
+
+	fn union(&mut self, union: &Union) {
+		if union.inaccessible && !self.federation {
+			return;
+		}
+
+		self.description(&union.description, "");
+		let _ = write!(self.sdl, "union {}", union.name);
+		self.inaccessible(union.inaccessible);
+		self.directives(&union.directives);
+		let possible_types: Vec<&str> = union.possible_types.iter().map(String::as_str).collect();
+		let _ = write!(self.sdl, " = {}\n\n", possible_types.join(" | "));
+	}
+
+	fn subscription(&mut self, subscription: &Subscription) {
+		self.description(&subscription.description, "");
+		let _ = writeln!(self.sdl, "type {} {{", subscription.name);
+		for field in subscription.fields.values() {
+			self.subscription_field(field);
+		}
+		self.sdl.push_str("}\n\n");
+	}
+
+	fn subscription_field(&mut self, field: &SubscriptionField) {
+		self.description(&field.description, "\t");
+		let _ = write!(self.sdl, "\t{}", field.name);
+		self.arguments(&field.arguments);
+		let _ = write!(self.sdl, ": {}", type_ref(&field.ty));
+		self.deprecation(&field.deprecation);
+		self.sdl.push('\n');
+	}
+
+	fn arguments(&mut self, arguments: &BTreeMap<String, Field>) {
+		let arguments: Vec<&Field> = arguments
+			.values()
+			.filter(|argument| self.federation || !argument.inaccessible)
+			.collect();
+
+		if arguments.is_empty() {
+			return;
+		}
+
+		self.sdl.push('(');
+		for (index, argument) in arguments.into_iter().enumerate() {
+			if index > 0 {
+				self.sdl.push_str(", ");
+			}
+			self.input_value(argument, "");
+		}
+		self.sdl.push(')');
+	}
+
+	fn input_value(&mut self, field: &Field, indent: &str) {
+		if !indent.is_empty() {
+			self.description(&field.description, indent);
+		}
+		let _ = write!(self.sdl, "{}{}: {}", indent, field.name, type_ref(&field.ty));
+		if let Some(default_value) = &field.default_value {
+			let _ = write!(self.sdl, " = {}", value(default_value));
+		}
+		self.deprecation(&field.deprecation);
+		self.inaccessible(field.inaccessible);
+	}
+
+	fn implements<'b>(&mut self, implements: impl Iterator<Item = &'b String>) {
+		let implements: Vec<&str> = implements.map(String::as_str).collect();
+
+		if !implements.is_empty() {
+			let _ = write!(self.sdl, " implements {}", implements.join(" & "));
+		}
+	}
+
+	fn description(&mut self, description: &Option<String>, indent: &str) {
+		if let Some(description) = description {
+			if description.contains('\n') {
+				let _ = writeln!(self.sdl, "{}\"\"\"", indent);
+				for line in description.lines() {
+					let _ = writeln!(self.sdl, "{}{}", indent, line.replace("\"\"\"", "\\\"\"\""));
+				}
+				let _ = writeln!(self.sdl, "{}\"\"\"", indent);
+			} else {
+				let _ = writeln!(self.sdl, "{}{}", indent, string(description));
+			}
+		}
+	}
+
+	fn deprecation(&mut self, deprecation: &DeprecationStatus) {
+		if let DeprecationStatus::Deprecated(reason) = deprecation {
+			match reason {
+				Some(reason) => {
+					let _ = write!(self.sdl, " @deprecated(reason: {})", string(reason));
+				}
+				None => self.sdl.push_str(" @deprecated"),
+			}
+		}
+	}
+
+	fn inaccessible(&mut self, inaccessible: bool) {
+		if inaccessible && self.federation {
+			self.sdl.push_str(" @inaccessible");
+		}
+	}
+
+	fn directives(&mut self, directives: &[Directive]) {
+		for directive in directives {
+			let _ = write!(self.sdl, " @{}", directive.name);
+			if !directive.args.is_empty() {
+				let args: Vec<String> = directive
+					.args
+					.iter()
+					.map(|(name, arg)| match name {
+						Value::String(name) => format!("{}: {}", name, value(arg)),
+						name => format!("{}: {}", value(name), value(arg)),
+					})
+					.collect();
+				let _ = write!(self.sdl, "({})", args.join(", "));
+			}
+		}
+	}
+}
+
+/// used to print a type reference, the built-in scalars are referenced by lowercase names
+fn type_ref(ty: &TypeRef) -> String {
+	match ty {
+		TypeRef::Named(name) => match name.as_ref() {
+			TypeRef::INT => "Int".to_string(),
+			TypeRef::FLOAT => "Float".to_string(),
+			TypeRef::STRING => "String".to_string(),
+			TypeRef::BOOLEAN => "Boolean".to_string(),
+			TypeRef::ID => "ID".to_string(),
+			name => name.to_string(),
+		},
+		TypeRef::NonNull(ty) => format!("{}!", type_ref(ty)),
+		TypeRef::List(ty) => format!("[{}]", type_ref(ty)),
+	}
+}
+
+fn string(value: &str) -> String {
+	serde_json::to_string(value).unwrap_or_default()
+}
+
+/// used to print a value as a GraphQL literal
+fn value(value: &Value) -> String {
+	match value {
+		Value::Int8(data) => data.to_string(),
+		Value::Int16(data) => data.to_string(),
+		Value::Int32(data) => data.to_string(),
+		Value::Int64(data) => data.to_string(),
+		Value::Int128(data) => data.to_string(),
+		Value::Intsize(data) => data.to_string(),
+		Value::UInt8(data) => data.to_string(),
+		Value::UInt16(data) => data.to_string(),
+		Value::UInt32(data) => data.to_string(),
+		Value::UInt64(data) => data.to_string(),
+		Value::UInt128(data) => data.to_string(),
+		Value::UIntsize(data) => data.to_string(),
+		Value::Float32(data) => data.to_string(),
+		Value::Float64(data) => data.to_string(),
+		Value::Bool(data) => data.to_string(),
+		Value::Char(data) => string(&data.to_string()),
+		Value::String(data) => string(data),
+		// bytes are written as hex strings like the binary columns of entities
+		Value::Bytes(data) => string(&hex::encode(data)),
+		Value::List(values) => {
+			let values: Vec<String> = values.iter().map(self::value).collect();
+			format!("[{}]", values.join(", "))
+		}
+		Value::Map(fields) => {
+			let fields: Vec<String> = fields
+				.iter()
+				.map(|(name, field)| match name {
+					Value::String(name) => format!("{}: {}", name, self::value(field)),
+					name => format!("{}: {}", self::value(name), self::value(field)),
+				})
+				.collect();
+			format!("{{{}}}", fields.join(", "))
+		}
+		Value::Option(data) => match data.as_ref() {
+			Some(data) => self::value(data),
+			None => "null".to_string(),
+		},
+		Value::Var(data) => self::value(&data.1),
+		Value::Null => "null".to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use juniper::{
+		InputValue,
+		http::{GraphQLRequest, GraphQLResponse},
+	};
+
+	use crate::{
+		FieldFuture, FieldValue, SeaographyError, Value,
+		graphql::{Field, JuniperValue, Object, Schema, TypeRef},
+	};
+
+	struct User {
+		id: String,
+	}
+
+	fn schema() -> Schema {
+		let user = Object::new("User")
+			.key("id")
+			.field(Field::output("id", TypeRef::named_nn(TypeRef::ID), |ctx| {
+				FieldFuture::new(async move {
+					Ok(Some(Value::from(ctx.parent_value.try_downcast_ref::<User>()?.id.clone())))
+				})
+			}))
+			.field(
+				Field::output("name", TypeRef::named_nn(TypeRef::STRING), |_| {
+					FieldFuture::new(async move { Ok(Some(Value::from("name"))) })
+				})
+				.shareable(),
+			);
+
+		let query = Object::new("Query").field(Field::output("me", TypeRef::named("User"), |_| {
+			FieldFuture::new(async move {
+				Ok(Some(FieldValue::owned_any(User {
+					id: "1".to_string(),
+				})))
+			})
+		}));
+
+		Schema::build(query.type_name(), None, None)
+			.register(user)
+			.register(query)
+			.entity_resolver(|ctx| {
+				FieldFuture::new(async move {
+					let mut entities = Vec::new();
+					for representation in ctx.args.try_get("representations")?.list()?.to_iter() {
+						let representation = representation.object()?;
+						match representation.try_get("__typename")?.string()? {
+							"User" => entities.push(
+								FieldValue::owned_any(User {
+									id: representation.try_get("id")?.string()?.to_string(),
+								})
+								.with_type("User"),
+							),
+							name => {
+								return Err(SeaographyError::new(format!(
+									"Unknown entity `{}`",
+									name
+								)));
+							}
+						}
+					}
+					Ok(Some(FieldValue::list(entities)))
+				})
+			})
+			.finish()
+			.unwrap()
+	}
+
+	#[test]
+	fn sdl() {
+		let sdl = schema().sdl().to_string();
+
+		assert!(
+			sdl.starts_with(
+				"extend schema @link(url: \"https://specs.apollo.dev/federation/v2.3\""
+			)
+		);
+		assert!(sdl.contains(
+			"type User @key(fields: \"id\") {\n\tid: ID!\n\tname: String! @shareable\n}"
+		));
+		assert!(sdl.contains("type Query {\n\tme: User\n}"));
+		assert!(!sdl.contains("_entities"));
+	}
+
+	#[tokio::test]
+	async fn service() {
+		let schema = schema();
+		let res = schema
+			.executer(GraphQLRequest::new("{ _service { sdl } }".to_string(), None, None))
+			.await;
+
+		assert_eq!(
+			res,
+			GraphQLResponse::from_result(Ok((
+				JuniperValue::object(juniper::Object::from_iter(vec![(
+					"_service",
+					JuniperValue::scalar(Value::Map(
+						[(Value::from("sdl"), Value::from(schema.sdl()))].into()
+					))
+				)])),
+				vec![]
+			)))
+		);
+	}
+
+	#[tokio::test]
+	async fn entities() {
+		let representations: InputValue<Value> = serde_json::from_value(serde_json::json!({
+			"representations": [{ "__typename": "User", "id": "42" }]
+		}))
+		.unwrap();
+
+		let res = schema()
+			.executer(GraphQLRequest::new(
+				"query($representations: [_Any!]!) { _entities(representations: $representations) { ... on User { id name } } }"
+					.to_string(),
+				None,
+				Some(representations),
+			))
+			.await;
+
+		assert_eq!(
+			res,
+			GraphQLResponse::from_result(Ok((
+				JuniperValue::object(juniper::Object::from_iter(vec![(
+					"_entities",
+					JuniperValue::scalar(Value::List(vec![Value::Map(
+						[
+							(Value::from("id"), Value::from("42")),
+							(Value::from("name"), Value::from("name"))
+						]
+						.into()
+					)]))
+				)])),
+				vec![]
+			)))
+		);
+	}
+}
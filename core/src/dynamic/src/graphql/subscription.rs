@@ -1,8 +1,19 @@
-use super::{DeprecationStatus, Field, TypeRef};
-use crate::{BoxResolverFn, FieldFuture, ResolverContext};
+use super::{
+	Arguments, DeprecationStatus, ExecutionResult, Executor, Field, FieldError, GraphQLType,
+	GraphQLValue, GraphQLValueAsync, JuniperField, JuniperValue, MetaType, Registry, SelectionSet,
+	TypeInfo, TypeRef, TypeRefToMeta, TypeRegistry, type_ref_to_value,
+};
+use crate::{
+	BoxSubscriptionResolverFn, ContextBase, FieldValue, ObjectAccessor, ResolverContext,
+	SubscriptionFieldFuture, Value,
+};
+use futures::{FutureExt, StreamExt, future::BoxFuture};
+use juniper::{ExecutionError, IntoFieldError, ValuesStream};
 use std::{
+	borrow::Cow,
 	collections::BTreeMap,
 	fmt::{self, Debug},
+	sync::Arc,
 };
 
 /// A GraphQL subscription field
@@ -11,7 +22,7 @@ pub struct SubscriptionField {
 	pub(crate) description: Option<String>,
 	pub(crate) arguments: BTreeMap<String, Field>,
 	pub(crate) ty: TypeRef,
-	pub(crate) resolver_fn: BoxResolverFn,
+	pub(crate) resolver_fn: BoxSubscriptionResolverFn,
 	pub(crate) deprecation: DeprecationStatus,
 }
 
@@ -21,7 +32,7 @@ impl SubscriptionField {
 	where
 		N: Into<String>,
 		T: Into<TypeRef>,
-		F: for<'b> Fn(ResolverContext<'b>) -> FieldFuture<'b> + Send + Sync + 'static,
+		F: for<'b> Fn(ResolverContext<'b>) -> SubscriptionFieldFuture<'b> + Send + Sync + 'static,
 	{
 		Self {
 			name: name.into(),
@@ -48,6 +59,30 @@ impl SubscriptionField {
 		self.arguments.insert(input_value.name.clone(), input_value);
 		self
 	}
+
+	pub fn meta<'r>(
+		&self,
+		types: &Arc<TypeRegistry>,
+		registry: &mut Registry<'r, Value>,
+	) -> JuniperField<'r, Value> {
+		let mut type_ref = TypeRefToMeta::new(self.type_name(), types);
+		type_ref.from_type_ref(&self.ty);
+		let mut field = type_ref.to_ouput_meta(registry);
+
+		for arg in self.arguments.values().filter(|arg| !arg.inaccessible) {
+			if let Some(arg) = arg.meta_input(types, registry) {
+				field = field.argument(arg);
+			}
+		}
+		if let Some(description) = &self.description {
+			field = field.description(description);
+		}
+		if let DeprecationStatus::Deprecated(reason) = &self.deprecation {
+			field = field.deprecated(reason.as_deref());
+		}
+
+		field
+	}
 }
 
 impl Debug for SubscriptionField {
@@ -96,158 +131,180 @@ impl Subscription {
 	pub fn type_name(&self) -> &str {
 		&self.name
 	}
+}
+
+impl GraphQLType<Value> for Subscription {
+	fn name(info: &Self::TypeInfo) -> Option<&str> {
+		Some(info.type_name())
+	}
 
-	// #[doc(hidden)]
-	// pub fn register(&self, registry: &mut Registry) -> Result<(), SchemaError> {
-	//     let mut fields = BTreeMap::new();
-
-	//     for field in self.fields.values() {
-	//         let mut args = BTreeMap::new();
-
-	//         for argument in field.arguments.values() {
-	//             args.insert(argument.name.clone(), argument.to_meta_input_value());
-	//         }
-
-	//         fields.insert(
-	//             field.name.clone(),
-	//             MetaField {
-	//                 name: field.name.clone(),
-	//                 description: field.description.clone(),
-	//                 args,
-	//                 ty: field.ty.to_string(),
-	//                 deprecation: field.deprecation.clone(),
-	//                 cache_control: Default::default(),
-	//                 external: false,
-	//                 requires: None,
-	//                 provides: None,
-	//                 visible: None,
-	//                 shareable: false,
-	//                 inaccessible: false,
-	//                 tags: vec![],
-	//                 override_from: None,
-	//                 compute_complexity: None,
-	//                 directive_invocations: vec![],
-	//                 requires_scopes: vec![],
-	//             },
-	//         );
-	//     }
-
-	//     registry.types.insert(
-	//         self.name.clone(),
-	//         MetaType::Object {
-	//             name: self.name.clone(),
-	//             description: self.description.clone(),
-	//             fields,
-	//             cache_control: Default::default(),
-	//             extends: false,
-	//             shareable: false,
-	//             resolvable: true,
-	//             keys: None,
-	//             visible: None,
-	//             inaccessible: false,
-	//             interface_object: false,
-	//             tags: vec![],
-	//             is_subscription: true,
-	//             rust_typename: None,
-	//             directive_invocations: vec![],
-	//             requires_scopes: vec![],
-	//         },
-	//     );
-
-	//     Ok(())
-	// }
-
-	// pub(crate) fn collect_streams<'a>(
-	//     &self,
-	//     schema: &Schema,
-	//     ctx: &ContextSelectionSet<'a>,
-	//     streams: &mut Vec<BoxFieldStream<'a>>,
-	//     root_value: &'a FieldValue<'static>,
-	// ) {
-	//     for selection in &ctx.item.node.items {
-	//         if let Selection::Field(field) = &selection.node {
-	//             if let Some(field_def) = self.fields.get(field.node.name.node.as_str()) {
-	//                 let schema = schema.clone();
-	//                 let field_type = field_def.ty.clone();
-	//                 let resolver_fn = field_def.resolver_fn.clone();
-	//                 let ctx = ctx.clone();
-
-	//                 streams.push(
-	//                     async_stream::try_stream! {
-	//                         let ctx_field = ctx.with_field(field);
-	//                         let field_name = ctx_field.item.node.response_key().node.clone();
-	//                         let arguments = ObjectAccessor(Cow::Owned(
-	//                             field
-	//                                 .node
-	//                                 .arguments
-	//                                 .iter()
-	//                                 .map(|(name, value)| {
-	//                                     ctx_field
-	//                                         .resolve_input_value(value.clone())
-	//                                         .map(|value| (name.node.clone(), value))
-	//                                 })
-	//                                 .collect::<ServerResult<BTreeMap<Name, Value>>>()?,
-	//                         ));
-
-	//                         let mut stream = resolver_fn(ResolverContext {
-	//                             ctx: &ctx_field,
-	//                             args: arguments,
-	//                             parent_value: root_value,
-	//                         })
-	//                         .0
-	//                         .await
-	//                         .map_err(|err| ctx_field.set_error_path(err.into_server_error(ctx_field.item.pos)))?;
-
-	//                         while let Some(value) = stream.next().await.transpose().map_err(|err| ctx_field.set_error_path(err.into_server_error(ctx_field.item.pos)))? {
-	//                             let f = |execute_data: Option<Data>| {
-	//                                 let schema = schema.clone();
-	//                                 let field_name = field_name.clone();
-	//                                 let field_type = field_type.clone();
-	//                                 let ctx_field = ctx_field.clone();
-
-	//                                 async move {
-	//                                     let mut ctx_field = ctx_field.clone();
-	//                                     ctx_field.execute_data = execute_data.as_ref();
-	//                                     let ri = ResolveInfo {
-	//                                         path_node: &QueryPathNode {
-	//                                             parent: None,
-	//                                             segment: QueryPathSegment::Name(&field_name),
-	//                                         },
-	//                                         parent_type: schema.0.env.registry.subscription_type.as_ref().unwrap(),
-	//                                         return_type: &field_type.to_string(),
-	//                                         name: field.node.name.node.as_str(),
-	//                                         alias: field.node.alias.as_ref().map(|alias| alias.node.as_str()),
-	//                                         is_for_introspection: false,
-	//                                         field: &field.node,
-	//                                     };
-	//                                     let resolve_fut = resolve(&schema, &ctx_field, &field_type, Some(&value));
-	//                                     futures_util::pin_mut!(resolve_fut);
-	//                                     let value = ctx_field.query_env.extensions.resolve(ri, &mut resolve_fut).await;
-
-	//                                     match value {
-	//                                         Ok(value) => {
-	//                                             let mut map = BTreeMap::new();
-	//                                             map.insert(field_name.clone(), value.unwrap_or_default());
-	//                                             Response::new(Value::Object(map))
-	//                                         },
-	//                                         Err(err) => Response::from_errors(vec![err]),
-	//                                     }
-	//                                 }
-	//                             };
-	//                             let resp = ctx_field.query_env.extensions.execute(ctx_field.query_env.operation_name.as_deref(), f).await;
-	//                             let is_err = !resp.errors.is_empty();
-	//                             yield resp;
-	//                             if is_err {
-	//                                 break;
-	//                             }
-	//                         }
-	//                     }.map(|res| {
-	//                         res.unwrap_or_else(|err| Response::from_errors(vec![err]))
-	//                     })
-	//                     .boxed(),
-	//                 );
-	//             }
-	//         }
-	//     }
-	// }
+	fn meta<'r>(info: &Self::TypeInfo, registry: &mut Registry<'r, Value>) -> MetaType<'r, Value>
+	where
+		Value: 'r,
+	{
+		let subscription = match info.ty.as_subscription() {
+			Some(subscription) => subscription,
+			None => panic!("Type `{}` is not a subscription", info.type_name()),
+		};
+
+		let fields: Vec<_> =
+			subscription.fields.values().map(|field| field.meta(&info.types, registry)).collect();
+
+		let mut meta_type = registry.build_object_type::<Self>(info, &fields);
+
+		if let Some(description) = &subscription.description {
+			meta_type = meta_type.description(description);
+		}
+
+		meta_type.into_meta()
+	}
+}
+
+impl GraphQLValue<Value> for Subscription {
+	type Context = ContextBase;
+	type TypeInfo = TypeInfo;
+	fn type_name<'i>(&self, info: &'i Self::TypeInfo) -> Option<&'i str> {
+		Some(info.type_name())
+	}
+
+	fn concrete_type_name(&self, _context: &Self::Context, info: &Self::TypeInfo) -> String {
+		info.type_name().to_string()
+	}
+}
+
+impl GraphQLValueAsync<Value> for Subscription
+where
+	Self::TypeInfo: Sync,
+	Self::Context: Sync,
+{
+	fn resolve_field_async<'a>(
+		&'a self,
+		info: &'a Self::TypeInfo,
+		field_name: &'a str,
+		_arguments: &'a Arguments<Value>,
+		_executor: &'a Executor<Self::Context, Value>,
+	) -> BoxFuture<'a, ExecutionResult<Value>> {
+		async move {
+			Err(FieldError::from(format!(
+				"Field `{}` of subscription `{}` can only be resolved into a stream",
+				field_name,
+				info.type_name()
+			)))
+		}
+		.boxed()
+	}
+}
+
+/// used to resolve a field of the given subscription type into a stream of values,
+/// every item of the resolver stream is resolved with the selection set of the field
+pub(crate) fn collect_stream<'a>(
+	info: TypeInfo,
+	field_name: &str,
+	executor: &Executor<'_, 'a, ContextBase, Value>,
+) -> ValuesStream<'a, Value> {
+	let field_name = field_name.to_owned();
+	let owned_executor = executor.as_owned_executor();
+
+	async_stream::stream! {
+		let field = match info.ty.as_subscription().and_then(|subscription| subscription.fields.get(&field_name)) {
+			Some(field) => field,
+			None => {
+				yield Err(ExecutionError::at_origin(FieldError::from(format!(
+					"Field `{}` not found on type `{}`",
+					field_name,
+					info.type_name()
+				))));
+				return;
+			}
+		};
+
+		let executor = owned_executor.as_executor();
+		let ctx = executor.context();
+		let selection_set = SelectionSet::from_look_ahead(&executor.look_ahead());
+
+		let future = (field.resolver_fn)(ResolverContext {
+			ctx,
+			args: ObjectAccessor(Cow::Borrowed(&selection_set.arguments)),
+			parent_value: &FieldValue::NULL,
+		});
+
+		let mut stream = match future.0.await {
+			Ok(stream) => stream,
+			Err(err) => {
+				yield Err(ExecutionError::at_origin(err.into_field_error()));
+				return;
+			}
+		};
+
+		while let Some(item) = stream.next().await {
+			let value = match item {
+				Ok(value) => type_ref_to_value(&field.ty, &info.types, ctx, &selection_set, &value, None).await,
+				Err(err) => Err(err),
+			};
+
+			match value {
+				Ok(value) => yield Ok(JuniperValue::scalar(value)),
+				Err(err) => {
+					yield Err(ExecutionError::at_origin(err.into_field_error()));
+					break;
+				}
+			}
+		}
+	}
+	.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+	use futures::StreamExt;
+	use juniper::http::{GraphQLRequest, GraphQLResponse};
+
+	use crate::{
+		FieldFuture, SeaographyError, SubscriptionFieldFuture, Value,
+		graphql::{Field, JuniperValue, Object, Schema, Subscription, SubscriptionField, TypeRef},
+	};
+
+	#[tokio::test]
+	async fn subscription_stream() {
+		let query = Object::new("Query").field(Field::output(
+			"value",
+			TypeRef::named(TypeRef::INT),
+			|_| FieldFuture::from_value(Some(Value::from(10))),
+		));
+
+		let subscription = Subscription::new("Subscription").field(SubscriptionField::new(
+			"counter",
+			TypeRef::named_nn(TypeRef::INT),
+			|_| {
+				SubscriptionFieldFuture::new(async move {
+					Ok(futures::stream::iter(0..3)
+						.map(|value| Ok::<_, SeaographyError>(Value::from(value))))
+				})
+			},
+		));
+
+		let schema = Schema::build(query.type_name(), None, Some(subscription.type_name()))
+			.register(query)
+			.register(subscription)
+			.finish()
+			.unwrap();
+
+		let responses: Vec<_> = schema
+			.execute_stream(GraphQLRequest::new("subscription { counter }".to_string(), None, None))
+			.collect()
+			.await;
+
+		assert_eq!(
+			responses,
+			(0..3)
+				.map(|value| GraphQLResponse::from_result(Ok((
+					JuniperValue::object(juniper::Object::from_iter(vec![(
+						"counter",
+						JuniperValue::scalar(Value::from(value))
+					)])),
+					vec![]
+				))))
+				.collect::<Vec<_>>()
+		);
+	}
 }
@@ -3,16 +3,14 @@ use super::{
 	Interface, JuniperField, JuniperTypeRef, MetaType, Object, Registry, Scalar, SelectionSet,
 	Subscription, Union,
 };
-use crate::{BoxFieldFutureJson, ContextBase, FieldValue, ObjectAccessor, SeaResult, Value};
+use crate::{BoxFieldFutureJson, ContextBase, FieldValue, SeaResult, Value};
 use futures::future::BoxFuture;
-use once_cell::sync::Lazy;
 use std::{
 	collections::BTreeMap,
 	sync::{Arc, RwLock},
 };
 
-pub static TYPE_REGISTRY: Lazy<TypeRegistry> = Lazy::new(TypeRegistry::new);
-
+/// The types of a schema, every schema resolves its types through its own registry
 #[derive(Debug)]
 pub struct TypeRegistry {
 	types: RwLock<BTreeMap<String, Arc<Type>>>,
@@ -51,6 +49,16 @@ impl TypeRegistry {
 		types.get(name).map(|ty| ty.clone())
 	}
 
+	/// used to take a type out of the registry, to extend it after it was registered
+	pub(crate) fn remove(&self, name: &str) -> Option<Arc<Type>> {
+		let mut types = match self.types.write() {
+			Ok(types) => types,
+			Err(e) => panic!("Failed access types: {}", e),
+		};
+
+		types.remove(name)
+	}
+
 	pub fn get_filtered<F>(&self, mut f: F) -> BTreeMap<String, Arc<Type>>
 	where
 		F: FnMut(&str, &Arc<Type>) -> bool,
@@ -75,6 +83,29 @@ impl TypeRegistry {
 	}
 }
 
+/// The type info of the types referencing other types, used to look them up in the registry
+/// of the schema the type belongs to
+#[derive(Clone, Debug)]
+pub struct TypeInfo {
+	pub(crate) ty: Arc<Type>,
+	pub(crate) types: Arc<TypeRegistry>,
+}
+
+impl TypeInfo {
+	pub(crate) fn new(ty: Arc<Type>, types: Arc<TypeRegistry>) -> Self {
+		Self {
+			ty,
+			types,
+		}
+	}
+
+	/// Returns the type name
+	#[inline]
+	pub fn type_name(&self) -> &str {
+		self.ty.name()
+	}
+}
+
 /// A GraphQL type
 #[derive(Debug)]
 pub enum Type {
@@ -180,38 +211,16 @@ impl Type {
 		}
 	}
 
-	pub(crate) fn get_type<'a>(&self, registry: &mut Registry<'a, Value>) -> JuniperTypeRef<'a> {
-		match self {
-			Type::Scalar(scalar) => registry.get_type::<Scalar>(scalar),
-			Type::Object(object) => registry.get_type::<Object>(object),
-			Type::Enum(e) => registry.get_type::<Enum>(e),
-			Type::Interface(interface) => registry.get_type::<Interface>(interface),
-			Type::Union(union) => registry.get_type::<Union>(union),
-			Type::Subscription(subscription) => unimplemented!(),
-		}
-	}
-
-	pub(crate) fn meta<'a>(&self, registry: &mut Registry<'a, Value>) -> MetaType<'a, Value> {
-		match self {
-			Type::Scalar(scalar) => Scalar::meta(scalar, registry),
-			Type::Object(object) => Object::meta(object, registry),
-			Type::Enum(e) => Enum::meta(e, registry),
-			Type::Interface(interface) => Interface::meta(interface, registry),
-			Type::Union(union) => Union::meta(union, registry),
-			Type::Subscription(subscription) => unimplemented!(),
-		}
-	}
-
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		selection_set: &'a SelectionSet,
-		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
 	) -> Vec<BoxFieldFutureJson<'a>> {
 		match self {
 			Type::Scalar(scalar) => Vec::from([scalar.collect()]),
-			Type::Object(object) => object.collect(ctx, selection_set, arguments, parent_value),
+			Type::Object(object) => object.collect(types, ctx, selection_set, parent_value),
 			Type::Enum(en) => Vec::from([en.collect()]),
 			Type::Interface(interface) => Vec::from([interface.collect()]),
 			Type::Union(union) => Vec::from([union.collect()]),
@@ -227,14 +236,39 @@ impl Type {
 		}
 	}
 
-	pub fn check(&self, type_name: &str) -> SeaResult<()> {
+	pub fn check(&self, types: &TypeRegistry, type_name: &str) -> SeaResult<()> {
 		match self {
-			Type::Interface(interface) => interface.check(type_name),
+			Type::Interface(interface) => interface.check(types, type_name),
 			Type::Union(union) => union.check(type_name),
-			Type::Object(object) => object.check(type_name),
+			Type::Object(object) => object.check(types, type_name),
 			_ => Ok(()),
 		}
 	}
+}
+
+// Register
+impl TypeInfo {
+	pub(crate) fn get_type<'a>(&self, registry: &mut Registry<'a, Value>) -> JuniperTypeRef<'a> {
+		match &*self.ty {
+			Type::Scalar(scalar) => registry.get_type::<Scalar>(scalar),
+			Type::Object(_) => registry.get_type::<Object>(self),
+			Type::Enum(e) => registry.get_type::<Enum>(e),
+			Type::Interface(_) => registry.get_type::<Interface>(self),
+			Type::Union(_) => registry.get_type::<Union>(self),
+			Type::Subscription(_) => registry.get_type::<Subscription>(self),
+		}
+	}
+
+	pub(crate) fn meta<'a>(&self, registry: &mut Registry<'a, Value>) -> MetaType<'a, Value> {
+		match &*self.ty {
+			Type::Scalar(scalar) => Scalar::meta(scalar, registry),
+			Type::Object(_) => Object::meta(self, registry),
+			Type::Enum(e) => Enum::meta(e, registry),
+			Type::Interface(_) => Interface::meta(self, registry),
+			Type::Union(_) => Union::meta(self, registry),
+			Type::Subscription(_) => Subscription::meta(self, registry),
+		}
+	}
 
 	pub fn resolve<'a>(
 		&'a self,
@@ -242,32 +276,31 @@ impl Type {
 		arguments: &'a Arguments<Value>,
 		executor: &'a Executor<ContextBase, Value>,
 	) -> BoxFuture<'a, ExecutionResult<Value>> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => scalar.resolve_field_async(scalar, field, arguments, executor),
-			Type::Object(object) => object.resolve_field_async(object, field, arguments, executor),
+			Type::Object(object) => object.resolve_field_async(self, field, arguments, executor),
 			Type::Enum(e) => e.resolve_field_async(e, field, arguments, executor),
 			Type::Interface(interface) => {
-				interface.resolve_field_async(interface, field, arguments, executor)
+				interface.resolve_field_async(self, field, arguments, executor)
+			}
+			Type::Union(union) => union.resolve_field_async(self, field, arguments, executor),
+			Type::Subscription(subscription) => {
+				subscription.resolve_field_async(self, field, arguments, executor)
 			}
-			Type::Union(union) => union.resolve_field_async(union, field, arguments, executor),
-			Type::Subscription(subscription) => todo!(),
 		}
 	}
-}
 
-// Register
-impl Type {
 	pub(crate) fn field<'a>(
 		&self,
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.field::<Scalar>(field_name, scalar),
-			Type::Object(object) => registry.field::<Object>(field_name, object),
+			Type::Object(_) => registry.field::<Object>(field_name, self),
 			Type::Enum(e) => registry.field::<Enum>(field_name, e),
-			Type::Interface(interface) => registry.field::<Interface>(field_name, interface),
-			Type::Union(union) => registry.field::<Union>(field_name, union),
+			Type::Interface(_) => registry.field::<Interface>(field_name, self),
+			Type::Union(_) => registry.field::<Union>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -277,14 +310,12 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.field::<Option<Scalar>>(field_name, scalar),
-			Type::Object(object) => registry.field::<Option<Object>>(field_name, object),
+			Type::Object(_) => registry.field::<Option<Object>>(field_name, self),
 			Type::Enum(e) => registry.field::<Option<Enum>>(field_name, e),
-			Type::Interface(interface) => {
-				registry.field::<Option<Interface>>(field_name, interface)
-			}
-			Type::Union(union) => registry.field::<Option<Union>>(field_name, union),
+			Type::Interface(_) => registry.field::<Option<Interface>>(field_name, self),
+			Type::Union(_) => registry.field::<Option<Union>>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -294,12 +325,12 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.field::<Vec<Scalar>>(field_name, scalar),
-			Type::Object(object) => registry.field::<Vec<Object>>(field_name, object),
+			Type::Object(_) => registry.field::<Vec<Object>>(field_name, self),
 			Type::Enum(e) => registry.field::<Vec<Enum>>(field_name, e),
-			Type::Interface(interface) => registry.field::<Vec<Interface>>(field_name, interface),
-			Type::Union(union) => registry.field::<Vec<Union>>(field_name, union),
+			Type::Interface(_) => registry.field::<Vec<Interface>>(field_name, self),
+			Type::Union(_) => registry.field::<Vec<Union>>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -309,14 +340,12 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.field::<Vec<Option<Scalar>>>(field_name, scalar),
-			Type::Object(object) => registry.field::<Vec<Option<Object>>>(field_name, object),
+			Type::Object(_) => registry.field::<Vec<Option<Object>>>(field_name, self),
 			Type::Enum(e) => registry.field::<Vec<Option<Enum>>>(field_name, e),
-			Type::Interface(interface) => {
-				registry.field::<Vec<Option<Interface>>>(field_name, interface)
-			}
-			Type::Union(union) => registry.field::<Vec<Option<Union>>>(field_name, union),
+			Type::Interface(_) => registry.field::<Vec<Option<Interface>>>(field_name, self),
+			Type::Union(_) => registry.field::<Vec<Option<Union>>>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -326,14 +355,12 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.field::<Option<Vec<Scalar>>>(field_name, scalar),
-			Type::Object(object) => registry.field::<Option<Vec<Object>>>(field_name, object),
+			Type::Object(_) => registry.field::<Option<Vec<Object>>>(field_name, self),
 			Type::Enum(e) => registry.field::<Option<Vec<Enum>>>(field_name, e),
-			Type::Interface(interface) => {
-				registry.field::<Option<Vec<Interface>>>(field_name, interface)
-			}
-			Type::Union(union) => registry.field::<Option<Vec<Union>>>(field_name, union),
+			Type::Interface(_) => registry.field::<Option<Vec<Interface>>>(field_name, self),
+			Type::Union(_) => registry.field::<Option<Vec<Union>>>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -343,18 +370,14 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> JuniperField<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => {
 				registry.field::<Option<Vec<Option<Scalar>>>>(field_name, scalar)
 			}
-			Type::Object(object) => {
-				registry.field::<Option<Vec<Option<Object>>>>(field_name, object)
-			}
+			Type::Object(_) => registry.field::<Option<Vec<Option<Object>>>>(field_name, self),
 			Type::Enum(e) => registry.field::<Option<Vec<Enum>>>(field_name, e),
-			Type::Interface(interface) => {
-				registry.field::<Option<Vec<Interface>>>(field_name, interface)
-			}
-			Type::Union(union) => registry.field::<Option<Vec<Union>>>(field_name, union),
+			Type::Interface(_) => registry.field::<Option<Vec<Interface>>>(field_name, self),
+			Type::Union(_) => registry.field::<Option<Vec<Union>>>(field_name, self),
 			Type::Subscription(subscription) => unimplemented!(),
 		}
 	}
@@ -364,9 +387,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Scalar>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Object>(field_name, object),
+			Type::Object(_) => registry.arg::<Object>(field_name, self),
 			Type::Enum(e) => registry.arg::<Enum>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -379,9 +402,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Option<Scalar>>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Option<Object>>(field_name, object),
+			Type::Object(_) => registry.arg::<Option<Object>>(field_name, self),
 			Type::Enum(e) => registry.arg::<Option<Enum>>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -394,9 +417,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Vec<Scalar>>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Vec<Object>>(field_name, object),
+			Type::Object(_) => registry.arg::<Vec<Object>>(field_name, self),
 			Type::Enum(e) => registry.arg::<Vec<Enum>>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -409,9 +432,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Vec<Option<Scalar>>>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Vec<Option<Object>>>(field_name, object),
+			Type::Object(_) => registry.arg::<Vec<Option<Object>>>(field_name, self),
 			Type::Enum(e) => registry.arg::<Vec<Option<Enum>>>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -424,9 +447,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Option<Vec<Scalar>>>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Option<Vec<Object>>>(field_name, object),
+			Type::Object(_) => registry.arg::<Option<Vec<Object>>>(field_name, self),
 			Type::Enum(e) => registry.arg::<Option<Vec<Enum>>>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -439,9 +462,9 @@ impl Type {
 		field_name: &str,
 		registry: &mut Registry<'a, Value>,
 	) -> Argument<'a, Value> {
-		match self {
+		match &*self.ty {
 			Type::Scalar(scalar) => registry.arg::<Option<Vec<Option<Scalar>>>>(field_name, scalar),
-			Type::Object(object) => registry.arg::<Option<Vec<Option<Object>>>>(field_name, object),
+			Type::Object(_) => registry.arg::<Option<Vec<Option<Object>>>>(field_name, self),
 			Type::Enum(e) => registry.arg::<Option<Vec<Option<Enum>>>>(field_name, e),
 			Type::Subscription(subscription) => unimplemented!(),
 			Type::Interface(_) => panic!("Interface is not a valid argument type"),
@@ -42,7 +42,7 @@ impl TypeRef {
 	pub const ID: &'static str = "id";
 
 	/// A Upload type
-	// pub const UPLOAD: &'static str = "Upload";
+	pub const UPLOAD: &'static str = "Upload";
 
 	/// Returns the nullable type reference
 	///
@@ -188,5 +188,9 @@ impl TypeRefTrait for TypeRef {
 	const STRING: &'static str = TypeRef::STRING;
 	const BYTES: &'static str = TypeRef::STRING;
 	const ID: &'static str = TypeRef::ID;
-	// const UPLOAD: &'static str = TypeRef::UPLOAD;
+	const UPLOAD: &'static str = TypeRef::UPLOAD;
+	// timestamps, JSON documents and field masks are serialized into strings
+	const TIMESTAMP: &'static str = TypeRef::STRING;
+	const JSON: &'static str = TypeRef::STRING;
+	const FIELD_MASK: &'static str = TypeRef::STRING;
 }
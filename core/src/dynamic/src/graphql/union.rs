@@ -1,5 +1,5 @@
 use super::{
-	Directive, GraphQLType, GraphQLValue, GraphQLValueAsync, MetaType, Registry, TYPE_REGISTRY,
+	Directive, GraphQLType, GraphQLValue, GraphQLValueAsync, MetaType, Registry, TypeInfo,
 };
 use crate::{BoxFieldFutureJson, ContextBase, SeaResult, SeaographyError, Value};
 use futures::FutureExt;
@@ -152,11 +152,16 @@ impl GraphQLType<Value> for Union {
 	where
 		Value: 'r,
 	{
+		let union = match info.ty.as_union() {
+			Some(union) => union,
+			None => panic!("Type `{}` is not an union", info.type_name()),
+		};
+
 		let mut types = vec![];
 
-		for name in &info.possible_types {
-			let ty = match TYPE_REGISTRY.get(name) {
-				Some(ty) => ty.get_type(registry),
+		for name in &union.possible_types {
+			let ty = match info.types.get(name) {
+				Some(ty) => TypeInfo::new(ty, info.types.clone()).get_type(registry),
 				None => panic!("Type {} not found", name),
 			};
 			types.push(ty);
@@ -164,7 +169,7 @@ impl GraphQLType<Value> for Union {
 
 		let mut meta_type = registry.build_union_type::<Self>(info, &types);
 
-		if let Some(description) = &info.description {
+		if let Some(description) = &union.description {
 			meta_type = meta_type.description(description);
 		}
 
@@ -174,7 +179,7 @@ impl GraphQLType<Value> for Union {
 
 impl GraphQLValue<Value> for Union {
 	type Context = ContextBase;
-	type TypeInfo = Self;
+	type TypeInfo = TypeInfo;
 	fn type_name<'i>(&self, info: &'i Self::TypeInfo) -> Option<&'i str> {
 		Some(info.type_name())
 	}
@@ -1,9 +1,12 @@
-use super::{Argument, Field, JuniperField, Registry, TYPE_REGISTRY, TypeRef};
-use crate::{FieldFuture, ObjectAccessor, Value};
-use juniper::{ID, LookAheadSelection};
+use super::{
+	Argument, Field, InputValue, JuniperField, JuniperTypeRef, Registry, Type, TypeInfo, TypeRef,
+	TypeRegistry,
+};
+use crate::{FieldFuture, Value};
+use juniper::{Definition, Document, ID, Operation, Spanning, Variables};
 use std::{
-	borrow::Cow,
 	collections::{BTreeMap, BTreeSet},
+	sync::Arc,
 };
 
 pub fn type_name(type_name: String) -> (String, Field) {
@@ -15,14 +18,6 @@ pub fn type_name(type_name: String) -> (String, Field) {
 	)
 }
 
-pub fn to_object_accessor<'a>(look_ahead: LookAheadSelection<'a, Value>) -> ObjectAccessor<'a> {
-	let mut btreemap = BTreeMap::new();
-	for argument in look_ahead.arguments() {
-		btreemap.insert(Value::from(argument.name()), Value::from(argument.value()));
-	}
-	ObjectAccessor(Cow::Owned(btreemap))
-}
-
 #[derive(Clone, Debug)]
 pub enum NodeType {
 	Query,
@@ -44,13 +39,15 @@ impl NodeType {
 pub struct NodeInfo {
 	pub(crate) name: String,
 	pub(crate) node_type: NodeType,
+	pub(crate) types: Arc<TypeRegistry>,
 }
 
 impl NodeInfo {
-	pub fn new(name: String, node_type: NodeType) -> Self {
+	pub fn new(name: String, node_type: NodeType, types: Arc<TypeRegistry>) -> Self {
 		Self {
 			name,
 			node_type,
+			types,
 		}
 	}
 
@@ -66,14 +63,21 @@ pub struct SelectionSet {
 	pub(crate) name: String,
 	pub(crate) alias: Option<String>,
 	pub(crate) childs: BTreeSet<SelectionSet>,
+	pub(crate) arguments: BTreeMap<Value, Value>,
 }
 
 impl SelectionSet {
-	pub fn new(name: String, alias: Option<String>, childs: BTreeSet<SelectionSet>) -> Self {
+	pub fn new(
+		name: String,
+		alias: Option<String>,
+		childs: BTreeSet<SelectionSet>,
+		arguments: BTreeMap<Value, Value>,
+	) -> Self {
 		Self {
 			name,
 			alias,
 			childs,
+			arguments,
 		}
 	}
 
@@ -86,12 +90,17 @@ impl SelectionSet {
 				childs.insert(Self::from_look_ahead(child));
 			}
 		}
-		Self::new(name, alias, childs)
+		let arguments = look_ahead
+			.arguments()
+			.map(|argument| (Value::from(argument.name()), Value::from(argument.value())))
+			.collect();
+		Self::new(name, alias, childs, arguments)
 	}
 }
 
 pub struct TypeRefToMeta<'a> {
 	name: &'a str,
+	types: &'a Arc<TypeRegistry>,
 	type_name: String,
 	list: bool,
 	non_null: bool,
@@ -99,9 +108,10 @@ pub struct TypeRefToMeta<'a> {
 }
 
 impl<'a> TypeRefToMeta<'a> {
-	pub fn new(name: &'a str) -> Self {
+	pub fn new(name: &'a str, types: &'a Arc<TypeRegistry>) -> Self {
 		Self {
 			name,
+			types,
 			type_name: "".to_string(),
 			list: false,
 			non_null: false,
@@ -152,8 +162,9 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.arg::<Vec<String>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.arg::<Vec<bool>>(self.name, &()),
 						TypeRef::ID => registry.arg::<Vec<ID>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.argument_vec(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.argument_vec(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -164,8 +175,9 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.arg::<Vec<Option<String>>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.arg::<Vec<Option<bool>>>(self.name, &()),
 						TypeRef::ID => registry.arg::<Vec<Option<ID>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.argument_null_vec(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.argument_null_vec(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -177,8 +189,8 @@ impl<'a> TypeRefToMeta<'a> {
 				TypeRef::STRING => registry.arg::<String>(self.name, &()),
 				TypeRef::BOOLEAN => registry.arg::<bool>(self.name, &()),
 				TypeRef::ID => registry.arg::<ID>(self.name, &()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.argument(self.name, registry),
+				name => match self.types.get(name) {
+					Some(ty) => TypeInfo::new(ty, self.types.clone()).argument(self.name, registry),
 					None => panic!("Unsupported type for non-null argument: {}", name),
 				},
 			};
@@ -191,8 +203,9 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.arg::<Option<Vec<String>>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.arg::<Option<Vec<bool>>>(self.name, &()),
 						TypeRef::ID => registry.arg::<Option<Vec<ID>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.argument_vec_null(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.argument_vec_null(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -207,8 +220,9 @@ impl<'a> TypeRefToMeta<'a> {
 							registry.arg::<Option<Vec<Option<bool>>>>(self.name, &())
 						}
 						TypeRef::ID => registry.arg::<Option<Vec<Option<ID>>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.argument_null_vec_null(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.argument_null_vec_null(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -220,8 +234,10 @@ impl<'a> TypeRefToMeta<'a> {
 				TypeRef::STRING => registry.arg::<Option<String>>(self.name, &()),
 				TypeRef::BOOLEAN => registry.arg::<Option<bool>>(self.name, &()),
 				TypeRef::ID => registry.arg::<Option<ID>>(self.name, &()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.argument_null(self.name, registry),
+				name => match self.types.get(name) {
+					Some(ty) => {
+						TypeInfo::new(ty, self.types.clone()).argument_null(self.name, registry)
+					}
 					None => panic!("Unsupported type for non-null argument: {}", name),
 				},
 			}
@@ -241,8 +257,10 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.field::<Vec<String>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.field::<Vec<bool>>(self.name, &()),
 						TypeRef::ID => registry.field::<Vec<ID>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.field_vec(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => {
+								TypeInfo::new(ty, self.types.clone()).field_vec(self.name, registry)
+							}
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -253,8 +271,9 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.field::<Vec<Option<String>>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.field::<Vec<Option<bool>>>(self.name, &()),
 						TypeRef::ID => registry.field::<Vec<Option<ID>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.field_null_vec(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.field_null_vec(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -266,8 +285,8 @@ impl<'a> TypeRefToMeta<'a> {
 				TypeRef::STRING => registry.field::<String>(self.name, &()),
 				TypeRef::BOOLEAN => registry.field::<bool>(self.name, &()),
 				TypeRef::ID => registry.field::<ID>(self.name, &()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.field(self.name, registry),
+				name => match self.types.get(name) {
+					Some(ty) => TypeInfo::new(ty, self.types.clone()).field(self.name, registry),
 					None => panic!("Unsupported type for non-null argument: {}", name),
 				},
 			};
@@ -280,8 +299,9 @@ impl<'a> TypeRefToMeta<'a> {
 						TypeRef::STRING => registry.field::<Option<Vec<String>>>(self.name, &()),
 						TypeRef::BOOLEAN => registry.field::<Option<Vec<bool>>>(self.name, &()),
 						TypeRef::ID => registry.field::<Option<Vec<ID>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.field_vec_null(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.field_vec_null(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -298,8 +318,9 @@ impl<'a> TypeRefToMeta<'a> {
 							registry.field::<Option<Vec<Option<bool>>>>(self.name, &())
 						}
 						TypeRef::ID => registry.field::<Option<Vec<Option<ID>>>>(self.name, &()),
-						name => match TYPE_REGISTRY.get(name) {
-							Some(ty) => ty.field_null_vec_null(self.name, registry),
+						name => match self.types.get(name) {
+							Some(ty) => TypeInfo::new(ty, self.types.clone())
+								.field_null_vec_null(self.name, registry),
 							None => panic!("Unsupported type for non-null argument: {}", name),
 						},
 					};
@@ -311,11 +332,112 @@ impl<'a> TypeRefToMeta<'a> {
 				TypeRef::STRING => registry.field::<Option<String>>(self.name, &()),
 				TypeRef::BOOLEAN => registry.field::<Option<bool>>(self.name, &()),
 				TypeRef::ID => registry.field::<Option<ID>>(self.name, &()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.field_null(self.name, registry),
+				name => match self.types.get(name) {
+					Some(ty) => {
+						TypeInfo::new(ty, self.types.clone()).field_null(self.name, registry)
+					}
 					None => panic!("Unsupported type for non-null argument: {}", name),
 				},
 			}
 		}
 	}
 }
+
+/// The scalars every GraphQL schema provides
+pub(crate) const SYSTEM_SCALARS: [&str; 5] = ["Int", "Float", "Boolean", "String", "ID"];
+
+/// used to select the operation of a document that a request executes
+pub(crate) fn select_operation<'a>(
+	document: &'a Document<'a, Value>,
+	operation_name: Option<&str>,
+) -> Option<&'a Spanning<Operation<'a, Value>>> {
+	let mut operations = document.iter().filter_map(|definition| match definition {
+		Definition::Operation(operation) => Some(operation),
+		Definition::Fragment(_) => None,
+	});
+
+	match operation_name {
+		Some(name) => operations
+			.find(|operation| operation.item.name.as_ref().map(|name| name.item) == Some(name)),
+		None => match (operations.next(), operations.next()) {
+			(Some(operation), None) => Some(operation),
+			_ => None,
+		},
+	}
+}
+
+/// used to convert an argument of the document to a `Value`, variables are replaced by their
+/// values
+pub(crate) fn to_value(value: &InputValue<Value>, variables: &Variables<Value>) -> Value {
+	match value {
+		InputValue::Null => Value::Null,
+		InputValue::Scalar(scalar) => scalar.clone(),
+		InputValue::Enum(name) => Value::String(name.clone()),
+		InputValue::Variable(name) => match variables.get(name) {
+			Some(value) => to_value(value, variables),
+			None => Value::Null,
+		},
+		InputValue::List(values) => {
+			Value::List(values.iter().map(|value| to_value(&value.item, variables)).collect())
+		}
+		InputValue::Object(fields) => Value::Map(
+			fields
+				.iter()
+				.map(|(name, value)| {
+					(Value::String(name.item.clone()), to_value(&value.item, variables))
+				})
+				.collect(),
+		),
+	}
+}
+
+/// used to turn the lists and objects passed as variables of custom scalars, like the
+/// representations of `_entities`, into scalar values juniper accepts
+pub(crate) fn coerce_variables(
+	types: &TypeRegistry,
+	document: &Document<'_, Value>,
+	operation_name: Option<&str>,
+	variables: &mut Option<InputValue<Value>>,
+) {
+	let fields = match variables {
+		Some(InputValue::Object(fields)) => fields,
+		_ => return,
+	};
+
+	let operation = match select_operation(document, operation_name) {
+		Some(operation) => operation,
+		None => return,
+	};
+	let definitions = match &operation.item.variable_definitions {
+		Some(definitions) => definitions,
+		None => return,
+	};
+
+	for (name, definition) in definitions.item.items.iter() {
+		if let Some((_, value)) = fields.iter_mut().find(|(field, _)| field.item == name.item) {
+			coerce_variable(types, &definition.var_type.item, &mut value.item);
+		}
+	}
+}
+
+fn coerce_variable(types: &TypeRegistry, ty: &JuniperTypeRef<'_>, value: &mut InputValue<Value>) {
+	match ty {
+		JuniperTypeRef::List(ty, _) | JuniperTypeRef::NonNullList(ty, _) => match value {
+			InputValue::List(values) => {
+				for value in values.iter_mut() {
+					coerce_variable(types, ty, &mut value.item);
+				}
+			}
+			value => coerce_variable(types, ty, value),
+		},
+		JuniperTypeRef::Named(name) | JuniperTypeRef::NonNullNamed(name) => {
+			if SYSTEM_SCALARS.contains(&name.as_ref()) {
+				return;
+			}
+			let is_scalar = types.get(name).is_some_and(|ty| matches!(&*ty, Type::Scalar(_)));
+			if is_scalar && matches!(value, InputValue::List(_) | InputValue::Object(_)) {
+				*value = InputValue::Scalar(to_value(value, &Variables::new()));
+			}
+		}
+	}
+}
@@ -1,26 +1,166 @@
-use crate::{Value, graphql::Schema};
+use crate::{
+	graphql::{BatchRequest, Request, Schema},
+	subscriptions,
+};
 use actix_web::{
-	App, HttpResponse, HttpServer, Result, guard,
-	web::{self, Data},
+	App, HttpRequest, HttpResponse, HttpServer, Result,
+	error::ErrorBadRequest,
+	guard,
+	http::header,
+	web::{self, Bytes, Data},
 };
-use juniper::http::{GraphQLRequest, graphiql::graphiql_source};
+use futures::stream;
+use juniper::http::graphiql::graphiql_source;
+use serde::Deserialize;
+use std::{collections::HashMap, convert::Infallible};
+
+/// The maximum size of a request body, files of multipart requests are read into memory
+pub const MAX_BODY_SIZE: usize = 32 * 1024 * 1024;
+
+/// The headers one of which multipart requests have to send, simple requests cannot set them,
+/// so browsers preflight the requests having them and cross-site requests are blocked
+pub const PREFLIGHT_HEADERS: [&str; 2] = ["apollo-require-preflight", "x-apollo-operation-name"];
+
+/// The query string of a GraphQL request sent over GET, the variables and extensions are JSON
+/// encoded
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetRequest {
+	query: Option<String>,
+	operation_name: Option<String>,
+	variables: Option<String>,
+	extensions: Option<String>,
+}
+
+/// used to execute the queries sent over GET, requests without a document are answered with
+/// the GraphiQL playground
+pub async fn graphql_get(
+	schema: Data<Schema>,
+	request: web::Query<GetRequest>,
+) -> Result<HttpResponse> {
+	let request = request.into_inner();
 
-async fn index(schema: Data<Schema>, request: web::Json<GraphQLRequest<Value>>) -> HttpResponse {
-	schema.execute(request.into_inner()).await
+	if request.query.is_none() && request.extensions.is_none() {
+		return Ok(HttpResponse::Ok()
+			.content_type("text/html; charset=utf-8")
+			.body(graphiql_source("/graphql", Some("/graphql"))));
+	}
+
+	let variables = match request.variables {
+		Some(variables) => Some(serde_json::from_str(&variables).map_err(ErrorBadRequest)?),
+		None => None,
+	};
+	let extensions = match request.extensions {
+		Some(extensions) => Some(serde_json::from_str(&extensions).map_err(ErrorBadRequest)?),
+		None => None,
+	};
+
+	let request = Request {
+		query: request.query,
+		operation_name: request.operation_name,
+		variables,
+		extensions,
+		queries_only: true,
+	};
+
+	Ok(schema.execute(request).await)
 }
 
-async fn playground() -> Result<HttpResponse> {
-	Ok(HttpResponse::Ok()
-		.content_type("text/html; charset=utf-8")
-		.body(graphiql_source("/graphql", None)))
+/// used to execute a request or a batch of requests sent as JSON or as a GraphQL multipart
+/// request
+pub async fn graphql_post(
+	schema: Data<Schema>,
+	request: HttpRequest,
+	body: Bytes,
+) -> Result<HttpResponse> {
+	let content_type = request
+		.headers()
+		.get(header::CONTENT_TYPE)
+		.and_then(|content_type| content_type.to_str().ok())
+		.unwrap_or_default();
+
+	let request = match multer::parse_boundary(content_type) {
+		// multipart forms can be posted cross-site without a preflight
+		Ok(_) if !preflighted(&request) => {
+			return Err(ErrorBadRequest(format!(
+				"Multipart requests require one of the headers {}",
+				PREFLIGHT_HEADERS.join(", ")
+			)));
+		}
+		Ok(boundary) => multipart(body, boundary).await?,
+		Err(_) => serde_json::from_slice::<BatchRequest>(&body).map_err(ErrorBadRequest)?,
+	};
+
+	Ok(schema.execute_batch(request).await)
+}
+
+/// used to check if a request has one of the headers browsers preflight
+fn preflighted(request: &HttpRequest) -> bool {
+	PREFLIGHT_HEADERS
+		.iter()
+		.any(|name| request.headers().get(*name).is_some_and(|value| !value.as_bytes().is_empty()))
+}
+
+/// used to serve the schema definition language of the schema
+pub async fn graphql_sdl(schema: Data<Schema>) -> HttpResponse {
+	HttpResponse::Ok().content_type("text/plain; charset=utf-8").body(schema.sdl().to_string())
+}
+
+/// used to read a GraphQL multipart request, the files named in the `map` field are set at
+/// their paths in the `operations` field
+async fn multipart(body: Bytes, boundary: String) -> Result<BatchRequest> {
+	let mut multipart =
+		multer::Multipart::new(stream::once(async move { Ok::<_, Infallible>(body) }), boundary);
+
+	let mut operations = None;
+	let mut map = HashMap::<String, Vec<String>>::new();
+	let mut files = HashMap::new();
+
+	while let Some(field) = multipart.next_field().await.map_err(ErrorBadRequest)? {
+		let name = field.name().unwrap_or_default().to_string();
+		let content = field.bytes().await.map_err(ErrorBadRequest)?;
+
+		match name.as_str() {
+			"operations" => {
+				operations = Some(
+					serde_json::from_slice::<BatchRequest>(&content).map_err(ErrorBadRequest)?,
+				);
+			}
+			"map" => map = serde_json::from_slice(&content).map_err(ErrorBadRequest)?,
+			_ => {
+				files.insert(name, content);
+			}
+		}
+	}
+
+	let mut operations = operations.ok_or_else(|| ErrorBadRequest("Missing `operations` field"))?;
+
+	for (name, paths) in map {
+		let content =
+			files.get(&name).ok_or_else(|| ErrorBadRequest(format!("Missing file `{}`", name)))?;
+
+		for path in paths {
+			operations.set_upload(&path, content.to_vec()).map_err(ErrorBadRequest)?;
+		}
+	}
+
+	Ok(operations)
 }
 
 fn graphql(cfg: &mut web::ServiceConfig, schema: Schema) {
 	cfg.service(
 		web::scope("/graphql")
 			.app_data(Data::new(schema.clone()))
-			.service(web::resource("").guard(guard::Post()).to(index))
-			.service(web::resource("").guard(guard::Get()).to(playground)), // .service(web::resource("/sdl").guard(guard::Get()).to(sdl)), // .service(web::redirect("", "/graphql/")),
+			.app_data(web::PayloadConfig::new(MAX_BODY_SIZE))
+			.service(web::resource("").guard(guard::Post()).to(graphql_post))
+			.service(
+				web::resource("")
+					.guard(guard::Get())
+					.guard(guard::Header("upgrade", "websocket"))
+					.to(subscriptions),
+			)
+			.service(web::resource("").guard(guard::Get()).to(graphql_get))
+			.service(web::resource("/sdl").guard(guard::Get()).to(graphql_sdl)),
 	);
 }
 
@@ -33,3 +173,168 @@ pub async fn http_server(schema: Schema) {
 		.await
 		.unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+	use actix_web::{
+		App,
+		http::{StatusCode, header},
+		test,
+	};
+
+	use super::graphql;
+	use crate::{
+		FieldFuture, SeaographyError, Value,
+		graphql::{Field, Object, Scalar, Schema, SchemaBuilder, TypeRef},
+	};
+
+	fn schema() -> Schema {
+		builder().finish().unwrap()
+	}
+
+	fn builder() -> SchemaBuilder {
+		let query = Object::new("Query").field(Field::output(
+			"value",
+			TypeRef::named_nn(TypeRef::STRING),
+			|_| FieldFuture::new(async move { Ok(Some(Value::from("value"))) }),
+		));
+		let mutation = Object::new("Mutation").field(
+			Field::output("size", TypeRef::named_nn(TypeRef::INT), |ctx| {
+				FieldFuture::new(async move {
+					let file = ctx.args.try_get("file")?;
+					let size = file.bytes().map_err(|_| SeaographyError::new("not a file"))?.len();
+					Ok(Some(Value::from(size as i32)))
+				})
+			})
+			.argument(Field::input("file", TypeRef::named_nn(TypeRef::UPLOAD))),
+		);
+
+		Schema::build(query.type_name(), Some(mutation.type_name()), None)
+			.register(Scalar::new(TypeRef::UPLOAD))
+			.register(query)
+			.register(mutation)
+	}
+
+	#[actix_web::test]
+	async fn get() {
+		let app = test::init_service(App::new().configure(|cfg| graphql(cfg, schema()))).await;
+
+		let req = test::TestRequest::get().uri("/graphql?query=%7B%20value%20%7D").to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(res, serde_json::json!({ "data": { "value": "value" } }));
+
+		let req = test::TestRequest::get()
+			.uri("/graphql?query=mutation%20%7B%20size%28file%3A%20%2200%22%29%20%7D")
+			.to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(res["errors"][0]["message"], "Only queries can be executed over GET");
+
+		let req = test::TestRequest::get().uri("/graphql").to_request();
+		let res = test::call_service(&app, req).await;
+		assert_eq!(res.headers().get(header::CONTENT_TYPE).unwrap(), "text/html; charset=utf-8");
+	}
+
+	#[actix_web::test]
+	async fn batch() {
+		let app = test::init_service(App::new().configure(|cfg| graphql(cfg, schema()))).await;
+
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "application/json"))
+			.set_payload(r#"[{ "query": "{ value }" }, { "query": "{ value }" }]"#)
+			.to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(
+			res,
+			serde_json::json!([{ "data": { "value": "value" } }, { "data": { "value": "value" } }])
+		);
+
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "application/json"))
+			.set_payload(r#"{ "query": "{ value }" }"#)
+			.to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(res, serde_json::json!({ "data": { "value": "value" } }));
+	}
+
+	#[actix_web::test]
+	async fn batch_size() {
+		let schema = builder().limit_batch_size(1).finish().unwrap();
+		let app = test::init_service(App::new().configure(|cfg| graphql(cfg, schema))).await;
+
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "application/json"))
+			.set_payload(r#"[{ "query": "{ value }" }, { "query": "{ value }" }]"#)
+			.to_request();
+		let res = test::call_service(&app, req).await;
+		assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+		let res: serde_json::Value = test::read_body_json(res).await;
+		assert_eq!(
+			res["errors"][0]["message"],
+			"Batch of 2 requests exceeds the limit of 1 requests"
+		);
+
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "application/json"))
+			.set_payload(r#"[{ "query": "{ value }" }]"#)
+			.to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(res, serde_json::json!([{ "data": { "value": "value" } }]));
+	}
+
+	#[actix_web::test]
+	async fn multipart() {
+		let app = test::init_service(App::new().configure(|cfg| graphql(cfg, schema()))).await;
+
+		let body = [
+			"--boundary",
+			"Content-Disposition: form-data; name=\"operations\"",
+			"",
+			r#"{ "query": "mutation($file: Upload!) { size(file: $file) }", "variables": { "file": null } }"#,
+			"--boundary",
+			"Content-Disposition: form-data; name=\"map\"",
+			"",
+			r#"{ "0": ["variables.file"] }"#,
+			"--boundary",
+			"Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"",
+			"Content-Type: text/plain",
+			"",
+			"hello",
+			"--boundary--",
+			"",
+		]
+		.join("\r\n");
+
+		// without a preflighted header the request could be a cross-site form post
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=boundary"))
+			.set_payload(body.clone())
+			.to_request();
+		let res = test::call_service(&app, req).await;
+		assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+		let req = test::TestRequest::post()
+			.uri("/graphql")
+			.insert_header((header::CONTENT_TYPE, "multipart/form-data; boundary=boundary"))
+			.insert_header(("apollo-require-preflight", "true"))
+			.set_payload(body)
+			.to_request();
+		let res: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+		assert_eq!(res, serde_json::json!({ "data": { "size": 5 } }));
+	}
+
+	#[actix_web::test]
+	async fn sdl() {
+		let schema = schema();
+		let sdl = schema.sdl().to_string();
+		let app = test::init_service(App::new().configure(|cfg| graphql(cfg, schema))).await;
+
+		let req = test::TestRequest::get().uri("/graphql/sdl").to_request();
+		let res = test::call_and_read_body(&app, req).await;
+		assert_eq!(res, sdl.as_bytes());
+	}
+}
@@ -1,5 +1,9 @@
 mod graphql;
 mod proto;
+mod subscription;
+mod thrift;
 
 pub use graphql::*;
 pub use proto::*;
+pub use subscription::*;
+pub use thrift::*;
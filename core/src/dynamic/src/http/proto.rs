@@ -1,10 +1,29 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use std::{
+	sync::Arc,
+	task::{Context, Poll},
+};
 use tokio::signal;
-use tonic::transport::Server;
+use tonic::{
+	Code, Status,
+	body::Body,
+	codegen::{
+		BoxFuture,
+		http::{HeaderValue, Method, Request, Response, StatusCode, Version, header},
+	},
+	transport::Server,
+};
 use tonic_health::server::health_reporter;
 use tonic_reflection::server::Builder;
 use tonic_web::GrpcWebLayer;
+use tower_layer::Layer;
+use tower_service::Service;
 
-use crate::proto::{Proto, WrapperMutation, WrapperQuery};
+use crate::proto::{PCKNAME, Proto, WrapperMutation, WrapperQuery, http_status, status_json};
+
+/// The maximum size of a JSON request body, mirrors the default decoding limit of tonic
+const MAX_JSON_SIZE: usize = 4 * 1024 * 1024;
 
 pub async fn grpc_server(proto: Proto) {
 	let reflection = Builder::configure()
@@ -16,12 +35,16 @@ pub async fn grpc_server(proto: Proto) {
 	let (mut _health_reporter, health_service) = health_reporter();
 
 	println!("Visit gRPC at grpc://127.0.0.1:50051");
+	println!("Download the proto file at http://127.0.0.1:50051/{}.proto", PCKNAME);
+	println!("Call the methods with JSON at http://127.0.0.1:50051/{}.<Service>/<method>", PCKNAME);
 	Server::builder()
 		.accept_http1(true)
+		.layer(ProtoSdlLayer::new(&proto))
+		.layer(ProtoJsonLayer::new(&proto))
 		.layer(GrpcWebLayer::new())
 		.add_service(reflection)
-		.add_service(WrapperQuery::new(proto.get_data()))
-		.add_service(WrapperMutation::new(proto.get_data()))
+		.add_service(WrapperQuery::new(proto.get_data(), proto.get_types()))
+		.add_service(WrapperMutation::new(proto.get_data(), proto.get_types()))
 		.add_service(health_service)
 		.serve_with_shutdown("0.0.0.0:50051".parse().unwrap(), async {
 			signal::ctrl_c().await.expect("failed to listen for ctrl_c");
@@ -29,3 +52,361 @@ pub async fn grpc_server(proto: Proto) {
 		.await
 		.unwrap();
 }
+
+/// A layer serving the `.proto` file of a proto on `GET /<package>.proto`, it has to be added
+/// before the `GrpcWebLayer` which rejects plain http/1 requests
+#[derive(Clone)]
+pub struct ProtoSdlLayer {
+	path: Arc<str>,
+	sdl: Bytes,
+}
+
+impl ProtoSdlLayer {
+	pub fn new(proto: &Proto) -> Self {
+		Self {
+			path: format!("/{}.proto", PCKNAME).into(),
+			sdl: Bytes::from(proto.sdl()),
+		}
+	}
+}
+
+impl<S> Layer<S> for ProtoSdlLayer {
+	type Service = ProtoSdl<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		ProtoSdl {
+			inner,
+			path: self.path.clone(),
+			sdl: self.sdl.clone(),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct ProtoSdl<S> {
+	inner: S,
+	path: Arc<str>,
+	sdl: Bytes,
+}
+
+impl<S, B> Service<Request<B>> for ProtoSdl<S>
+where
+	S: Service<Request<B>, Response = Response<Body>>,
+	S::Future: Send + 'static,
+{
+	type Response = Response<Body>;
+	type Error = S::Error;
+	type Future = BoxFuture<Self::Response, Self::Error>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request<B>) -> Self::Future {
+		if req.method() != Method::GET || req.uri().path() != &*self.path {
+			return Box::pin(self.inner.call(req));
+		}
+
+		let mut res = Response::new(Body::new(Full::new(self.sdl.clone())));
+		let headers = res.headers_mut();
+		headers.insert(header::CONTENT_TYPE, "text/plain; charset=utf-8".parse().unwrap());
+		headers.insert(
+			header::CONTENT_DISPOSITION,
+			format!("attachment; filename=\"{}.proto\"", PCKNAME).parse().unwrap(),
+		);
+
+		Box::pin(async move { Ok(res) })
+	}
+}
+
+/// A layer serving the methods of a proto to clients sending proto3 JSON on
+/// `POST /<package>.<service>/<method>`, the JSON is transcoded into a gRPC call of the wrapped
+/// service and the response back into JSON. It has to be added before the `GrpcWebLayer` which
+/// rejects plain http/1 requests
+#[derive(Clone)]
+pub struct ProtoJsonLayer {
+	proto: Proto,
+}
+
+impl ProtoJsonLayer {
+	pub fn new(proto: &Proto) -> Self {
+		Self {
+			proto: proto.clone(),
+		}
+	}
+}
+
+impl<S> Layer<S> for ProtoJsonLayer {
+	type Service = ProtoJson<S>;
+
+	fn layer(&self, inner: S) -> Self::Service {
+		ProtoJson {
+			inner,
+			proto: self.proto.clone(),
+		}
+	}
+}
+
+#[derive(Clone)]
+pub struct ProtoJson<S> {
+	inner: S,
+	proto: Proto,
+}
+
+impl<S> Service<Request<Body>> for ProtoJson<S>
+where
+	S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+	S::Future: Send + 'static,
+{
+	type Response = Response<Body>;
+	type Error = S::Error;
+	type Future = BoxFuture<Self::Response, Self::Error>;
+
+	fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, req: Request<Body>) -> Self::Future {
+		let is_json = req
+			.headers()
+			.get(header::CONTENT_TYPE)
+			.and_then(|value| value.to_str().ok())
+			.is_some_and(|value| value.starts_with("application/json"));
+
+		// the path of a method is `/<package>.<service>/<method>`
+		let method = req
+			.uri()
+			.path()
+			.strip_prefix(&format!("/{}.", PCKNAME))
+			.and_then(|path| path.split_once('/'))
+			.map(|(service, method)| (service.to_string(), method.to_string()));
+
+		let (service, method) = match method {
+			Some(method) if is_json && req.method() == Method::POST => method,
+			_ => return Box::pin(self.inner.call(req)),
+		};
+
+		// the inner service has been polled ready, the clone takes its place
+		let clone = self.inner.clone();
+		let mut inner = std::mem::replace(&mut self.inner, clone);
+		let proto = self.proto.clone();
+
+		Box::pin(async move {
+			let (client_streaming, server_streaming) = match proto.streaming(&service, &method) {
+				Some(streaming) => streaming,
+				None => {
+					let status =
+						Status::unimplemented(format!("Method `{}.{}` not found", service, method));
+					return Ok(error_response(&status));
+				}
+			};
+
+			let (parts, body) = req.into_parts();
+			let body = match Limited::new(body, MAX_JSON_SIZE).collect().await {
+				Ok(body) => body.to_bytes(),
+				Err(err) if err.is::<LengthLimitError>() => {
+					let status = Status::resource_exhausted(format!(
+						"Request body is larger than {} bytes",
+						MAX_JSON_SIZE
+					));
+					return Ok(json_response(StatusCode::PAYLOAD_TOO_LARGE, &status_json(&status)));
+				}
+				Err(err) => {
+					let status = match err.downcast::<Status>() {
+						Ok(status) => *status,
+						Err(err) => Status::internal(err.to_string()),
+					};
+					return Ok(error_response(&status));
+				}
+			};
+
+			// an empty body is an empty request message
+			let json = match body.is_empty() {
+				true => serde_json::Value::Object(Default::default()),
+				false => match serde_json::from_slice::<serde_json::Value>(&body) {
+					Ok(json) => json,
+					Err(err) => {
+						let status = Status::invalid_argument(format!("Invalid JSON: {}", err));
+						return Ok(error_response(&status));
+					}
+				},
+			};
+
+			// a client stream is sent as an array of request messages
+			let messages = match json {
+				serde_json::Value::Array(messages) if client_streaming => messages,
+				json => vec![json],
+			};
+
+			let mut frames = BytesMut::new();
+			for message in messages.iter() {
+				let buf = match proto.request_from_json(&service, &method, message) {
+					Ok(buf) => buf,
+					Err(err) => return Ok(error_response(&err.into())),
+				};
+				frames.put_u8(0);
+				frames.put_u32(buf.len() as u32);
+				frames.put_slice(&buf);
+			}
+
+			let mut request = Request::new(Body::new(Full::new(frames.freeze())));
+			*request.method_mut() = Method::POST;
+			*request.uri_mut() = parts.uri;
+			*request.version_mut() = Version::HTTP_2;
+			*request.headers_mut() = parts.headers;
+			*request.extensions_mut() = parts.extensions;
+			let headers = request.headers_mut();
+			headers.remove(header::CONTENT_LENGTH);
+			headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+			headers.insert(header::TE, HeaderValue::from_static("trailers"));
+
+			let (parts, body) = inner.call(request).await?.into_parts();
+
+			// a failed call may only send the headers
+			if let Some(status) = Status::from_header_map(&parts.headers) {
+				if status.code() != Code::Ok {
+					return Ok(error_response(&status));
+				}
+			}
+			let body = match body.collect().await {
+				Ok(body) => body,
+				Err(status) => return Ok(error_response(&status)),
+			};
+			if let Some(status) = body.trailers().and_then(Status::from_header_map) {
+				if status.code() != Code::Ok {
+					return Ok(error_response(&status));
+				}
+			}
+
+			let mut messages = Vec::new();
+			let mut frames = &body.to_bytes()[..];
+			while frames.len() >= 5 {
+				let len = u32::from_be_bytes([frames[1], frames[2], frames[3], frames[4]]) as usize;
+				if frames[0] != 0 || frames.len() < 5 + len {
+					let status = Status::internal("Invalid response frame");
+					return Ok(error_response(&status));
+				}
+				match proto.response_to_json(&service, &method, &frames[5..5 + len]) {
+					Ok(message) => messages.push(message),
+					Err(err) => return Ok(error_response(&err.into())),
+				}
+				frames = &frames[5 + len..];
+			}
+
+			// a server stream is sent as an array of response messages
+			let json = match server_streaming {
+				true => serde_json::Value::Array(messages),
+				false => messages.into_iter().next().unwrap_or_default(),
+			};
+
+			Ok(json_response(StatusCode::OK, &json))
+		})
+	}
+}
+
+fn json_response(status: StatusCode, json: &serde_json::Value) -> Response<Body> {
+	let body = Bytes::from(serde_json::to_vec(json).unwrap_or_default());
+	let mut res = Response::new(Body::new(Full::new(body)));
+	*res.status_mut() = status;
+	res.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+	res
+}
+
+fn error_response(status: &Status) -> Response<Body> {
+	json_response(http_status(status.code()), &status_json(status))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		FieldFuture, TypeRefTrait, Value,
+		proto::{Field, Message, TypeRef},
+	};
+	use std::convert::Infallible;
+
+	fn proto() -> Proto {
+		let query = Message::new("Query").field(
+			Field::output("count", 1, TypeRef::named_nn(TypeRef::INT64), |_| {
+				FieldFuture::new(async move { Ok(None::<Value>) })
+			})
+			.argument(Field::input("name", 1, TypeRef::named(TypeRef::STRING))),
+		);
+
+		Proto::build(vec!["Query"]).register(query).finish().unwrap()
+	}
+
+	/// A gRPC service answering `count` with the length of the name.
+	#[derive(Clone)]
+	struct Count;
+
+	impl Service<Request<Body>> for Count {
+		type Response = Response<Body>;
+		type Error = Infallible;
+		type Future = BoxFuture<Self::Response, Self::Error>;
+
+		fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+
+		fn call(&mut self, req: Request<Body>) -> Self::Future {
+			Box::pin(async move {
+				assert_eq!(req.headers()[header::CONTENT_TYPE], "application/grpc");
+				let body = req.into_body().collect().await.unwrap().to_bytes();
+
+				// `0x0a <len> <name>` framed behind 5 bytes
+				let len = body.len() as u8 - 7;
+				let mut res = match len {
+					0 => Response::new(Body::empty()),
+					_ => Response::new(Body::new(Full::new(Bytes::from(vec![
+						0, 0, 0, 0, 2, 0x08, len,
+					])))),
+				};
+				let code = if len == 0 {
+					"3"
+				} else {
+					"0"
+				};
+				res.headers_mut().insert("grpc-status", HeaderValue::from_static(code));
+				Ok(res)
+			})
+		}
+	}
+
+	async fn call(body: impl Into<Bytes>) -> (StatusCode, serde_json::Value) {
+		let mut service = ProtoJsonLayer::new(&proto()).layer(Count);
+		let req = Request::builder()
+			.method(Method::POST)
+			.uri(format!("/{}.Query/count", PCKNAME))
+			.header(header::CONTENT_TYPE, "application/json")
+			.body(Body::new(Full::new(body.into())))
+			.unwrap();
+		let res = service.call(req).await.unwrap();
+		let status = res.status();
+		let body = res.into_body().collect().await.unwrap().to_bytes();
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	/// Should transcode a JSON request into a gRPC call and the response back.
+	#[tokio::test]
+	async fn transcodes() {
+		assert_eq!(call(r#"{"name":"foo"}"#).await, (StatusCode::OK, serde_json::json!("3")));
+
+		let (status, json) = call(r#"{"name":""}"#).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		assert_eq!(json["code"], 3);
+
+		let (status, json) = call(r#"{"unknown":1}"#).await;
+		assert_eq!(status, StatusCode::BAD_REQUEST);
+		assert_eq!(json["code"], 3);
+	}
+
+	/// Should reject JSON bodies larger than the decoding limit.
+	#[tokio::test]
+	async fn limits_body_size() {
+		let body = format!(r#"{{"name":"{}"}}"#, "a".repeat(MAX_JSON_SIZE));
+		let (status, json) = call(body).await;
+		assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+		assert_eq!(json["code"], Code::ResourceExhausted as i32);
+	}
+}
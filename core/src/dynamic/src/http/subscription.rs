@@ -0,0 +1,251 @@
+use crate::{
+	Value,
+	graphql::{Request, Schema},
+};
+use actix::{
+	Actor, ActorContext, ActorFutureExt, ActorStreamExt, AsyncContext, SpawnHandle, StreamHandler,
+	WrapStream, fut,
+};
+use actix_web::{
+	Error, HttpRequest, HttpResponse,
+	error::ErrorBadRequest,
+	web::{Data, Payload},
+};
+use actix_web_actors::ws::{
+	CloseCode, CloseReason, Message, ProtocolError, WebsocketContext, WsResponseBuilder,
+};
+use futures::StreamExt;
+use juniper::http::GraphQLResponse;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+/// The websocket sub protocol of the subscription endpoint
+pub const GRAPHQL_TRANSPORT_WS_PROTOCOL: &str = "graphql-transport-ws";
+
+const CONNECTION_INIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Messages sent by the client
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+	ConnectionInit,
+	Ping {
+		payload: Option<serde_json::Value>,
+	},
+	Pong,
+	Subscribe {
+		id: String,
+		payload: Request,
+	},
+	Complete {
+		id: String,
+	},
+}
+
+/// Messages sent by the server
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+	ConnectionAck,
+	Pong {
+		#[serde(skip_serializing_if = "Option::is_none")]
+		payload: Option<serde_json::Value>,
+	},
+	Next {
+		id: String,
+		payload: GraphQLResponse<Value>,
+	},
+	Error {
+		id: String,
+		payload: serde_json::Value,
+	},
+	Complete {
+		id: String,
+	},
+}
+
+impl ServerMessage {
+	fn to_text(&self) -> String {
+		serde_json::to_string(self).unwrap_or_default()
+	}
+}
+
+/// A websocket connection serving the operations of a schema over the `graphql-transport-ws`
+/// protocol, every subscription event is sent as a `next` message
+struct GraphQLSubscription {
+	schema: Schema,
+	acknowledged: bool,
+	subscriptions: HashMap<String, SpawnHandle>,
+}
+
+impl GraphQLSubscription {
+	fn new(schema: Schema) -> Self {
+		Self {
+			schema,
+			acknowledged: false,
+			subscriptions: HashMap::new(),
+		}
+	}
+
+	fn close(ctx: &mut WebsocketContext<Self>, code: u16, description: impl Into<String>) {
+		ctx.close(Some(CloseReason {
+			code: CloseCode::Other(code),
+			description: Some(description.into()),
+		}));
+		ctx.stop();
+	}
+
+	fn receive(&mut self, message: ClientMessage, ctx: &mut WebsocketContext<Self>) {
+		match message {
+			ClientMessage::ConnectionInit => {
+				if self.acknowledged {
+					return Self::close(ctx, 4429, "Too many initialisation requests");
+				}
+				self.acknowledged = true;
+				ctx.text(ServerMessage::ConnectionAck.to_text());
+			}
+			ClientMessage::Ping {
+				payload,
+			} => ctx.text(
+				ServerMessage::Pong {
+					payload,
+				}
+				.to_text(),
+			),
+			ClientMessage::Pong => {}
+			ClientMessage::Subscribe {
+				id,
+				payload,
+			} => {
+				if !self.acknowledged {
+					return Self::close(ctx, 4401, "Unauthorized");
+				}
+				if self.subscriptions.contains_key(&id) {
+					return Self::close(ctx, 4409, format!("Subscriber for {} already exists", id));
+				}
+
+				let handle = ctx.spawn(
+					Self::messages(self.schema.clone(), id.clone(), payload)
+						.into_actor(self)
+						.map(|text, _act, ctx| ctx.text(text))
+						.finish()
+						.then({
+							let id = id.clone();
+							move |_, act, _ctx| {
+								act.subscriptions.remove(&id);
+								fut::ready(())
+							}
+						}),
+				);
+				self.subscriptions.insert(id, handle);
+			}
+			ClientMessage::Complete {
+				id,
+			} => {
+				if let Some(handle) = self.subscriptions.remove(&id) {
+					ctx.cancel_future(handle);
+				}
+			}
+		}
+	}
+
+	/// used to get the messages of an operation, failed operations send a single `error`
+	/// message while the others are completed after their last response
+	fn messages(
+		schema: Schema,
+		id: String,
+		request: Request,
+	) -> impl futures::Stream<Item = String> {
+		async_stream::stream! {
+			let mut responses = Box::pin(schema.execute_stream(request));
+
+			while let Some(response) = responses.next().await {
+				if !response.is_ok() {
+					let payload = match serde_json::to_value(&response) {
+						Ok(serde_json::Value::Object(mut response)) => {
+							response.remove("errors").unwrap_or_default()
+						}
+						_ => serde_json::Value::Array(Vec::new()),
+					};
+
+					yield ServerMessage::Error {
+						id,
+						payload,
+					}
+					.to_text();
+					return;
+				}
+
+				yield ServerMessage::Next {
+					id: id.clone(),
+					payload: response,
+				}
+				.to_text();
+			}
+
+			yield ServerMessage::Complete {
+				id,
+			}
+			.to_text();
+		}
+	}
+}
+
+impl Actor for GraphQLSubscription {
+	type Context = WebsocketContext<Self>;
+
+	fn started(&mut self, ctx: &mut Self::Context) {
+		ctx.run_later(CONNECTION_INIT_TIMEOUT, |act, ctx| {
+			if !act.acknowledged {
+				Self::close(ctx, 4408, "Connection initialisation timeout");
+			}
+		});
+	}
+}
+
+impl StreamHandler<Result<Message, ProtocolError>> for GraphQLSubscription {
+	fn handle(&mut self, message: Result<Message, ProtocolError>, ctx: &mut Self::Context) {
+		let text = match message {
+			Ok(Message::Text(text)) => text.to_string(),
+			Ok(Message::Binary(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+			Ok(Message::Ping(bytes)) => return ctx.pong(&bytes),
+			Ok(Message::Close(reason)) => {
+				ctx.close(reason);
+				return ctx.stop();
+			}
+			Ok(_) => return,
+			Err(_) => return ctx.stop(),
+		};
+
+		match serde_json::from_str::<ClientMessage>(&text) {
+			Ok(message) => self.receive(message, ctx),
+			Err(err) => Self::close(ctx, 4400, err.to_string()),
+		}
+	}
+}
+
+/// used to serve the subscriptions of the schema over the `graphql-transport-ws` protocol
+pub async fn subscriptions(
+	schema: Data<Schema>,
+	request: HttpRequest,
+	payload: Payload,
+) -> Result<HttpResponse, Error> {
+	let supported = request
+		.headers()
+		.get("sec-websocket-protocol")
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|protocols| {
+			protocols.split(',').any(|protocol| protocol.trim() == GRAPHQL_TRANSPORT_WS_PROTOCOL)
+		});
+
+	if !supported {
+		return Err(ErrorBadRequest(format!(
+			"Unsupported websocket protocol, expected `{}`",
+			GRAPHQL_TRANSPORT_WS_PROTOCOL
+		)));
+	}
+
+	WsResponseBuilder::new(GraphQLSubscription::new(schema.get_ref().clone()), &request, payload)
+		.protocols(&[GRAPHQL_TRANSPORT_WS_PROTOCOL])
+		.start()
+}
@@ -0,0 +1,115 @@
+use crate::{Protocol, ThriftRequest, proto::Proto};
+use bytes::{Buf, BytesMut};
+use std::io;
+use tokio::{
+	io::{AsyncReadExt, AsyncWriteExt},
+	net::{TcpListener, TcpStream},
+	signal,
+};
+
+/// The maximum size of a framed or unframed message, mirrors the default of the apache thrift
+/// servers
+const MAX_FRAME_SIZE: usize = 16_384_000;
+
+pub async fn thrift_server(proto: Proto) {
+	let listener = TcpListener::bind("0.0.0.0:9090").await.unwrap();
+
+	println!("Visit Thrift at tcp://127.0.0.1:9090");
+	tokio::select! {
+		res = serve_thrift(proto, listener) => res.unwrap(),
+		_ = signal::ctrl_c() => {}
+	}
+}
+
+/// used to serve the services of a proto to thrift clients, every connection may use the
+/// binary or the compact protocol with a framed or an unframed transport
+pub async fn serve_thrift(proto: Proto, listener: TcpListener) -> io::Result<()> {
+	loop {
+		let (stream, _) = listener.accept().await?;
+		let proto = proto.clone();
+
+		tokio::spawn(async move {
+			if let Err(err) = connection(proto, stream).await {
+				println!("Thrift connection closed: {}", err);
+			}
+		});
+	}
+}
+
+async fn connection(proto: Proto, mut stream: TcpStream) -> io::Result<()> {
+	let mut buf = BytesMut::new();
+
+	loop {
+		if buf.is_empty() && stream.read_buf(&mut buf).await? == 0 {
+			return Ok(());
+		}
+
+		// unframed messages start with the id of their protocol, framed ones with their size
+		let framed = Protocol::detect(buf[0]).is_none();
+		let request = match framed {
+			true => {
+				let size = match buf.get(..4) {
+					Some(size) => u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize,
+					None => usize::MAX,
+				};
+				if size != usize::MAX && size > MAX_FRAME_SIZE {
+					return Err(invalid_data(format!("Frame of {} bytes is too large", size)));
+				}
+				if size == usize::MAX || buf.len() < size + 4 {
+					if stream.read_buf(&mut buf).await? == 0 {
+						return Ok(());
+					}
+					continue;
+				}
+
+				let frame = buf.split_to(size + 4);
+				match proto.read_thrift(&frame[4..]).map_err(|err| invalid_data(err.to_string()))? {
+					ThriftRequest::Incomplete => return Err(invalid_data("Incomplete frame")),
+					request => request,
+				}
+			}
+			false => match proto.read_thrift(&buf).map_err(|err| invalid_data(err.to_string()))? {
+				ThriftRequest::Incomplete => {
+					if buf.len() > MAX_FRAME_SIZE {
+						return Err(invalid_data(format!(
+							"Message of more than {} bytes is too large",
+							MAX_FRAME_SIZE
+						)));
+					}
+					if stream.read_buf(&mut buf).await? == 0 {
+						return Ok(());
+					}
+					continue;
+				}
+				request => request,
+			},
+		};
+
+		let (protocol, header, method, arguments) = match request {
+			ThriftRequest::Call {
+				consumed,
+				protocol,
+				header,
+				method,
+				arguments,
+			} => {
+				if !framed {
+					buf.advance(consumed);
+				}
+				(protocol, header, method, arguments)
+			}
+			ThriftRequest::Incomplete => continue,
+		};
+
+		if let Some(reply) = proto.execute_thrift(protocol, header, method, arguments).await {
+			if framed {
+				stream.write_all(&(reply.len() as u32).to_be_bytes()).await?;
+			}
+			stream.write_all(&reply).await?;
+		}
+	}
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -41,20 +41,41 @@ where
 		self.types.push(ty);
 		self
 	}
+
+	/// Set the entity resolver used for GraphQL federation
+	#[must_use]
+	pub fn entity_resolver<F>(mut self, resolver_fn: F) -> Self
+	where
+		F: for<'b> Fn(ResolverContext<'b>) -> FieldFuture<'b> + Send + Sync + 'static,
+	{
+		self.schema_builder = self.schema_builder.entity_resolver(resolver_fn);
+		self
+	}
 }
 
 impl DynamicBuilder<GraphQLTypeRef, GraphQLEnum> {
 	pub fn builder(mut self) -> SchemaBuilder {
 		self.schema_builder.data.merge(self.data);
-		self.types
-			.into_iter()
-			.fold(self.schema_builder, |builder, ty| builder.register(ty.register()))
+
+		// binary inputs accept the files of multipart requests
+		let upload = GraphQLScalar::new(GraphQLTypeRef::UPLOAD)
+			.description("A file sent with a multipart request or a hex encoded string")
+			.validator(|value| matches!(value, Value::Bytes(_) | Value::String(_)));
+
+		self.types.into_iter().fold(self.schema_builder.register(upload), |builder, ty| {
+			builder.register(ty.register())
+		})
 	}
 
 	pub fn register_schema(mut self, ty: impl Into<GraphQLType>) -> Self {
 		self.schema_builder = self.schema_builder.register(ty);
 		self
 	}
+
+	pub fn register_subscription(mut self, subscription: GraphQLSubscription) -> Self {
+		self.schema_builder = self.schema_builder.subscription(subscription);
+		self
+	}
 }
 
 impl DynamicBuilder<ProtoTypeRef, ProtoEnum> {
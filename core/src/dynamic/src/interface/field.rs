@@ -1,8 +1,9 @@
-use std::{collections::BTreeMap, ops::Add};
+use std::{collections::BTreeMap, ops::Add, sync::Arc};
 
 use super::IO;
 use crate::{
-	BoxResolverFn, FieldFuture, ResolverContext, TypeRefTrait,
+	BoxResolverFn, BoxSubscriptionResolverFn, ComputeComplexityFn, FieldFuture, ObjectAccessor,
+	ResolverContext, SeaResult, SubscriptionFieldFuture, TypeRefTrait,
 	prelude::{GraphQLField, GraphQLTypeRef, ProtoField, ProtoTypeRef},
 };
 
@@ -14,6 +15,10 @@ where
 	pub(crate) name: String,
 	pub(crate) ty: T,
 	pub(crate) resolver_fn: Option<BoxResolverFn>,
+	pub(crate) stream_fn: Option<BoxSubscriptionResolverFn>,
+	pub(crate) compute_complexity: Option<ComputeComplexityFn>,
+	pub(crate) client_streaming: bool,
+	pub(crate) proto_only: bool,
 }
 
 impl<T> Field<T>
@@ -31,6 +36,10 @@ where
 			arguments: Default::default(),
 			ty,
 			resolver_fn: None,
+			stream_fn: None,
+			compute_complexity: None,
+			client_streaming: false,
+			proto_only: false,
 		}
 	}
 
@@ -50,6 +59,64 @@ where
 			arguments: Default::default(),
 			ty,
 			resolver_fn: Some(Box::new(resolver_fn)),
+			stream_fn: None,
+			compute_complexity: None,
+			client_streaming: false,
+			proto_only: false,
+		}
+	}
+
+	/// Create a new server streaming output field, only used by Protobuf
+	pub fn stream<F>(name: impl Into<String>, ty: T, stream_fn: F) -> Self
+	where
+		F: for<'b> Fn(ResolverContext<'b>) -> SubscriptionFieldFuture<'b> + Send + Sync + 'static,
+	{
+		Self {
+			name: name.into(),
+			arguments: Default::default(),
+			ty,
+			resolver_fn: None,
+			stream_fn: Some(Box::new(stream_fn)),
+			compute_complexity: None,
+			client_streaming: false,
+			proto_only: false,
+		}
+	}
+
+	/// Returns true if the field is a server streaming field
+	#[inline]
+	pub fn is_stream(&self) -> bool {
+		self.stream_fn.is_some()
+	}
+
+	/// Set the field as client streaming, only used by Protobuf
+	#[inline]
+	pub fn set_client_streaming(mut self) -> Self {
+		self.client_streaming = true;
+		self
+	}
+
+	/// Set the field as only served over Protobuf, GraphQL skips it as argument
+	#[inline]
+	pub fn set_proto_only(mut self) -> Self {
+		self.proto_only = true;
+		self
+	}
+
+	/// Returns true if the field is only served over Protobuf
+	#[inline]
+	pub fn is_proto_only(&self) -> bool {
+		self.proto_only
+	}
+
+	/// Set the complexity of the field, only used by GraphQL
+	pub fn compute_complexity<F>(self, complexity_fn: F) -> Self
+	where
+		F: Fn(&ObjectAccessor<'_>, usize) -> SeaResult<usize> + Send + Sync + 'static,
+	{
+		Self {
+			compute_complexity: Some(Arc::new(complexity_fn)),
+			..self
 		}
 	}
 }
@@ -60,12 +127,18 @@ impl Field<GraphQLTypeRef> {
 			IO::Input => GraphQLField::input(self.name, self.ty),
 			IO::Output => {
 				if let Some(resolver_fn) = self.resolver_fn {
-					self.arguments.into_iter().fold(
-						GraphQLField::output(self.name, self.ty, move |ctx| {
-							resolver_fn(ctx.into())
-						}),
-						|builder, (_, field)| builder.argument(field.to_field(&IO::Input)),
-					)
+					let field = GraphQLField::output(self.name, self.ty, resolver_fn);
+					let field = GraphQLField {
+						compute_complexity: self.compute_complexity,
+						..field
+					};
+
+					self.arguments
+						.into_iter()
+						.filter(|(_, field)| !field.is_proto_only())
+						.fold(field, |builder, (_, field)| {
+							builder.argument(field.to_field(&IO::Input))
+						})
 				} else {
 					panic!("resolver_fn not found")
 				}
@@ -79,18 +152,26 @@ impl Field<ProtoTypeRef> {
 		match io {
 			IO::Input => ProtoField::input(self.name, tag, self.ty),
 			IO::Output => {
-				if let Some(resolver_fn) = self.resolver_fn {
-					self.arguments.into_iter().enumerate().fold(
+				let field = match (self.resolver_fn, self.stream_fn) {
+					(_, Some(stream_fn)) => ProtoField::stream(self.name, tag, self.ty, stream_fn),
+					(Some(resolver_fn), None) => {
 						ProtoField::output(self.name, tag, self.ty, move |ctx| {
 							resolver_fn(ctx.into())
-						}),
-						|builder, (index, (_, field))| {
-							builder.argument(field.to_field(index.add(1) as u32, &IO::Input))
-						},
-					)
-				} else {
-					panic!("resolver_fn not found")
-				}
+						})
+					}
+					(None, None) => panic!("resolver_fn not found"),
+				};
+				let field = match self.client_streaming {
+					true => field.set_client_streaming(),
+					false => field,
+				};
+
+				self.arguments.into_iter().enumerate().fold(
+					field,
+					|builder, (index, (_, field))| {
+						builder.argument(field.to_field(index.add(1) as u32, &IO::Input))
+					},
+				)
 			}
 		}
 	}
@@ -22,6 +22,7 @@ where
 	pub(crate) fields: IndexMap<String, Field<T>>,
 	pub(crate) io: IO,
 	pub(crate) namespace: Option<String>,
+	pub(crate) keys: Vec<String>,
 }
 
 impl<T> Object<T>
@@ -37,6 +38,7 @@ where
 			fields: Default::default(),
 			io, // arguments: Default::default(),
 			namespace: None,
+			keys: Vec::new(),
 		}
 	}
 
@@ -63,6 +65,13 @@ where
 		self
 	}
 
+	/// Add an entity key to the object, only used by GraphQL federation
+	#[inline]
+	pub fn key(mut self, fields: impl Into<String>) -> Self {
+		self.keys.push(fields.into());
+		self
+	}
+
 	/// Returns the type name
 	#[inline]
 	pub fn type_name(&self) -> &str {
@@ -72,11 +81,14 @@ where
 
 impl Object<GraphQLTypeRef> {
 	pub fn to_type(self) -> GraphQLType {
-		GraphQLType::Object(self.fields.into_iter().fold(
-			match self.description {
-				Some(description) => GraphQLObject::new(self.name).description(description),
-				None => GraphQLObject::new(self.name),
-			},
+		let object = match self.description {
+			Some(description) => GraphQLObject::new(self.name).description(description),
+			None => GraphQLObject::new(self.name),
+		};
+
+		// streaming fields are only served over grpc
+		GraphQLType::Object(self.fields.into_iter().filter(|(_, field)| !field.is_stream()).fold(
+			self.keys.into_iter().fold(object, |builder, key| builder.key(key)),
 			|builder, (_, field)| builder.field(field.to_field(&self.io)),
 		))
 	}
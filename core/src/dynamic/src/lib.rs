@@ -8,7 +8,9 @@ mod interface;
 pub mod prelude;
 mod proto;
 mod registry;
+mod thrift;
 
 pub use common::*;
 pub use http::*;
 pub use registry::*;
+pub use thrift::*;
@@ -51,3 +51,51 @@ macro_rules! impl_directive {
 		}
 	};
 }
+
+macro_rules! impl_set_extends {
+	() => {
+		/// Indicate that an object or interface definition is an extension of another
+		/// definition of that same type when using Apollo Federation
+		///
+		/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#extends>
+		#[inline]
+		pub fn extends(self) -> Self {
+			Self {
+				extends: true,
+				..self
+			}
+		}
+	};
+}
+
+macro_rules! impl_set_shareable {
+	() => {
+		/// Indicate that an object type or field can be resolved by multiple subgraphs when
+		/// using Apollo Federation
+		///
+		/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#shareable>
+		#[inline]
+		pub fn shareable(self) -> Self {
+			Self {
+				shareable: true,
+				..self
+			}
+		}
+	};
+}
+
+macro_rules! impl_set_external {
+	() => {
+		/// Indicate that a field is usually resolved by another subgraph when using Apollo
+		/// Federation
+		///
+		/// Reference: <https://www.apollographql.com/docs/federation/federated-types/federated-directives/#external>
+		#[inline]
+		pub fn external(self) -> Self {
+			Self {
+				external: true,
+				..self
+			}
+		}
+	};
+}
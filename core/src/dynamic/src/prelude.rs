@@ -1,8 +1,8 @@
 pub use crate::graphql::{
 	Enum as GraphQLEnum, Field as GraphQLField, Interface as GraphQLInterface,
 	InterfaceField as GraphQLInterfaceField, Name, Object as GraphQLObject,
-	Registry as GraphQLRegistry, Scalar as GraphQLScalar, Schema, SchemaBuilder,
-	ServerError as GraphQLServerError, Subscription as GraphQLSubscription,
+	Registry as GraphQLRegistry, Request as GraphQLRequest, Scalar as GraphQLScalar, Schema,
+	SchemaBuilder, ServerError as GraphQLServerError, Subscription as GraphQLSubscription,
 	SubscriptionField as GraphQLSubscriptionField, Type as GraphQLType, TypeRef as GraphQLTypeRef,
 	Union as GraphQLUnion, Upload as GraphQLUpload,
 };
@@ -1,16 +1,15 @@
-use super::{Error, Result, TypeRef, from_bytes, to_bytes};
+use super::{
+	Error, Result, TypeRef, decode_error, insert_value, to_bytes, unpack, wire_type_error,
+};
 use crate::{
-	BoxFieldFutureByte, EnumItemTrait, EnumTrait, ObjectAccessor, SeaResult, SeaographyError,
-	Value, ValueAccessor,
+	BoxFieldFutureByte, EnumItemTrait, EnumTrait, ObjectAccessor, ProtoRegistry, ProtobufEnumValue,
+	ProtobufKind, SeaResult, SeaographyError, Value, ValueAccessor,
 };
-use binary::proto::{DecoderLit, Encoder};
-use bytes::{Buf, BufMut};
+use binary::proto::{DecoderLit, Encoder, Typ, decode_int32};
+use bytes::BufMut;
 use futures::FutureExt;
 use juniper::ScalarValue;
-use prost::{
-	DecodeError,
-	encoding::{DecodeContext, WireType},
-};
+use prost::DecodeError;
 use prost_types::{
 	EnumDescriptorProto, EnumOptions, EnumValueDescriptorProto, EnumValueOptions,
 	FileDescriptorProto,
@@ -189,38 +188,28 @@ impl Enum {
 		}
 	}
 
-	pub fn bytes<B>(
+	/// used to decode the value of an enum field into the name of its item, a number without
+	/// item is kept as the number
+	pub fn bytes(
 		&self,
-		buf: &mut B,
-		ctx: DecodeContext,
-		wire_type: WireType,
+		name: &str,
+		typ: Typ,
+		buf: &[u8],
 		is_repeated: bool,
 		arguments: &mut BTreeMap<Value, Value>,
-	) -> Result<(), DecodeError>
-	where
-		B: Buf,
-	{
-		let mut argument = BTreeMap::new();
-		from_bytes(
-			self.type_name(),
-			TypeRef::INT32,
-			is_repeated,
-			ctx,
-			buf,
-			wire_type,
-			&mut argument,
-		)?;
-
-		if let Some((key, value)) = argument.first_key_value() {
-			match value {
-				Value::Int32(number) => {
-					if let Some(item) = self.get_item_by_tag(*number as u32) {
-						println!("item: {:?}", item.type_name());
-						arguments.insert(key.to_owned(), Value::from(item.type_name()));
-					}
-				}
-				_ => unreachable!(),
-			}
+	) -> Result<(), DecodeError> {
+		if !is_repeated && typ != Typ::Varint {
+			return Err(wire_type_error(typ, Typ::Varint));
+		}
+
+		for bytes in unpack(Typ::Varint, typ, buf)? {
+			let mut number = 0;
+			decode_int32(bytes, &mut number).map_err(decode_error)?;
+			let value = match self.get_item_by_tag(number as u32) {
+				Some(item) => Value::from(item.type_name()),
+				None => Value::from(number),
+			};
+			insert_value(arguments, name, is_repeated, value);
 		}
 
 		Ok(())
@@ -273,6 +262,32 @@ impl Enum {
 
 		file.enum_type.push(enum_descriptor);
 	}
+
+	pub(crate) fn register_sdl(&self, registry: &mut ProtoRegistry) {
+		let fields = self
+			.enum_values
+			.values()
+			.filter_map(|item| {
+				let value = ProtobufEnumValue {
+					name: item.name.clone(),
+					description: item.description.clone(),
+					tag: item.tag? as i32,
+				};
+				Some((item.name.clone(), value))
+			})
+			.collect();
+
+		registry.types.insert(
+			self.name.clone(),
+			ProtobufKind::Enum {
+				name: self.name.clone(),
+				description: self.description.clone(),
+				fields,
+				visible: None,
+				rust_typename: None,
+			},
+		);
+	}
 }
 
 impl EnumTrait for Enum {
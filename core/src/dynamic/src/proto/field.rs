@@ -1,17 +1,22 @@
-use super::{Error, Result, TYPE_REGISTRY, Type, TypeRef, from_bytes, to_bytes};
+use super::{
+	Result, Type, TypeRef, TypeRegistry, decode_error, from_bytes, insert_value, is_well_known,
+	json_name, to_bytes, well_known_from_bytes, well_known_to_bytes, wire_type_error,
+};
 use crate::{
-	BoxFieldFutureByte, BoxResolverFn, ContextBase, FieldFuture, FieldValue, FieldValueInner,
-	ObjectAccessor, ResolverContext, SeaResult, SeaographyError, TypeRefTrait, Value,
+	BoxFieldFutureByte, BoxResolverFn, BoxSubscriptionResolverFn, ContextBase, FieldFuture,
+	FieldValue, FieldValueInner, ObjectAccessor, ProtobufField, ProtobufFieldLabel, ProtobufMethod,
+	ResolverContext, SeaResult, SeaographyError, SubscriptionFieldFuture, TypeRefTrait, Value,
 	proto::Message,
 };
-use binary::proto::{Decoder, DecoderLit, Encoder, EncoderLit};
-use bytes::{Buf, BufMut, Bytes, BytesMut, buf};
+use binary::proto::{Decoder, DecoderLit, Encoder, EncoderLit, Fields, Typ};
+use bytes::{BufMut, BytesMut, buf};
 use futures::FutureExt;
-use prost::{
-	DecodeError,
-	encoding::{DecodeContext, WireType, decode_key, merge_loop},
+use heck::ToUpperCamelCase;
+use prost::{DecodeError, encoding::WireType};
+use prost_types::{
+	DescriptorProto, FieldDescriptorProto, FieldOptions, MessageOptions, MethodDescriptorProto,
+	MethodOptions,
 };
-use prost_types::{FieldDescriptorProto, FieldOptions, MethodDescriptorProto, MethodOptions};
 use std::{
 	collections::BTreeMap,
 	fmt::{self, Debug},
@@ -29,7 +34,10 @@ pub struct Field {
 	pub(crate) optional: bool,
 	pub(crate) deprecated: bool,
 	pub(crate) packed: bool,
+	pub(crate) client_streaming: bool,
+	pub(crate) server_streaming: bool,
 	pub(crate) resolver_fn: Option<BoxResolverFn>,
+	pub(crate) stream_fn: Option<BoxSubscriptionResolverFn>,
 	pub(crate) default_value: Option<Value>,
 }
 
@@ -43,6 +51,8 @@ impl Debug for Field {
 			.field("optional", &self.optional)
 			.field("deprecated", &self.deprecated)
 			.field("packed", &self.packed)
+			.field("client_streaming", &self.client_streaming)
+			.field("server_streaming", &self.server_streaming)
 			.finish()
 	}
 }
@@ -65,7 +75,36 @@ impl Field {
 			optional: false,
 			deprecated: false,
 			packed: false,
+			client_streaming: false,
+			server_streaming: false,
 			resolver_fn: Some(Box::new(resolver_fn)),
+			stream_fn: None,
+			default_value: None,
+		}
+	}
+
+	/// Create a new Protobuf server streaming output field, every item of the stream is sent
+	/// as its own response message
+	pub fn stream<N, T, F>(name: N, tag: u32, ty: T, stream_fn: F) -> Self
+	where
+		N: Into<String>,
+		T: Into<TypeRef>,
+		F: for<'a> Fn(ResolverContext<'a>) -> SubscriptionFieldFuture<'a> + Send + Sync + 'static,
+	{
+		Self {
+			name: name.into(),
+			tag,
+			description: None,
+			arguments: Default::default(),
+			ty: ty.into(),
+			repeated: false,
+			optional: false,
+			deprecated: false,
+			packed: false,
+			client_streaming: false,
+			server_streaming: true,
+			resolver_fn: None,
+			stream_fn: Some(Box::new(stream_fn)),
 			default_value: None,
 		}
 	}
@@ -86,7 +125,10 @@ impl Field {
 			optional: false,
 			deprecated: false,
 			packed: false,
+			client_streaming: false,
+			server_streaming: false,
 			resolver_fn: None,
+			stream_fn: None,
 			default_value: None,
 		}
 	}
@@ -119,6 +161,14 @@ impl Field {
 		self
 	}
 
+	/// Set the field as client streaming, the arguments of all request messages are merged
+	/// before the field is resolved
+	#[inline]
+	pub fn set_client_streaming(mut self) -> Self {
+		self.client_streaming = true;
+		self
+	}
+
 	/// Set the default value
 	#[inline]
 	pub fn default_value(self, value: impl Into<Value>) -> Self {
@@ -160,6 +210,7 @@ impl Field {
 	pub(crate) async fn encode<'a, B>(
 		&self,
 		buf: &mut B,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		val: &'a FieldValue<'a>,
 		arguments: &'a ObjectAccessor<'a>,
@@ -171,28 +222,18 @@ impl Field {
 		B: BufMut,
 	{
 		match &val.0 {
-			FieldValueInner::Value(value) => match self.ty.type_name() {
-				TypeRef::DOUBLE
-				| TypeRef::FLOAT
-				| TypeRef::INT32
-				| TypeRef::INT64
-				| TypeRef::UINT32
-				| TypeRef::UINT64
-				| TypeRef::SINT32
-				| TypeRef::SINT64
-				| TypeRef::FIXED32
-				| TypeRef::FIXED64
-				| TypeRef::SFIXED32
-				| TypeRef::SFIXED64
-				| TypeRef::BOOL
-				| TypeRef::STRING
-				| TypeRef::BYTES => to_bytes(buf, value, self.tag, self.ty.type_name()),
-				name => match TYPE_REGISTRY.get(name) {
-					Some(ty) => ty.to_value(buf, value, self.tag),
-					None => {
-						Err(SeaographyError::new(format!("Unsupported type for field `{}`", name)))
-					}
+			FieldValueInner::Value(value) => match types.get(self.ty.type_name()) {
+				// the body of a response message is sent without a key
+				Some(ty) if is_response && self.ty.as_map().is_none() => match ty.as_message() {
+					Some(message) => message.encode_value(types, buf, value),
+					None => self.encode_value(types, buf, value),
 				},
+				None if is_response && is_well_known(self.ty.type_name()) => {
+					let body = well_known_to_bytes(types, self.ty.type_name(), value)?;
+					buf.put_slice(&body);
+					Ok(body.len())
+				}
+				_ => self.encode_value(types, buf, value),
 			},
 			FieldValueInner::List(values) => {
 				let mut size = 0;
@@ -200,6 +241,7 @@ impl Field {
 				for value in values.iter() {
 					size += Box::pin(self.encode(
 						buf,
+						types,
 						ctx,
 						value,
 						arguments,
@@ -212,12 +254,12 @@ impl Field {
 
 				Ok(size)
 			}
-			FieldValueInner::OwnedAny(..) => match TYPE_REGISTRY.get(self.ty.type_name()) {
+			FieldValueInner::OwnedAny(..) => match types.get(self.ty.type_name()) {
 				Some(inner) => {
 					let mut size = 0;
 					let mut buffer = BytesMut::new();
 
-					for field in inner.collect(ctx, arguments, Some(val), recursion) {
+					for field in inner.collect(types, ctx, arguments, Some(val), recursion) {
 						let (field_size, fielf_buffer) = field.await?;
 						if field_size > 0 {
 							buffer.put_slice(&fielf_buffer[..]);
@@ -243,12 +285,12 @@ impl Field {
 				}
 				None => Ok(0),
 			},
-			FieldValueInner::BorrowedAny(..) => match TYPE_REGISTRY.get(self.ty.type_name()) {
+			FieldValueInner::BorrowedAny(..) => match types.get(self.ty.type_name()) {
 				Some(inner) => {
 					let mut size = 0;
 					let mut buffer = BytesMut::new();
 
-					for field in inner.collect(ctx, arguments, Some(val), recursion) {
+					for field in inner.collect(types, ctx, arguments, Some(val), recursion) {
 						let (field_size, fielf_buffer) = field.await?;
 						if field_size > 0 {
 							buffer.put_slice(&fielf_buffer[..]);
@@ -279,6 +321,7 @@ impl Field {
 			} => {
 				Box::pin(self.encode(
 					buf,
+					types,
 					ctx,
 					value,
 					arguments,
@@ -291,8 +334,136 @@ impl Field {
 		}
 	}
 
+	/// used to encode a value of the field, lists are encoded as repeated fields and maps as
+	/// messages or as the entries of a map field
+	pub(crate) fn encode_value<B>(
+		&self,
+		types: &TypeRegistry,
+		buf: &mut B,
+		value: &Value,
+	) -> SeaResult<usize>
+	where
+		B: BufMut,
+	{
+		match (self.ty.type_name(), value) {
+			(_, Value::Map(entries)) if self.ty.as_map().is_some() => {
+				self.encode_entries(types, buf, entries)
+			}
+			(_, Value::List(values)) => {
+				let mut size = 0;
+				for value in values {
+					size += self.encode_value(types, buf, value)?;
+				}
+				Ok(size)
+			}
+			(
+				TypeRef::DOUBLE
+				| TypeRef::FLOAT
+				| TypeRef::INT32
+				| TypeRef::INT64
+				| TypeRef::UINT32
+				| TypeRef::UINT64
+				| TypeRef::SINT32
+				| TypeRef::SINT64
+				| TypeRef::FIXED32
+				| TypeRef::FIXED64
+				| TypeRef::SFIXED32
+				| TypeRef::SFIXED64
+				| TypeRef::BOOL
+				| TypeRef::STRING
+				| TypeRef::BYTES,
+				value,
+			) => to_bytes(buf, value, self.tag, self.ty.type_name()),
+			(name, Value::Null) if is_well_known(name) => Ok(0),
+			(name, value) if is_well_known(name) => {
+				let body = well_known_to_bytes(types, name, value)?;
+
+				prost::encoding::encode_key(self.tag, WireType::LengthDelimited, buf);
+				prost::encoding::encode_varint(body.len() as u64, buf);
+				buf.put_slice(&body);
+
+				Ok(prost::encoding::key_len(self.tag)
+					+ prost::encoding::encoded_len_varint(body.len() as u64)
+					+ body.len())
+			}
+			(name, value) => match types.get(name) {
+				Some(ty) => match ty.as_message() {
+					Some(message) => {
+						let mut buffer = BytesMut::new();
+						message.encode_value(types, &mut buffer, value)?;
+
+						prost::encoding::encode_key(self.tag, WireType::LengthDelimited, buf);
+						prost::encoding::encode_varint(buffer.len() as u64, buf);
+						buf.put_slice(&buffer[..]);
+
+						Ok(prost::encoding::key_len(self.tag)
+							+ prost::encoding::encoded_len_varint(buffer.len() as u64)
+							+ buffer.len())
+					}
+					None => ty.to_value(buf, value, self.tag),
+				},
+				None => Err(SeaographyError::new(format!("Unsupported type for field `{}`", name))),
+			},
+		}
+	}
+
+	/// used to encode the entries of a map field, every entry is a message of its key and value
+	fn encode_entries<B>(
+		&self,
+		types: &TypeRegistry,
+		buf: &mut B,
+		entries: &BTreeMap<Value, Value>,
+	) -> SeaResult<usize>
+	where
+		B: BufMut,
+	{
+		let (key, value) = match self.entry_fields() {
+			Some(fields) => fields,
+			None => return Err(SeaographyError::new(format!("Field `{}` is no map", self.name))),
+		};
+
+		let mut size = 0;
+		for (entry_key, entry_value) in entries {
+			let mut entry = BytesMut::new();
+			key.encode_value(types, &mut entry, entry_key)?;
+			value.encode_value(types, &mut entry, entry_value)?;
+
+			prost::encoding::encode_key(self.tag, WireType::LengthDelimited, buf);
+			prost::encoding::encode_varint(entry.len() as u64, buf);
+			buf.put_slice(&entry[..]);
+
+			size += prost::encoding::key_len(self.tag)
+				+ prost::encoding::encoded_len_varint(entry.len() as u64)
+				+ entry.len();
+		}
+
+		Ok(size)
+	}
+
+	/// used to get the key and the value field of the entries of a map field
+	fn entry_fields(&self) -> Option<(Field, Field)> {
+		let (key, value) = self.ty.as_map()?;
+		Some((Field::input("key", 1, key.clone()), Field::input("value", 2, value.clone())))
+	}
+
+	/// used to describe the entries of a map field as the message `protoc` generates for them
+	pub(crate) fn map_entry(&self, types: &TypeRegistry) -> Option<DescriptorProto> {
+		let (key, value) = self.entry_fields()?;
+
+		Some(DescriptorProto {
+			name: Some(format!("{}Entry", self.name.to_upper_camel_case())),
+			field: vec![key.field_descriptor(types, false), value.field_descriptor(types, false)],
+			options: Some(MessageOptions {
+				map_entry: Some(true),
+				..Default::default()
+			}),
+			..Default::default()
+		})
+	}
+
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
@@ -325,6 +496,7 @@ impl Field {
 					Some(val) => {
 						self.encode(
 							&mut buf,
+							types,
 							ctx,
 							&val,
 							arguments,
@@ -338,6 +510,7 @@ impl Field {
 						Some(value) => {
 							self.encode(
 								&mut buf,
+								types,
 								ctx,
 								&FieldValue::value(value.to_owned()),
 								arguments,
@@ -350,25 +523,60 @@ impl Field {
 						None => 0usize,
 					},
 				};
-				Ok::<(usize, BytesMut), Error>((size, buf))
+				Ok::<(usize, BytesMut), SeaographyError>((size, buf))
 			};
 			futures::pin_mut!(resolve_fut);
 
-			Ok(resolve_fut.await?)
+			resolve_fut.await
 		}
 		.boxed()
 	}
 
-	pub(crate) fn decode<B>(
+	/// used to decode a value of the field, `buf` is the value of the field as the `Fields`
+	/// iterator returns it
+	pub(crate) fn decode(
 		&self,
-		buf: &mut B,
-		ctx: DecodeContext,
-		wire_type: WireType,
+		types: &TypeRegistry,
+		typ: Typ,
+		buf: &[u8],
+		depth: usize,
 		arguments: &mut BTreeMap<Value, Value>,
-	) -> Result<(), DecodeError>
-	where
-		B: Buf,
-	{
+	) -> Result<(), DecodeError> {
+		let is_repeated = self.repeated || self.ty.is_repeated();
+
+		if let Some((key, value)) = self.entry_fields() {
+			let mut values = BTreeMap::new();
+			for field in Fields::new(length_delimited(typ, buf)?) {
+				match field.map_err(decode_error)? {
+					(1, typ, bytes) => key.decode(types, typ, bytes, depth, &mut values)?,
+					(2, typ, bytes) => value.decode(types, typ, bytes, depth, &mut values)?,
+					_ => continue,
+				}
+			}
+
+			// a missing key or value has the default value of its type
+			let entry_key = match values.remove(&Value::from("key")) {
+				Some(entry_key) => entry_key,
+				None => match key.ty.type_name() {
+					TypeRef::STRING => Value::from(""),
+					TypeRef::BOOL => Value::from(false),
+					_ => Value::from(0),
+				},
+			};
+			let entry_value = values.remove(&Value::from("value")).unwrap_or(Value::Null);
+
+			return match arguments
+				.entry(Value::from(self.type_name()))
+				.or_insert_with(|| Value::Map(BTreeMap::new()))
+			{
+				Value::Map(entries) => {
+					entries.insert(entry_key, entry_value);
+					Ok(())
+				}
+				_ => Err(DecodeError::new(format!("Field `{}` is no map", self.name))),
+			};
+		}
+
 		match self.ty.type_name() {
 			TypeRef::DOUBLE
 			| TypeRef::FLOAT
@@ -384,56 +592,38 @@ impl Field {
 			| TypeRef::SFIXED64
 			| TypeRef::BOOL
 			| TypeRef::STRING
-			| TypeRef::BYTES => from_bytes(
-				self.type_name(),
-				self.ty.type_name(),
-				self.ty.is_repeated(),
-				ctx,
-				buf,
-				wire_type,
-				arguments,
-			),
-			_ => {
-				let mut argument = BTreeMap::new();
-
-				match TYPE_REGISTRY.get(self.ty.type_name()) {
-					Some(inner) => match &*inner {
-						// Type::Scalar(scalar) => {
-						// 	let mut value = self.bytes(buf, tag)?;
-						// 	arguments.insert(Value::from(scalar.type_name()), value);
-						// 	println!("name: {:?} scalar: {:?}", inner.type_name(), scalar);
-						// }
-						Type::Message(message) => {
-							message.decode(buf, ctx, &mut argument)?;
-						}
-						Type::Enum(e) => {
-							e.bytes(buf, ctx, wire_type, self.ty.is_repeated(), &mut argument)?;
-						} // Type::Service(service) => {
-						  // 	let mut value = service.decode(decoder, buf)?;
-						  // 	arguments.insert(Value::from(service.type_name()), value.into());
-						  // }
-					},
-					None => {
-						return Err(DecodeError::new(format!(
-							"Unknown type {}",
-							self.ty.type_name()
-						)));
-					}
-				};
-
-				arguments.insert(Value::from(self.type_name()), Value::Map(argument));
-
+			| TypeRef::BYTES => {
+				from_bytes(self.type_name(), self.ty.type_name(), is_repeated, typ, buf, arguments)
+			}
+			name if is_well_known(name) => {
+				let value = well_known_from_bytes(types, name, length_delimited(typ, buf)?)?;
+				insert_value(arguments, self.type_name(), is_repeated, value);
 				Ok(())
 			}
+			name => match types.get(name).as_deref() {
+				Some(Type::Message(message)) => {
+					let mut argument = BTreeMap::new();
+					message.decode(types, length_delimited(typ, buf)?, depth, &mut argument)?;
+					insert_value(arguments, self.type_name(), is_repeated, Value::Map(argument));
+					Ok(())
+				}
+				Some(Type::Enum(e)) => e.bytes(self.type_name(), typ, buf, is_repeated, arguments),
+				None => Err(DecodeError::new(format!("Unknown type {}", name))),
+			},
 		}
 	}
 
-	pub(crate) fn field_descriptor(&self, oneof: bool) -> FieldDescriptorProto {
+	pub(crate) fn field_descriptor(
+		&self,
+		types: &TypeRegistry,
+		oneof: bool,
+	) -> FieldDescriptorProto {
 		let mut field = FieldDescriptorProto::default();
 		field.name = Some(self.name.clone());
+		field.json_name = Some(json_name(&self.name));
 		field.number = Some(self.tag as i32);
 		field.label = Some(self.ty.field_label().into());
-		field.r#type = Some(self.ty.field_type().into());
+		field.r#type = Some(self.ty.field_type(types).into());
 		field.type_name = Some(self.ty.type_name().to_string());
 		if oneof {
 			field.oneof_index = Some(self.tag as i32);
@@ -450,6 +640,49 @@ impl Field {
 		field
 	}
 
+	pub(crate) fn protobuf_field(&self) -> ProtobufField {
+		// map fields are implicitly repeated
+		if self.ty.as_map().is_some() {
+			return ProtobufField {
+				name: self.name.clone(),
+				description: self.description.clone(),
+				field_type: self.ty.to_string(),
+				tag: self.tag,
+				label: None,
+			};
+		}
+
+		let label = if self.repeated || self.ty.is_repeated() {
+			ProtobufFieldLabel::Repeated
+		} else if self.optional {
+			ProtobufFieldLabel::Optional
+		} else {
+			ProtobufFieldLabel::Required
+		};
+
+		ProtobufField {
+			name: self.name.clone(),
+			description: self.description.clone(),
+			field_type: self.ty.type_name().to_string(),
+			tag: self.tag,
+			label: Some(label),
+		}
+	}
+
+	/// used to describe the field as method, methods without arguments take an empty message
+	pub(crate) fn protobuf_method(&self, input_type: Option<String>) -> ProtobufMethod {
+		let method = self.method_descriptor(input_type);
+
+		ProtobufMethod {
+			name: self.name.clone(),
+			description: self.description.clone(),
+			input_type: method.input_type.unwrap_or_else(|| "google.protobuf.Empty".to_string()),
+			output_type: method.output_type.unwrap_or_default(),
+			client_streaming: method.client_streaming.unwrap_or_default(),
+			server_streaming: method.server_streaming.unwrap_or_default(),
+		}
+	}
+
 	pub(super) fn method_descriptor(&self, input_type: Option<String>) -> MethodDescriptorProto {
 		let mut method = MethodDescriptorProto::default();
 		method.name = Some(self.name.clone());
@@ -472,6 +705,8 @@ impl Field {
 			TypeRef::BYTES => "google.protobuf.BytesValue".to_string(),
 			name => name.to_string(),
 		});
+		method.client_streaming = Some(self.client_streaming);
+		method.server_streaming = Some(self.server_streaming);
 		method.options = Some(MethodOptions {
 			deprecated: Some(self.deprecated),
 			..Default::default()
@@ -480,3 +715,11 @@ impl Field {
 		method
 	}
 }
+
+/// used to get the body of a length-delimited value
+fn length_delimited(typ: Typ, buf: &[u8]) -> Result<&[u8], DecodeError> {
+	match typ {
+		Typ::LengthDelimited => Ok(buf),
+		typ => Err(wire_type_error(typ, Typ::LengthDelimited)),
+	}
+}
@@ -0,0 +1,1101 @@
+use super::{Enum, Field, PCKNAME, Type, TypeRef, TypeRegistry, wrapped_scalar};
+use crate::{SeaResult, SeaographyError};
+use base64::{
+	Engine,
+	engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+};
+use binary::proto::{
+	DecoderError, Fields, Typ, decode_bool, decode_double, decode_fixed32, decode_fixed64,
+	decode_float, decode_int32, decode_int64, decode_sfixed32, decode_sfixed64, decode_sint32,
+	decode_sint64, decode_uint32, decode_uint64, decode_varint, encode_bool, encode_bytes,
+	encode_double, encode_fixed32, encode_fixed64, encode_float, encode_int32, encode_int64,
+	encode_key, encode_sfixed32, encode_sfixed64, encode_sint32, encode_sint64, encode_uint32,
+	encode_uint64,
+};
+use chrono::{DateTime, SecondsFormat};
+use heck::{ToShoutySnakeCase, ToSnakeCase};
+use serde_json::{Map, Number, Value as JsonValue};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The JSON mapping of a type
+enum Kind {
+	/// A scalar, the name is one of the scalar names of `TypeRef`
+	Scalar(&'static str),
+	/// A registered message or enum
+	Type(Arc<Type>),
+	/// A wrapper message, it is mapped to the JSON of its scalar
+	Wrapper(&'static str),
+	Timestamp,
+	Duration,
+	Empty,
+	/// A struct, it is mapped to a JSON object
+	Struct,
+	/// A value, it is mapped to any JSON value
+	Value,
+	/// A list value, it is mapped to a JSON array
+	ListValue,
+	/// A field mask, it is mapped to a comma separated string of camel case paths
+	FieldMask,
+}
+
+impl Kind {
+	fn new(types: &TypeRegistry, name: &str) -> SeaResult<Self> {
+		let kind = match name {
+			TypeRef::DOUBLE => Kind::Scalar(TypeRef::DOUBLE),
+			TypeRef::FLOAT => Kind::Scalar(TypeRef::FLOAT),
+			TypeRef::INT32 => Kind::Scalar(TypeRef::INT32),
+			TypeRef::INT64 => Kind::Scalar(TypeRef::INT64),
+			TypeRef::UINT32 => Kind::Scalar(TypeRef::UINT32),
+			TypeRef::UINT64 => Kind::Scalar(TypeRef::UINT64),
+			TypeRef::SINT32 => Kind::Scalar(TypeRef::SINT32),
+			TypeRef::SINT64 => Kind::Scalar(TypeRef::SINT64),
+			TypeRef::FIXED32 => Kind::Scalar(TypeRef::FIXED32),
+			TypeRef::FIXED64 => Kind::Scalar(TypeRef::FIXED64),
+			TypeRef::SFIXED32 => Kind::Scalar(TypeRef::SFIXED32),
+			TypeRef::SFIXED64 => Kind::Scalar(TypeRef::SFIXED64),
+			TypeRef::BOOL => Kind::Scalar(TypeRef::BOOL),
+			TypeRef::STRING => Kind::Scalar(TypeRef::STRING),
+			TypeRef::BYTES => Kind::Scalar(TypeRef::BYTES),
+			TypeRef::TIMESTAMP => Kind::Timestamp,
+			TypeRef::DURATION => Kind::Duration,
+			TypeRef::EMPTY => Kind::Empty,
+			TypeRef::STRUCT => Kind::Struct,
+			TypeRef::VALUE => Kind::Value,
+			TypeRef::LIST_VALUE => Kind::ListValue,
+			TypeRef::FIELD_MASK => Kind::FieldMask,
+			name => match wrapped_scalar(name) {
+				Some(scalar) => Kind::Wrapper(scalar),
+				None => {
+					// registered types may be referenced with the package
+					let name = name
+						.strip_prefix(PCKNAME)
+						.and_then(|name| name.strip_prefix('.'))
+						.unwrap_or(name);
+					match types.get(name) {
+						Some(ty) => Kind::Type(ty),
+						None => {
+							return Err(SeaographyError::new(format!("Type `{}` not found", name)));
+						}
+					}
+				}
+			},
+		};
+		Ok(kind)
+	}
+
+	/// the wire type of a single value, values of a varint or fixed size type are packed when
+	/// they are repeated
+	fn wire_type(&self) -> Typ {
+		match self {
+			Kind::Scalar(TypeRef::FIXED32 | TypeRef::SFIXED32 | TypeRef::FLOAT) => Typ::Bit32,
+			Kind::Scalar(TypeRef::FIXED64 | TypeRef::SFIXED64 | TypeRef::DOUBLE) => Typ::Bit64,
+			Kind::Scalar(TypeRef::STRING | TypeRef::BYTES) => Typ::LengthDelimited,
+			Kind::Scalar(_) => Typ::Varint,
+			Kind::Type(ty) if ty.as_enum().is_some() => Typ::Varint,
+			_ => Typ::LengthDelimited,
+		}
+	}
+
+	fn is_message(&self) -> bool {
+		match self {
+			Kind::Scalar(_) => false,
+			Kind::Type(ty) => ty.as_message().is_some(),
+			_ => true,
+		}
+	}
+
+	/// the JSON of a value that is not present on the wire
+	fn default_json(&self) -> JsonValue {
+		match self {
+			Kind::Scalar(name) | Kind::Wrapper(name) => match *name {
+				TypeRef::INT64
+				| TypeRef::UINT64
+				| TypeRef::SINT64
+				| TypeRef::FIXED64
+				| TypeRef::SFIXED64 => JsonValue::from("0"),
+				TypeRef::STRING | TypeRef::BYTES => JsonValue::from(""),
+				TypeRef::BOOL => JsonValue::from(false),
+				_ => JsonValue::from(0),
+			},
+			Kind::Type(ty) => match ty.as_enum() {
+				Some(en) => JsonValue::from(unspecified(en)),
+				None => JsonValue::Object(Map::new()),
+			},
+			Kind::Timestamp => JsonValue::from("1970-01-01T00:00:00Z"),
+			Kind::Duration => JsonValue::from("0s"),
+			Kind::Empty | Kind::Struct => JsonValue::Object(Map::new()),
+			Kind::Value => JsonValue::Null,
+			Kind::ListValue => JsonValue::Array(Vec::new()),
+			Kind::FieldMask => JsonValue::from(""),
+		}
+	}
+}
+
+/// used to get the JSON name of a field, underscores are dropped and the letter following them
+/// is capitalized like `protoc` does
+pub(crate) fn json_name(name: &str) -> String {
+	let mut json = String::with_capacity(name.len());
+	let mut capitalize = false;
+	for c in name.chars() {
+		if c == '_' {
+			capitalize = true;
+		} else if capitalize {
+			json.extend(c.to_uppercase());
+			capitalize = false;
+		} else {
+			json.push(c);
+		}
+	}
+	json
+}
+
+/// used to transcode a message of the given type from the binary format into its proto3 JSON
+pub(crate) fn to_json(types: &TypeRegistry, type_name: &str, buf: &[u8]) -> SeaResult<JsonValue> {
+	let kind = Kind::new(types, type_name)?;
+	if !kind.is_message() {
+		return Err(SeaographyError::new(format!("Type `{}` is not a message", type_name)));
+	}
+	value_to_json(types, &kind, Typ::LengthDelimited, buf)
+}
+
+/// used to transcode the proto3 JSON of a message of the given type into the binary format
+pub(crate) fn from_json(
+	types: &TypeRegistry,
+	type_name: &str,
+	json: &JsonValue,
+) -> SeaResult<Vec<u8>> {
+	let kind = Kind::new(types, type_name)?;
+	if !kind.is_message() {
+		return Err(SeaographyError::new(format!("Type `{}` is not a message", type_name)));
+	}
+	let mut buf = Vec::new();
+	value_from_json(types, &kind, json, &mut buf)?;
+	Ok(buf)
+}
+
+/// used to transcode the proto3 JSON of the request message of a method into the binary format,
+/// the request message holds the arguments of the method
+pub(crate) fn request_from_json(
+	types: &TypeRegistry,
+	service: &str,
+	method: &str,
+	json: &JsonValue,
+) -> SeaResult<Vec<u8>> {
+	let service = service_type(types, service)?;
+	let field = method_field(&service, method)?;
+
+	let mut buf = Vec::new();
+	fields_from_json(types, &format!("Input{}", field.name), &field.arguments, json, &mut buf)?;
+	Ok(buf)
+}
+
+/// used to transcode the response message of a method from the binary format into its proto3
+/// JSON, scalars and enums are sent as the only field of a wrapper message
+pub(crate) fn response_to_json(
+	types: &TypeRegistry,
+	service: &str,
+	method: &str,
+	buf: &[u8],
+) -> SeaResult<JsonValue> {
+	let service = service_type(types, service)?;
+	let field = method_field(&service, method)?;
+
+	let kind = Kind::new(types, field.ty.type_name())?;
+	if kind.is_message() {
+		return value_to_json(types, &kind, Typ::LengthDelimited, buf);
+	}
+
+	let mut value = kind.default_json();
+	for item in Fields::new(buf) {
+		let (_, typ, bytes) = item?;
+		value = value_to_json(types, &kind, typ, bytes)?;
+	}
+	Ok(value)
+}
+
+/// used to get the client and server streaming flags of a method
+pub(crate) fn streaming(types: &TypeRegistry, service: &str, method: &str) -> Option<(bool, bool)> {
+	let service = types.get(service)?;
+	let field = service.as_message()?.field_by_name(method)?;
+	Some((field.client_streaming, field.server_streaming))
+}
+
+fn service_type(types: &TypeRegistry, name: &str) -> SeaResult<Arc<Type>> {
+	types.get(name).ok_or_else(|| SeaographyError::new(format!("Service `{}` not found", name)))
+}
+
+fn method_field<'a>(service: &'a Type, name: &str) -> SeaResult<&'a Field> {
+	service
+		.as_message()
+		.and_then(|message| message.field_by_name(name))
+		.ok_or_else(|| SeaographyError::new(format!("Method `{}` not found", name)))
+}
+
+fn is_repeated(field: &Field) -> bool {
+	field.repeated || field.ty.is_repeated()
+}
+
+/// used to create the error of a JSON value that can not be read as the given type
+fn conversion(type_name: &str, json: &JsonValue) -> SeaographyError {
+	SeaographyError::TypeConversionError(
+		format!("invalid JSON value `{}`", json),
+		type_name.to_string(),
+	)
+}
+
+fn unspecified(en: &Enum) -> String {
+	format!("{}_UNSPECIFIED", en.type_name().to_shouty_snake_case())
+}
+
+/// used to transcode the fields of a message, fields unknown to the message are dropped
+fn fields_to_json(
+	types: &TypeRegistry,
+	fields: &BTreeMap<String, Field>,
+	buf: &[u8],
+) -> SeaResult<JsonValue> {
+	let mut object = Map::new();
+
+	for item in Fields::new(buf) {
+		let (tag, typ, bytes) = item?;
+		let field = match fields.values().find(|field| field.tag == tag) {
+			Some(field) => field,
+			None => continue,
+		};
+		let kind = Kind::new(types, field.ty.type_name())?;
+		let name = json_name(&field.name);
+
+		// the entries of a map are collected into an object
+		if let Some((key, _)) = field.ty.as_map() {
+			let (key, value) =
+				entry_to_json(types, &Kind::new(types, key.type_name())?, &kind, typ, bytes)?;
+			match object.entry(name).or_insert_with(|| JsonValue::Object(Map::new())) {
+				JsonValue::Object(entries) => entries.insert(key, value),
+				_ => unreachable!(),
+			};
+			continue;
+		}
+
+		if !is_repeated(field) {
+			object.insert(name, value_to_json(types, &kind, typ, bytes)?);
+			continue;
+		}
+
+		let values = match object.entry(name).or_insert_with(|| JsonValue::Array(Vec::new())) {
+			JsonValue::Array(values) => values,
+			_ => unreachable!(),
+		};
+
+		let wire_type = kind.wire_type();
+		if typ != Typ::LengthDelimited || wire_type == Typ::LengthDelimited {
+			values.push(value_to_json(types, &kind, typ, bytes)?);
+			continue;
+		}
+
+		// a packed repeated field
+		let mut rest = bytes;
+		while !rest.is_empty() {
+			let size = match wire_type {
+				Typ::Bit32 => 4,
+				Typ::Bit64 => 8,
+				_ => decode_varint(rest, &mut 0)?,
+			};
+			if rest.len() < size {
+				return Err(DecoderError::InputUnderflow.into());
+			}
+			values.push(value_to_json(types, &kind, wire_type, &rest[..size])?);
+			rest = &rest[size..];
+		}
+	}
+
+	Ok(JsonValue::Object(object))
+}
+
+/// used to transcode the entry of a map into its JSON key and value, keys that are no strings
+/// are written as their JSON text
+fn entry_to_json(
+	types: &TypeRegistry,
+	key: &Kind,
+	value: &Kind,
+	typ: Typ,
+	buf: &[u8],
+) -> SeaResult<(String, JsonValue)> {
+	if typ != Typ::LengthDelimited {
+		return Err(DecoderError::InvalidInput.into());
+	}
+
+	let (mut key_json, mut value_json) = (key.default_json(), value.default_json());
+	for item in Fields::new(buf) {
+		match item? {
+			(1, typ, bytes) => key_json = value_to_json(types, key, typ, bytes)?,
+			(2, typ, bytes) => value_json = value_to_json(types, value, typ, bytes)?,
+			_ => continue,
+		}
+	}
+
+	let key_json = match key_json {
+		JsonValue::String(key) => key,
+		key => key.to_string(),
+	};
+	Ok((key_json, value_json))
+}
+
+fn value_to_json(types: &TypeRegistry, kind: &Kind, typ: Typ, buf: &[u8]) -> SeaResult<JsonValue> {
+	if typ != kind.wire_type() {
+		return Err(DecoderError::InvalidInput.into());
+	}
+
+	match kind {
+		Kind::Scalar(name) => scalar_to_json(name, buf),
+		Kind::Type(ty) => match &**ty {
+			Type::Enum(en) => {
+				let mut number = 0;
+				decode_int32(buf, &mut number)?;
+				Ok(match number {
+					0 => JsonValue::from(unspecified(en)),
+					number => match en.get_item_by_tag(number as u32) {
+						Some(item) => JsonValue::from(item.type_name()),
+						None => JsonValue::from(number),
+					},
+				})
+			}
+			Type::Message(message) => fields_to_json(types, &message.fields, buf),
+		},
+		Kind::Wrapper(name) => {
+			let mut value = kind.default_json();
+			for item in Fields::new(buf) {
+				let (tag, typ, bytes) = item?;
+				if tag == 1 {
+					value = value_to_json(types, &Kind::Scalar(name), typ, bytes)?;
+				}
+			}
+			Ok(value)
+		}
+		Kind::Timestamp => {
+			let (seconds, nanos) = seconds_to_json(buf)?;
+			let timestamp = u32::try_from(nanos)
+				.ok()
+				.and_then(|nanos| DateTime::from_timestamp(seconds, nanos))
+				.ok_or(DecoderError::InvalidInput)?;
+			Ok(JsonValue::from(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)))
+		}
+		Kind::Duration => {
+			let (seconds, nanos) = seconds_to_json(buf)?;
+			let sign = if seconds < 0 || nanos < 0 {
+				"-"
+			} else {
+				""
+			};
+			let (seconds, nanos) = (seconds.unsigned_abs(), nanos.unsigned_abs());
+			Ok(JsonValue::from(match nanos {
+				0 => format!("{}{}s", sign, seconds),
+				nanos if nanos % 1_000_000 == 0 => {
+					format!("{}{}.{:03}s", sign, seconds, nanos / 1_000_000)
+				}
+				nanos if nanos % 1_000 == 0 => format!("{}{}.{:06}s", sign, seconds, nanos / 1_000),
+				nanos => format!("{}{}.{:09}s", sign, seconds, nanos),
+			}))
+		}
+		Kind::Empty => Ok(JsonValue::Object(Map::new())),
+		Kind::Struct => {
+			let mut object = Map::new();
+			for item in Fields::new(buf) {
+				if let (1, typ, bytes) = item? {
+					let (key, value) = entry_to_json(
+						types,
+						&Kind::Scalar(TypeRef::STRING),
+						&Kind::Value,
+						typ,
+						bytes,
+					)?;
+					object.insert(key, value);
+				}
+			}
+			Ok(JsonValue::Object(object))
+		}
+		Kind::Value => {
+			// the members of the `kind` oneof, the last one on the wire wins
+			let mut value = JsonValue::Null;
+			for item in Fields::new(buf) {
+				let (tag, typ, bytes) = item?;
+				let kind = match tag {
+					1 => {
+						value = JsonValue::Null;
+						continue;
+					}
+					2 => Kind::Scalar(TypeRef::DOUBLE),
+					3 => Kind::Scalar(TypeRef::STRING),
+					4 => Kind::Scalar(TypeRef::BOOL),
+					5 => Kind::Struct,
+					6 => Kind::ListValue,
+					_ => continue,
+				};
+				value = value_to_json(types, &kind, typ, bytes)?;
+			}
+			Ok(value)
+		}
+		Kind::ListValue => {
+			let mut values = Vec::new();
+			for item in Fields::new(buf) {
+				if let (1, typ, bytes) = item? {
+					values.push(value_to_json(types, &Kind::Value, typ, bytes)?);
+				}
+			}
+			Ok(JsonValue::Array(values))
+		}
+		Kind::FieldMask => {
+			let mut paths = Vec::new();
+			for item in Fields::new(buf) {
+				if let (1, typ, bytes) = item? {
+					if let JsonValue::String(path) =
+						value_to_json(types, &Kind::Scalar(TypeRef::STRING), typ, bytes)?
+					{
+						paths.push(json_name(&path));
+					}
+				}
+			}
+			Ok(JsonValue::from(paths.join(",")))
+		}
+	}
+}
+
+/// used to read the `seconds` and `nanos` fields shared by `Timestamp` and `Duration`
+fn seconds_to_json(buf: &[u8]) -> SeaResult<(i64, i32)> {
+	let (mut seconds, mut nanos) = (0, 0);
+	for item in Fields::new(buf) {
+		match item? {
+			(1, Typ::Varint, bytes) => decode_int64(bytes, &mut seconds)?,
+			(2, Typ::Varint, bytes) => decode_int32(bytes, &mut nanos)?,
+			_ => continue,
+		};
+	}
+	Ok((seconds, nanos))
+}
+
+fn scalar_to_json(name: &str, buf: &[u8]) -> SeaResult<JsonValue> {
+	let value = match name {
+		TypeRef::INT32 => {
+			let mut value = 0;
+			decode_int32(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		TypeRef::SINT32 => {
+			let mut value = 0;
+			decode_sint32(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		TypeRef::SFIXED32 => {
+			let mut value = 0;
+			decode_sfixed32(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		TypeRef::UINT32 => {
+			let mut value = 0;
+			decode_uint32(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		TypeRef::FIXED32 => {
+			let mut value = 0;
+			decode_fixed32(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		// 64-bit integers are strings as JSON numbers lose precision above 2^53
+		TypeRef::INT64 => {
+			let mut value = 0;
+			decode_int64(buf, &mut value)?;
+			JsonValue::from(value.to_string())
+		}
+		TypeRef::SINT64 => {
+			let mut value = 0;
+			decode_sint64(buf, &mut value)?;
+			JsonValue::from(value.to_string())
+		}
+		TypeRef::SFIXED64 => {
+			let mut value = 0;
+			decode_sfixed64(buf, &mut value)?;
+			JsonValue::from(value.to_string())
+		}
+		TypeRef::UINT64 => {
+			let mut value = 0;
+			decode_uint64(buf, &mut value)?;
+			JsonValue::from(value.to_string())
+		}
+		TypeRef::FIXED64 => {
+			let mut value = 0;
+			decode_fixed64(buf, &mut value)?;
+			JsonValue::from(value.to_string())
+		}
+		TypeRef::FLOAT => {
+			let mut value = 0.0;
+			decode_float(buf, &mut value)?;
+			// the shortest representation of the `f32` rather than of its `f64` widening
+			float_to_json(value.to_string().parse().unwrap_or(f64::from(value)))
+		}
+		TypeRef::DOUBLE => {
+			let mut value = 0.0;
+			decode_double(buf, &mut value)?;
+			float_to_json(value)
+		}
+		TypeRef::BOOL => {
+			let mut value = false;
+			decode_bool(buf, &mut value)?;
+			JsonValue::from(value)
+		}
+		TypeRef::STRING => JsonValue::from(
+			String::from_utf8(buf.to_vec()).map_err(|_| DecoderError::InvalidInput)?,
+		),
+		TypeRef::BYTES => JsonValue::from(STANDARD.encode(buf)),
+		name => return Err(SeaographyError::new(format!("Unsupported type `{}`", name))),
+	};
+	Ok(value)
+}
+
+fn float_to_json(value: f64) -> JsonValue {
+	match Number::from_f64(value) {
+		Some(number) => JsonValue::Number(number),
+		None if value.is_nan() => JsonValue::from("NaN"),
+		None if value.is_sign_positive() => JsonValue::from("Infinity"),
+		None => JsonValue::from("-Infinity"),
+	}
+}
+
+/// used to transcode the fields of a message, a field is looked up by its JSON name or by its
+/// name and null fields are skipped
+fn fields_from_json(
+	types: &TypeRegistry,
+	type_name: &str,
+	fields: &BTreeMap<String, Field>,
+	json: &JsonValue,
+	buf: &mut Vec<u8>,
+) -> SeaResult<()> {
+	let object = match json {
+		JsonValue::Object(object) => object,
+		json => return Err(conversion(type_name, json)),
+	};
+
+	if let Some(key) = object.keys().find(|key| {
+		!fields.values().any(|field| field.name == **key || json_name(&field.name) == **key)
+	}) {
+		return Err(SeaographyError::TypeConversionError(
+			format!("unknown field `{}`", key),
+			type_name.to_string(),
+		));
+	}
+
+	// the fields are written in the order of their tags
+	let mut fields = fields.values().collect::<Vec<_>>();
+	fields.sort_by_key(|field| field.tag);
+
+	for field in fields {
+		// a null is only sent for a value, for other types it is the same as a missing field
+		let value = match object.get(&json_name(&field.name)).or_else(|| object.get(&field.name)) {
+			Some(value) if !value.is_null() || field.ty.type_name() == TypeRef::VALUE => value,
+			_ => continue,
+		};
+		let kind = Kind::new(types, field.ty.type_name())?;
+
+		if let Some((key_type, _)) = field.ty.as_map() {
+			let entries = match value {
+				JsonValue::Object(entries) => entries,
+				value => return Err(conversion(&field.ty.to_string(), value)),
+			};
+			let key_kind = Kind::new(types, key_type.type_name())?;
+			for (key, value) in entries {
+				// the keys of a map are always strings in JSON
+				let key = match (key_type.type_name(), key.as_str()) {
+					(TypeRef::BOOL, "true") => JsonValue::from(true),
+					(TypeRef::BOOL, "false") => JsonValue::from(false),
+					(_, key) => JsonValue::from(key),
+				};
+				entry_from_json(types, (&key_kind, &kind), field.tag, (&key, value), buf)?;
+			}
+			continue;
+		}
+
+		if !is_repeated(field) {
+			field_from_json(types, &kind, field.tag, value, buf)?;
+			continue;
+		}
+
+		let values = match value {
+			JsonValue::Array(values) => values,
+			value => return Err(conversion(field.ty.type_name(), value)),
+		};
+
+		if kind.wire_type() == Typ::LengthDelimited {
+			for value in values {
+				field_from_json(types, &kind, field.tag, value, buf)?;
+			}
+			continue;
+		}
+
+		// repeated values of a varint or fixed size type are packed
+		let mut packed = Vec::new();
+		for value in values {
+			value_from_json(types, &kind, value, &mut packed)?;
+		}
+		if !packed.is_empty() {
+			encode_key(field.tag, Typ::LengthDelimited, buf)?;
+			encode_bytes(packed, buf)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// used to write the entry of a map as a message of its key and its value
+fn entry_from_json(
+	types: &TypeRegistry,
+	(key_kind, value_kind): (&Kind, &Kind),
+	tag: u32,
+	(key, value): (&JsonValue, &JsonValue),
+	buf: &mut Vec<u8>,
+) -> SeaResult<()> {
+	let mut entry = Vec::new();
+	field_from_json(types, key_kind, 1, key, &mut entry)?;
+	if !value.is_null() || matches!(value_kind, Kind::Value) {
+		field_from_json(types, value_kind, 2, value, &mut entry)?;
+	}
+
+	encode_key(tag, Typ::LengthDelimited, buf)?;
+	encode_bytes(entry, buf)?;
+	Ok(())
+}
+
+/// used to write a value together with its key
+fn field_from_json(
+	types: &TypeRegistry,
+	kind: &Kind,
+	tag: u32,
+	json: &JsonValue,
+	buf: &mut Vec<u8>,
+) -> SeaResult<()> {
+	let mut value = Vec::new();
+	let typ = value_from_json(types, kind, json, &mut value)?;
+
+	encode_key(tag, typ, buf)?;
+	match typ {
+		Typ::LengthDelimited => {
+			encode_bytes(value, buf)?;
+		}
+		_ => buf.extend_from_slice(&value),
+	}
+	Ok(())
+}
+
+/// used to write a value without its key, a length-delimited value is written without its length
+fn value_from_json(
+	types: &TypeRegistry,
+	kind: &Kind,
+	json: &JsonValue,
+	buf: &mut Vec<u8>,
+) -> SeaResult<Typ> {
+	match kind {
+		Kind::Scalar(name) => scalar_from_json(name, json, buf)?,
+		Kind::Type(ty) => match &**ty {
+			Type::Enum(en) => {
+				encode_int32(enum_from_json(en, json)?, buf)?;
+			}
+			Type::Message(message) => {
+				fields_from_json(types, message.type_name(), &message.fields, json, buf)?;
+			}
+		},
+		Kind::Wrapper(name) => field_from_json(types, &Kind::Scalar(name), 1, json, buf)?,
+		Kind::Timestamp => {
+			let timestamp = json
+				.as_str()
+				.and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+				.ok_or_else(|| conversion(TypeRef::TIMESTAMP, json))?;
+			seconds_from_json(
+				timestamp.timestamp(),
+				timestamp.timestamp_subsec_nanos() as i32,
+				buf,
+			)?;
+		}
+		Kind::Duration => {
+			let (seconds, nanos) = json
+				.as_str()
+				.and_then(duration_from_json)
+				.ok_or_else(|| conversion(TypeRef::DURATION, json))?;
+			seconds_from_json(seconds, nanos, buf)?;
+		}
+		Kind::Empty => {
+			if !json.is_object() {
+				return Err(conversion(TypeRef::EMPTY, json));
+			}
+		}
+		Kind::Struct => {
+			let object = json.as_object().ok_or_else(|| conversion(TypeRef::STRUCT, json))?;
+			let key_kind = Kind::Scalar(TypeRef::STRING);
+			for (key, value) in object {
+				let key = JsonValue::from(key.as_str());
+				entry_from_json(types, (&key_kind, &Kind::Value), 1, (&key, value), buf)?;
+			}
+		}
+		Kind::Value => match json {
+			JsonValue::Null => {
+				encode_key(1, Typ::Varint, buf)?;
+				encode_int32(0, buf)?;
+			}
+			JsonValue::Number(_) => {
+				field_from_json(types, &Kind::Scalar(TypeRef::DOUBLE), 2, json, buf)?
+			}
+			JsonValue::String(_) => {
+				field_from_json(types, &Kind::Scalar(TypeRef::STRING), 3, json, buf)?
+			}
+			JsonValue::Bool(_) => {
+				field_from_json(types, &Kind::Scalar(TypeRef::BOOL), 4, json, buf)?
+			}
+			JsonValue::Object(_) => field_from_json(types, &Kind::Struct, 5, json, buf)?,
+			JsonValue::Array(_) => field_from_json(types, &Kind::ListValue, 6, json, buf)?,
+		},
+		Kind::ListValue => {
+			let values = json.as_array().ok_or_else(|| conversion(TypeRef::LIST_VALUE, json))?;
+			for value in values {
+				field_from_json(types, &Kind::Value, 1, value, buf)?;
+			}
+		}
+		Kind::FieldMask => {
+			let paths = json.as_str().ok_or_else(|| conversion(TypeRef::FIELD_MASK, json))?;
+			for path in paths.split(',').filter(|path| !path.is_empty()) {
+				// the paths are camel case in JSON and snake case on the wire
+				let path = path.split('.').map(|name| name.to_snake_case()).collect::<Vec<_>>();
+				encode_key(1, Typ::LengthDelimited, buf)?;
+				encode_bytes(path.join(".").into_bytes(), buf)?;
+			}
+		}
+	};
+	Ok(kind.wire_type())
+}
+
+/// used to write the `seconds` and `nanos` fields shared by `Timestamp` and `Duration`
+fn seconds_from_json(seconds: i64, nanos: i32, buf: &mut Vec<u8>) -> SeaResult<()> {
+	if seconds != 0 {
+		encode_key(1, Typ::Varint, buf)?;
+		encode_int64(seconds, buf)?;
+	}
+	if nanos != 0 {
+		encode_key(2, Typ::Varint, buf)?;
+		encode_int32(nanos, buf)?;
+	}
+	Ok(())
+}
+
+/// used to read a duration like `-1.5s`, the nanos take the sign of the seconds
+fn duration_from_json(duration: &str) -> Option<(i64, i32)> {
+	let duration = duration.strip_suffix('s')?;
+	let (negative, duration) = match duration.strip_prefix('-') {
+		Some(duration) => (true, duration),
+		None => (false, duration),
+	};
+	let (seconds, fraction) = duration.split_once('.').unwrap_or((duration, ""));
+	if seconds.is_empty()
+		|| fraction.len() > 9
+		|| !seconds.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+	{
+		return None;
+	}
+
+	let seconds = seconds.parse::<i64>().ok()?;
+	let nanos = format!("{:0<9}", fraction).parse::<i32>().ok()?;
+	match negative {
+		true => Some((-seconds, -nanos)),
+		false => Some((seconds, nanos)),
+	}
+}
+
+fn enum_from_json(en: &Enum, json: &JsonValue) -> SeaResult<i32> {
+	let number = match json {
+		JsonValue::String(name) => match en.get_item(name) {
+			Some(item) => item.tag.map(|tag| tag as i32),
+			None if *name == unspecified(en) => Some(0),
+			None => None,
+		},
+		JsonValue::Number(number) => number.as_i64().and_then(|number| i32::try_from(number).ok()),
+		_ => None,
+	};
+	number.ok_or_else(|| conversion(en.type_name(), json))
+}
+
+fn scalar_from_json(name: &str, json: &JsonValue, buf: &mut Vec<u8>) -> SeaResult<()> {
+	match name {
+		TypeRef::INT32 => encode_int32(int_from_json(name, json)?, buf)?,
+		TypeRef::SINT32 => encode_sint32(int_from_json(name, json)?, buf)?,
+		TypeRef::SFIXED32 => encode_sfixed32(int_from_json(name, json)?, buf)?,
+		TypeRef::UINT32 => encode_uint32(int_from_json(name, json)?, buf)?,
+		TypeRef::FIXED32 => encode_fixed32(int_from_json(name, json)?, buf)?,
+		TypeRef::INT64 => encode_int64(int_from_json(name, json)?, buf)?,
+		TypeRef::SINT64 => encode_sint64(int_from_json(name, json)?, buf)?,
+		TypeRef::SFIXED64 => encode_sfixed64(int_from_json(name, json)?, buf)?,
+		TypeRef::UINT64 => encode_uint64(int_from_json(name, json)?, buf)?,
+		TypeRef::FIXED64 => encode_fixed64(int_from_json(name, json)?, buf)?,
+		TypeRef::FLOAT => {
+			let value = float_from_json(name, json)?;
+			if value.is_finite() && (value as f32).is_infinite() {
+				return Err(conversion(name, json));
+			}
+			encode_float(value as f32, buf)?
+		}
+		TypeRef::DOUBLE => encode_double(float_from_json(name, json)?, buf)?,
+		TypeRef::BOOL => encode_bool(json.as_bool().ok_or_else(|| conversion(name, json))?, buf)?,
+		TypeRef::STRING => {
+			let value = json.as_str().ok_or_else(|| conversion(name, json))?;
+			buf.extend_from_slice(value.as_bytes());
+			value.len()
+		}
+		TypeRef::BYTES => {
+			// both the standard and the url-safe alphabet are accepted, with or without padding
+			let value = json
+				.as_str()
+				.map(|value| value.trim_end_matches('=').replace('-', "+").replace('_', "/"))
+				.and_then(|value| STANDARD_NO_PAD.decode(value).ok())
+				.ok_or_else(|| conversion(name, json))?;
+			buf.extend_from_slice(&value);
+			value.len()
+		}
+		name => return Err(SeaographyError::new(format!("Unsupported type `{}`", name))),
+	};
+	Ok(())
+}
+
+/// used to read an integer that is sent either as number or as string
+fn int_from_json<T: TryFrom<i128>>(name: &str, json: &JsonValue) -> SeaResult<T> {
+	let value = match json {
+		JsonValue::Number(number) => match (number.as_i64(), number.as_u64(), number.as_f64()) {
+			(Some(value), _, _) => Some(i128::from(value)),
+			(_, Some(value), _) => Some(i128::from(value)),
+			(_, _, Some(value)) if value.fract() == 0.0 => Some(value as i128),
+			_ => None,
+		},
+		JsonValue::String(value) => value.parse::<i128>().ok(),
+		_ => None,
+	};
+	value.and_then(|value| T::try_from(value).ok()).ok_or_else(|| conversion(name, json))
+}
+
+/// used to read a floating point number that is sent either as number or as string, the
+/// strings `NaN`, `Infinity` and `-Infinity` are accepted as well
+fn float_from_json(name: &str, json: &JsonValue) -> SeaResult<f64> {
+	let value = match json {
+		JsonValue::Number(number) => number.as_f64(),
+		JsonValue::String(value) => match value.as_str() {
+			"NaN" => Some(f64::NAN),
+			"Infinity" => Some(f64::INFINITY),
+			"-Infinity" => Some(f64::NEG_INFINITY),
+			value => value.parse::<f64>().ok().filter(|value| value.is_finite()),
+		},
+		_ => None,
+	};
+	value.ok_or_else(|| conversion(name, json))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		FieldFuture, TypeRefTrait, Value,
+		proto::{EnumItem, Message, Proto},
+	};
+	use serde_json::json;
+
+	fn proto() -> Proto {
+		let status =
+			Enum::new("UserStatus").items([EnumItem::new("ACTIVE"), EnumItem::new("BANNED")]);
+
+		let profile = Message::new("Profile")
+			.field(Field::input("display_name", 1, TypeRef::named(TypeRef::STRING)))
+			.field(Field::input("avatar", 2, TypeRef::named(TypeRef::BYTES)));
+
+		let user = Message::new("User")
+			.field(Field::input("id", 1, TypeRef::named_nn(TypeRef::INT32)))
+			.field(Field::input("balance", 2, TypeRef::named(TypeRef::INT64)))
+			.field(Field::input("scores", 3, TypeRef::named_list(TypeRef::UINT64)))
+			.field(Field::input("ratio", 4, TypeRef::named(TypeRef::DOUBLE)))
+			.field(Field::input("weight", 5, TypeRef::named(TypeRef::FLOAT)))
+			.field(Field::input("status", 6, TypeRef::named("UserStatus")))
+			.field(Field::input("profile", 7, TypeRef::named("Profile")))
+			.field(Field::input("friends", 8, TypeRef::named_list("Profile")))
+			.field(Field::input("created_at", 9, TypeRef::named(TypeRef::TIMESTAMP)))
+			.field(Field::input("timeout", 10, TypeRef::named(TypeRef::DURATION)))
+			.field(Field::input("nickname", 11, TypeRef::named("google.protobuf.StringValue")))
+			.field(Field::input("statuses", 12, TypeRef::named_list("UserStatus")))
+			.field(Field::input("limits", 17, TypeRef::map(TypeRef::STRING, TypeRef::INT64)))
+			.field(Field::input("flags", 18, TypeRef::map(TypeRef::BOOL, "UserStatus")))
+			.field(Field::input("settings", 19, TypeRef::named(TypeRef::STRUCT)))
+			.field(Field::input("mask", 20, TypeRef::named(TypeRef::FIELD_MASK)));
+
+		let query = Message::new("Query")
+			.field(
+				Field::output("user", 1, TypeRef::named_nn("User"), |_| {
+					FieldFuture::new(async move { Ok(None::<Value>) })
+				})
+				.argument(Field::input("user_id", 1, TypeRef::named_nn(TypeRef::INT64)))
+				.argument(Field::input("tags", 2, TypeRef::named_list(TypeRef::STRING))),
+			)
+			.field(Field::output("count", 2, TypeRef::named_nn(TypeRef::INT64), |_| {
+				FieldFuture::new(async move { Ok(None::<Value>) })
+			}));
+
+		Proto::build(vec!["Query"])
+			.register(status)
+			.register(profile)
+			.register(user)
+			.register(query)
+			.finish()
+			.unwrap()
+	}
+
+	/// Should transcode a message into canonical JSON and back.
+	#[test]
+	fn round_trips() {
+		let proto = proto();
+
+		let user = json!({
+			"id": 150,
+			"balance": "-9007199254740993",
+			"scores": ["1", "18446744073709551615"],
+			"ratio": "NaN",
+			"weight": 0.1,
+			"status": "BANNED",
+			"profile": { "displayName": "foo", "avatar": "AAH/" },
+			"friends": [{ "displayName": "bar" }, {}],
+			"createdAt": "1972-01-01T10:00:20.021Z",
+			"timeout": "-1.000340s",
+			"nickname": "baz",
+			"statuses": ["ACTIVE", "USER_STATUS_UNSPECIFIED"],
+		});
+
+		let buf = proto.from_json("User", &user).unwrap();
+		assert_eq!(&buf[..3], &[0x08, 0x96, 0x01]);
+		assert_eq!(proto.to_json("apy.User", &buf).unwrap(), user);
+
+		// numbers, names and alphabets that are accepted but not canonical
+		let input = json!({
+			"id": "150",
+			"balance": -9007199254740993i64,
+			"scores": [1, 18446744073709551615u64],
+			"ratio": "NaN",
+			"weight": "0.1",
+			"status": 2,
+			"profile": { "display_name": "foo", "avatar": "AAH_" },
+			"friends": [{ "displayName": "bar" }, {}],
+			"created_at": "1972-01-01T11:00:20.021+01:00",
+			"timeout": "-1.00034s",
+			"nickname": "baz",
+			"statuses": [1, 0],
+		});
+		assert_eq!(proto.from_json("User", &input).unwrap(), buf);
+	}
+
+	/// Should map the wire format of well-known types.
+	#[test]
+	fn well_known_types() {
+		let proto = proto();
+
+		let buf = proto.from_json(TypeRef::TIMESTAMP, &json!("1970-01-01T00:00:01.5Z")).unwrap();
+		assert_eq!(buf, vec![0x08, 0x01, 0x10, 0x80, 0xca, 0xb5, 0xee, 0x01]);
+		assert_eq!(
+			proto.to_json(TypeRef::TIMESTAMP, &buf).unwrap(),
+			json!("1970-01-01T00:00:01.500Z")
+		);
+
+		let buf = proto.from_json(TypeRef::DURATION, &json!("3s")).unwrap();
+		assert_eq!(buf, vec![0x08, 0x03]);
+		assert_eq!(proto.to_json(TypeRef::DURATION, &[]).unwrap(), json!("0s"));
+
+		let buf = proto.from_json("google.protobuf.Int64Value", &json!("5")).unwrap();
+		assert_eq!(buf, vec![0x08, 0x05]);
+		assert_eq!(proto.to_json("google.protobuf.Int64Value", &[]).unwrap(), json!("0"));
+		assert_eq!(proto.from_json(TypeRef::EMPTY, &json!({})).unwrap(), Vec::<u8>::new());
+	}
+
+	/// Should map maps to objects and structs, values and field masks to their JSON.
+	#[test]
+	fn maps_and_structs() {
+		let proto = proto();
+
+		let user = json!({
+			"limits": { "a": "1", "b": "0" },
+			"flags": { "false": "BANNED", "true": "ACTIVE" },
+			"settings": { "theme": "dark", "size": 1.5, "tags": ["a", null, true], "nested": {} },
+			"mask": "limits,profile.displayName",
+		});
+
+		let buf = proto.from_json("User", &user).unwrap();
+		assert_eq!(proto.to_json("User", &buf).unwrap(), user);
+		// the paths of a field mask are snake case on the wire
+		assert!(buf.windows(20).any(|path| path == b"profile.display_name"));
+
+		// a value field takes any JSON, a null included
+		let value = proto.from_json(TypeRef::VALUE, &json!(null)).unwrap();
+		assert_eq!(value, vec![0x08, 0x00]);
+		assert_eq!(proto.to_json(TypeRef::VALUE, &value).unwrap(), json!(null));
+		assert_eq!(proto.to_json(TypeRef::LIST_VALUE, &[]).unwrap(), json!([]));
+
+		for json in [
+			json!({ "limits": ["1"] }),
+			json!({ "flags": { "yes": "ACTIVE" } }),
+			json!({ "settings": [] }),
+			json!({ "mask": ["limits"] }),
+		] {
+			assert!(proto.from_json("User", &json).is_err(), "{}", json);
+		}
+	}
+
+	/// Should transcode the request and response messages of a method.
+	#[test]
+	fn methods() {
+		let proto = proto();
+
+		let buf = proto
+			.request_from_json("Query", "user", &json!({ "tags": ["a", "b"], "userId": "7" }))
+			.unwrap();
+		assert_eq!(buf, vec![0x08, 0x07, 0x12, 0x01, 0x61, 0x12, 0x01, 0x62]);
+
+		let buf = proto
+			.request_from_json("Query", "user", &json!({ "user_id": 7, "tags": null }))
+			.unwrap();
+		assert_eq!(buf, vec![0x08, 0x07]);
+
+		let buf = proto.request_from_json("Query", "count", &json!({})).unwrap();
+		assert!(buf.is_empty());
+
+		// a scalar response is the only field of its wrapper
+		assert_eq!(proto.response_to_json("Query", "count", &[0x10, 0x2a]).unwrap(), json!("42"));
+		assert_eq!(proto.response_to_json("Query", "count", &[]).unwrap(), json!("0"));
+		assert_eq!(
+			proto.response_to_json("Query", "user", &[0x08, 0x01, 0x68, 0x01]).unwrap(),
+			json!({ "id": 1 })
+		);
+	}
+
+	/// Should reject JSON that does not match the message.
+	#[test]
+	fn errors() {
+		let proto = proto();
+
+		for json in [
+			json!({ "unknown": 1 }),
+			json!({ "id": "abc" }),
+			json!({ "id": 1.5 }),
+			json!({ "id": 4294967296i64 }),
+			json!({ "weight": 1e300 }),
+			json!({ "status": "UNKNOWN" }),
+			json!({ "scores": "1" }),
+			json!({ "createdAt": "yesterday" }),
+			json!({ "timeout": "1.5" }),
+			json!({ "profile": { "avatar": "***" } }),
+			json!([]),
+		] {
+			let err = proto.from_json("User", &json).unwrap_err();
+			assert!(matches!(err, SeaographyError::TypeConversionError(..)), "{}", json);
+		}
+
+		assert!(proto.from_json("Unknown", &json!({})).is_err());
+		assert!(proto.from_json(TypeRef::INT32, &json!(1)).is_err());
+		// a string sent as varint
+		assert!(matches!(
+			proto.to_json("Profile", &[0x08, 0x01]).unwrap_err(),
+			SeaographyError::DecoderError(DecoderError::InvalidInput)
+		));
+	}
+}
@@ -1,11 +1,14 @@
-use super::{Error, Field, Result, descriptor};
-use crate::{BoxFieldFutureByte, ContextBase, FieldValue, ObjectAccessor, Value};
-use binary::proto::Decoder;
-use bytes::{Buf, BufMut, BytesMut};
-use prost::{
-	DecodeError,
-	encoding::{DecodeContext, WireType, decode_key, merge_loop},
+use super::{
+	Error, Field, RECURSION_LIMIT, Result, TypeRegistry, UNKNOWN_FIELDS, decode_error, descriptor,
+	merge_unknown,
 };
+use crate::{
+	BoxFieldFutureByte, ContextBase, FieldValue, ObjectAccessor, ProtoRegistry, ProtobufKind,
+	ProtobufOneofGroup, SeaResult, SeaographyError, Value,
+};
+use binary::proto::Fields;
+use bytes::{BufMut, BytesMut};
+use prost::DecodeError;
 use prost_types::{FileDescriptorProto, ServiceDescriptorProto, ServiceOptions};
 use std::collections::BTreeMap;
 
@@ -86,30 +89,98 @@ impl Message {
 		}
 	}
 
-	pub(crate) fn decode<B>(
+	/// used to decode the fields of a message, the value of every field is a slice of `buf`
+	pub(crate) fn decode(
 		&self,
-		buf: &mut B,
-		ctx: DecodeContext,
+		types: &TypeRegistry,
+		buf: &[u8],
+		depth: usize,
 		arguments: &mut BTreeMap<Value, Value>,
-	) -> Result<(), DecodeError>
+	) -> Result<(), DecodeError> {
+		if depth >= RECURSION_LIMIT {
+			return Err(DecodeError::new("recursion limit reached"));
+		}
+
+		for field in Fields::new(buf) {
+			let (tag, typ, bytes) = field.map_err(decode_error)?;
+			match self.field_by_tag(tag) {
+				Some(field) => field.decode(types, typ, bytes, depth + 1, arguments)?,
+				None => merge_unknown(tag, typ, bytes, arguments)?,
+			}
+		}
+
+		Ok(())
+	}
+
+	/// used to encode a decoded message, the fields unknown to the message are written back
+	/// verbatim after the known fields
+	pub(crate) fn encode_value<B>(
+		&self,
+		types: &TypeRegistry,
+		buf: &mut B,
+		value: &Value,
+	) -> SeaResult<usize>
 	where
-		B: Buf,
+		B: BufMut,
 	{
-		merge_loop(arguments, buf, ctx, |msg, buffer, ctx| {
-			let (tag, wire_type) = decode_key(buffer)?;
-			match self.field_by_tag(tag) {
-				Some(field) => field.decode(buffer, ctx, wire_type, msg),
-				None => Err(DecodeError::new(format!(
-					"Message `{}` has no field with Tag `{}`",
+		let values = match value {
+			Value::Map(values) => values,
+			Value::Null => return Ok(0),
+			value => {
+				return Err(SeaographyError::new(format!(
+					"Message `{}` can not be encoded from a value of type `{}`",
 					self.type_name(),
-					tag,
-				))),
+					value.type_name()
+				)));
+			}
+		};
+
+		let mut size = 0;
+		let mut unknown = None;
+		for (name, value) in values {
+			let name = match name {
+				Value::String(name) => name.as_str(),
+				name => {
+					return Err(SeaographyError::new(format!("invalid field name `{}`", name)));
+				}
+			};
+
+			if name == UNKNOWN_FIELDS {
+				match value {
+					Value::Bytes(bytes) => unknown = Some(bytes),
+					value => {
+						return Err(SeaographyError::new(format!(
+							"unknown fields need to be of type bytes, got `{}`",
+							value.type_name()
+						)));
+					}
+				}
+				continue;
+			}
+
+			match self.field_by_name(name) {
+				Some(field) => size += field.encode_value(types, buf, value)?,
+				None => {
+					return Err(SeaographyError::new(format!(
+						"Message `{}` has no field with name `{}`",
+						self.type_name(),
+						name,
+					)));
+				}
 			}
-		})
+		}
+
+		if let Some(unknown) = unknown {
+			buf.put_slice(unknown);
+			size += unknown.len();
+		}
+
+		Ok(size)
 	}
 
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
@@ -117,11 +188,16 @@ impl Message {
 	) -> Vec<BoxFieldFutureByte<'a, BytesMut>> {
 		self.fields
 			.iter()
-			.map(|(_, field)| field.collect(ctx, arguments, parent_value, recursion, false))
+			.map(|(_, field)| field.collect(types, ctx, arguments, parent_value, recursion, false))
 			.collect()
 	}
 
-	pub(crate) fn register(&self, file: &mut FileDescriptorProto, is_service: bool) {
+	pub(crate) fn register(
+		&self,
+		types: &TypeRegistry,
+		file: &mut FileDescriptorProto,
+		is_service: bool,
+	) {
 		if is_service {
 			let mut service = ServiceDescriptorProto::default();
 			service.name = Some(self.name.clone());
@@ -133,6 +209,7 @@ impl Message {
 					let name = format!("Input{}", method.name);
 					descriptor(
 						file,
+						types,
 						Some(name.clone()),
 						&method.arguments,
 						false,
@@ -155,7 +232,93 @@ impl Message {
 
 			file.service.push(service);
 		} else {
-			descriptor(file, Some(self.name.clone()), &self.fields, self.oneof, self.deprecated);
+			descriptor(
+				file,
+				types,
+				Some(self.name.clone()),
+				&self.fields,
+				self.oneof,
+				self.deprecated,
+			);
+		}
+	}
+
+	pub(crate) fn register_sdl(&self, registry: &mut ProtoRegistry, is_service: bool) {
+		if is_service {
+			let mut methods = BTreeMap::new();
+
+			for method in self.fields.values() {
+				let input_type = match method.arguments.is_empty() {
+					true => None,
+					false => {
+						let name = format!("Input{}", method.name);
+						registry.types.insert(
+							name.clone(),
+							message_kind(&name, None, &method.arguments, false),
+						);
+						Some(name)
+					}
+				};
+				methods.insert(method.name.clone(), method.protobuf_method(input_type));
+			}
+
+			registry.types.insert(
+				self.name.clone(),
+				ProtobufKind::Service {
+					name: self.name.clone(),
+					description: self.description.clone(),
+					methods,
+					visible: None,
+					rust_typename: None,
+				},
+			);
+		} else {
+			registry.types.insert(
+				self.name.clone(),
+				message_kind(&self.name, self.description.clone(), &self.fields, self.oneof),
+			);
+		}
+	}
+}
+
+/// used to describe a message, the fields of a oneof message are a single oneof group
+fn message_kind(
+	name: &str,
+	description: Option<String>,
+	fields: &BTreeMap<String, Field>,
+	oneof: bool,
+) -> ProtobufKind {
+	let fields = fields
+		.values()
+		.map(|field| (field.name.clone(), field.protobuf_field()))
+		.collect::<BTreeMap<_, _>>();
+
+	let (fields, oneof_groups) = match oneof {
+		true => {
+			let fields = fields
+				.into_iter()
+				.map(|(name, mut field)| {
+					// oneof fields have no label
+					field.label = None;
+					(name, field)
+				})
+				.collect();
+			let group = ProtobufOneofGroup {
+				name: "value".to_string(),
+				fields,
+			};
+			(BTreeMap::new(), vec![group])
 		}
+		false => (fields, Vec::new()),
+	};
+
+	ProtobufKind::Message {
+		name: name.to_string(),
+		description,
+		fields,
+		oneof_groups,
+		nested: Vec::new(),
+		visible: None,
+		rust_typename: None,
 	}
 }
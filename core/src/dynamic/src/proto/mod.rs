@@ -1,21 +1,27 @@
 mod r#enum;
 mod error;
 mod field;
+mod json;
 mod message;
 mod proto;
 mod scalar;
 mod service;
+mod status;
 mod r#type;
 mod type_ref;
 mod utils;
+mod well_known;
 
 pub use r#enum::*;
 pub use error::*;
 pub use field::*;
+pub(crate) use json::*;
 pub use message::*;
 pub use proto::*;
 pub use scalar::*;
 pub use service::*;
+pub use status::*;
 pub use r#type::*;
 pub use type_ref::*;
 pub use utils::*;
+pub(crate) use well_known::*;
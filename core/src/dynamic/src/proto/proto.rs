@@ -1,7 +1,10 @@
-use super::{Error, Field, PCKNAME, Result, TYPE_REGISTRY, Type, well_known_types};
+use super::{
+	Error, Field, PCKNAME, Result, Type, TypeRegistry, request_from_json, response_to_json,
+	streaming, well_known_types,
+};
 use crate::{
 	ApiType, BoxResolverFn, Data, FieldFuture, ObjectAccessor, ProtoRegistry, ResolverContext,
-	SchemaError, Value,
+	SchemaError, SeaResult, Value,
 };
 use binary::proto::{Decoder, Encoder};
 use bytes::Bytes;
@@ -83,6 +86,7 @@ use std::{any::Any, borrow::Cow, collections::BTreeMap, fmt::Debug, sync::Arc};
 
 /// Dynamic schema builder
 pub struct ProtoBuilder {
+	types: TypeRegistry,
 	pub data: Data,
 	services: Vec<String>,
 	entity_resolver: Option<BoxResolverFn>,
@@ -93,7 +97,7 @@ impl ProtoBuilder {
 	#[must_use]
 	pub fn register(self, ty: impl Into<Type>) -> Self {
 		let ty = ty.into();
-		TYPE_REGISTRY.add(ty.type_name().to_owned(), ty);
+		self.types.add(ty.type_name().to_owned(), ty);
 		self
 	}
 
@@ -117,11 +121,12 @@ impl ProtoBuilder {
 	}
 
 	pub fn finish(self) -> Result<Proto, SchemaError> {
-		let mut registry = ProtoRegistry {
-			types: Default::default(),
-			proto_type: self.services[0].clone(),
-			ignore_name_conflicts: Default::default(),
-		};
+		let mut registry = ProtoRegistry::new(self.services[0].clone());
+		registry.package = Some(PCKNAME.to_string());
+
+		for ty in self.types.all().values() {
+			ty.register_sdl(&mut registry, self.services.iter().any(|s| s == ty.type_name()));
+		}
 
 		// // create system scalars
 		// for ty in [
@@ -138,6 +143,7 @@ impl ProtoBuilder {
 		let inner = ProtoInner {
 			registry,
 			services: self.services,
+			types: Arc::new(self.types),
 			data: Arc::new(self.data),
 		};
 		// inner.check()?;
@@ -160,6 +166,7 @@ impl Debug for Proto {
 pub struct ProtoInner {
 	pub(crate) registry: ProtoRegistry,
 	pub(crate) services: Vec<String>,
+	pub(crate) types: Arc<TypeRegistry>,
 	pub(crate) data: Arc<Data>,
 }
 
@@ -167,6 +174,7 @@ impl Proto {
 	/// Create a schema builder
 	pub fn build(services: Vec<&str>) -> ProtoBuilder {
 		ProtoBuilder {
+			types: TypeRegistry::new(),
 			services: services.into_iter().map(|s| s.to_string()).collect(),
 			data: Default::default(),
 			entity_resolver: None,
@@ -213,7 +221,7 @@ impl Proto {
 	// 	ctx.execute_data = Some(self.0.data.clone());
 
 	// 	async move {
-	// 		match TYPE_REGISTRY.get(&self.0.registry.proto_type) {
+	// 		match self.0.types.get(&self.0.registry.proto_type) {
 	// 			Some(inner) => match inner.as_message() {
 	// 				Some(service) => match service.get_field(name) {
 	// 					Some(field) => {
@@ -243,6 +251,51 @@ impl Proto {
 		self.0.data.clone()
 	}
 
+	pub fn get_types(&self) -> Arc<TypeRegistry> {
+		self.0.types.clone()
+	}
+
+	/// Returns the `.proto` file of this schema
+	pub fn sdl(&self) -> String {
+		self.0.registry.build()
+	}
+
+	/// Returns the canonical proto3 JSON of a message of the given type encoded in the binary
+	/// format, the type is either registered or a well-known type
+	pub fn to_json(&self, type_name: &str, buf: &[u8]) -> SeaResult<serde_json::Value> {
+		super::to_json(&self.0.types, type_name, buf)
+	}
+
+	/// Returns the binary format of a message of the given type from its proto3 JSON
+	pub fn from_json(&self, type_name: &str, json: &serde_json::Value) -> SeaResult<Vec<u8>> {
+		super::from_json(&self.0.types, type_name, json)
+	}
+
+	/// Returns the binary format of the request message of a method from its proto3 JSON
+	pub fn request_from_json(
+		&self,
+		service: &str,
+		method: &str,
+		json: &serde_json::Value,
+	) -> SeaResult<Vec<u8>> {
+		request_from_json(&self.0.types, service, method, json)
+	}
+
+	/// Returns the proto3 JSON of the response message of a method encoded in the binary format
+	pub fn response_to_json(
+		&self,
+		service: &str,
+		method: &str,
+		buf: &[u8],
+	) -> SeaResult<serde_json::Value> {
+		response_to_json(&self.0.types, service, method, buf)
+	}
+
+	/// Returns the client and server streaming flags of a method
+	pub(crate) fn streaming(&self, service: &str, method: &str) -> Option<(bool, bool)> {
+		streaming(&self.0.types, service, method)
+	}
+
 	/// Returns the registry of this schema.
 	pub fn registry(&self) -> FileDescriptorSet {
 		let mut file_set = FileDescriptorSet::default();
@@ -252,15 +305,176 @@ impl Proto {
 		file.name = Some(format!("{}.proto", PCKNAME));
 		file.package = Some(PCKNAME.to_string());
 
-		file.dependency.push("google/protobuf/wrappers.proto".to_string());
+		let well_known = well_known_types();
+		file.dependency.extend(well_known.iter().map(|file| file.name().to_string()));
 
-		for ty in TYPE_REGISTRY.all().values() {
-			ty.register(&mut file, self.0.services.iter().any(|s| s == ty.type_name()));
+		for ty in self.0.types.all().values() {
+			ty.register(
+				&self.0.types,
+				&mut file,
+				self.0.services.iter().any(|s| s == ty.type_name()),
+			);
 		}
 
 		file_set.file.push(file);
-		file_set.file.push(well_known_types());
+		file_set.file.extend(well_known);
 
 		file_set
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+
+	use binary::proto::Fields;
+	use bytes::BytesMut;
+
+	use crate::{
+		FieldFuture, TypeRefTrait, Value,
+		proto::{Enum, EnumItem, Field, Message, Proto, TypeRef},
+	};
+
+	#[test]
+	fn sdl() {
+		let status =
+			Enum::new("UserStatus").items([EnumItem::new("ACTIVE"), EnumItem::new("BANNED")]);
+
+		let user = Message::new("User")
+			.description("A registered user\nof the platform")
+			.field(Field::input("id", 1, TypeRef::named_nn(TypeRef::INT32)))
+			.field(Field::input("name", 2, TypeRef::named(TypeRef::STRING)).set_optional())
+			.field(Field::input("tags", 3, TypeRef::named_list(TypeRef::STRING)))
+			.field(Field::input("status", 4, TypeRef::named_nn("UserStatus")));
+
+		let lookup = Message::new("Lookup")
+			.field(Field::input("id", 1, TypeRef::named(TypeRef::INT32)))
+			.field(Field::input("email", 2, TypeRef::named(TypeRef::STRING)))
+			.set_oneof();
+
+		let query = Message::new("Query")
+			.field(
+				Field::output("user", 1, TypeRef::named_nn("User"), |_| {
+					FieldFuture::new(async move { Ok(None::<Value>) })
+				})
+				.argument(Field::input("lookup", 1, TypeRef::named_nn("Lookup"))),
+			)
+			.field(Field::output("count", 2, TypeRef::named_nn(TypeRef::INT64), |_| {
+				FieldFuture::new(async move { Ok(None::<Value>) })
+			}));
+
+		let proto = Proto::build(vec!["Query"])
+			.register(status)
+			.register(user)
+			.register(lookup)
+			.register(query)
+			.finish()
+			.unwrap();
+
+		assert_eq!(
+			proto.sdl(),
+			[
+				"syntax = \"proto3\";",
+				"",
+				"package apy;",
+				"",
+				"import \"google/protobuf/empty.proto\";",
+				"import \"google/protobuf/wrappers.proto\";",
+				"",
+				"enum UserStatus {",
+				"  USER_STATUS_UNSPECIFIED = 0;",
+				"  ACTIVE = 1;",
+				"  BANNED = 2;",
+				"}",
+				"",
+				"message Inputuser {",
+				"  Lookup lookup = 1;",
+				"}",
+				"",
+				"message Lookup {",
+				"  oneof value {",
+				"    int32 id = 1;",
+				"    string email = 2;",
+				"  }",
+				"}",
+				"",
+				"// A registered user",
+				"// of the platform",
+				"message User {",
+				"  int32 id = 1;",
+				"  optional string name = 2;",
+				"  repeated string tags = 3;",
+				"  UserStatus status = 4;",
+				"}",
+				"",
+				"service Query {",
+				"  rpc count(google.protobuf.Empty) returns (google.protobuf.Int64Value);",
+				"  rpc user(Inputuser) returns (User);",
+				"}",
+				"",
+			]
+			.join("\n")
+		);
+	}
+
+	#[test]
+	fn maps_and_well_known_types() {
+		let user = Message::new("User")
+			.field(Field::input("scores", 1, TypeRef::map(TypeRef::STRING, TypeRef::INT32)))
+			.field(Field::input("created_at", 2, TypeRef::named(TypeRef::TIMESTAMP)))
+			.field(Field::input("settings", 3, TypeRef::named(TypeRef::STRUCT)))
+			.field(Field::input("mask", 4, TypeRef::named(TypeRef::FIELD_MASK)));
+
+		let query = Message::new("Query").field(
+			Field::output("user", 1, TypeRef::named_nn("User"), |_| {
+				FieldFuture::new(async move { Ok(None::<Value>) })
+			})
+			.argument(Field::input("user", 1, TypeRef::named_nn("User"))),
+		);
+
+		let proto = Proto::build(vec!["Query"]).register(user).register(query).finish().unwrap();
+
+		let sdl = proto.sdl();
+		for line in [
+			"import \"google/protobuf/field_mask.proto\";\n",
+			"import \"google/protobuf/struct.proto\";\n",
+			"import \"google/protobuf/timestamp.proto\";\n",
+			"  map<string, int32> scores = 1;\n",
+			"  google.protobuf.Timestamp created_at = 2;\n",
+		] {
+			assert!(sdl.contains(line), "{}", line);
+		}
+
+		// the entries of a map are a nested message like `protoc` generates
+		let registry = proto.registry();
+		let user = registry.file[0].message_type.iter().find(|ty| ty.name() == "User").unwrap();
+		let scores = user.field.iter().find(|field| field.name() == "scores").unwrap();
+		assert_eq!(scores.type_name(), "User.ScoresEntry");
+		assert_eq!(user.nested_type[0].name(), "ScoresEntry");
+		assert_eq!(user.nested_type[0].options.as_ref().unwrap().map_entry, Some(true));
+		assert!(registry.file.iter().any(|file| file.name() == "google/protobuf/struct.proto"));
+
+		let value = Value::Map(BTreeMap::from([
+			(
+				Value::from("scores"),
+				Value::Map(BTreeMap::from([
+					(Value::from("a"), Value::from(1)),
+					(Value::from("b"), Value::from(2)),
+				])),
+			),
+			(Value::from("created_at"), Value::from("2024-03-01T10:00:00Z")),
+			(Value::from("settings"), Value::from(r#"{"theme":"dark"}"#)),
+			(Value::from("mask"), Value::from("scores,created_at")),
+		]));
+
+		let types = proto.get_types();
+		let field = Field::input("user", 1, TypeRef::named("User"));
+		let mut buf = BytesMut::new();
+		field.encode_value(&types, &mut buf, &value).unwrap();
+
+		let (_, typ, bytes) = Fields::new(&buf).next().unwrap().unwrap();
+		let mut arguments = BTreeMap::new();
+		field.decode(&types, typ, bytes, 0, &mut arguments).unwrap();
+		assert_eq!(arguments.remove(&Value::from("user")), Some(value));
+	}
+}
@@ -4,13 +4,16 @@ use std::convert::Infallible;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use binary::proto::Fields;
 use bytes::{Buf, BufMut, Bytes, BytesMut, buf};
+use futures::{StreamExt, stream::BoxStream};
 use once_cell::sync::Lazy;
 use ordered_float::OrderedFloat;
 use prost::{
 	DecodeError, EncodeError, Message,
-	encoding::{DecodeContext, WireType, decode_varint},
+	encoding::{DecodeContext, WireType, encode_key},
 };
+use tonic::Streaming;
 use tonic::codec::{CompressionEncoding, EnabledCompressionEncodings};
 use tonic::server::{Grpc, NamedService};
 
@@ -19,8 +22,8 @@ use tonic::codegen::{
 	http::{Request, Response},
 };
 
-use super::{PCKNAME, TYPE_REGISTRY, Type};
-use crate::{ApiType, Data, ObjectAccessor, Value};
+use super::{Field, Type, TypeRegistry, decode_error, decode_status};
+use crate::{ApiType, Data, FieldValue, ObjectAccessor, ResolverContext, Value};
 
 #[derive(Clone, PartialEq, prost::Message)]
 pub struct VotingResponse {
@@ -38,20 +41,10 @@ pub struct VotingRequest {
 	pub test: Vec<String>,
 }
 
-#[derive(Debug)]
-pub struct BytesRequestInner {
-	bytes: Bytes,
-	tag: u32,
-	wire_type: WireType,
-	ctx: DecodeContext,
-}
-
-#[derive(Debug)]
+/// The message of a request, it is kept encoded and its fields are decoded as slices of it
+#[derive(Debug, Default)]
 pub struct BytesRequest {
 	bytes: Bytes,
-	tag: u32,
-	wire_type: WireType,
-	ctx: DecodeContext,
 }
 
 // Implement Message trait for BytesRequest
@@ -63,25 +56,34 @@ impl Message for BytesRequest {
 		panic!("BytesRequest can only be used as an incoming request not as an response")
 	}
 
+	fn merge(&mut self, mut buf: impl Buf) -> Result<(), DecodeError>
+	where
+		Self: Sized,
+	{
+		self.bytes = buf.copy_to_bytes(buf.remaining());
+		Ok(())
+	}
+
 	fn merge_field(
 		&mut self,
 		tag: u32,
 		wire_type: WireType,
 		buf: &mut impl Buf,
-		ctx: DecodeContext,
+		_ctx: DecodeContext,
 	) -> Result<(), DecodeError>
 	where
 		Self: Sized,
 	{
-		self.bytes = buf.copy_to_bytes(buf.remaining());
-		self.tag = tag;
-		self.wire_type = wire_type;
-		self.ctx = ctx;
+		// the key has already been read, it is written back so the bytes are the whole message
+		let mut bytes = BytesMut::new();
+		encode_key(tag, wire_type, &mut bytes);
+		bytes.put(buf.copy_to_bytes(buf.remaining()));
+		self.bytes = bytes.freeze();
 		Ok(())
 	}
 
 	fn encoded_len(&self) -> usize {
-		panic!("BytesRequest can only be used as an incoming request not as an response")
+		self.bytes.len()
 	}
 
 	fn clear(&mut self) {
@@ -89,17 +91,6 @@ impl Message for BytesRequest {
 	}
 }
 
-impl Default for BytesRequest {
-	fn default() -> Self {
-		Self {
-			bytes: Bytes::new(),
-			tag: 0,
-			wire_type: WireType::Varint,
-			ctx: DecodeContext::default(),
-		}
-	}
-}
-
 #[derive(Debug)]
 pub struct BytesResponce {
 	bytes: Bytes,
@@ -151,64 +142,233 @@ struct Svc<'a> {
 	service_name: &'a str,
 	name: &'a str,
 	data: Arc<Data>,
+	types: Arc<TypeRegistry>,
 }
 
 impl<'a> tonic::server::UnaryService<BytesRequest> for Svc<'a> {
 	type Response = BytesResponce;
 	type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
 	fn call(&mut self, request: tonic::Request<BytesRequest>) -> Self::Future {
-		let service = match TYPE_REGISTRY.get(&self.service_name) {
+		let service = match self.types.get(self.service_name) {
 			Some(ser) => ser,
-			None => panic!("Type not found"),
+			None => {
+				let status = tonic::Status::unimplemented(format!(
+					"Service `{}` not found",
+					self.service_name
+				));
+				return Box::pin(async move { Err(status) });
+			}
 		};
 
-		let mut req = request.into_inner();
 		let mut arguments = BTreeMap::new();
-
-		let mut index = 1;
-		while req.bytes.has_remaining() {
-			service
-				.decode(
-					&self.name,
-					index,
-					&mut req.bytes,
-					req.wire_type,
-					req.ctx.clone(),
-					&mut arguments,
-				)
-				.unwrap();
-
-			if !req.bytes.is_empty() {
-				req.bytes.advance(1usize);
-			}
-			index += 1;
+		if let Err(err) =
+			decode_arguments(&service, &self.types, self.name, request.into_inner(), &mut arguments)
+		{
+			return Box::pin(async move { Err(decode_status(err)) });
 		}
 
 		let accessor = ObjectAccessor(Cow::Owned(arguments));
 
 		let name = self.name.to_owned();
+		let types = self.types.clone();
 		let mut ctx = crate::ContextBase::new(ApiType::Proto);
 
 		ctx.execute_data = Some(self.data.clone());
 
 		Box::pin(async move {
-			let (size, buf) = service.encode(&ctx, &accessor, name.as_str()).await.unwrap();
+			let (size, buf) = service.encode(&types, &ctx, &accessor, name.as_str()).await?;
 
 			Ok(tonic::Response::new(BytesResponce {
 				bytes: buf.into(),
 				size,
 			}))
 		})
+	}
+}
 
-		// println!("res: {:#?}", res.0);
-		// println!("val: {:#?}", res.1);
+/// used to serve a server streaming field, every item of the resolver stream is encoded and
+/// sent as soon as it is yielded
+struct ServerStreamingSvc {
+	service: Arc<Type>,
+	name: String,
+	data: Arc<Data>,
+	types: Arc<TypeRegistry>,
+}
 
-		// let encoder = Encoder::default();
-		// let mut output = Vec::new();
-		// field.encode(0u32, &encoder, &mut output, res.1)?;
-		// Ok(Bytes::from(output))
+impl tonic::server::ServerStreamingService<BytesRequest> for ServerStreamingSvc {
+	type Response = BytesResponce;
+	type ResponseStream = BoxStream<'static, Result<Self::Response, tonic::Status>>;
+	type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+
+	fn call(&mut self, request: tonic::Request<BytesRequest>) -> Self::Future {
+		let mut arguments = BTreeMap::new();
+		let decoded = decode_arguments(
+			&self.service,
+			&self.types,
+			&self.name,
+			request.into_inner(),
+			&mut arguments,
+		);
+
+		let service = self.service.clone();
+		let name = self.name.clone();
+		let data = self.data.clone();
+		let types = self.types.clone();
+
+		Box::pin(async move {
+			decoded.map_err(decode_status)?;
+
+			let stream = async_stream::stream! {
+				let field = match service.as_message().and_then(|message| message.field_by_name(&name)) {
+					Some(field) => field,
+					None => {
+						yield Err(tonic::Status::unimplemented(format!("Method `{}` not found", name)));
+						return;
+					}
+				};
+				let stream_fn = match &field.stream_fn {
+					Some(stream_fn) => stream_fn,
+					None => {
+						yield Err(tonic::Status::unimplemented(format!("Method `{}` is not streaming", name)));
+						return;
+					}
+				};
+
+				let mut ctx = crate::ContextBase::new(ApiType::Proto);
+				ctx.execute_data = Some(data);
+				let accessor = ObjectAccessor(Cow::Owned(arguments));
+
+				let future = (stream_fn)(ResolverContext {
+					ctx: &ctx,
+					args: accessor.clone(),
+					parent_value: &FieldValue::NULL,
+				});
+
+				let mut items = match future.0.await {
+					Ok(items) => items,
+					Err(err) => {
+						yield Err(err.into());
+						return;
+					}
+				};
 
-		// Box::pin(async move { Ok(tonic::Response::new(Bytes::from_static(b"Hello, World"))) })
+				while let Some(item) = items.next().await {
+					let mut buf = BytesMut::new();
+					let size = match item {
+						Ok(value) => field.encode(&mut buf, &types, &ctx, &value, &accessor, None, 0, true).await,
+						Err(err) => Err(err),
+					};
+
+					match size {
+						Ok(size) => yield Ok(BytesResponce {
+							bytes: buf.freeze(),
+							size,
+						}),
+						Err(err) => {
+							yield Err(err.into());
+							break;
+						}
+					}
+				}
+			};
+
+			Ok(tonic::Response::new(stream.boxed()))
+		})
+	}
+}
+
+/// used to serve a client streaming field, the field is resolved once the client closed its
+/// stream with the merged arguments of all messages
+struct ClientStreamingSvc {
+	service: Arc<Type>,
+	name: String,
+	data: Arc<Data>,
+	types: Arc<TypeRegistry>,
+}
+
+impl tonic::server::ClientStreamingService<BytesRequest> for ClientStreamingSvc {
+	type Response = BytesResponce;
+	type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+
+	fn call(&mut self, request: tonic::Request<Streaming<BytesRequest>>) -> Self::Future {
+		let service = self.service.clone();
+		let name = self.name.clone();
+		let data = self.data.clone();
+		let types = self.types.clone();
+
+		Box::pin(async move {
+			let field =
+				service.as_message().and_then(|message| message.field_by_name(&name)).ok_or_else(
+					|| tonic::Status::unimplemented(format!("Method `{}` not found", name)),
+				)?;
+
+			let mut stream = request.into_inner();
+			let mut arguments = BTreeMap::new();
+			while let Some(req) = stream.message().await? {
+				let mut next = BTreeMap::new();
+				decode_arguments(&service, &types, &name, req, &mut next).map_err(decode_status)?;
+				merge_arguments(field, &mut arguments, next);
+			}
+
+			let accessor = ObjectAccessor(Cow::Owned(arguments));
+			let mut ctx = crate::ContextBase::new(ApiType::Proto);
+			ctx.execute_data = Some(data);
+
+			let (size, buf) = service.encode(&types, &ctx, &accessor, name.as_str()).await?;
+
+			Ok(tonic::Response::new(BytesResponce {
+				bytes: buf.into(),
+				size,
+			}))
+		})
+	}
+}
+
+/// used to decode the arguments of the called field from a request message
+fn decode_arguments(
+	service: &Type,
+	types: &TypeRegistry,
+	name: &str,
+	req: BytesRequest,
+	arguments: &mut BTreeMap<Value, Value>,
+) -> Result<(), DecodeError> {
+	for field in Fields::new(&req.bytes) {
+		let (tag, typ, bytes) = field.map_err(decode_error)?;
+		service.decode(types, name, tag, typ, bytes, arguments)?;
+	}
+	Ok(())
+}
+
+/// used to merge the arguments of the next message of a client stream, repeated arguments are
+/// appended while every other argument is replaced
+fn merge_arguments(
+	field: &Field,
+	arguments: &mut BTreeMap<Value, Value>,
+	next: BTreeMap<Value, Value>,
+) {
+	for (key, value) in next {
+		let repeated = match &key {
+			Value::String(name) => field
+				.argument_by_name(name.clone())
+				.is_some_and(|argument| argument.repeated || argument.ty.is_repeated()),
+			_ => false,
+		};
+
+		if !repeated {
+			arguments.insert(key, value);
+			continue;
+		}
+
+		let values = match arguments.remove(&key) {
+			Some(Value::List(values)) => values,
+			Some(value) => vec![value],
+			None => Vec::new(),
+		};
+		let values = match value {
+			Value::List(next) => values.into_iter().chain(next).collect(),
+			value => values.into_iter().chain([value]).collect(),
+		};
+		arguments.insert(key, Value::List(values));
 	}
 }
 
@@ -261,6 +421,7 @@ impl Handler {
 		&self,
 		req: Request<B>,
 		data: Arc<Data>,
+		types: Arc<TypeRegistry>,
 	) -> BoxFuture<Response<tonic::body::Body>, Infallible>
 	where
 		B: Body + std::marker::Send + std::fmt::Debug + 'static,
@@ -274,31 +435,66 @@ impl Handler {
 		let max_encoding_message_size = self.max_encoding_message_size;
 
 		Box::pin(async move {
-			if let Some(name) = path.split('/').last() {
-				let codec = tonic::codec::ProstCodec::default();
-				let mut grpc = tonic::server::Grpc::new(codec)
-					.apply_compression_config(
-						accept_compression_encodings,
-						send_compression_encodings,
-					)
-					.apply_max_message_size_config(
-						max_decoding_message_size,
-						max_encoding_message_size,
-					);
-
-				let method = Svc {
-					service_name: "Query",
-					name,
-					data,
-				};
-				let res = grpc.unary(method, req).await;
-				println!("res: {:#?}", res.body());
-				Ok(res)
-			} else {
-				// let status = tonic::Status::invalid_argument("wrong url");
-				let res = Response::builder().body(tonic::body::Body::empty()).unwrap();
-				Ok(res)
-			}
+			// the path of a method is `/<package>.<service>/<method>`
+			let (service_name, name) = match path.trim_start_matches('/').split_once('/') {
+				Some((service, name)) => {
+					(service.rsplit('.').next().unwrap_or(service).to_string(), name.to_string())
+				}
+				None => {
+					let status = tonic::Status::unimplemented(format!("Unknown path `{}`", path));
+					return Ok(status.into_http());
+				}
+			};
+
+			let service = types.get(&service_name);
+			let streaming = service
+				.as_ref()
+				.and_then(|service| service.as_message())
+				.and_then(|message| message.field_by_name(&name))
+				.map(|field| (field.client_streaming, field.server_streaming));
+
+			let codec = tonic::codec::ProstCodec::default();
+			let mut grpc = tonic::server::Grpc::new(codec)
+				.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+				.apply_max_message_size_config(
+					max_decoding_message_size,
+					max_encoding_message_size,
+				);
+
+			let res = match (service, streaming) {
+				(Some(_), Some((false, false))) => {
+					let method = Svc {
+						service_name: &service_name,
+						name: &name,
+						data,
+						types,
+					};
+					grpc.unary(method, req).await
+				}
+				(Some(service), Some((false, true))) => {
+					let method = ServerStreamingSvc {
+						service,
+						name,
+						data,
+						types,
+					};
+					grpc.server_streaming(method, req).await
+				}
+				(Some(service), Some((true, false))) => {
+					let method = ClientStreamingSvc {
+						service,
+						name,
+						data,
+						types,
+					};
+					grpc.client_streaming(method, req).await
+				}
+				_ => {
+					tonic::Status::unimplemented(format!("Method `{}` not found", path)).into_http()
+				}
+			};
+
+			Ok(res)
 		})
 	}
 }
@@ -306,12 +502,14 @@ impl Handler {
 #[derive(Clone)]
 pub struct WrapperQuery {
 	data: Arc<Data>,
+	types: Arc<TypeRegistry>,
 }
 
 impl WrapperQuery {
-	pub fn new(data: Arc<Data>) -> Self {
+	pub fn new(data: Arc<Data>, types: Arc<TypeRegistry>) -> Self {
 		Self {
 			data,
+			types,
 		}
 	}
 }
@@ -336,19 +534,21 @@ where
 	}
 
 	fn call(&mut self, req: Request<B>) -> Self::Future {
-		Handler::new().call(req, self.data.clone())
+		Handler::new().call(req, self.data.clone(), self.types.clone())
 	}
 }
 
 #[derive(Clone)]
 pub struct WrapperMutation {
 	data: Arc<Data>,
+	types: Arc<TypeRegistry>,
 }
 
 impl WrapperMutation {
-	pub fn new(data: Arc<Data>) -> Self {
+	pub fn new(data: Arc<Data>, types: Arc<TypeRegistry>) -> Self {
 		Self {
 			data,
+			types,
 		}
 	}
 }
@@ -373,6 +573,250 @@ where
 	}
 
 	fn call(&mut self, req: Request<B>) -> Self::Future {
-		Handler::new().call(req, self.data.clone())
+		Handler::new().call(req, self.data.clone(), self.types.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+
+	use futures::{StreamExt, stream};
+	use tonic::server::ServerStreamingService;
+
+	use bytes::Bytes;
+	use prost::Message as _;
+	use tonic::{Code, server::UnaryService};
+
+	use super::{BytesRequest, ServerStreamingSvc, Svc, merge_arguments};
+	use crate::proto::RECURSION_LIMIT;
+	use crate::{
+		FieldFuture, SeaographyError, SubscriptionFieldFuture, TypeRefTrait, Value,
+		proto::{Field, Message, Proto, TypeRef},
+	};
+
+	fn proto() -> Proto {
+		let user = Message::new("User").field(Field::output(
+			"id",
+			1,
+			TypeRef::named_nn(TypeRef::INT32),
+			|ctx| {
+				FieldFuture::new(async move {
+					Ok(Some(Value::from(*ctx.parent_value.try_downcast_ref::<i32>()?)))
+				})
+			},
+		));
+
+		let query = Message::new("Query")
+			.field(Field::stream("users", 1, TypeRef::named_nn("User"), |_| {
+				SubscriptionFieldFuture::new(async move {
+					Ok(stream::iter((1..=3).map(|id| Ok(crate::FieldValue::owned_any(id)))))
+				})
+			}))
+			.field(
+				Field::output("createUsers", 2, TypeRef::named_nn(TypeRef::INT32), |ctx| {
+					FieldFuture::new(async move {
+						let ids = ctx.args.try_get("ids")?.list()?;
+						Ok(Some(Value::from(ids.len() as i32)))
+					})
+				})
+				.argument(Field::input("ids", 1, TypeRef::named_list(TypeRef::INT32)))
+				.set_client_streaming(),
+			)
+			.field(Field::output("secret", 3, TypeRef::named_nn(TypeRef::STRING), |_| {
+				FieldFuture::new(async move {
+					Err::<Option<Value>, _>(SeaographyError::Forbidden(
+						"Field guard triggered.".into(),
+					))
+				})
+			}));
+
+		Proto::build(vec!["Query"]).register(user).register(query).finish().unwrap()
+	}
+
+	#[test]
+	fn streaming_methods() {
+		let proto = proto();
+
+		let registry = proto.registry();
+		let methods = &registry.file[0].service[0].method;
+		let flags = |name: &str| {
+			let method = methods.iter().find(|method| method.name() == name).unwrap();
+			(method.client_streaming(), method.server_streaming())
+		};
+		assert_eq!(flags("users"), (false, true));
+		assert_eq!(flags("createUsers"), (true, false));
+
+		let sdl = proto.sdl();
+		assert!(sdl.contains("  rpc users(google.protobuf.Empty) returns (stream User);\n"));
+		assert!(sdl.contains(
+			"  rpc createUsers(stream InputcreateUsers) returns (google.protobuf.Int32Value);\n"
+		));
+	}
+
+	#[tokio::test]
+	async fn server_streaming() {
+		let proto = proto();
+		let types = proto.get_types();
+
+		let mut svc = ServerStreamingSvc {
+			service: types.get("Query").unwrap(),
+			name: "users".to_string(),
+			data: proto.get_data(),
+			types,
+		};
+
+		let stream = svc.call(tonic::Request::new(BytesRequest::default())).await.unwrap();
+		let items =
+			stream.into_inner().map(|item| item.unwrap().bytes.to_vec()).collect::<Vec<_>>().await;
+
+		assert_eq!(items, vec![vec![0x08, 0x01], vec![0x08, 0x02], vec![0x08, 0x03]]);
+	}
+
+	#[test]
+	fn merge_client_stream() {
+		let proto = proto();
+		let query = proto.get_types().get("Query").unwrap();
+		let field = query.as_message().unwrap().field_by_name("createUsers").unwrap();
+
+		let mut arguments = BTreeMap::new();
+		for id in [1, 2, 3] {
+			let next = BTreeMap::from([(Value::from("ids"), Value::List(vec![Value::from(id)]))]);
+			merge_arguments(field, &mut arguments, next);
+		}
+
+		assert_eq!(
+			arguments.get(&Value::from("ids")),
+			Some(&Value::List(vec![Value::from(1), Value::from(2), Value::from(3)]))
+		);
+	}
+
+	#[tokio::test]
+	async fn unary_errors() {
+		let proto = proto();
+
+		let mut svc = Svc {
+			service_name: "Query",
+			name: "secret",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+		let status = svc.call(tonic::Request::new(BytesRequest::default())).await.unwrap_err();
+		assert_eq!(status.code(), Code::PermissionDenied);
+
+		// a length delimited argument with a truncated length
+		let mut svc = Svc {
+			service_name: "Query",
+			name: "createUsers",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+		let request = BytesRequest {
+			bytes: Bytes::from_static(&[0x0a, 0xff]),
+		};
+		let status = svc.call(tonic::Request::new(request)).await.unwrap_err();
+		assert_eq!(status.code(), Code::InvalidArgument);
+
+		let mut svc = Svc {
+			service_name: "Mutation",
+			name: "secret",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+		let status = svc.call(tonic::Request::new(BytesRequest::default())).await.unwrap_err();
+		assert_eq!(status.code(), Code::Unimplemented);
+	}
+
+	#[tokio::test]
+	async fn forwards_unknown_fields() {
+		let profile =
+			Message::new("Profile").field(Field::input("name", 1, TypeRef::named(TypeRef::STRING)));
+		let user = Message::new("User")
+			.field(Field::input("id", 1, TypeRef::named_nn(TypeRef::INT32)))
+			.field(Field::input("profile", 2, TypeRef::named("Profile")));
+		let query = Message::new("Query").field(
+			Field::output("echo", 1, TypeRef::named_nn("User"), |ctx| {
+				FieldFuture::new(async move { Ok(Some(ctx.args.try_get("user")?.as_value())) })
+			})
+			.argument(Field::input("user", 1, TypeRef::named_nn("User"))),
+		);
+		let proto = Proto::build(vec!["Query"])
+			.register(profile)
+			.register(user)
+			.register(query)
+			.finish()
+			.unwrap();
+
+		// id = 150, profile { name = "a", unknown varint 9 }, unknown varint 5, unknown group 6
+		// and unknown string 7
+		let user = [
+			0x08, 0x96, 0x01, 0x12, 0x05, 0x0a, 0x01, 0x61, 0x48, 0x01, 0x28, 0x01, 0x33, 0x08,
+			0x01, 0x34, 0x3a, 0x02, 0x68, 0x69,
+		];
+		// the user argument is followed by an unknown argument
+		let bytes = [&[0x0a, user.len() as u8][..], &user, &[0x48, 0x01]].concat();
+
+		let mut svc = Svc {
+			service_name: "Query",
+			name: "echo",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+		let request = BytesRequest {
+			bytes: Bytes::from(bytes),
+		};
+		let response = svc.call(tonic::Request::new(request)).await.unwrap().into_inner();
+
+		assert_eq!(response.bytes.to_vec(), user.to_vec());
+		assert_eq!(response.size, user.len());
+	}
+
+	#[tokio::test]
+	async fn decodes_request_fields() {
+		let proto = proto();
+		let mut svc = Svc {
+			service_name: "Query",
+			name: "createUsers",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+
+		// ids = [1, 2] packed followed by ids = 3 unpacked
+		let request = BytesRequest::decode(&[0x0a, 0x02, 0x01, 0x02, 0x08, 0x03][..]).unwrap();
+		let response = svc.call(tonic::Request::new(request)).await.unwrap().into_inner();
+		assert_eq!(response.bytes.to_vec(), vec![0x10, 0x03]);
+
+		// a packed value that ends within a varint
+		let request = BytesRequest::decode(&[0x0a, 0x01, 0x80][..]).unwrap();
+		let status = svc.call(tonic::Request::new(request)).await.unwrap_err();
+		assert_eq!(status.code(), Code::InvalidArgument);
+
+		let node = Message::new("Node").field(Field::input("child", 1, TypeRef::named("Node")));
+		let query = Message::new("Query").field(
+			Field::output("depth", 1, TypeRef::named_nn(TypeRef::INT32), |_| {
+				FieldFuture::new(async move { Ok(Some(Value::from(0))) })
+			})
+			.argument(Field::input("node", 1, TypeRef::named("Node"))),
+		);
+		let proto = Proto::build(vec!["Query"]).register(node).register(query).finish().unwrap();
+		let mut svc = Svc {
+			service_name: "Query",
+			name: "depth",
+			data: proto.get_data(),
+			types: proto.get_types(),
+		};
+
+		// nodes nested deeper than the recursion limit
+		let mut bytes = Vec::new();
+		for _ in 0..=RECURSION_LIMIT {
+			let mut node = Vec::new();
+			prost::encoding::encode_key(1, prost::encoding::WireType::LengthDelimited, &mut node);
+			prost::encoding::encode_varint(bytes.len() as u64, &mut node);
+			node.extend(bytes);
+			bytes = node;
+		}
+		let request = BytesRequest::decode(&bytes[..]).unwrap();
+		let status = svc.call(tonic::Request::new(request)).await.unwrap_err();
+		assert_eq!(status.code(), Code::InvalidArgument);
 	}
 }
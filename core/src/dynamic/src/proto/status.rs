@@ -0,0 +1,232 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use bytes::Bytes;
+use prost::{DecodeError, Message};
+use prost_types::Any;
+use serde_json::json;
+use std::collections::HashMap;
+use tonic::{Code, Status, codegen::http::StatusCode};
+
+use super::{Error, PCKNAME};
+use crate::SeaographyError;
+
+/// The `google.rpc.Status` message sent in the `grpc-status-details-bin` trailer
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RpcStatus {
+	#[prost(int32, tag = "1")]
+	pub code: i32,
+
+	#[prost(string, tag = "2")]
+	pub message: String,
+
+	#[prost(message, repeated, tag = "3")]
+	pub details: Vec<Any>,
+}
+
+/// The `google.rpc.ErrorInfo` detail describing why a rpc failed
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ErrorInfo {
+	#[prost(string, tag = "1")]
+	pub reason: String,
+
+	#[prost(string, tag = "2")]
+	pub domain: String,
+
+	#[prost(map = "string, string", tag = "3")]
+	pub metadata: HashMap<String, String>,
+}
+
+impl ErrorInfo {
+	pub const TYPE_URL: &'static str = "type.googleapis.com/google.rpc.ErrorInfo";
+}
+
+/// used to create a status carrying an `ErrorInfo` with the given reason in its details
+pub(crate) fn status(code: Code, message: impl Into<String>, reason: impl Into<String>) -> Status {
+	let message = message.into();
+
+	let info = ErrorInfo {
+		reason: reason.into(),
+		domain: PCKNAME.to_string(),
+		metadata: HashMap::new(),
+	};
+	let details = RpcStatus {
+		code: code as i32,
+		message: message.clone(),
+		details: vec![Any {
+			type_url: ErrorInfo::TYPE_URL.to_string(),
+			value: info.encode_to_vec(),
+		}],
+	};
+
+	Status::with_details(code, message, Bytes::from(details.encode_to_vec()))
+}
+
+/// used to create the status of a request message that could not be decoded
+pub(crate) fn decode_status(err: DecodeError) -> Status {
+	status(Code::InvalidArgument, err.to_string(), "DECODE_ERROR")
+}
+
+/// used to get the http status of a status code, the codes are mapped like the grpc-gateway maps
+/// them
+pub(crate) fn http_status(code: Code) -> StatusCode {
+	match code {
+		Code::Ok => StatusCode::OK,
+		// client closed request
+		Code::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+		Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+			StatusCode::BAD_REQUEST
+		}
+		Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+		Code::NotFound => StatusCode::NOT_FOUND,
+		Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+		Code::PermissionDenied => StatusCode::FORBIDDEN,
+		Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+		Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+		Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+		Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+		Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+	}
+}
+
+/// used to write a status as the proto3 JSON of a `google.rpc.Status`, an `ErrorInfo` detail is
+/// written as its JSON while any other detail keeps its base64 encoded value
+pub(crate) fn status_json(status: &Status) -> serde_json::Value {
+	let details = match RpcStatus::decode(status.details()) {
+		Ok(details) => details.details,
+		Err(_) => Vec::new(),
+	};
+
+	let details = details
+		.into_iter()
+		.map(|detail| match detail.type_url.as_str() {
+			ErrorInfo::TYPE_URL => match ErrorInfo::decode(&detail.value[..]) {
+				Ok(info) => json!({
+					"@type": detail.type_url,
+					"reason": info.reason,
+					"domain": info.domain,
+					"metadata": info.metadata,
+				}),
+				Err(_) => {
+					json!({ "@type": detail.type_url, "value": STANDARD.encode(&detail.value) })
+				}
+			},
+			_ => json!({ "@type": detail.type_url, "value": STANDARD.encode(&detail.value) }),
+		})
+		.collect::<Vec<_>>();
+
+	json!({
+		"code": status.code() as i32,
+		"message": status.message(),
+		"details": details,
+	})
+}
+
+impl From<SeaographyError> for Status {
+	fn from(err: SeaographyError) -> Self {
+		let code = match &err {
+			SeaographyError::AsyncProtoError(err) if err.decode_error.is_some() => {
+				Code::InvalidArgument
+			}
+			SeaographyError::TryFromIntError(_)
+			| SeaographyError::ParseIntError(_)
+			| SeaographyError::ParseUuidError(_)
+			| SeaographyError::ParseStringError(_)
+			| SeaographyError::TypeConversionError(..)
+			| SeaographyError::NestedArrayConversionError
+			| SeaographyError::DecoderError(_) => Code::InvalidArgument,
+			SeaographyError::Forbidden(_) => Code::PermissionDenied,
+			SeaographyError::NotFound(_)
+			| SeaographyError::DB(sea_orm::DbErr::RecordNotFound(_)) => Code::NotFound,
+			SeaographyError::Conflict(_) => Code::Aborted,
+			_ => Code::Internal,
+		};
+
+		// the reason falls back to the name of the status code
+		let reason = match err.code() {
+			Some(reason) => reason,
+			None => match code {
+				Code::InvalidArgument => "INVALID_ARGUMENT",
+				Code::PermissionDenied => "PERMISSION_DENIED",
+				Code::NotFound => "NOT_FOUND",
+				Code::Aborted => "ABORTED",
+				_ => "INTERNAL",
+			},
+		};
+
+		status(code, err.to_string(), reason)
+	}
+}
+
+impl From<Error> for Status {
+	fn from(err: Error) -> Self {
+		SeaographyError::from(err).into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use prost::Message;
+	use tonic::{Code, Status};
+
+	use serde_json::json;
+	use tonic::codegen::http::StatusCode;
+
+	use super::{ErrorInfo, RpcStatus, decode_status, http_status, status_json};
+	use crate::SeaographyError;
+
+	fn reason(status: &Status) -> String {
+		let details = RpcStatus::decode(status.details()).unwrap();
+		assert_eq!(details.code, status.code() as i32);
+		assert_eq!(details.details[0].type_url, ErrorInfo::TYPE_URL);
+		ErrorInfo::decode(&details.details[0].value[..]).unwrap().reason
+	}
+
+	#[test]
+	fn codes() {
+		let status = Status::from(SeaographyError::Forbidden("Entity guard triggered.".into()));
+		assert_eq!(status.code(), Code::PermissionDenied);
+		assert_eq!(status.message(), "[forbidden] Entity guard triggered.");
+		assert_eq!(reason(&status), "FORBIDDEN");
+
+		let status = Status::from(SeaographyError::Conflict("stale".into()));
+		assert_eq!(status.code(), Code::Aborted);
+		assert_eq!(reason(&status), "CONFLICT");
+
+		let status = Status::from(SeaographyError::NotFound("missing".into()));
+		assert_eq!(status.code(), Code::NotFound);
+
+		let status = Status::from(SeaographyError::TypeConversionError("a".into(), "int".into()));
+		assert_eq!(status.code(), Code::InvalidArgument);
+		assert_eq!(reason(&status), "INVALID_ARGUMENT");
+
+		let status = Status::from(SeaographyError::new("boom"));
+		assert_eq!(status.code(), Code::Internal);
+		assert_eq!(reason(&status), "INTERNAL");
+
+		let status = decode_status(prost::DecodeError::new("invalid varint"));
+		assert_eq!(status.code(), Code::InvalidArgument);
+		assert_eq!(reason(&status), "DECODE_ERROR");
+	}
+
+	#[test]
+	fn json() {
+		let status = Status::from(SeaographyError::NotFound("missing".into()));
+		assert_eq!(http_status(status.code()), StatusCode::NOT_FOUND);
+		assert_eq!(
+			status_json(&status),
+			json!({
+				"code": 5,
+				"message": "[not found] missing",
+				"details": [{
+					"@type": ErrorInfo::TYPE_URL,
+					"reason": "NOT_FOUND",
+					"domain": "apy",
+					"metadata": {},
+				}],
+			})
+		);
+
+		let status = Status::unimplemented("Method `foo` not found");
+		assert_eq!(http_status(status.code()), StatusCode::NOT_IMPLEMENTED);
+		assert_eq!(status_json(&status)["details"], json!([]));
+	}
+}
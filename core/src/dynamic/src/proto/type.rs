@@ -1,18 +1,17 @@
-use super::{Enum, Field, Message, TypeRef, from_bytes};
+use super::{Enum, Field, Message, TypeRef, from_bytes, merge_unknown};
 use crate::{
-	BoxFieldFutureByte, ContextBase, FieldValue, ObjectAccessor, SeaResult, SeaographyError, Value,
+	BoxFieldFutureByte, ContextBase, FieldValue, ObjectAccessor, ProtoRegistry, SeaResult,
+	SeaographyError, Value,
 };
-use bytes::{Buf, BufMut, Bytes, BytesMut, buf};
-use once_cell::sync::Lazy;
+use binary::proto::Typ;
+use bytes::{BufMut, Bytes, BytesMut, buf};
 use prost::DecodeError;
-use prost::encoding::{DecodeContext, WireType, decode_key, merge_loop};
 use prost_types::FileDescriptorProto;
 use prost_types::field_descriptor_proto::Type as FieldType;
 use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock};
 
-pub static TYPE_REGISTRY: Lazy<TypeRegistry> = Lazy::new(TypeRegistry::new);
-
+/// The types of a proto, every proto resolves its types through its own registry
 #[derive(Debug)]
 pub struct TypeRegistry {
 	types: RwLock<BTreeMap<String, Arc<Type>>>,
@@ -144,26 +143,20 @@ impl Type {
 		}
 	}
 
-	pub(crate) fn decode<B>(
+	pub(crate) fn decode(
 		&self,
+		types: &TypeRegistry,
 		name: &str,
 		tag: u32,
-		buf: &mut B,
-		wire_type: WireType,
-		ctx: DecodeContext,
+		typ: Typ,
+		buf: &[u8],
 		arguments: &mut BTreeMap<Value, Value>,
-	) -> Result<(), DecodeError>
-	where
-		B: Buf,
-	{
+	) -> Result<(), DecodeError> {
 		if let Some(message) = self.as_message() {
 			match message.field_by_name(name) {
 				Some(field) => match field.argument_by_tag(tag) {
-					Some(arg) => arg.decode(buf, ctx, wire_type, arguments),
-					None => Err(DecodeError::new(format!(
-						"Message `{}` has no argument with tag `{}`",
-						name, tag
-					))),
+					Some(arg) => arg.decode(types, typ, buf, 0, arguments),
+					None => merge_unknown(tag, typ, buf, arguments),
 				},
 				None => Err(DecodeError::new(format!(
 					"Message `{}` has no field with name `{}`",
@@ -181,6 +174,7 @@ impl Type {
 
 	pub(crate) async fn encode<'a>(
 		&self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		accessor: &'a ObjectAccessor<'a>,
 		name: &'a str,
@@ -188,7 +182,7 @@ impl Type {
 		if let Some(message) = self.as_message() {
 			match message.field_by_name(name) {
 				Some(field) => {
-					let res = field.collect(ctx, accessor, None, 0, true).await?;
+					let res = field.collect(types, ctx, accessor, None, 0, true).await?;
 
 					Ok(res)
 				}
@@ -218,6 +212,7 @@ impl Type {
 
 	pub(crate) fn collect<'a>(
 		&'a self,
+		types: &'a TypeRegistry,
 		ctx: &'a ContextBase,
 		arguments: &'a ObjectAccessor<'a>,
 		parent_value: Option<&'a FieldValue<'a>>,
@@ -227,17 +222,29 @@ impl Type {
 			return Vec::new();
 		}
 		match self {
-			Type::Message(m) => m.collect(ctx, arguments, parent_value, recursion),
+			Type::Message(m) => m.collect(types, ctx, arguments, parent_value, recursion),
 			Type::Enum(e) => Vec::from([e.collect()]),
 		}
 	}
 
-	pub(crate) fn register(&self, file: &mut FileDescriptorProto, is_service: bool) {
+	pub(crate) fn register(
+		&self,
+		types: &TypeRegistry,
+		file: &mut FileDescriptorProto,
+		is_service: bool,
+	) {
 		match self {
-			Type::Message(m) => m.register(file, is_service),
+			Type::Message(m) => m.register(types, file, is_service),
 			Type::Enum(e) => e.register(file),
 		};
 	}
+
+	pub(crate) fn register_sdl(&self, registry: &mut ProtoRegistry, is_service: bool) {
+		match self {
+			Type::Message(m) => m.register_sdl(registry, is_service),
+			Type::Enum(e) => e.register_sdl(registry),
+		};
+	}
 }
 
 impl From<Message> for Type {
@@ -5,7 +5,7 @@ use std::{
 	fmt::{self, Display},
 };
 
-use super::TYPE_REGISTRY;
+use super::{TypeRegistry, is_well_known};
 
 /// A type reference
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -16,6 +16,8 @@ pub enum TypeRef {
 	NonNull(Box<TypeRef>),
 	/// List type
 	List(Box<TypeRef>),
+	/// Map type of a key and a value type
+	Map(Box<TypeRef>, Box<TypeRef>),
 }
 
 impl Display for TypeRef {
@@ -24,6 +26,7 @@ impl Display for TypeRef {
 			TypeRef::Named(name) => write!(f, "{}", name),
 			TypeRef::NonNull(ty) => write!(f, "{}", ty),
 			TypeRef::List(ty) => write!(f, "{}", ty),
+			TypeRef::Map(key, value) => write!(f, "map<{}, {}>", key, value),
 		}
 	}
 }
@@ -108,7 +111,10 @@ impl TypeRefTrait for TypeRef {
 	const STRING: &'static str = TypeRef::STRING;
 	const BYTES: &'static str = TypeRef::BYTES;
 	const ID: &'static str = TypeRef::STRING;
-	// const UPLOAD: &'static str = TypeRef::BYTES;
+	const UPLOAD: &'static str = TypeRef::BYTES;
+	const TIMESTAMP: &'static str = TypeRef::TIMESTAMP;
+	const JSON: &'static str = TypeRef::VALUE;
+	const FIELD_MASK: &'static str = TypeRef::FIELD_MASK;
 }
 
 impl TypeRef {
@@ -157,7 +163,49 @@ impl TypeRef {
 	/// Protobuf bytes type
 	pub const BYTES: &'static str = "bytes";
 
-	/// Returns the type name
+	/// Protobuf well-known timestamp type
+	pub const TIMESTAMP: &'static str = "google.protobuf.Timestamp";
+
+	/// Protobuf well-known duration type
+	pub const DURATION: &'static str = "google.protobuf.Duration";
+
+	/// Protobuf well-known empty type
+	pub const EMPTY: &'static str = "google.protobuf.Empty";
+
+	/// Protobuf well-known struct type
+	pub const STRUCT: &'static str = "google.protobuf.Struct";
+
+	/// Protobuf well-known value type
+	pub const VALUE: &'static str = "google.protobuf.Value";
+
+	/// Protobuf well-known list value type
+	pub const LIST_VALUE: &'static str = "google.protobuf.ListValue";
+
+	/// Protobuf well-known field mask type
+	pub const FIELD_MASK: &'static str = "google.protobuf.FieldMask";
+
+	/// Returns the map type reference
+	///
+	/// Protobuf Type: `map<K, V>`
+	#[inline]
+	pub fn map(key: impl Into<String>, value: impl Into<String>) -> TypeRef {
+		TypeRef::Map(
+			Box::new(TypeRef::Named(key.into().into())),
+			Box::new(TypeRef::Named(value.into().into())),
+		)
+	}
+
+	/// Returns the key and the value type of a map type reference
+	#[inline]
+	pub fn as_map(&self) -> Option<(&TypeRef, &TypeRef)> {
+		match self {
+			TypeRef::Map(key, value) => Some((key, value)),
+			TypeRef::NonNull(inner) => inner.as_map(),
+			_ => None,
+		}
+	}
+
+	/// Returns the type name, the type name of a map is the one of its values
 	///
 	/// `[Foo!]` -> `Foo`
 	#[inline(always)]
@@ -166,11 +214,12 @@ impl TypeRef {
 			TypeRef::Named(name) => name,
 			TypeRef::NonNull(inner) => inner.type_name(),
 			TypeRef::List(inner) => inner.type_name(),
+			TypeRef::Map(_, value) => value.type_name(),
 		}
 	}
 
 	#[inline]
-	pub(crate) fn field_type(&self) -> Type {
+	pub(crate) fn field_type(&self, types: &TypeRegistry) -> Type {
 		match self {
 			TypeRef::Named(name) => match name.as_ref() {
 				Self::DOUBLE => Type::Double,
@@ -188,13 +237,17 @@ impl TypeRef {
 				Self::BOOL => Type::Bool,
 				Self::STRING => Type::String,
 				Self::BYTES => Type::Bytes,
-				name => match TYPE_REGISTRY.get(name) {
+				"google.protobuf.NullValue" => Type::Enum,
+				name if is_well_known(name) => Type::Message,
+				name => match types.get(name) {
 					Some(ty) => ty.field_type(),
 					None => panic!("custom types was not found"),
 				},
 			},
-			TypeRef::NonNull(inner) => inner.field_type(),
-			TypeRef::List(inner) => inner.field_type(),
+			TypeRef::NonNull(inner) => inner.field_type(types),
+			TypeRef::List(inner) => inner.field_type(types),
+			// the entries of a map are messages
+			TypeRef::Map(..) => Type::Message,
 		}
 	}
 
@@ -217,6 +270,7 @@ impl TypeRef {
 			//TODO: Change back to false
 			TypeRef::NonNull(_) => true,
 			TypeRef::List(_) => true,
+			TypeRef::Map(..) => true,
 		}
 	}
 
@@ -226,6 +280,7 @@ impl TypeRef {
 			TypeRef::Named(_) => false,
 			TypeRef::NonNull(inner) => inner.is_repeated(),
 			TypeRef::List(_) => true,
+			TypeRef::Map(..) => true,
 		}
 	}
 
@@ -261,6 +316,9 @@ impl TypeRef {
 				(TypeRef::List(super_type), TypeRef::List(sub_type)) => {
 					is_subtype(super_type, sub_type)
 				}
+				(TypeRef::Map(super_key, super_value), TypeRef::Map(sub_key, sub_value)) => {
+					super_key == sub_key && is_subtype(super_value, sub_value)
+				}
 				_ => false,
 			}
 		}
@@ -1,22 +1,25 @@
-use crate::{
-	ObjectAccessor, SeaResult, SeaographyError, Value,
-	proto::{TYPE_REGISTRY, Type},
-};
+use crate::{ObjectAccessor, SeaResult, SeaographyError, Value, proto::Type};
 
-use super::{Field, TypeRef};
-use bytes::{Buf, BufMut, Bytes};
-use juniper::ScalarValue;
-use prost::{
-	DecodeError, EncodeError,
-	encoding::{DecodeContext, WireType},
+use super::{Field, TypeRef, TypeRegistry};
+use binary::proto::{
+	DecoderError, Encoder, Typ, UnknownFields, decode_bool, decode_double, decode_fixed32,
+	decode_fixed64, decode_float, decode_int32, decode_int64, decode_sfixed32, decode_sfixed64,
+	decode_sint32, decode_sint64, decode_uint32, decode_uint64, decode_varint,
 };
+use bytes::BufMut;
+use juniper::ScalarValue;
+use prost::{DecodeError, EncodeError};
 use prost_types::{DescriptorProto, FileDescriptorProto, MessageOptions, OneofDescriptorProto};
 use std::{borrow::Cow, collections::BTreeMap};
 // CONSTS
 pub const PCKNAME: &str = "apy";
+/// the key the fields unknown to a message are stored at in its decoded value, the fields are
+/// kept encoded and written back verbatim when the value is encoded again
+pub const UNKNOWN_FIELDS: &str = "$unknown";
 
 pub(crate) fn descriptor(
 	file: &mut FileDescriptorProto,
+	types: &TypeRegistry,
 	name: Option<String>,
 	fields: &BTreeMap<String, Field>,
 	oneof: bool,
@@ -35,7 +38,15 @@ pub(crate) fn descriptor(
 			});
 		};
 
-		descriptor.field.push(field.field_descriptor(oneof));
+		let mut field_descriptor = field.field_descriptor(types, oneof);
+
+		// the entries of a map are a nested message
+		if let Some(entry) = field.map_entry(types) {
+			field_descriptor.type_name = Some(format!("{}.{}", descriptor.name(), entry.name()));
+			descriptor.nested_type.push(entry);
+		}
+
+		descriptor.field.push(field_descriptor);
 	}
 
 	if !oneof_decl.is_empty() {
@@ -50,221 +61,191 @@ pub(crate) fn descriptor(
 	file.message_type.push(descriptor);
 }
 
-pub(crate) fn well_known_types() -> FileDescriptorProto {
-	let mut file = FileDescriptorProto::default();
-	file.name = Some("google/protobuf/wrappers.proto".to_string());
-	file.package = Some("google.protobuf".to_string());
+/// the depth of nested messages a request is decoded to, deeper messages are rejected before
+/// they can exhaust the stack
+pub(crate) const RECURSION_LIMIT: usize = 100;
+
+/// used to keep a field that is unknown to a message, so forwarding the message does not drop it
+pub(crate) fn merge_unknown(
+	tag: u32,
+	typ: Typ,
+	buf: &[u8],
+	arguments: &mut BTreeMap<Value, Value>,
+) -> Result<(), DecodeError> {
+	let mut fields = UnknownFields::new();
+	fields.push((tag, typ, buf.to_vec()));
+
+	let key = Value::from(UNKNOWN_FIELDS);
+	let mut bytes = match arguments.remove(&key) {
+		Some(Value::Bytes(bytes)) => bytes,
+		_ => Vec::new(),
+	};
+	Encoder.encode_unknown(&fields, &mut bytes).map_err(|err| DecodeError::new(err.to_string()))?;
+	arguments.insert(key, Value::Bytes(bytes));
 
-	for name in [
-		"StringValue",
-		"Int32Value",
-		"Int64Value",
-		"UInt32Value",
-		"UInt64Value",
-		"BoolValue",
-		"FloatValue",
-		"DoubleValue",
-	] {
-		descriptor(&mut file, Some(name.to_string()), &BTreeMap::new(), false, false);
+	Ok(())
+}
+
+pub(crate) fn decode_error(err: DecoderError) -> DecodeError {
+	DecodeError::new(err.to_string())
+}
+
+pub(crate) fn wire_type_error(typ: Typ, expected: Typ) -> DecodeError {
+	DecodeError::new(format!("invalid wire type: {:?} (expected {:?})", typ, expected))
+}
+
+/// used to split the value of a field into its single values, values of a varint or fixed size
+/// type are packed into one length-delimited value when they are repeated
+pub(crate) fn unpack(wire_type: Typ, typ: Typ, buf: &[u8]) -> Result<Vec<&[u8]>, DecodeError> {
+	if typ == wire_type {
+		return Ok(vec![buf]);
+	}
+	if typ != Typ::LengthDelimited || wire_type == Typ::LengthDelimited {
+		return Err(wire_type_error(typ, wire_type));
 	}
 
-	file
+	let mut values = Vec::new();
+	let mut rest = buf;
+	while !rest.is_empty() {
+		let size = match wire_type {
+			Typ::Bit32 => 4,
+			Typ::Bit64 => 8,
+			_ => decode_varint(rest, &mut 0).map_err(decode_error)?,
+		};
+		if rest.len() < size {
+			return Err(decode_error(DecoderError::InputUnderflow));
+		}
+		values.push(&rest[..size]);
+		rest = &rest[size..];
+	}
+	Ok(values)
+}
+
+/// used to add a decoded value to the arguments, the values of a repeated field are appended
+pub(crate) fn insert_value(
+	arguments: &mut BTreeMap<Value, Value>,
+	name: &str,
+	is_repeated: bool,
+	value: Value,
+) {
+	let key = Value::from(name);
+	if !is_repeated {
+		arguments.insert(key, value);
+		return;
+	}
+
+	match arguments.entry(key).or_insert_with(|| Value::List(Vec::new())) {
+		Value::List(values) => values.push(value),
+		other => *other = Value::List(vec![value]),
+	}
+}
+
+/// the wire type of a single value of a scalar
+pub(crate) fn scalar_wire_type(type_ref: &str) -> Typ {
+	match type_ref {
+		TypeRef::FIXED32 | TypeRef::SFIXED32 | TypeRef::FLOAT => Typ::Bit32,
+		TypeRef::FIXED64 | TypeRef::SFIXED64 | TypeRef::DOUBLE => Typ::Bit64,
+		TypeRef::STRING | TypeRef::BYTES => Typ::LengthDelimited,
+		_ => Typ::Varint,
+	}
 }
 
-pub(crate) fn from_bytes<B>(
+/// used to decode the value of a scalar field, every value of a packed repeated field is added
+/// on its own
+pub(crate) fn from_bytes(
 	name: &str,
 	type_ref: &str,
 	is_repeated: bool,
-	ctx: DecodeContext,
-	buf: &mut B,
-	wire_type: WireType,
+	typ: Typ,
+	buf: &[u8],
 	arguments: &mut BTreeMap<Value, Value>,
-) -> Result<(), DecodeError>
-where
-	B: Buf,
-{
-	match (type_ref, is_repeated) {
-		(TypeRef::DOUBLE, false) => {
-			let mut value = 0.0;
-			prost::encoding::double::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::DOUBLE, true) => {
-			let mut value = Vec::new();
-			prost::encoding::double::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::FLOAT, false) => {
-			let mut value = 0.0;
-			prost::encoding::float::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::FLOAT, true) => {
-			let mut value = Vec::new();
-			prost::encoding::float::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::INT32, false) => {
+) -> Result<(), DecodeError> {
+	let wire_type = scalar_wire_type(type_ref);
+	if !is_repeated && typ != wire_type {
+		return Err(wire_type_error(typ, wire_type));
+	}
+
+	for bytes in unpack(wire_type, typ, buf)? {
+		let value = scalar_from_bytes(type_ref, bytes).map_err(decode_error)?;
+		insert_value(arguments, name, is_repeated, value);
+	}
+	Ok(())
+}
+
+fn scalar_from_bytes(type_ref: &str, buf: &[u8]) -> Result<Value, DecoderError> {
+	let value = match type_ref {
+		TypeRef::INT32 => {
 			let mut value = 0;
-			prost::encoding::int32::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::INT32, true) => {
-			let mut value = Vec::new();
-			prost::encoding::int32::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_int32(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::INT64, false) => {
+		TypeRef::SINT32 => {
 			let mut value = 0;
-			prost::encoding::int64::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::INT64, true) => {
-			let mut value = Vec::new();
-			prost::encoding::int64::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_sint32(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::UINT32, false) => {
+		TypeRef::SFIXED32 => {
 			let mut value = 0;
-			prost::encoding::uint32::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_sfixed32(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::UINT32, true) => {
-			let mut value = Vec::new();
-			prost::encoding::uint32::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::UINT64, false) => {
+		TypeRef::UINT32 => {
 			let mut value = 0;
-			prost::encoding::uint64::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::UINT64, true) => {
-			let mut value = Vec::new();
-			prost::encoding::uint64::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_uint32(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::SINT32, false) => {
+		TypeRef::FIXED32 => {
 			let mut value = 0;
-			prost::encoding::sint32::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_fixed32(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::SINT32, true) => {
-			let mut value = Vec::new();
-			prost::encoding::sint32::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::SINT64, false) => {
+		TypeRef::INT64 => {
 			let mut value = 0;
-			prost::encoding::sint64::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::SINT64, true) => {
-			let mut value = Vec::new();
-			prost::encoding::sint64::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_int64(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::FIXED32, false) => {
+		TypeRef::SINT64 => {
 			let mut value = 0;
-			prost::encoding::fixed32::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_sint64(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::FIXED32, true) => {
-			let mut value = Vec::new();
-			prost::encoding::fixed32::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::FIXED64, false) => {
+		TypeRef::SFIXED64 => {
 			let mut value = 0;
-			prost::encoding::fixed64::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::FIXED64, true) => {
-			let mut value = Vec::new();
-			prost::encoding::fixed64::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_sfixed64(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::SFIXED32, false) => {
+		TypeRef::UINT64 => {
 			let mut value = 0;
-			prost::encoding::sfixed32::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_uint64(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::SFIXED32, true) => {
-			let mut value = Vec::new();
-			prost::encoding::sfixed32::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::SFIXED64, false) => {
+		TypeRef::FIXED64 => {
 			let mut value = 0;
-			prost::encoding::sfixed64::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::SFIXED64, true) => {
-			let mut value = Vec::new();
-			prost::encoding::sfixed64::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::BOOL, false) => {
-			let mut value = false;
-			prost::encoding::bool::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::BOOL, true) => {
-			let mut value = Vec::new();
-			prost::encoding::bool::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
+			decode_fixed64(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::STRING, false) => {
-			let mut value = String::new();
-			prost::encoding::string::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.into());
-		}
-		(TypeRef::STRING, true) => {
-			let mut value = Vec::new();
-			prost::encoding::string::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			let key = Value::from(name);
-			if let Some(arg) = arguments.get_mut(&key) {
-				match arg {
-					Value::List(values) => {
-						values.extend(value.into_iter().map(Into::into));
-					}
-					_ => panic!(),
-				};
-			} else {
-				arguments.insert(key, value.into());
-			}
+		TypeRef::FLOAT => {
+			let mut value = 0.0;
+			decode_float(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::BYTES, false) => {
-			let mut value = Bytes::new();
-			prost::encoding::bytes::merge(wire_type, &mut value, buf, ctx)?;
-			arguments.insert(Value::from(name), value.to_vec().into());
+		TypeRef::DOUBLE => {
+			let mut value = 0.0;
+			decode_double(buf, &mut value)?;
+			Value::from(value)
 		}
-		(TypeRef::BYTES, true) => {
-			let mut value: Vec<Bytes> = Vec::new();
-			prost::encoding::bytes::merge_repeated(wire_type, &mut value, buf, ctx)?;
-			let key = Value::from(name);
-			if let Some(arg) = arguments.get_mut(&key) {
-				match arg {
-					Value::List(values) => {
-						values.extend(value.into_iter().map(|b| b.to_vec().into()))
-					}
-					_ => panic!(),
-				};
-			} else {
-				arguments.insert(
-					key,
-					value.into_iter().map(|b| b.to_vec()).collect::<Vec<Vec<u8>>>().into(),
-				);
-			}
+		TypeRef::BOOL => {
+			let mut value = false;
+			decode_bool(buf, &mut value)?;
+			Value::from(value)
 		}
-		(name, _) => {
-			return Err(DecodeError::new(format!(
-				"Unsupported type or wire type combination: {}",
-				name
-			)));
+		TypeRef::STRING => {
+			Value::from(String::from_utf8(buf.to_vec()).map_err(|_| DecoderError::InvalidInput)?)
 		}
+		TypeRef::BYTES => Value::Bytes(buf.to_vec()),
+		_ => return Err(DecoderError::InvalidInput),
 	};
-	Ok(())
+	Ok(value)
 }
 
 pub(crate) fn to_bytes<B>(buf: &mut B, value: &Value, tag: u32, type_ref: &str) -> SeaResult<usize>
@@ -370,6 +351,16 @@ where
 				name
 			))),
 		},
+		Value::Bytes(bytes) => match type_ref {
+			TypeRef::BYTES => {
+				prost::encoding::bytes::encode(tag, bytes, buf);
+				Ok(prost::encoding::bytes::encoded_len(tag, bytes))
+			}
+			name => Err(SeaographyError::new(format!(
+				"TypeRef is of type `{}` but needs to be of type BYTES",
+				name
+			))),
+		},
 		Value::Null => Ok(0),
 		_ => Err(SeaographyError::new(format!("Value type `{}` not supported", value.type_name()))),
 	}
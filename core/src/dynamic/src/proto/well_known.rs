@@ -0,0 +1,358 @@
+use super::{
+	TypeRef, TypeRegistry, decode_error, from_bytes, from_json, json_name, to_bytes, to_json,
+};
+use crate::{SeaResult, SeaographyError, Value};
+use binary::proto::{Fields, Typ, encode_bytes, encode_key};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use prost::DecodeError;
+use prost_types::{
+	DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+	FileDescriptorProto, MessageOptions, OneofDescriptorProto,
+	field_descriptor_proto::{Label, Type as FieldType},
+};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+
+/// The package of the well-known types
+const PACKAGE: &str = "google.protobuf";
+
+/// used to check if a type is one of the well-known types of the `google.protobuf` package
+pub(crate) fn is_well_known(name: &str) -> bool {
+	name.strip_prefix(PACKAGE).is_some_and(|name| name.starts_with('.'))
+}
+
+/// used to get the scalar a wrapper message holds in its `value` field
+pub(crate) fn wrapped_scalar(name: &str) -> Option<&'static str> {
+	let scalar = match name {
+		"google.protobuf.DoubleValue" => TypeRef::DOUBLE,
+		"google.protobuf.FloatValue" => TypeRef::FLOAT,
+		"google.protobuf.Int64Value" => TypeRef::INT64,
+		"google.protobuf.UInt64Value" => TypeRef::UINT64,
+		"google.protobuf.Int32Value" => TypeRef::INT32,
+		"google.protobuf.UInt32Value" => TypeRef::UINT32,
+		"google.protobuf.BoolValue" => TypeRef::BOOL,
+		"google.protobuf.StringValue" => TypeRef::STRING,
+		"google.protobuf.BytesValue" => TypeRef::BYTES,
+		_ => return None,
+	};
+	Some(scalar)
+}
+
+/// used to describe the files of the well-known types a proto may reference
+pub(crate) fn well_known_types() -> Vec<FileDescriptorProto> {
+	let wrappers = [
+		("DoubleValue", FieldType::Double),
+		("FloatValue", FieldType::Float),
+		("Int64Value", FieldType::Int64),
+		("UInt64Value", FieldType::Uint64),
+		("Int32Value", FieldType::Int32),
+		("UInt32Value", FieldType::Uint32),
+		("BoolValue", FieldType::Bool),
+		("StringValue", FieldType::String),
+		("BytesValue", FieldType::Bytes),
+	]
+	.into_iter()
+	.map(|(name, ty)| message(name, vec![field("value", 1, ty, None)]))
+	.collect();
+
+	let seconds = || {
+		vec![field("seconds", 1, FieldType::Int64, None), field("nanos", 2, FieldType::Int32, None)]
+	};
+
+	let mut entry = message(
+		"FieldsEntry",
+		vec![
+			field("key", 1, FieldType::String, None),
+			field("value", 2, FieldType::Message, Some("Value")),
+		],
+	);
+	entry.options = Some(MessageOptions {
+		map_entry: Some(true),
+		..Default::default()
+	});
+	let mut structure = message(
+		"Struct",
+		vec![repeated(field("fields", 1, FieldType::Message, Some("Struct.FieldsEntry")))],
+	);
+	structure.nested_type.push(entry);
+
+	let mut value = message(
+		"Value",
+		vec![
+			field("null_value", 1, FieldType::Enum, Some("NullValue")),
+			field("number_value", 2, FieldType::Double, None),
+			field("string_value", 3, FieldType::String, None),
+			field("bool_value", 4, FieldType::Bool, None),
+			field("struct_value", 5, FieldType::Message, Some("Struct")),
+			field("list_value", 6, FieldType::Message, Some("ListValue")),
+		],
+	);
+	for field in value.field.iter_mut() {
+		field.oneof_index = Some(0);
+	}
+	value.oneof_decl.push(OneofDescriptorProto {
+		name: Some("kind".to_string()),
+		..Default::default()
+	});
+
+	let list =
+		message("ListValue", vec![repeated(field("values", 1, FieldType::Message, Some("Value")))]);
+
+	let null = EnumDescriptorProto {
+		name: Some("NullValue".to_string()),
+		value: vec![EnumValueDescriptorProto {
+			name: Some("NULL_VALUE".to_string()),
+			number: Some(0),
+			..Default::default()
+		}],
+		..Default::default()
+	};
+
+	vec![
+		file("wrappers", wrappers, vec![]),
+		file("empty", vec![message("Empty", vec![])], vec![]),
+		file("timestamp", vec![message("Timestamp", seconds())], vec![]),
+		file("duration", vec![message("Duration", seconds())], vec![]),
+		file(
+			"field_mask",
+			vec![message("FieldMask", vec![repeated(field("paths", 1, FieldType::String, None))])],
+			vec![],
+		),
+		file("struct", vec![structure, value, list], vec![null]),
+	]
+}
+
+fn file(
+	name: &str,
+	message_type: Vec<DescriptorProto>,
+	enum_type: Vec<EnumDescriptorProto>,
+) -> FileDescriptorProto {
+	FileDescriptorProto {
+		name: Some(format!("google/protobuf/{}.proto", name)),
+		package: Some(PACKAGE.to_string()),
+		message_type,
+		enum_type,
+		syntax: Some("proto3".to_string()),
+		..Default::default()
+	}
+}
+
+fn message(name: &str, field: Vec<FieldDescriptorProto>) -> DescriptorProto {
+	DescriptorProto {
+		name: Some(name.to_string()),
+		field,
+		..Default::default()
+	}
+}
+
+fn field(name: &str, number: i32, ty: FieldType, type_name: Option<&str>) -> FieldDescriptorProto {
+	FieldDescriptorProto {
+		name: Some(name.to_string()),
+		json_name: Some(json_name(name)),
+		number: Some(number),
+		label: Some(Label::Optional.into()),
+		r#type: Some(ty.into()),
+		type_name: type_name.map(|type_name| format!(".{}.{}", PACKAGE, type_name)),
+		..Default::default()
+	}
+}
+
+fn repeated(mut field: FieldDescriptorProto) -> FieldDescriptorProto {
+	field.label = Some(Label::Repeated.into());
+	field
+}
+
+/// used to encode a value into the body of a message of a well-known type, a timestamp or a
+/// duration is a string, a field mask a comma separated string of paths and a struct, value or
+/// list value either a value or its JSON text
+pub(crate) fn well_known_to_bytes(
+	types: &TypeRegistry,
+	type_name: &str,
+	value: &Value,
+) -> SeaResult<Vec<u8>> {
+	let mut buf = Vec::new();
+
+	if let Some(scalar) = wrapped_scalar(type_name) {
+		to_bytes(&mut buf, value, 1, scalar)?;
+		return Ok(buf);
+	}
+
+	let json = match (type_name, value) {
+		(TypeRef::EMPTY, _) => return Ok(buf),
+		(TypeRef::FIELD_MASK, Value::String(paths)) => {
+			for path in paths.split(',').filter(|path| !path.is_empty()) {
+				encode_key(1, Typ::LengthDelimited, &mut buf)?;
+				encode_bytes(path.as_bytes().to_vec(), &mut buf)?;
+			}
+			return Ok(buf);
+		}
+		(TypeRef::TIMESTAMP, Value::String(timestamp)) => match timestamp_from_str(timestamp) {
+			Some(timestamp) => {
+				JsonValue::from(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+			}
+			None => return Err(conversion(type_name, value)),
+		},
+		(TypeRef::STRUCT | TypeRef::LIST_VALUE, Value::String(document)) => {
+			serde_json::from_str(document).map_err(|_| conversion(type_name, value))?
+		}
+		// a string that is no JSON document is a string value
+		(TypeRef::VALUE, Value::String(document)) => {
+			serde_json::from_str(document).unwrap_or_else(|_| JsonValue::from(document.as_str()))
+		}
+		(_, value) => serde_json::to_value(value).map_err(|_| conversion(type_name, value))?,
+	};
+
+	buf.extend(from_json(types, type_name, &json)?);
+	Ok(buf)
+}
+
+/// used to decode the body of a message of a well-known type into a value, the inverse of
+/// `well_known_to_bytes` where a struct, value or list value is decoded into its JSON text
+pub(crate) fn well_known_from_bytes(
+	types: &TypeRegistry,
+	type_name: &str,
+	buf: &[u8],
+) -> Result<Value, DecodeError> {
+	if let Some(scalar) = wrapped_scalar(type_name) {
+		let mut arguments = BTreeMap::new();
+		for field in Fields::new(buf) {
+			if let (1, typ, bytes) = field.map_err(decode_error)? {
+				from_bytes("value", scalar, false, typ, bytes, &mut arguments)?;
+			}
+		}
+		return Ok(arguments.remove(&Value::from("value")).unwrap_or(Value::Null));
+	}
+
+	let value = match type_name {
+		TypeRef::EMPTY => Value::Map(BTreeMap::new()),
+		TypeRef::FIELD_MASK => {
+			let mut paths = Vec::new();
+			for field in Fields::new(buf) {
+				match field.map_err(decode_error)? {
+					(1, Typ::LengthDelimited, path) => paths.push(
+						String::from_utf8(path.to_vec())
+							.map_err(|err| DecodeError::new(err.to_string()))?,
+					),
+					_ => continue,
+				}
+			}
+			Value::from(paths.join(","))
+		}
+		type_name => {
+			let json =
+				to_json(types, type_name, buf).map_err(|err| DecodeError::new(err.to_string()))?;
+			match (type_name, json) {
+				(TypeRef::STRUCT | TypeRef::VALUE | TypeRef::LIST_VALUE, json) => {
+					Value::from(json.to_string())
+				}
+				(_, JsonValue::String(value)) => Value::from(value),
+				(type_name, _) => {
+					return Err(DecodeError::new(format!("Unsupported type `{}`", type_name)));
+				}
+			}
+		}
+	};
+	Ok(value)
+}
+
+/// used to read a timestamp in RFC 3339 or in the format chrono displays date times, a
+/// timestamp without offset is in UTC
+fn timestamp_from_str(timestamp: &str) -> Option<DateTime<Utc>> {
+	if let Ok(timestamp) = DateTime::parse_from_rfc3339(timestamp) {
+		return Some(timestamp.to_utc());
+	}
+	if let Ok(timestamp) = DateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f %:z") {
+		return Some(timestamp.to_utc());
+	}
+	let timestamp = timestamp.strip_suffix(" UTC").unwrap_or(timestamp);
+	NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f")
+		.or_else(|_| NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f"))
+		.ok()
+		.map(|timestamp| timestamp.and_utc())
+}
+
+fn conversion(type_name: &str, value: &Value) -> SeaographyError {
+	SeaographyError::TypeConversionError(
+		format!("invalid value `{}`", value),
+		type_name.to_string(),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Should encode values of the well-known types and decode them back.
+	#[test]
+	fn round_trips() {
+		let types = TypeRegistry::new();
+		let round_trip = |type_name: &str, value: Value| {
+			let buf = well_known_to_bytes(&types, type_name, &value).unwrap();
+			well_known_from_bytes(&types, type_name, &buf).unwrap()
+		};
+
+		// the formats chrono displays date times in
+		for timestamp in [
+			"2024-03-01T10:00:00.5Z",
+			"2024-03-01 10:00:00.500",
+			"2024-03-01 10:00:00.500 UTC",
+			"2024-03-01 11:00:00.500 +01:00",
+		] {
+			assert_eq!(
+				round_trip(TypeRef::TIMESTAMP, Value::from(timestamp)),
+				Value::from("2024-03-01T10:00:00.500Z")
+			);
+		}
+		assert_eq!(round_trip(TypeRef::DURATION, Value::from("-1.5s")), Value::from("-1.500s"));
+		assert_eq!(
+			round_trip(TypeRef::FIELD_MASK, Value::from("name,profile.display_name")),
+			Value::from("name,profile.display_name")
+		);
+		assert_eq!(round_trip(TypeRef::EMPTY, Value::Null), Value::Map(BTreeMap::new()));
+		assert_eq!(round_trip("google.protobuf.Int64Value", Value::from(5i64)), Value::from(5i64));
+
+		let document = r#"{"a":[1.0,"b",null,true],"c":{}}"#;
+		assert_eq!(round_trip(TypeRef::STRUCT, Value::from(document)), Value::from(document));
+		assert_eq!(round_trip(TypeRef::VALUE, Value::from(document)), Value::from(document));
+		assert_eq!(round_trip(TypeRef::VALUE, Value::from("text")), Value::from(r#""text""#));
+		assert_eq!(round_trip(TypeRef::VALUE, Value::from(2.5)), Value::from("2.5"));
+		assert_eq!(round_trip(TypeRef::LIST_VALUE, Value::from("[]")), Value::from("[]"));
+
+		assert!(well_known_to_bytes(&types, TypeRef::TIMESTAMP, &Value::from("now")).is_err());
+		assert!(well_known_to_bytes(&types, TypeRef::STRUCT, &Value::from("[]")).is_err());
+	}
+
+	/// Should describe every well-known type in the file it is imported from.
+	#[test]
+	fn descriptors() {
+		let files = well_known_types();
+		let names = files
+			.iter()
+			.flat_map(|file| {
+				file.message_type
+					.iter()
+					.map(move |message| format!("{}/{}", file.name(), message.name()))
+			})
+			.collect::<Vec<_>>();
+
+		for name in [
+			"google/protobuf/wrappers.proto/BytesValue",
+			"google/protobuf/timestamp.proto/Timestamp",
+			"google/protobuf/duration.proto/Duration",
+			"google/protobuf/field_mask.proto/FieldMask",
+			"google/protobuf/struct.proto/Struct",
+			"google/protobuf/struct.proto/Value",
+			"google/protobuf/struct.proto/ListValue",
+		] {
+			assert!(names.contains(&name.to_string()), "{}", name);
+		}
+
+		let value = &files[5].message_type[1];
+		assert_eq!(value.field.len(), 6);
+		assert!(value.field.iter().all(|field| field.oneof_index == Some(0)));
+		assert_eq!(
+			files[5].message_type[0].field[0].type_name(),
+			".google.protobuf.Struct.FieldsEntry"
+		);
+	}
+}
@@ -4,8 +4,12 @@ use std::{
 	sync::Arc,
 };
 
-use crate::{InputType, Value, graphql::Context};
-use async_graphql::{Any, CacheControl, IntrospectionMode, OutputType, SubscriptionType};
+use crate::{ContextBase, ObjectAccessor, ScalarValidatorFn, SeaResult, Value};
+use async_graphql::{
+	CacheControl, IntrospectionMode,
+	parser::types::{BaseType as ParsedBaseType, Type as ParsedType},
+	registry::__DirectiveLocation,
+};
 
 fn strip_brackets(type_name: &str) -> Option<&str> {
 	type_name.strip_prefix('[').map(|rest| &rest[..rest.len() - 1])
@@ -30,7 +34,7 @@ impl Display for MetaTypeName<'_> {
 
 impl MetaTypeName<'_> {
 	#[inline]
-	pub fn create(type_name: &str) -> MetaTypeName {
+	pub fn create(type_name: &str) -> MetaTypeName<'_> {
 		if let Some(type_name) = type_name.strip_suffix('!') {
 			MetaTypeName::NonNull(type_name)
 		} else if let Some(type_name) = strip_brackets(type_name) {
@@ -145,12 +149,10 @@ pub struct MetaInputValue {
 	pub directive_invocations: Vec<MetaDirectiveInvocation>,
 }
 
-type ComputeComplexityFn = fn(
-	&VisitorContext<'_>,
-	&[Positioned<VariableDefinition>],
-	&Field,
-	usize,
-) -> ServerResult<usize>;
+/// A function that receives the arguments of a field and the complexity of its selection set and
+/// returns the complexity of the field
+pub type ComputeComplexityFn =
+	Arc<dyn Fn(&ObjectAccessor<'_>, usize) -> SeaResult<usize> + Send + Sync>;
 
 #[derive(Debug, Clone, Default)]
 pub enum Deprecation {
@@ -241,7 +243,7 @@ pub struct MetaEnumValue {
 	pub directive_invocations: Vec<MetaDirectiveInvocation>,
 }
 
-type MetaVisibleFn = fn(&Context<'_>) -> bool;
+type MetaVisibleFn = fn(&ContextBase) -> bool;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MetaTypeId {
@@ -253,88 +255,6 @@ pub enum MetaTypeId {
 	InputObject,
 }
 
-impl MetaTypeId {
-	fn create_fake_type(&self, rust_typename: &'static str) -> MetaType {
-		match self {
-			MetaTypeId::Scalar => MetaType::Scalar {
-				name: "".to_string(),
-				description: None,
-				is_valid: None,
-				visible: None,
-				inaccessible: false,
-				tags: vec![],
-				specified_by_url: None,
-				directive_invocations: vec![],
-				requires_scopes: vec![],
-			},
-			MetaTypeId::Object => MetaType::Object {
-				name: "".to_string(),
-				description: None,
-				fields: Default::default(),
-				cache_control: Default::default(),
-				extends: false,
-				shareable: false,
-				resolvable: true,
-				inaccessible: false,
-				interface_object: false,
-				tags: vec![],
-				keys: None,
-				visible: None,
-				is_subscription: false,
-				rust_typename: Some(rust_typename),
-				directive_invocations: vec![],
-				requires_scopes: vec![],
-			},
-			MetaTypeId::Interface => MetaType::Interface {
-				name: "".to_string(),
-				description: None,
-				fields: Default::default(),
-				possible_types: Default::default(),
-				extends: false,
-				inaccessible: false,
-				tags: vec![],
-				keys: None,
-				visible: None,
-				rust_typename: Some(rust_typename),
-				directive_invocations: vec![],
-				requires_scopes: vec![],
-			},
-			MetaTypeId::Union => MetaType::Union {
-				name: "".to_string(),
-				description: None,
-				possible_types: Default::default(),
-				visible: None,
-				inaccessible: false,
-				tags: vec![],
-				rust_typename: Some(rust_typename),
-				directive_invocations: vec![],
-			},
-			MetaTypeId::Enum => MetaType::Enum {
-				name: "".to_string(),
-				description: None,
-				enum_values: Default::default(),
-				visible: None,
-				inaccessible: false,
-				tags: vec![],
-				rust_typename: Some(rust_typename),
-				directive_invocations: vec![],
-				requires_scopes: vec![],
-			},
-			MetaTypeId::InputObject => MetaType::InputObject {
-				name: "".to_string(),
-				description: None,
-				input_fields: Default::default(),
-				visible: None,
-				inaccessible: false,
-				tags: vec![],
-				rust_typename: Some(rust_typename),
-				oneof: false,
-				directive_invocations: vec![],
-			},
-		}
-	}
-}
-
 impl Display for MetaTypeId {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		f.write_str(match self {
@@ -348,9 +268,6 @@ impl Display for MetaTypeId {
 	}
 }
 
-/// A validator for scalar
-pub type ScalarValidatorFn = Arc<dyn Fn(&Value) -> bool + Send + Sync>;
-
 /// Type metadata
 #[derive(Clone)]
 pub enum MetaType {
@@ -635,17 +552,17 @@ impl MetaType {
 			MetaType::Object {
 				fields,
 				..
-			} => Some(&fields),
+			} => Some(fields),
 			MetaType::Interface {
 				fields,
 				..
-			} => Some(&fields),
+			} => Some(fields),
 			_ => None,
 		}
 	}
 
 	#[inline]
-	pub fn is_visible(&self, ctx: &Context<'_>) -> bool {
+	pub fn is_visible(&self, ctx: &ContextBase) -> bool {
 		let visible = match self {
 			MetaType::Scalar {
 				visible,
@@ -681,7 +598,7 @@ impl MetaType {
 			MetaType::Scalar {
 				name,
 				..
-			} => &name,
+			} => name,
 			MetaType::Object {
 				name,
 				..
@@ -822,45 +739,6 @@ pub struct MetaDirective {
 	pub composable: Option<String>,
 }
 
-impl MetaDirective {
-	pub(crate) fn sdl(&self, options: &SDLExportOptions) -> String {
-		let mut sdl = String::new();
-
-		if let Some(description) = &self.description {
-			self::export_sdl::write_description(&mut sdl, options, 0, description);
-		}
-
-		write!(sdl, "directive @{}", self.name).ok();
-
-		if !self.args.is_empty() {
-			let args = self
-				.args
-				.values()
-				.map(|value| self.argument_sdl(value))
-				.collect::<Vec<_>>()
-				.join(", ");
-			write!(sdl, "({})", args).ok();
-		}
-		let locations = self
-			.locations
-			.iter()
-			.map(|location| location.to_value().to_string())
-			.collect::<Vec<_>>()
-			.join(" | ");
-		write!(sdl, " on {}", locations).ok();
-		sdl
-	}
-
-	pub(crate) fn argument_sdl(&self, argument: &MetaInputValue) -> String {
-		let argument_default = match &argument.default_value {
-			Some(default) => format!(" = {default}"),
-			None => "".to_string(),
-		};
-
-		format!("{}: {}{}", argument.name, argument.ty, argument_default)
-	}
-}
-
 /// A type registry for build schemas
 #[derive(Default)]
 pub struct GraphQLRegistry {
@@ -1017,97 +895,6 @@ impl GraphQLRegistry {
 			visible: None,
 			composable: None,
 		});
-
-		// create system scalars
-		<bool as InputType>::create_type_info(self);
-		<i32 as InputType>::create_type_info(self);
-		<f32 as InputType>::create_type_info(self);
-		<String as InputType>::create_type_info(self);
-		<ID as InputType>::create_type_info(self);
-	}
-
-	pub fn create_input_type<T, F>(&mut self, type_id: MetaTypeId, mut f: F) -> String
-	where
-		T: InputType,
-		F: FnMut(&mut GraphQLRegistry) -> MetaType,
-	{
-		self.create_type(&mut f, &T::type_name(), std::any::type_name::<T>(), type_id);
-		T::qualified_type_name()
-	}
-
-	pub fn create_output_type<T, F>(&mut self, type_id: MetaTypeId, mut f: F) -> String
-	where
-		T: OutputType + ?Sized,
-		F: FnMut(&mut GraphQLRegistry) -> MetaType,
-	{
-		self.create_type(&mut f, &T::type_name(), std::any::type_name::<T>(), type_id);
-		T::qualified_type_name()
-	}
-
-	pub fn create_subscription_type<T, F>(&mut self, mut f: F) -> String
-	where
-		T: SubscriptionType + ?Sized,
-		F: FnMut(&mut GraphQLRegistry) -> MetaType,
-	{
-		self.create_type(&mut f, &T::type_name(), std::any::type_name::<T>(), MetaTypeId::Object);
-		T::qualified_type_name()
-	}
-
-	fn create_type<F: FnMut(&mut GraphQLRegistry) -> MetaType>(
-		&mut self,
-		f: &mut F,
-		name: &str,
-		rust_typename: &'static str,
-		type_id: MetaTypeId,
-	) {
-		match self.types.get(name) {
-			Some(ty) => {
-				if let Some(prev_typename) = ty.rust_typename() {
-					if prev_typename == "__fake_type__" {
-						return;
-					}
-
-					if rust_typename != prev_typename && !self.ignore_name_conflicts.contains(name)
-					{
-						panic!(
-							"`{}` and `{}` have the same GraphQL name `{}`",
-							prev_typename, rust_typename, name,
-						);
-					}
-
-					if ty.type_id() != type_id {
-						panic!(
-							"Register `{}` as `{}`, but it is already registered as `{}`",
-							name,
-							type_id,
-							ty.type_id()
-						);
-					}
-				}
-			}
-			None => {
-				// Inserting a fake type before calling the function allows recursive types to
-				// exist.
-				self.types.insert(name.to_string(), type_id.create_fake_type(rust_typename));
-				let ty = f(self);
-				*self.types.get_mut(name).unwrap() = ty;
-			}
-		}
-	}
-
-	pub fn create_fake_output_type<T: OutputType>(&mut self) -> MetaType {
-		T::create_type_info(self);
-		self.types.get(&*T::type_name()).cloned().expect("You definitely encountered a bug!")
-	}
-
-	pub fn create_fake_input_type<T: InputType>(&mut self) -> MetaType {
-		T::create_type_info(self);
-		self.types.get(&*T::type_name()).cloned().expect("You definitely encountered a bug!")
-	}
-
-	pub fn create_fake_subscription_type<T: SubscriptionType>(&mut self) -> MetaType {
-		T::create_type_info(self);
-		self.types.get(&*T::type_name()).cloned().expect("You definitely encountered a bug!")
 	}
 
 	pub fn add_directive(&mut self, directive: MetaDirective) {
@@ -1293,8 +1080,6 @@ impl GraphQLRegistry {
 
 	#[doc(hidden)]
 	pub fn create_introspection_types(&mut self) {
-		__Schema::create_type_info(self);
-
 		if let Some(MetaType::Object {
 			fields,
 			..
@@ -1368,8 +1153,6 @@ impl GraphQLRegistry {
 
 	#[doc(hidden)]
 	pub fn create_federation_types(&mut self) {
-		<Any as InputType>::create_type_info(self);
-
 		self.types.insert(
 			"_Service".to_string(),
 			MetaType::Object {
@@ -1451,15 +1234,10 @@ impl GraphQLRegistry {
 					..
 				} => {
 					names.insert(name.clone());
-					names.extend(
-						fields
-							.values()
-							.map(|field| {
-								std::iter::once(field.name.clone())
-									.chain(field.args.values().map(|arg| arg.name.to_string()))
-							})
-							.flatten(),
-					);
+					names.extend(fields.values().flat_map(|field| {
+						std::iter::once(field.name.clone())
+							.chain(field.args.values().map(|arg| arg.name.to_string()))
+					}));
 				}
 				MetaType::Enum {
 					name,
@@ -1630,11 +1408,11 @@ impl GraphQLRegistry {
 		}
 	}
 
-	pub fn find_visible_types(&self, ctx: &Context<'_>) -> HashSet<&str> {
+	pub fn find_visible_types(&self, ctx: &ContextBase) -> HashSet<&str> {
 		let mut visible_types = HashSet::new();
 
 		fn traverse_field<'a>(
-			ctx: &Context<'_>,
+			ctx: &ContextBase,
 			types: &'a BTreeMap<String, MetaType>,
 			visible_types: &mut HashSet<&'a str>,
 			field: &'a MetaField,
@@ -1650,7 +1428,7 @@ impl GraphQLRegistry {
 		}
 
 		fn traverse_input_value<'a>(
-			ctx: &Context<'_>,
+			ctx: &ContextBase,
 			types: &'a BTreeMap<String, MetaType>,
 			visible_types: &mut HashSet<&'a str>,
 			input_value: &'a MetaInputValue,
@@ -1668,7 +1446,7 @@ impl GraphQLRegistry {
 		}
 
 		fn traverse_type<'a>(
-			ctx: &Context<'_>,
+			ctx: &ContextBase,
 			types: &'a BTreeMap<String, MetaType>,
 			visible_types: &mut HashSet<&'a str>,
 			type_name: &'a str,
@@ -1786,7 +1564,7 @@ impl GraphQLRegistry {
 	}
 }
 
-pub(crate) fn is_visible(ctx: &Context<'_>, visible: &Option<MetaVisibleFn>) -> bool {
+pub(crate) fn is_visible(ctx: &ContextBase, visible: &Option<MetaVisibleFn>) -> bool {
 	match visible {
 		Some(f) => f(ctx),
 		None => true,
@@ -1,8 +1,8 @@
-// mod graphql;
+mod graphql;
 mod proto;
 mod thrift;
 
-// pub use graphql::*;
+pub use graphql::*;
 pub use proto::*;
 pub use thrift::*;
 
@@ -1,5 +1,6 @@
 use crate::ScalarValidatorFn;
-use std::collections::{BTreeMap, HashSet};
+use heck::ToShoutySnakeCase;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Write;
 
 use super::MetaVisibleFn;
@@ -58,6 +59,7 @@ pub enum ProtobufKind {
 		description: Option<String>,
 		fields: BTreeMap<String, ProtobufField>,
 		oneof_groups: Vec<ProtobufOneofGroup>,
+		nested: Vec<ProtobufKind>,
 		visible: Option<MetaVisibleFn>,
 		rust_typename: Option<&'static str>,
 	},
@@ -198,6 +200,7 @@ impl ProtobufKind {
 pub struct ProtoRegistry {
 	pub types: BTreeMap<String, ProtobufKind>,
 	pub proto_type: String,
+	pub package: Option<String>,
 	// pub proto_enum_type: String,
 	// pub mutation_type: Option<String>,
 	// pub subscription_type: Option<String>,
@@ -209,74 +212,207 @@ impl ProtoRegistry {
 		Self {
 			types: BTreeMap::default(),
 			proto_type,
+			package: None,
 			ignore_name_conflicts: HashSet::new(),
 		}
 	}
 
+	/// used to print the registry as a proto3 file, enums are printed first and services last
 	pub fn build(&self) -> String {
 		let mut sdl = String::new();
 
 		writeln!(sdl, "syntax = \"proto3\";").ok();
 
-		self.types.iter().for_each(|(_, kind)| match kind {
+		if let Some(package) = &self.package {
+			writeln!(sdl, "\npackage {};", package).ok();
+		}
+
+		let mut imports = BTreeSet::new();
+		for kind in self.types.values() {
+			kind.imports(&mut imports);
+		}
+		if !imports.is_empty() {
+			writeln!(sdl).ok();
+			for import in imports {
+				writeln!(sdl, "import \"{}\";", import).ok();
+			}
+		}
+
+		for type_id in [ProtobufTypeId::Enum, ProtobufTypeId::Message, ProtobufTypeId::Service] {
+			for kind in self.types.values().filter(|kind| kind.type_id() == type_id) {
+				writeln!(sdl).ok();
+				kind.print(&mut sdl, 0);
+			}
+		}
+
+		sdl
+	}
+}
+
+impl ProtobufKind {
+	/// used to collect the files of the well known types used by this type
+	fn imports(&self, imports: &mut BTreeSet<&'static str>) {
+		match self {
+			ProtobufKind::Message {
+				fields,
+				oneof_groups,
+				nested,
+				..
+			} => {
+				let groups = oneof_groups.iter().flat_map(|group| group.fields.values());
+				for field in fields.values().chain(groups) {
+					imports.extend(import(&field.field_type));
+				}
+				for kind in nested {
+					kind.imports(imports);
+				}
+			}
+			ProtobufKind::Service {
+				methods,
+				..
+			} => {
+				for method in methods.values() {
+					imports.extend(import(&method.input_type));
+					imports.extend(import(&method.output_type));
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn print(&self, sdl: &mut String, depth: usize) {
+		let indent = "  ".repeat(depth);
+
+		match self {
 			ProtobufKind::Enum {
 				name,
 				description,
 				fields,
-				visible,
-				rust_typename,
+				..
 			} => {
-				let line = fields
-					.iter()
-					.map(|(name, value)| format!("{} = {};", value.name, value.tag))
-					.fold(String::new(), |start, data| format!("{}\n{}", start, data));
+				let mut values = fields.values().collect::<Vec<_>>();
+				values.sort_by_key(|value| value.tag);
 
-				writeln!(sdl, "enum {} {{{}}}", name, line).ok();
+				print_description(sdl, description, &indent);
+				writeln!(sdl, "{}enum {} {{", indent, name).ok();
+				// the first value of a proto3 enum has to be zero
+				if values.first().is_none_or(|value| value.tag != 0) {
+					writeln!(sdl, "{}  {}_UNSPECIFIED = 0;", indent, name.to_shouty_snake_case())
+						.ok();
+				}
+				for value in values {
+					print_description(sdl, &value.description, &format!("{}  ", indent));
+					writeln!(sdl, "{}  {} = {};", indent, value.name, value.tag).ok();
+				}
+				writeln!(sdl, "{}}}", indent).ok();
 			}
 			ProtobufKind::Message {
 				name,
 				description,
 				fields,
 				oneof_groups,
-				visible,
-				rust_typename,
+				nested,
+				..
 			} => {
-				let line = fields
-					.iter()
-					.map(|(name, field)| {
-						format!("{} {} = {};", field.field_type, field.name, field.tag)
-					})
-					.fold(String::new(), |start, data| format!("{}\n{}", start, data));
-
-				writeln!(sdl, "message {} {{{}}}", name, line).ok();
+				print_description(sdl, description, &indent);
+				writeln!(sdl, "{}message {} {{", indent, name).ok();
+				for kind in nested {
+					kind.print(sdl, depth + 1);
+				}
+				for field in sorted(fields.values()) {
+					print_field(sdl, field, &format!("{}  ", indent));
+				}
+				for group in oneof_groups {
+					writeln!(sdl, "{}  oneof {} {{", indent, group.name).ok();
+					for field in sorted(group.fields.values()) {
+						print_field(sdl, field, &format!("{}    ", indent));
+					}
+					writeln!(sdl, "{}  }}", indent).ok();
+				}
+				writeln!(sdl, "{}}}", indent).ok();
 			}
-			ProtobufKind::Scalar {
-				name,
-				description,
-				is_valid,
-				visible,
-			} => {}
 			ProtobufKind::Service {
 				name,
 				description,
 				methods,
-				visible,
-				rust_typename,
+				..
 			} => {
-				let line = methods
-					.iter()
-					.map(|(name, method)| {
-						format!(
-							"rpc {}({}) returns ({}) {{}};",
-							method.name, method.input_type, method.output_type
-						)
-					})
-					.fold(String::new(), |start, data| format!("{}\n{}", start, data));
-
-				writeln!(sdl, "service {} {{{}}}", name, line).ok();
+				print_description(sdl, description, &indent);
+				writeln!(sdl, "{}service {} {{", indent, name).ok();
+				for method in methods.values() {
+					let stream = |streaming: bool| {
+						if streaming {
+							"stream "
+						} else {
+							""
+						}
+					};
+
+					print_description(sdl, &method.description, &format!("{}  ", indent));
+					writeln!(
+						sdl,
+						"{}  rpc {}({}{}) returns ({}{});",
+						indent,
+						method.name,
+						stream(method.client_streaming),
+						method.input_type,
+						stream(method.server_streaming),
+						method.output_type
+					)
+					.ok();
+				}
+				writeln!(sdl, "{}}}", indent).ok();
 			}
-		});
-		// println!("{}", sdl);
-		sdl
+			// custom scalars are transported as their underlying proto type
+			ProtobufKind::Scalar {
+				..
+			} => {}
+		}
+	}
+}
+
+/// used to get the file defining a well known type
+fn import(type_name: &str) -> Option<&'static str> {
+	// the values of a map decide its import, the keys are always scalars
+	let type_name = match type_name.strip_prefix("map<").and_then(|ty| ty.strip_suffix('>')) {
+		Some(ty) => ty.split_once(',').map_or(ty, |(_, value)| value.trim()),
+		None => type_name,
+	};
+	let name = type_name.trim_start_matches('.').strip_prefix("google.protobuf.")?;
+
+	Some(match name {
+		"Empty" => "google/protobuf/empty.proto",
+		"Timestamp" => "google/protobuf/timestamp.proto",
+		"Duration" => "google/protobuf/duration.proto",
+		"Any" => "google/protobuf/any.proto",
+		"FieldMask" => "google/protobuf/field_mask.proto",
+		"Struct" | "Value" | "ListValue" | "NullValue" => "google/protobuf/struct.proto",
+		name if name.ends_with("Value") => "google/protobuf/wrappers.proto",
+		_ => return None,
+	})
+}
+
+fn sorted<'a>(fields: impl Iterator<Item = &'a ProtobufField>) -> Vec<&'a ProtobufField> {
+	let mut fields = fields.collect::<Vec<_>>();
+	fields.sort_by_key(|field| field.tag);
+	fields
+}
+
+fn print_field(sdl: &mut String, field: &ProtobufField, indent: &str) {
+	let label = match field.label {
+		Some(ProtobufFieldLabel::Optional) => "optional ",
+		Some(ProtobufFieldLabel::Repeated) => "repeated ",
+		Some(ProtobufFieldLabel::Required) | None => "",
+	};
+
+	print_description(sdl, &field.description, indent);
+	writeln!(sdl, "{}{}{} {} = {};", indent, label, field.field_type, field.name, field.tag).ok();
+}
+
+fn print_description(sdl: &mut String, description: &Option<String>, indent: &str) {
+	if let Some(description) = description {
+		for line in description.lines() {
+			writeln!(sdl, "{}// {}", indent, line).ok();
+		}
 	}
 }
@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
 
 use crate::ScalarValidatorFn;
 
@@ -201,6 +202,7 @@ impl ThriftKind {
 /// A type registry for build schemas
 pub struct ThriftRegistry {
 	pub types: BTreeMap<String, ThriftKind>,
+	pub namespace: Option<String>,
 	// pub proto_type: String,
 	// pub proto_enum_type: String,
 	// pub mutation_type: Option<String>,
@@ -212,14 +214,144 @@ impl ThriftRegistry {
 	pub fn new() -> Self {
 		Self {
 			types: BTreeMap::default(),
+			namespace: None,
 			ignore_name_conflicts: HashSet::new(),
 		}
 	}
 
+	/// used to print the registry as thrift IDL, enums are printed before the structs using
+	/// them and services last
 	pub fn build(&self) -> String {
-		let sdl = String::new();
+		let mut sdl = String::new();
+
+		if let Some(namespace) = &self.namespace {
+			writeln!(sdl, "namespace * {}", namespace).ok();
+		}
+
+		let kinds = [
+			ThriftTypeId::Enum,
+			ThriftTypeId::Struct,
+			ThriftTypeId::Union,
+			ThriftTypeId::Exception,
+			ThriftTypeId::Service,
+		];
+		for type_id in kinds {
+			for kind in self.types.values().filter(|kind| kind.type_id() == type_id) {
+				match kind {
+					ThriftKind::Enum {
+						name,
+						description,
+						fields,
+						..
+					} => {
+						let mut values = fields.values().collect::<Vec<_>>();
+						values.sort_by_key(|value| value.value);
+
+						writeln!(sdl).ok();
+						print_description(&mut sdl, description, "");
+						writeln!(sdl, "enum {} {{", name).ok();
+						for value in values {
+							print_description(&mut sdl, &value.description, "\t");
+							writeln!(sdl, "\t{} = {},", value.name, value.value).ok();
+						}
+						writeln!(sdl, "}}").ok();
+					}
+					ThriftKind::Struct {
+						name,
+						description,
+						fields,
+						..
+					}
+					| ThriftKind::Union {
+						name,
+						description,
+						fields,
+						..
+					}
+					| ThriftKind::Exception {
+						name,
+						description,
+						fields,
+						..
+					} => {
+						let keyword = match type_id {
+							ThriftTypeId::Union => "union",
+							ThriftTypeId::Exception => "exception",
+							_ => "struct",
+						};
+
+						writeln!(sdl).ok();
+						print_description(&mut sdl, description, "");
+						writeln!(sdl, "{} {} {{", keyword, name).ok();
+						for field in sorted(fields.values()) {
+							print_description(&mut sdl, &field.description, "\t");
+							writeln!(sdl, "\t{},", print_field(field)).ok();
+						}
+						writeln!(sdl, "}}").ok();
+					}
+					ThriftKind::Service {
+						name,
+						description,
+						methods,
+						..
+					} => {
+						writeln!(sdl).ok();
+						print_description(&mut sdl, description, "");
+						writeln!(sdl, "service {} {{", name).ok();
+						for method in methods.values() {
+							let arguments = sorted(method.arguments.iter())
+								.into_iter()
+								.map(print_field)
+								.collect::<Vec<_>>()
+								.join(", ");
+
+							print_description(&mut sdl, &method.description, "\t");
+							write!(sdl, "\t").ok();
+							if method.oneway {
+								write!(sdl, "oneway ").ok();
+							}
+							write!(sdl, "{} {}({})", method.return_type, method.name, arguments)
+								.ok();
+							if !method.throws.is_empty() {
+								let throws = sorted(method.throws.iter())
+									.into_iter()
+									.map(print_field)
+									.collect::<Vec<_>>()
+									.join(", ");
+								write!(sdl, " throws ({})", throws).ok();
+							}
+							writeln!(sdl, ",").ok();
+						}
+						writeln!(sdl, "}}").ok();
+					}
+					ThriftKind::Scalar {
+						..
+					} => {}
+				}
+			}
+		}
 
-		// println!("{}", sdl);
 		sdl
 	}
 }
+
+fn sorted<'a>(fields: impl Iterator<Item = &'a ThriftField>) -> Vec<&'a ThriftField> {
+	let mut fields = fields.collect::<Vec<_>>();
+	fields.sort_by_key(|field| field.key);
+	fields
+}
+
+fn print_field(field: &ThriftField) -> String {
+	let requiredness = if field.required {
+		"required"
+	} else {
+		"optional"
+	};
+	format!("{}: {} {} {}", field.key, requiredness, field.field_type, field.name)
+}
+
+fn print_description(sdl: &mut String, description: &Option<String>, indent: &str) {
+	if let Some(description) = description {
+		writeln!(sdl, "{}/** {} */", indent, description.replace("*/", "* /")).ok();
+	}
+}
@@ -0,0 +1,5 @@
+mod protocol;
+mod service;
+
+pub use protocol::*;
+pub use service::*;
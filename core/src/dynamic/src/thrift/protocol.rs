@@ -0,0 +1,800 @@
+use crate::{SeaResult, SeaographyError};
+use bytes::{BufMut, BytesMut};
+
+const BINARY_VERSION: u32 = 0x8001_0000;
+const BINARY_VERSION_MASK: u32 = 0xffff_0000;
+const COMPACT_PROTOCOL_ID: u8 = 0x82;
+const COMPACT_VERSION: u8 = 1;
+/// The maximum depth structs and collections may be nested to, mirrors the group limit of the
+/// proto decoder
+const DEPTH_LIMIT: usize = 100;
+
+/// The wire protocols a thrift message can be encoded with
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Protocol {
+	/// The strict binary protocol
+	Binary,
+	/// The compact protocol
+	Compact,
+}
+
+impl Protocol {
+	/// used to detect the protocol from the first byte of a message
+	pub fn detect(byte: u8) -> Option<Self> {
+		match byte {
+			0x80 => Some(Protocol::Binary),
+			COMPACT_PROTOCOL_ID => Some(Protocol::Compact),
+			_ => None,
+		}
+	}
+}
+
+/// The types of the thrift wire format
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TType {
+	Stop,
+	Bool,
+	Byte,
+	Double,
+	I16,
+	I32,
+	I64,
+	String,
+	Struct,
+	Map,
+	Set,
+	List,
+}
+
+impl TType {
+	fn to_binary(self) -> u8 {
+		match self {
+			TType::Stop => 0,
+			TType::Bool => 2,
+			TType::Byte => 3,
+			TType::Double => 4,
+			TType::I16 => 6,
+			TType::I32 => 8,
+			TType::I64 => 10,
+			TType::String => 11,
+			TType::Struct => 12,
+			TType::Map => 13,
+			TType::Set => 14,
+			TType::List => 15,
+		}
+	}
+
+	fn from_binary(byte: u8) -> SeaResult<Self> {
+		Ok(match byte {
+			0 => TType::Stop,
+			2 => TType::Bool,
+			3 => TType::Byte,
+			4 => TType::Double,
+			6 => TType::I16,
+			8 => TType::I32,
+			10 => TType::I64,
+			11 => TType::String,
+			12 => TType::Struct,
+			13 => TType::Map,
+			14 => TType::Set,
+			15 => TType::List,
+			byte => return Err(SeaographyError::new(format!("Unknown thrift type `{}`", byte))),
+		})
+	}
+
+	fn to_compact(self) -> u8 {
+		match self {
+			TType::Stop => 0,
+			TType::Bool => 1,
+			TType::Byte => 3,
+			TType::I16 => 4,
+			TType::I32 => 5,
+			TType::I64 => 6,
+			TType::Double => 7,
+			TType::String => 8,
+			TType::List => 9,
+			TType::Set => 10,
+			TType::Map => 11,
+			TType::Struct => 12,
+		}
+	}
+
+	fn from_compact(byte: u8) -> SeaResult<Self> {
+		Ok(match byte {
+			0 => TType::Stop,
+			1 | 2 => TType::Bool,
+			3 => TType::Byte,
+			4 => TType::I16,
+			5 => TType::I32,
+			6 => TType::I64,
+			7 => TType::Double,
+			8 => TType::String,
+			9 => TType::List,
+			10 => TType::Set,
+			11 => TType::Map,
+			12 => TType::Struct,
+			byte => return Err(SeaographyError::new(format!("Unknown thrift type `{}`", byte))),
+		})
+	}
+}
+
+/// The types of a thrift message
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MessageType {
+	Call,
+	Reply,
+	Exception,
+	Oneway,
+}
+
+impl MessageType {
+	fn to_u8(self) -> u8 {
+		match self {
+			MessageType::Call => 1,
+			MessageType::Reply => 2,
+			MessageType::Exception => 3,
+			MessageType::Oneway => 4,
+		}
+	}
+
+	fn from_u8(byte: u8) -> SeaResult<Self> {
+		Ok(match byte {
+			1 => MessageType::Call,
+			2 => MessageType::Reply,
+			3 => MessageType::Exception,
+			4 => MessageType::Oneway,
+			byte => {
+				return Err(SeaographyError::new(format!(
+					"Unknown thrift message type `{}`",
+					byte
+				)));
+			}
+		})
+	}
+}
+
+/// The header of a thrift message
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MessageHeader {
+	pub name: String,
+	pub message_type: MessageType,
+	pub seq_id: i32,
+}
+
+/// A reader of thrift messages, it never reads past the end of its buffer and marks itself as
+/// incomplete instead, which allows unframed messages to be read once more bytes arrived
+pub struct Reader<'a> {
+	protocol: Protocol,
+	buf: &'a [u8],
+	pos: usize,
+	incomplete: bool,
+	last_field_id: i16,
+	field_ids: Vec<i16>,
+	depth: usize,
+	bool_value: Option<bool>,
+}
+
+impl<'a> Reader<'a> {
+	pub fn new(protocol: Protocol, buf: &'a [u8]) -> Self {
+		Self {
+			protocol,
+			buf,
+			pos: 0,
+			incomplete: false,
+			last_field_id: 0,
+			field_ids: Vec::new(),
+			depth: 0,
+			bool_value: None,
+		}
+	}
+
+	/// Returns the number of bytes read so far
+	#[inline]
+	pub fn position(&self) -> usize {
+		self.pos
+	}
+
+	/// Returns true when a read ran past the end of the buffer
+	#[inline]
+	pub fn is_incomplete(&self) -> bool {
+		self.incomplete
+	}
+
+	fn take(&mut self, len: usize) -> SeaResult<&'a [u8]> {
+		match self.buf.get(self.pos..self.pos.saturating_add(len)) {
+			Some(bytes) => {
+				self.pos += len;
+				Ok(bytes)
+			}
+			None => {
+				self.incomplete = true;
+				Err(SeaographyError::new("Unexpected end of thrift message"))
+			}
+		}
+	}
+
+	fn take_array<const N: usize>(&mut self) -> SeaResult<[u8; N]> {
+		let mut array = [0u8; N];
+		array.copy_from_slice(self.take(N)?);
+		Ok(array)
+	}
+
+	fn read_u8(&mut self) -> SeaResult<u8> {
+		Ok(self.take(1)?[0])
+	}
+
+	fn read_varint(&mut self) -> SeaResult<u64> {
+		let mut value = 0u64;
+		for shift in (0..64).step_by(7) {
+			let byte = self.read_u8()?;
+			value |= ((byte & 0x7f) as u64) << shift;
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+		Err(SeaographyError::new("Invalid thrift varint"))
+	}
+
+	fn read_zigzag(&mut self) -> SeaResult<i64> {
+		let value = self.read_varint()?;
+		Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+	}
+
+	/// used to check the size of a collection or binary, every element takes at least one byte
+	/// so sizes larger than the rest of the buffer are never allocated
+	fn read_size(&mut self, size: i64) -> SeaResult<usize> {
+		let size = usize::try_from(size)
+			.map_err(|_| SeaographyError::new(format!("Invalid thrift size `{}`", size)))?;
+		if size > self.buf.len() - self.pos {
+			self.incomplete = true;
+			return Err(SeaographyError::new("Unexpected end of thrift message"));
+		}
+		Ok(size)
+	}
+
+	fn read_element_type(&self, ttype: TType, size: usize) -> SeaResult<TType> {
+		match ttype == TType::Stop && size > 0 {
+			true => Err(SeaographyError::new("Invalid thrift element type `Stop`")),
+			false => Ok(ttype),
+		}
+	}
+
+	/// used to enter a nested struct or collection, the depth is limited so that malformed
+	/// messages cannot exhaust the stack of the recursive readers
+	fn enter(&mut self) -> SeaResult<()> {
+		if self.depth >= DEPTH_LIMIT {
+			return Err(SeaographyError::new("Thrift nesting is too deep"));
+		}
+		self.depth += 1;
+		Ok(())
+	}
+
+	fn leave(&mut self) {
+		self.depth = self.depth.saturating_sub(1);
+	}
+
+	pub fn read_message_begin(&mut self) -> SeaResult<MessageHeader> {
+		match self.protocol {
+			Protocol::Binary => {
+				let version = self.read_i32()? as u32;
+				if version & BINARY_VERSION_MASK != BINARY_VERSION {
+					return Err(SeaographyError::new("Unsupported thrift binary protocol version"));
+				}
+				let message_type = MessageType::from_u8((version & 0xff) as u8)?;
+				let name = self.read_string()?;
+				let seq_id = self.read_i32()?;
+
+				Ok(MessageHeader {
+					name,
+					message_type,
+					seq_id,
+				})
+			}
+			Protocol::Compact => {
+				if self.read_u8()? != COMPACT_PROTOCOL_ID {
+					return Err(SeaographyError::new("Invalid thrift compact protocol id"));
+				}
+				let byte = self.read_u8()?;
+				if byte & 0x1f != COMPACT_VERSION {
+					return Err(SeaographyError::new(
+						"Unsupported thrift compact protocol version",
+					));
+				}
+				let message_type = MessageType::from_u8(byte >> 5)?;
+				let seq_id = self.read_varint()? as i32;
+				let name = self.read_string()?;
+
+				Ok(MessageHeader {
+					name,
+					message_type,
+					seq_id,
+				})
+			}
+		}
+	}
+
+	pub fn read_struct_begin(&mut self) -> SeaResult<()> {
+		self.enter()?;
+		self.field_ids.push(self.last_field_id);
+		self.last_field_id = 0;
+		Ok(())
+	}
+
+	pub fn read_struct_end(&mut self) {
+		self.last_field_id = self.field_ids.pop().unwrap_or_default();
+		self.leave();
+	}
+
+	/// used to read the header of the next field, the id is `0` once the `Stop` field is read
+	pub fn read_field_begin(&mut self) -> SeaResult<(TType, i16)> {
+		match self.protocol {
+			Protocol::Binary => {
+				let ttype = TType::from_binary(self.read_u8()?)?;
+				if ttype == TType::Stop {
+					return Ok((ttype, 0));
+				}
+				Ok((ttype, self.read_i16()?))
+			}
+			Protocol::Compact => {
+				let byte = self.read_u8()?;
+				let ttype = TType::from_compact(byte & 0x0f)?;
+				if ttype == TType::Stop {
+					return Ok((ttype, 0));
+				}
+				let id = match byte >> 4 {
+					0 => self.read_zigzag()? as i16,
+					delta => self
+						.last_field_id
+						.checked_add(delta as i16)
+						.ok_or_else(|| SeaographyError::new("Invalid thrift field id delta"))?,
+				};
+				if ttype == TType::Bool {
+					self.bool_value = Some(byte & 0x0f == 1);
+				}
+				self.last_field_id = id;
+				Ok((ttype, id))
+			}
+		}
+	}
+
+	/// used to read the header of a list or set, it must be followed by `read_list_end`
+	pub fn read_list_begin(&mut self) -> SeaResult<(TType, usize)> {
+		self.enter()?;
+		match self.protocol {
+			Protocol::Binary => {
+				let ttype = TType::from_binary(self.read_u8()?)?;
+				let size = self.read_i32()? as i64;
+				let size = self.read_size(size)?;
+				Ok((self.read_element_type(ttype, size)?, size))
+			}
+			Protocol::Compact => {
+				let byte = self.read_u8()?;
+				let ttype = TType::from_compact(byte & 0x0f)?;
+				let size = match byte >> 4 {
+					0x0f => self.read_varint()? as i64,
+					size => size as i64,
+				};
+				let size = self.read_size(size)?;
+				Ok((self.read_element_type(ttype, size)?, size))
+			}
+		}
+	}
+
+	pub fn read_list_end(&mut self) {
+		self.leave();
+	}
+
+	/// used to read the header of a map, it must be followed by `read_map_end`
+	pub fn read_map_begin(&mut self) -> SeaResult<(TType, TType, usize)> {
+		self.enter()?;
+		match self.protocol {
+			Protocol::Binary => {
+				let key = TType::from_binary(self.read_u8()?)?;
+				let value = TType::from_binary(self.read_u8()?)?;
+				let size = self.read_i32()? as i64;
+				let size = self.read_size(size)?;
+				Ok((self.read_element_type(key, size)?, self.read_element_type(value, size)?, size))
+			}
+			Protocol::Compact => {
+				let size = self.read_varint()? as i64;
+				if size == 0 {
+					return Ok((TType::Stop, TType::Stop, 0));
+				}
+				let size = self.read_size(size)?;
+				let byte = self.read_u8()?;
+				Ok((
+					self.read_element_type(TType::from_compact(byte >> 4)?, size)?,
+					self.read_element_type(TType::from_compact(byte & 0x0f)?, size)?,
+					size,
+				))
+			}
+		}
+	}
+
+	pub fn read_map_end(&mut self) {
+		self.leave();
+	}
+
+	pub fn read_bool(&mut self) -> SeaResult<bool> {
+		if let Some(value) = self.bool_value.take() {
+			return Ok(value);
+		}
+		match self.protocol {
+			Protocol::Binary => Ok(self.read_u8()? != 0),
+			Protocol::Compact => Ok(self.read_u8()? == 1),
+		}
+	}
+
+	pub fn read_byte(&mut self) -> SeaResult<i8> {
+		Ok(self.read_u8()? as i8)
+	}
+
+	pub fn read_i16(&mut self) -> SeaResult<i16> {
+		match self.protocol {
+			Protocol::Binary => Ok(i16::from_be_bytes(self.take_array()?)),
+			Protocol::Compact => Ok(self.read_zigzag()? as i16),
+		}
+	}
+
+	pub fn read_i32(&mut self) -> SeaResult<i32> {
+		match self.protocol {
+			Protocol::Binary => Ok(i32::from_be_bytes(self.take_array()?)),
+			Protocol::Compact => Ok(self.read_zigzag()? as i32),
+		}
+	}
+
+	pub fn read_i64(&mut self) -> SeaResult<i64> {
+		match self.protocol {
+			Protocol::Binary => Ok(i64::from_be_bytes(self.take_array()?)),
+			Protocol::Compact => self.read_zigzag(),
+		}
+	}
+
+	pub fn read_double(&mut self) -> SeaResult<f64> {
+		match self.protocol {
+			Protocol::Binary => Ok(f64::from_be_bytes(self.take_array()?)),
+			Protocol::Compact => Ok(f64::from_le_bytes(self.take_array()?)),
+		}
+	}
+
+	pub fn read_binary(&mut self) -> SeaResult<&'a [u8]> {
+		let size = match self.protocol {
+			Protocol::Binary => self.read_i32()? as i64,
+			Protocol::Compact => self.read_varint()? as i64,
+		};
+		let size = self.read_size(size)?;
+		self.take(size)
+	}
+
+	pub fn read_string(&mut self) -> SeaResult<String> {
+		let bytes = self.read_binary()?;
+		String::from_utf8(bytes.to_vec()).map_err(|err| SeaographyError::new(err.to_string()))
+	}
+
+	/// used to skip a value of the given type, e.g. unknown fields
+	pub fn skip(&mut self, ttype: TType) -> SeaResult<()> {
+		match ttype {
+			TType::Stop => Ok(()),
+			TType::Bool => self.read_bool().map(|_| ()),
+			TType::Byte => self.read_byte().map(|_| ()),
+			TType::Double => self.read_double().map(|_| ()),
+			TType::I16 => self.read_i16().map(|_| ()),
+			TType::I32 => self.read_i32().map(|_| ()),
+			TType::I64 => self.read_i64().map(|_| ()),
+			TType::String => self.read_binary().map(|_| ()),
+			TType::Struct => {
+				self.read_struct_begin()?;
+				loop {
+					let (ttype, _) = self.read_field_begin()?;
+					if ttype == TType::Stop {
+						break;
+					}
+					self.skip(ttype)?;
+				}
+				self.read_struct_end();
+				Ok(())
+			}
+			TType::Map => {
+				let (key, value, size) = self.read_map_begin()?;
+				for _ in 0..size {
+					self.skip(key)?;
+					self.skip(value)?;
+				}
+				self.read_map_end();
+				Ok(())
+			}
+			TType::Set | TType::List => {
+				let (ttype, size) = self.read_list_begin()?;
+				for _ in 0..size {
+					self.skip(ttype)?;
+				}
+				self.read_list_end();
+				Ok(())
+			}
+		}
+	}
+}
+
+/// A writer of thrift messages
+pub struct Writer {
+	protocol: Protocol,
+	buf: BytesMut,
+	last_field_id: i16,
+	field_ids: Vec<i16>,
+	bool_field_id: Option<i16>,
+}
+
+impl Writer {
+	pub fn new(protocol: Protocol) -> Self {
+		Self {
+			protocol,
+			buf: BytesMut::new(),
+			last_field_id: 0,
+			field_ids: Vec::new(),
+			bool_field_id: None,
+		}
+	}
+
+	/// Returns the written message
+	pub fn finish(self) -> BytesMut {
+		self.buf
+	}
+
+	fn write_varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.buf.put_u8((value as u8 & 0x7f) | 0x80);
+			value >>= 7;
+		}
+		self.buf.put_u8(value as u8);
+	}
+
+	fn write_zigzag(&mut self, value: i64) {
+		self.write_varint(((value << 1) ^ (value >> 63)) as u64);
+	}
+
+	pub fn write_message_begin(&mut self, header: &MessageHeader) {
+		match self.protocol {
+			Protocol::Binary => {
+				self.write_i32((BINARY_VERSION | header.message_type.to_u8() as u32) as i32);
+				self.write_string(&header.name);
+				self.write_i32(header.seq_id);
+			}
+			Protocol::Compact => {
+				self.buf.put_u8(COMPACT_PROTOCOL_ID);
+				self.buf.put_u8(COMPACT_VERSION | (header.message_type.to_u8() << 5));
+				self.write_varint(header.seq_id as u32 as u64);
+				self.write_string(&header.name);
+			}
+		}
+	}
+
+	pub fn write_struct_begin(&mut self) {
+		self.field_ids.push(self.last_field_id);
+		self.last_field_id = 0;
+	}
+
+	pub fn write_struct_end(&mut self) {
+		self.last_field_id = self.field_ids.pop().unwrap_or_default();
+	}
+
+	fn write_field_header(&mut self, ttype: u8, id: i16) {
+		let delta = id.wrapping_sub(self.last_field_id);
+		if delta > 0 && delta <= 15 {
+			self.buf.put_u8(((delta as u8) << 4) | ttype);
+		} else {
+			self.buf.put_u8(ttype);
+			self.write_zigzag(id as i64);
+		}
+		self.last_field_id = id;
+	}
+
+	pub fn write_field_begin(&mut self, ttype: TType, id: i16) {
+		match self.protocol {
+			Protocol::Binary => {
+				self.buf.put_u8(ttype.to_binary());
+				self.buf.put_i16(id);
+			}
+			// the value of a bool field is written into the header of the field
+			Protocol::Compact if ttype == TType::Bool => self.bool_field_id = Some(id),
+			Protocol::Compact => self.write_field_header(ttype.to_compact(), id),
+		}
+	}
+
+	pub fn write_field_stop(&mut self) {
+		self.buf.put_u8(0);
+	}
+
+	pub fn write_list_begin(&mut self, ttype: TType, size: usize) {
+		match self.protocol {
+			Protocol::Binary => {
+				self.buf.put_u8(ttype.to_binary());
+				self.buf.put_i32(size as i32);
+			}
+			Protocol::Compact if size < 15 => {
+				self.buf.put_u8(((size as u8) << 4) | ttype.to_compact());
+			}
+			Protocol::Compact => {
+				self.buf.put_u8(0xf0 | ttype.to_compact());
+				self.write_varint(size as u64);
+			}
+		}
+	}
+
+	pub fn write_bool(&mut self, value: bool) {
+		match (self.protocol, self.bool_field_id.take()) {
+			(Protocol::Binary, _) => self.buf.put_u8(value as u8),
+			(Protocol::Compact, Some(id)) => self.write_field_header(
+				if value {
+					1
+				} else {
+					2
+				},
+				id,
+			),
+			(Protocol::Compact, None) => self.buf.put_u8(if value {
+				1
+			} else {
+				2
+			}),
+		}
+	}
+
+	pub fn write_byte(&mut self, value: i8) {
+		self.buf.put_i8(value);
+	}
+
+	pub fn write_i16(&mut self, value: i16) {
+		match self.protocol {
+			Protocol::Binary => self.buf.put_i16(value),
+			Protocol::Compact => self.write_zigzag(value as i64),
+		}
+	}
+
+	pub fn write_i32(&mut self, value: i32) {
+		match self.protocol {
+			Protocol::Binary => self.buf.put_i32(value),
+			Protocol::Compact => self.write_zigzag(value as i64),
+		}
+	}
+
+	pub fn write_i64(&mut self, value: i64) {
+		match self.protocol {
+			Protocol::Binary => self.buf.put_i64(value),
+			Protocol::Compact => self.write_zigzag(value),
+		}
+	}
+
+	pub fn write_double(&mut self, value: f64) {
+		match self.protocol {
+			Protocol::Binary => self.buf.put_f64(value),
+			Protocol::Compact => self.buf.put_f64_le(value),
+		}
+	}
+
+	pub fn write_binary(&mut self, value: &[u8]) {
+		match self.protocol {
+			Protocol::Binary => self.buf.put_i32(value.len() as i32),
+			Protocol::Compact => self.write_varint(value.len() as u64),
+		}
+		self.buf.put_slice(value);
+	}
+
+	pub fn write_string(&mut self, value: &str) {
+		self.write_binary(value.as_bytes());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn round_trip(protocol: Protocol) {
+		let header = MessageHeader {
+			name: "user".to_string(),
+			message_type: MessageType::Call,
+			seq_id: 7,
+		};
+
+		let mut writer = Writer::new(protocol);
+		writer.write_message_begin(&header);
+		writer.write_struct_begin();
+		writer.write_field_begin(TType::I32, 1);
+		writer.write_i32(-42);
+		writer.write_field_begin(TType::Bool, 2);
+		writer.write_bool(true);
+		writer.write_field_begin(TType::List, 20);
+		writer.write_list_begin(TType::String, 2);
+		writer.write_string("a");
+		writer.write_string("b");
+		writer.write_field_begin(TType::Struct, 21);
+		writer.write_struct_begin();
+		writer.write_field_begin(TType::I64, 1);
+		writer.write_i64(i64::MIN);
+		writer.write_field_stop();
+		writer.write_struct_end();
+		writer.write_field_begin(TType::Double, 22);
+		writer.write_double(1.5);
+		writer.write_field_stop();
+		writer.write_struct_end();
+		let buf = writer.finish();
+
+		assert_eq!(Protocol::detect(buf[0]), Some(protocol));
+
+		let mut reader = Reader::new(protocol, &buf);
+		assert_eq!(reader.read_message_begin().unwrap(), header);
+		reader.read_struct_begin().unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::I32, 1));
+		assert_eq!(reader.read_i32().unwrap(), -42);
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::Bool, 2));
+		assert!(reader.read_bool().unwrap());
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::List, 20));
+		assert_eq!(reader.read_list_begin().unwrap(), (TType::String, 2));
+		assert_eq!(reader.read_string().unwrap(), "a");
+		assert_eq!(reader.read_string().unwrap(), "b");
+		reader.read_list_end();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::Struct, 21));
+		reader.skip(TType::Struct).unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::Double, 22));
+		assert_eq!(reader.read_double().unwrap(), 1.5);
+		assert_eq!(reader.read_field_begin().unwrap().0, TType::Stop);
+		reader.read_struct_end();
+		assert_eq!(reader.position(), buf.len());
+
+		let mut reader = Reader::new(protocol, &buf[..buf.len() - 1]);
+		assert!(reader.read_message_begin().is_ok());
+		assert!(reader.skip(TType::Struct).is_err());
+		assert!(reader.is_incomplete());
+	}
+
+	#[test]
+	fn binary() {
+		round_trip(Protocol::Binary);
+	}
+
+	#[test]
+	fn compact() {
+		round_trip(Protocol::Compact);
+	}
+
+	#[test]
+	fn malformed() {
+		// a list of `Stop` elements would be skipped without consuming a byte
+		let buf = [0x00, 0x00, 0x00, 0x00, 0x01, 0x00];
+		let mut reader = Reader::new(Protocol::Binary, &buf);
+		assert!(reader.skip(TType::List).is_err());
+		assert!(!reader.is_incomplete());
+
+		// sizes larger than the rest of the buffer are rejected before they are allocated
+		let buf = [0xf8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+		let mut reader = Reader::new(Protocol::Compact, &buf);
+		assert!(reader.read_list_begin().is_err());
+		assert!(reader.is_incomplete());
+
+		// nested structs are limited in depth
+		let buf = vec![0x1c; DEPTH_LIMIT + 1];
+		let mut reader = Reader::new(Protocol::Compact, &buf);
+		assert!(reader.skip(TType::Struct).is_err());
+		assert!(!reader.is_incomplete());
+
+		// nested collections share the limit, each byte is a list holding one list
+		let buf = vec![0x19; 1 << 20];
+		let mut reader = Reader::new(Protocol::Compact, &buf);
+		assert!(reader.skip(TType::List).is_err());
+		assert!(!reader.is_incomplete());
+		assert_eq!(reader.position(), DEPTH_LIMIT);
+
+		// and so do maps, each pair of bytes is a map with one map key
+		let buf = [0x01, 0xbb].repeat(1 << 19);
+		let mut reader = Reader::new(Protocol::Compact, &buf);
+		assert!(reader.skip(TType::Map).is_err());
+		assert!(!reader.is_incomplete());
+		assert_eq!(reader.position(), 2 * DEPTH_LIMIT);
+
+		// field id deltas must not overflow the field id
+		let buf = [0xf5, 0x00];
+		let mut reader = Reader::new(Protocol::Compact, &buf);
+		reader.read_struct_begin().unwrap();
+		reader.last_field_id = i16::MAX;
+		assert!(reader.read_field_begin().is_err());
+	}
+}
@@ -0,0 +1,829 @@
+use super::{MessageHeader, MessageType, Protocol, Reader, TType, Writer};
+use crate::{
+	ApiType, ContextBase, FieldFuture, FieldValue, FieldValueInner, ObjectAccessor,
+	ResolverContext, SeaResult, SeaographyError, ThriftEnumValue, ThriftField, ThriftKind,
+	ThriftMethod, ThriftRegistry, Value,
+	proto::{Field, PCKNAME, Proto, Type, TypeRef, TypeRegistry},
+};
+use bytes::BytesMut;
+use futures::{FutureExt, future::BoxFuture};
+use juniper::ScalarValue;
+use ordered_float::OrderedFloat;
+use std::{borrow::Cow, collections::BTreeMap};
+
+/// The maximum depth nested messages are resolved to, mirrors the proto encoder
+const MAX_DEPTH: usize = 5;
+
+/// The `TApplicationException` types sent back to the client
+const UNKNOWN_METHOD: i32 = 1;
+const INVALID_MESSAGE_TYPE: i32 = 2;
+const INTERNAL_ERROR: i32 = 6;
+
+/// The outcome of reading a thrift message from a buffer
+pub enum ThriftRequest {
+	/// More bytes are needed to read the message
+	Incomplete,
+	/// The message was read, `consumed` bytes of the buffer belong to it
+	Call {
+		consumed: usize,
+		protocol: Protocol,
+		header: MessageHeader,
+		method: Option<(String, String)>,
+		arguments: BTreeMap<Value, Value>,
+	},
+}
+
+impl Proto {
+	/// Returns the thrift registry of this proto, every service becomes a thrift service and
+	/// every other message a thrift struct
+	pub fn thrift_registry(&self) -> ThriftRegistry {
+		let mut registry = ThriftRegistry::new();
+		registry.namespace = Some(PCKNAME.to_string());
+
+		for (name, ty) in self.0.types.all() {
+			let kind = match &*ty {
+				Type::Message(message) if self.0.services.contains(&name) => ThriftKind::Service {
+					name: name.clone(),
+					description: message.description.clone(),
+					methods: message
+						.fields
+						.values()
+						// thrift has no streams, server streaming fields are grpc only
+						.filter(|field| !field.server_streaming)
+						.map(|field| {
+							let method = ThriftMethod {
+								name: field.name.clone(),
+								description: field.description.clone(),
+								return_type: thrift_type(&self.0.types, field),
+								arguments: field
+									.arguments
+									.values()
+									.map(|argument| thrift_field(&self.0.types, argument))
+									.collect(),
+								throws: Vec::new(),
+								oneway: false,
+							};
+							(field.name.clone(), method)
+						})
+						.collect(),
+					visible: None,
+					rust_typename: None,
+				},
+				Type::Message(message) => {
+					let fields = message
+						.fields
+						.values()
+						.map(|field| (field.name.clone(), thrift_field(&self.0.types, field)))
+						.collect();
+
+					if message.oneof {
+						ThriftKind::Union {
+							name: name.clone(),
+							description: message.description.clone(),
+							fields,
+							visible: None,
+							rust_typename: None,
+						}
+					} else {
+						ThriftKind::Struct {
+							name: name.clone(),
+							description: message.description.clone(),
+							fields,
+							visible: None,
+							rust_typename: None,
+						}
+					}
+				}
+				Type::Enum(en) => ThriftKind::Enum {
+					name: name.clone(),
+					description: en.description.clone(),
+					fields: en
+						.enum_values
+						.values()
+						.enumerate()
+						.map(|(index, item)| {
+							let value = ThriftEnumValue {
+								name: item.name.clone(),
+								description: item.description.clone(),
+								value: item.tag.unwrap_or(index as u32) as i32,
+							};
+							(item.name.clone(), value)
+						})
+						.collect(),
+					visible: None,
+					rust_typename: None,
+				},
+			};
+
+			registry.types.insert(name, kind);
+		}
+
+		registry
+	}
+
+	/// used to read a thrift call from the start of the buffer, the arguments are decoded
+	/// with the argument definitions of the called service field
+	pub fn read_thrift(&self, buf: &[u8]) -> SeaResult<ThriftRequest> {
+		let protocol = match buf.first() {
+			Some(byte) => Protocol::detect(*byte)
+				.ok_or_else(|| SeaographyError::new("Unknown thrift protocol"))?,
+			None => return Ok(ThriftRequest::Incomplete),
+		};
+
+		let mut reader = Reader::new(protocol, buf);
+		let mut arguments = BTreeMap::new();
+		let res = self.read_call(&mut reader, &mut arguments);
+		if reader.is_incomplete() {
+			return Ok(ThriftRequest::Incomplete);
+		}
+		let (header, method) = res?;
+
+		Ok(ThriftRequest::Call {
+			consumed: reader.position(),
+			protocol,
+			header,
+			method,
+			arguments,
+		})
+	}
+
+	fn read_call(
+		&self,
+		reader: &mut Reader,
+		arguments: &mut BTreeMap<Value, Value>,
+	) -> SeaResult<(MessageHeader, Option<(String, String)>)> {
+		let header = reader.read_message_begin()?;
+		let method = self.method(&header.name);
+
+		match &method {
+			Some((service, name)) => {
+				let ty = self.0.types.get(service);
+				let field = ty
+					.as_ref()
+					.and_then(|ty| ty.as_message())
+					.and_then(|message| message.get_field(name));
+				read_struct(
+					&self.0.types,
+					reader,
+					|tag| field.and_then(|field| field.argument_by_tag(tag)),
+					arguments,
+				)?;
+			}
+			None => reader.skip(TType::Struct)?,
+		}
+
+		Ok((header, method))
+	}
+
+	/// used to find the service and field of a method, multiplexed names are prefixed with
+	/// the service, e.g. `Query:user`
+	fn method(&self, name: &str) -> Option<(String, String)> {
+		let (services, name): (Vec<&String>, &str) = match name.split_once(':') {
+			Some((service, name)) => {
+				(self.0.services.iter().filter(|s| *s == service).collect(), name)
+			}
+			None => (self.0.services.iter().collect(), name),
+		};
+
+		services.into_iter().find_map(|service| {
+			let ty = self.0.types.get(service)?;
+			ty.as_message()?.get_field(name).filter(|field| !field.server_streaming)?;
+			Some((service.clone(), name.to_string()))
+		})
+	}
+
+	/// used to execute a thrift call read by `read_thrift`, returns the encoded reply or
+	/// `None` for oneway calls
+	pub async fn execute_thrift(
+		&self,
+		protocol: Protocol,
+		header: MessageHeader,
+		method: Option<(String, String)>,
+		arguments: BTreeMap<Value, Value>,
+	) -> Option<BytesMut> {
+		let oneway = header.message_type == MessageType::Oneway;
+		if !oneway && header.message_type != MessageType::Call {
+			return Some(exception(
+				protocol,
+				header,
+				INVALID_MESSAGE_TYPE,
+				"Expected a thrift call",
+			));
+		}
+
+		let (service, name) = match method {
+			Some(method) => method,
+			None => {
+				let message = format!("Unknown method `{}`", header.name);
+				return Some(exception(protocol, header, UNKNOWN_METHOD, &message));
+			}
+		};
+
+		let res = self.call(protocol, &header, &service, &name, arguments).await;
+		match (res, oneway) {
+			(_, true) => None,
+			(Ok(buf), false) => Some(buf),
+			(Err(err), false) => {
+				Some(exception(protocol, header, INTERNAL_ERROR, &err.to_string()))
+			}
+		}
+	}
+
+	async fn call(
+		&self,
+		protocol: Protocol,
+		header: &MessageHeader,
+		service: &str,
+		name: &str,
+		arguments: BTreeMap<Value, Value>,
+	) -> SeaResult<BytesMut> {
+		let types = &self.0.types;
+		let ty = types
+			.get(service)
+			.ok_or_else(|| SeaographyError::new(format!("Service `{}` not found", service)))?;
+		let field = ty
+			.as_message()
+			.and_then(|message| message.get_field(name))
+			.ok_or_else(|| SeaographyError::new(format!("Method `{}` not found", name)))?;
+
+		let mut ctx = ContextBase::new(ApiType::Thrift);
+		ctx.execute_data = Some(self.0.data.clone());
+
+		let accessor = ObjectAccessor(Cow::Owned(arguments));
+		let value = resolve(types, &ctx, field, &accessor, &FieldValue::NULL, 0).await?;
+
+		let mut writer = Writer::new(protocol);
+		writer.write_message_begin(&MessageHeader {
+			name: header.name.clone(),
+			message_type: MessageType::Reply,
+			seq_id: header.seq_id,
+		});
+		writer.write_struct_begin();
+		write_field(types, &mut writer, field, 0, &value)?;
+		writer.write_field_stop();
+		writer.write_struct_end();
+
+		Ok(writer.finish())
+	}
+}
+
+/// used to build a `TApplicationException` reply
+fn exception(protocol: Protocol, header: MessageHeader, ty: i32, message: &str) -> BytesMut {
+	let mut writer = Writer::new(protocol);
+	writer.write_message_begin(&MessageHeader {
+		message_type: MessageType::Exception,
+		..header
+	});
+	writer.write_struct_begin();
+	writer.write_field_begin(TType::String, 1);
+	writer.write_string(message);
+	writer.write_field_begin(TType::I32, 2);
+	writer.write_i32(ty);
+	writer.write_field_stop();
+	writer.write_struct_end();
+	writer.finish()
+}
+
+fn is_repeated(field: &Field) -> bool {
+	field.repeated || field.ty.is_repeated()
+}
+
+/// used to map a proto type name to its thrift type name
+fn thrift_type_name(type_name: &str) -> &str {
+	match type_name {
+		TypeRef::DOUBLE | TypeRef::FLOAT => "double",
+		TypeRef::INT32 | TypeRef::SINT32 | TypeRef::SFIXED32 => "i32",
+		TypeRef::INT64
+		| TypeRef::SINT64
+		| TypeRef::SFIXED64
+		| TypeRef::UINT32
+		| TypeRef::FIXED32
+		| TypeRef::UINT64
+		| TypeRef::FIXED64 => "i64",
+		TypeRef::BOOL => "bool",
+		TypeRef::STRING => "string",
+		TypeRef::BYTES => "binary",
+		name => name,
+	}
+}
+
+fn thrift_type(types: &TypeRegistry, field: &Field) -> String {
+	let name = match types.get(field.ty.type_name()) {
+		Some(ty) => ty.type_name().to_string(),
+		None => thrift_type_name(field.ty.type_name()).to_string(),
+	};
+
+	if is_repeated(field) {
+		format!("list<{}>", name)
+	} else {
+		name
+	}
+}
+
+fn thrift_field(types: &TypeRegistry, field: &Field) -> ThriftField {
+	ThriftField {
+		name: field.name.clone(),
+		description: field.description.clone(),
+		field_type: thrift_type(types, field),
+		key: field.tag as i16,
+		required: !field.optional && matches!(field.ty, TypeRef::NonNull(_)),
+	}
+}
+
+/// used to get the wire type of a single value of a proto type
+fn ttype(types: &TypeRegistry, type_name: &str) -> TType {
+	match type_name {
+		TypeRef::DOUBLE | TypeRef::FLOAT => TType::Double,
+		TypeRef::INT32 | TypeRef::SINT32 | TypeRef::SFIXED32 => TType::I32,
+		TypeRef::INT64
+		| TypeRef::SINT64
+		| TypeRef::SFIXED64
+		| TypeRef::UINT32
+		| TypeRef::FIXED32
+		| TypeRef::UINT64
+		| TypeRef::FIXED64 => TType::I64,
+		TypeRef::BOOL => TType::Bool,
+		TypeRef::STRING | TypeRef::BYTES => TType::String,
+		name => match types.get(name).as_deref() {
+			Some(Type::Enum(_)) => TType::I32,
+			_ => TType::Struct,
+		},
+	}
+}
+
+/// used to read a struct into `values`, fields are looked up by their id and unknown fields or
+/// fields of another type are skipped
+fn read_struct<'a, F>(
+	types: &TypeRegistry,
+	reader: &mut Reader,
+	field_by_tag: F,
+	values: &mut BTreeMap<Value, Value>,
+) -> SeaResult<()>
+where
+	F: Fn(u32) -> Option<&'a Field>,
+{
+	reader.read_struct_begin()?;
+	loop {
+		let (wire_type, id) = reader.read_field_begin()?;
+		if wire_type == TType::Stop {
+			break;
+		}
+
+		let field = match u32::try_from(id).ok().and_then(&field_by_tag) {
+			Some(field) => field,
+			None => {
+				reader.skip(wire_type)?;
+				continue;
+			}
+		};
+
+		let expected = match is_repeated(field) {
+			true => TType::List,
+			false => ttype(types, field.ty.type_name()),
+		};
+		if wire_type != expected {
+			reader.skip(wire_type)?;
+			continue;
+		}
+
+		let value = match is_repeated(field) {
+			true => {
+				// the reader rejects sizes larger than the rest of the message
+				let (element, size) = reader.read_list_begin()?;
+				let mut list = Vec::with_capacity(size);
+				for _ in 0..size {
+					if element == ttype(types, field.ty.type_name()) {
+						list.push(read_value(types, reader, field.ty.type_name())?);
+					} else {
+						reader.skip(element)?;
+					}
+				}
+				reader.read_list_end();
+				Value::List(list)
+			}
+			false => read_value(types, reader, field.ty.type_name())?,
+		};
+
+		values.insert(Value::from(field.name.as_str()), value);
+	}
+	reader.read_struct_end();
+
+	Ok(())
+}
+
+fn read_value(types: &TypeRegistry, reader: &mut Reader, type_name: &str) -> SeaResult<Value> {
+	Ok(match type_name {
+		TypeRef::DOUBLE => Value::Float64(OrderedFloat(reader.read_double()?)),
+		TypeRef::FLOAT => Value::Float32(OrderedFloat(reader.read_double()? as f32)),
+		TypeRef::INT32 | TypeRef::SINT32 | TypeRef::SFIXED32 => Value::Int32(reader.read_i32()?),
+		TypeRef::INT64 | TypeRef::SINT64 | TypeRef::SFIXED64 => Value::Int64(reader.read_i64()?),
+		TypeRef::UINT32 | TypeRef::FIXED32 => {
+			let value = reader.read_i64()?;
+			Value::UInt32(u32::try_from(value).map_err(|_| {
+				SeaographyError::new(format!("`{}` is not a valid {}", value, type_name))
+			})?)
+		}
+		TypeRef::UINT64 | TypeRef::FIXED64 => Value::UInt64(reader.read_i64()? as u64),
+		TypeRef::BOOL => Value::Bool(reader.read_bool()?),
+		TypeRef::STRING => Value::String(reader.read_string()?),
+		TypeRef::BYTES => Value::from(reader.read_binary()?.to_vec()),
+		name => match types.get(name).as_deref() {
+			Some(Type::Enum(en)) => {
+				let tag = reader.read_i32()?;
+				match en.get_item_by_tag(tag as u32) {
+					Some(item) => Value::from(item.type_name()),
+					None => {
+						return Err(SeaographyError::new(format!(
+							"enum `{}` has no value with tag `{}`",
+							name, tag
+						)));
+					}
+				}
+			}
+			Some(Type::Message(message)) => {
+				let mut values = BTreeMap::new();
+				read_struct(types, reader, |tag| message.field_by_tag(tag), &mut values)?;
+				Value::Map(values)
+			}
+			None => return Err(SeaographyError::new(format!("Unknown type `{}`", name))),
+		},
+	})
+}
+
+/// used to resolve a field into a plain value, nested messages are resolved field by field
+/// with the resolved value as parent value
+fn resolve<'a>(
+	types: &'a TypeRegistry,
+	ctx: &'a ContextBase,
+	field: &'a Field,
+	arguments: &'a ObjectAccessor<'a>,
+	parent_value: &'a FieldValue<'a>,
+	depth: usize,
+) -> BoxFuture<'a, SeaResult<Value>> {
+	async move {
+		let field_future = match &field.resolver_fn {
+			Some(resolver_fn) => (resolver_fn)(ResolverContext {
+				ctx,
+				args: arguments.clone(),
+				parent_value,
+			}),
+			None => FieldFuture::Value(None),
+		};
+
+		let value = match field_future {
+			FieldFuture::Value(value) => value,
+			FieldFuture::Future(future) => future.await?,
+		};
+
+		match value {
+			Some(value) => to_value(types, ctx, field, &value, arguments, depth).await,
+			None => Ok(field.default_value.clone().unwrap_or(Value::Null)),
+		}
+	}
+	.boxed()
+}
+
+fn to_value<'a>(
+	types: &'a TypeRegistry,
+	ctx: &'a ContextBase,
+	field: &'a Field,
+	value: &'a FieldValue<'a>,
+	arguments: &'a ObjectAccessor<'a>,
+	depth: usize,
+) -> BoxFuture<'a, SeaResult<Value>> {
+	async move {
+		match &value.0 {
+			FieldValueInner::Value(value) => Ok(value.clone()),
+			FieldValueInner::List(values) => {
+				let mut list = Vec::with_capacity(values.len());
+				for value in values {
+					list.push(to_value(types, ctx, field, value, arguments, depth).await?);
+				}
+				Ok(Value::List(list))
+			}
+			FieldValueInner::WithType {
+				value,
+				..
+			} => to_value(types, ctx, field, value, arguments, depth).await,
+			FieldValueInner::OwnedAny(..) | FieldValueInner::BorrowedAny(..) => {
+				let ty = types.get(field.ty.type_name());
+				let message = match ty.as_ref().and_then(|ty| ty.as_message()) {
+					Some(message) => message,
+					None => {
+						return Err(SeaographyError::new(format!(
+							"Type `{}` needs to be of type `Message`",
+							field.ty.type_name()
+						)));
+					}
+				};
+
+				let mut values = BTreeMap::new();
+				if depth + 1 < MAX_DEPTH {
+					for child in message.fields.values() {
+						let child_value =
+							resolve(types, ctx, child, arguments, value, depth + 1).await?;
+						values.insert(Value::from(child.name.as_str()), child_value);
+					}
+				}
+				Ok(Value::Map(values))
+			}
+		}
+	}
+	.boxed()
+}
+
+/// used to write a resolved value as field `id` of the current struct, null values are omitted
+fn write_field(
+	types: &TypeRegistry,
+	writer: &mut Writer,
+	field: &Field,
+	id: i16,
+	value: &Value,
+) -> SeaResult<()> {
+	let element = ttype(types, field.ty.type_name());
+
+	match (value, is_repeated(field)) {
+		(Value::Null, _) => {}
+		(Value::Option(value), _) => {
+			if let Some(value) = &**value {
+				write_field(types, writer, field, id, value)?;
+			}
+		}
+		(Value::List(values), true) => {
+			writer.write_field_begin(TType::List, id);
+			writer.write_list_begin(element, values.len());
+			for value in values {
+				write_value(types, writer, field.ty.type_name(), value)?;
+			}
+		}
+		(value, _) => {
+			writer.write_field_begin(element, id);
+			write_value(types, writer, field.ty.type_name(), value)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn write_value(
+	types: &TypeRegistry,
+	writer: &mut Writer,
+	type_name: &str,
+	value: &Value,
+) -> SeaResult<()> {
+	let invalid =
+		|| SeaographyError::new(format!("Value `{}` is not a valid {}", value, type_name));
+
+	match type_name {
+		TypeRef::DOUBLE | TypeRef::FLOAT => {
+			writer.write_double(value.as_float().ok_or_else(invalid)?)
+		}
+		TypeRef::INT32 | TypeRef::SINT32 | TypeRef::SFIXED32 => {
+			writer.write_i32(value.as_int().ok_or_else(invalid)?)
+		}
+		TypeRef::INT64
+		| TypeRef::SINT64
+		| TypeRef::SFIXED64
+		| TypeRef::UINT32
+		| TypeRef::FIXED32
+		| TypeRef::UINT64
+		| TypeRef::FIXED64 => writer.write_i64(as_i64(value).ok_or_else(invalid)?),
+		TypeRef::BOOL => writer.write_bool(value.as_bool().ok_or_else(invalid)?),
+		TypeRef::STRING => writer.write_string(&value.as_string().ok_or_else(invalid)?),
+		TypeRef::BYTES => match value {
+			Value::String(value) => writer.write_binary(value.as_bytes()),
+			Value::List(values) => {
+				let bytes = values
+					.iter()
+					.map(|value| match value {
+						Value::UInt8(byte) => Some(*byte),
+						_ => None,
+					})
+					.collect::<Option<Vec<u8>>>()
+					.ok_or_else(invalid)?;
+				writer.write_binary(&bytes);
+			}
+			_ => return Err(invalid()),
+		},
+		name => match types.get(name).as_deref() {
+			Some(Type::Enum(en)) => {
+				let tag = value
+					.as_string()
+					.and_then(|name| en.get_item(&name).and_then(|item| item.tag))
+					.ok_or_else(invalid)?;
+				writer.write_i32(tag as i32);
+			}
+			Some(Type::Message(message)) => {
+				let values = match value {
+					Value::Map(values) => values,
+					_ => return Err(invalid()),
+				};
+
+				writer.write_struct_begin();
+				let mut fields = message.fields.values().collect::<Vec<_>>();
+				fields.sort_by_key(|field| field.tag);
+				for field in fields {
+					if let Some(value) = values.get(&Value::from(field.name.as_str())) {
+						write_field(types, writer, field, field.tag as i16, value)?;
+					}
+				}
+				writer.write_field_stop();
+				writer.write_struct_end();
+			}
+			None => return Err(SeaographyError::new(format!("Unknown type `{}`", name))),
+		},
+	}
+
+	Ok(())
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+	match value {
+		Value::Int64(value) => Some(*value),
+		Value::Int128(value) => (*value).try_into().ok(),
+		Value::Intsize(value) => (*value).try_into().ok(),
+		Value::UInt32(value) => Some((*value).into()),
+		Value::UInt64(value) => Some(*value as i64),
+		Value::UInt128(value) => (*value).try_into().ok(),
+		Value::UIntsize(value) => (*value).try_into().ok(),
+		value => value.as_int().map(Into::into),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		TypeRefTrait,
+		proto::{Enum, EnumItem, Message},
+	};
+
+	fn proto() -> Proto {
+		let status =
+			Enum::new("Status").items([EnumItem::new("ACTIVE"), EnumItem::new("INACTIVE")]);
+
+		let user = Message::new("User")
+			.field(Field::output("id", 1, TypeRef::named_nn(TypeRef::INT32), |ctx| {
+				FieldFuture::new(async move {
+					Ok(Some(Value::from(*ctx.parent_value.try_downcast_ref::<i32>()?)))
+				})
+			}))
+			.field(Field::output("name", 2, TypeRef::named_nn(TypeRef::STRING), |ctx| {
+				FieldFuture::new(async move {
+					let id = ctx.parent_value.try_downcast_ref::<i32>()?;
+					Ok(Some(Value::from(format!("user-{}", id))))
+				})
+			}))
+			.field(Field::output("status", 3, TypeRef::named_nn("Status"), |_| {
+				FieldFuture::new(async move { Ok(Some(Value::from("INACTIVE"))) })
+			}))
+			.field(
+				Field::output("tags", 4, TypeRef::named_list(TypeRef::STRING), |_| {
+					FieldFuture::new(async move {
+						Ok(Some(FieldValue::list([Value::from("a"), Value::from("b")])))
+					})
+				})
+				.set_optional(),
+			);
+
+		let query = Message::new("Query").field(
+			Field::output("user", 1, TypeRef::named_nn("User"), |ctx| {
+				FieldFuture::new(async move {
+					let id = ctx.args.try_get("id")?.int32()?;
+					if id < 0 {
+						return Err(SeaographyError::new("User not found"));
+					}
+					Ok(Some(FieldValue::owned_any(id)))
+				})
+			})
+			.argument(Field::input("id", 1, TypeRef::named_nn(TypeRef::INT32))),
+		);
+
+		Proto::build(vec!["Query"])
+			.register(status)
+			.register(user)
+			.register(query)
+			.finish()
+			.unwrap()
+	}
+
+	async fn call(proto: &Proto, protocol: Protocol, name: &str, id: i32) -> BytesMut {
+		let mut writer = Writer::new(protocol);
+		writer.write_message_begin(&MessageHeader {
+			name: name.to_string(),
+			message_type: MessageType::Call,
+			seq_id: 3,
+		});
+		writer.write_struct_begin();
+		writer.write_field_begin(TType::I32, 1);
+		writer.write_i32(id);
+		writer.write_field_stop();
+		writer.write_struct_end();
+		let request = writer.finish();
+
+		match proto.read_thrift(&request[..request.len() - 1]).unwrap() {
+			ThriftRequest::Incomplete => {}
+			_ => panic!("expected an incomplete request"),
+		}
+
+		match proto.read_thrift(&request).unwrap() {
+			ThriftRequest::Call {
+				consumed,
+				protocol,
+				header,
+				method,
+				arguments,
+			} => {
+				assert_eq!(consumed, request.len());
+				proto.execute_thrift(protocol, header, method, arguments).await.unwrap()
+			}
+			ThriftRequest::Incomplete => panic!("expected a complete request"),
+		}
+	}
+
+	async fn reply(protocol: Protocol) {
+		let proto = proto();
+		let buf = call(&proto, protocol, "user", 42).await;
+
+		let mut reader = Reader::new(protocol, &buf);
+		let header = reader.read_message_begin().unwrap();
+		assert_eq!(header.message_type, MessageType::Reply);
+		assert_eq!(header.seq_id, 3);
+
+		reader.read_struct_begin().unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::Struct, 0));
+		reader.read_struct_begin().unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::I32, 1));
+		assert_eq!(reader.read_i32().unwrap(), 42);
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::String, 2));
+		assert_eq!(reader.read_string().unwrap(), "user-42");
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::I32, 3));
+		assert_eq!(reader.read_i32().unwrap(), 2);
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::List, 4));
+		assert_eq!(reader.read_list_begin().unwrap(), (TType::String, 2));
+		assert_eq!(reader.read_string().unwrap(), "a");
+		assert_eq!(reader.read_string().unwrap(), "b");
+		reader.read_list_end();
+		assert_eq!(reader.read_field_begin().unwrap().0, TType::Stop);
+		reader.read_struct_end();
+		assert_eq!(reader.read_field_begin().unwrap().0, TType::Stop);
+		reader.read_struct_end();
+		assert_eq!(reader.position(), buf.len());
+
+		let buf = call(&proto, protocol, "Query:user", -1).await;
+		let mut reader = Reader::new(protocol, &buf);
+		assert_eq!(reader.read_message_begin().unwrap().message_type, MessageType::Exception);
+		reader.read_struct_begin().unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::String, 1));
+		assert_eq!(reader.read_string().unwrap(), "[custom] User not found");
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::I32, 2));
+		assert_eq!(reader.read_i32().unwrap(), INTERNAL_ERROR);
+
+		let buf = call(&proto, protocol, "users", 1).await;
+		let mut reader = Reader::new(protocol, &buf);
+		assert_eq!(reader.read_message_begin().unwrap().message_type, MessageType::Exception);
+		reader.read_struct_begin().unwrap();
+		assert_eq!(reader.read_field_begin().unwrap(), (TType::String, 1));
+		assert_eq!(reader.read_string().unwrap(), "Unknown method `users`");
+	}
+
+	#[tokio::test]
+	async fn binary() {
+		reply(Protocol::Binary).await;
+	}
+
+	#[tokio::test]
+	async fn compact() {
+		reply(Protocol::Compact).await;
+	}
+
+	#[test]
+	fn idl() {
+		let idl = proto().thrift_registry().build();
+
+		assert_eq!(
+			idl,
+			[
+				"namespace * apy",
+				"",
+				"enum Status {",
+				"\tACTIVE = 1,",
+				"\tINACTIVE = 2,",
+				"}",
+				"",
+				"struct User {",
+				"\t1: required i32 id,",
+				"\t2: required string name,",
+				"\t3: required Status status,",
+				"\t4: optional list<string> tags,",
+				"}",
+				"",
+				"service Query {",
+				"\tUser user(1: required i32 id),",
+				"}",
+				"",
+			]
+			.join("\n")
+		);
+	}
+}
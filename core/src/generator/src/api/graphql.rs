@@ -1,5 +1,5 @@
 use crate::{entity, types::WebFrameworkEnum, util::escape_rust_keyword};
-use heck::ToUpperCamelCase;
+use heck::{ToLowerCamelCase, ToUpperCamelCase};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use std::collections::BTreeMap;
@@ -23,6 +23,60 @@ impl Graphql {
 				crate
 			},
 		};
+
+		// entities having a deleted_at column are soft deleted
+		let soft_deletes: Vec<TokenStream> = entities
+			.iter()
+			.filter_map(|entity| {
+				let column = entity.columns.iter().find(|column| column.name == "deleted_at")?;
+
+				let entity_name = entity.table_name.to_upper_camel_case();
+				let column_name = column.name.to_lower_camel_case();
+
+				Some(quote! {
+					context.soft_deletes.entity_soft_deletes.insert(#entity_name.into(), #column_name.into());
+				})
+			})
+			.collect();
+
+		// unique indexes spanning more than one column are conflict targets of upserts
+		let unique_keys: Vec<TokenStream> = entities
+			.iter()
+			.filter(|entity| !entity.unique_keys.is_empty())
+			.map(|entity| {
+				let entity_name = entity.table_name.to_upper_camel_case();
+				let unique_keys = entity.unique_keys.iter().map(|unique_key| {
+					let column_names =
+						unique_key.iter().map(|column_name| column_name.to_lower_camel_case());
+
+					quote! {
+						vec![#(#column_names.into()),*]
+					}
+				});
+
+				quote! {
+					context.entity_upsert_mutation.unique_keys.insert(#entity_name.into(), vec![#(#unique_keys),*]);
+				}
+			})
+			.collect();
+
+		let context = match soft_deletes.is_empty() && unique_keys.is_empty() {
+			true => quote! {
+				BuilderContext::default()
+			},
+			false => quote! {
+				{
+					let mut context = BuilderContext::default();
+
+					#(#soft_deletes)*
+
+					#(#unique_keys)*
+
+					context
+				}
+			},
+		};
+
 		let entities: Vec<TokenStream> = entities
 			.iter()
 			.map(|entity| {
@@ -56,7 +110,7 @@ impl Graphql {
 			use sea_orm::DatabaseConnection;
 
 			lazy_static::lazy_static! {
-				static ref CONTEXT: BuilderContext = BuilderContext::default();
+				static ref CONTEXT: BuilderContext = #context;
 			}
 
 			fn builder<T, E, F>(database: &DatabaseConnection) -> DynamicBuilder<T, E>
@@ -96,9 +150,9 @@ impl Graphql {
 			}
 
 			impl QueryRoot<DynamicSchema> for Schema {
-				fn config_schema(&mut self, depth: u16, complexity: u16) {
-					self.depth = Some(depth);
-					self.complexity = Some(complexity);
+				fn config_schema(&mut self, depth: Option<u16>, complexity: Option<u16>) {
+					self.depth = depth;
+					self.complexity = complexity;
 				}
 
 				fn root(&self, database: &DatabaseConnection)-> Result<DynamicSchema, SchemaError> {
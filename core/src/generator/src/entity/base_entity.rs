@@ -16,6 +16,8 @@ pub struct Entity {
 	pub(crate) relations: Vec<Relation>,
 	pub(crate) conjunct_relations: Vec<ConjunctRelation>,
 	pub(crate) primary_keys: Vec<PrimaryKey>,
+	/// the column names of the unique indexes spanning more than one column
+	pub(crate) unique_keys: Vec<Vec<String>>,
 }
 
 impl Entity {
@@ -315,6 +317,7 @@ mod tests {
 			primary_keys: vec![PrimaryKey {
 				name: "id".to_owned(),
 			}],
+			unique_keys: vec![],
 		}
 	}
 
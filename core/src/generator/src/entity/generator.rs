@@ -532,6 +532,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "_cake_filling_".to_owned(),
@@ -584,6 +585,7 @@ mod tests {
 						name: "filling_id".to_owned(),
 					},
 				],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "cake_filling_price".to_owned(),
@@ -630,6 +632,7 @@ mod tests {
 						name: "filling_id".to_owned(),
 					},
 				],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "filling".to_owned(),
@@ -657,6 +660,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "fruit".to_owned(),
@@ -711,6 +715,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "vendor".to_owned(),
@@ -752,6 +757,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "rust_keyword".to_owned(),
@@ -909,6 +915,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "cake_with_float".to_owned(),
@@ -953,6 +960,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "cake_with_double".to_owned(),
@@ -997,6 +1005,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "collection".to_owned(),
@@ -1028,6 +1037,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "collection_float".to_owned(),
@@ -1059,6 +1069,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "parent".to_owned(),
@@ -1098,6 +1109,7 @@ mod tests {
 						name: "id2".to_owned(),
 					},
 				],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "child".to_owned(),
@@ -1139,6 +1151,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 		]
 	}
@@ -1520,6 +1533,7 @@ mod tests {
 			primary_keys: vec![PrimaryKey {
 				name: "id".to_owned(),
 			}],
+			unique_keys: vec![],
 		};
 
 		assert_eq!(cake_entity.get_table_name_snake_case(), "cake");
@@ -1913,6 +1927,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 		];
 		const ENTITY_FILES: [&str; 1] = [include_str!("../../tests/postgres/binary_json.rs")];
@@ -2015,6 +2030,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 			Entity {
 				table_name: "tea_pairing_with_size".to_owned(),
@@ -2072,6 +2088,7 @@ mod tests {
 				primary_keys: vec![PrimaryKey {
 					name: "id".to_owned(),
 				}],
+				unique_keys: vec![],
 			},
 		];
 
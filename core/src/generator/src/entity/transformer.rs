@@ -116,12 +116,21 @@ impl EntityTransformer {
 					},
 				),
 			);
+			// single column unique indexes are marked on their column
+			let unique_keys: Vec<Vec<String>> = table_create
+				.get_indexes()
+				.iter()
+				.filter(|index| index.is_unique_key())
+				.map(|index| index.get_index_spec().get_column_names())
+				.filter(|col_names| col_names.len() > 1)
+				.collect();
 			let entity = Entity {
 				table_name: table_name.clone(),
 				columns,
 				relations: relations.clone(),
 				conjunct_relations: vec![],
 				primary_keys,
+				unique_keys,
 			};
 			entities.insert(table_name.clone(), entity.clone());
 			for mut rel in relations.into_iter() {
@@ -346,6 +355,31 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn unique_keys() -> Result<(), Box<dyn Error>> {
+		use sea_query::{Alias, ColumnDef, Index, Table};
+
+		let table = Table::create()
+			.table(Alias::new("films"))
+			.col(ColumnDef::new(Alias::new("id")).integer().not_null().primary_key())
+			.col(ColumnDef::new(Alias::new("code")).string().not_null())
+			.col(ColumnDef::new(Alias::new("title")).string().not_null())
+			.col(ColumnDef::new(Alias::new("release_year")).integer().not_null())
+			.index(Index::create().unique().col(Alias::new("code")))
+			.index(
+				Index::create().unique().col(Alias::new("title")).col(Alias::new("release_year")),
+			)
+			.to_owned();
+
+		let writer = EntityTransformer::transform(vec![table])?;
+		let entity = &writer.entities[0];
+
+		assert_eq!(entity.unique_keys, vec![vec!["title".to_owned(), "release_year".to_owned()]]);
+		assert!(entity.columns.iter().any(|column| column.name == "code" && column.unique));
+
+		Ok(())
+	}
+
 	fn validate_compact_entities(
 		table_create_stmts: Vec<TableCreateStatement>,
 		files: Vec<(&str, &str)>,
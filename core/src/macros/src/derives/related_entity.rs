@@ -47,7 +47,7 @@ impl DeriveRelatedEntity {
 		let ident = &self.ident;
 		let entity_ident = &self.entity_ident;
 
-		let variant_implementations: Vec<TokenStream> = self
+		let variant_implementations: Vec<(TokenStream, TokenStream)> = self
 			.variants
 			.iter()
 			.map(|variant| {
@@ -70,17 +70,32 @@ impl DeriveRelatedEntity {
 				let name = enum_name.to_string().to_lower_camel_case();
 
 				if let Some(def) = def {
-					Result::<_, syn::Error>::Ok(quote! {
-						Self::#enum_name => builder.get_relation::<#entity_ident, #target_entity, T, F>(#name, #def)
-					})
+					Result::<_, syn::Error>::Ok((
+						quote! {
+							Self::#enum_name => builder.get_relation::<#entity_ident, #target_entity, T, F>(#name, #def)
+						},
+						quote! {
+							Self::#enum_name => builder.get_nested_insert::<#entity_ident, #target_entity>(#name, #def).map(Some)
+						},
+					))
 				} else {
-					Result::<_, syn::Error>::Ok(quote! {
-						Self::#enum_name => via_builder.get_relation::<#entity_ident, #target_entity, T, F>(#name)
-					})
+					Result::<_, syn::Error>::Ok((
+						quote! {
+							Self::#enum_name => via_builder.get_relation::<#entity_ident, #target_entity, T, F>(#name)
+						},
+						quote! {
+							Self::#enum_name => via_builder.get_nested_insert::<#entity_ident, #target_entity>(#name)
+						},
+					))
 				}
 			})
 			.collect::<Result<Vec<_>, _>>()?;
 
+		let (variant_implementations, nested_insert_implementations): (
+			Vec<TokenStream>,
+			Vec<TokenStream>,
+		) = variant_implementations.into_iter().unzip();
+
 		Ok(quote! {
 			impl apy::RelationBuilder for #ident {
 				fn get_relation<T, F>(
@@ -98,6 +113,18 @@ impl DeriveRelatedEntity {
 						_ => panic!("No relations for this entity"),
 					}
 				}
+
+				fn get_nested_insert(
+					&self,
+					context: &'static apy::BuilderContext,
+				) -> dynamic::SeaResult<Option<apy::NestedInsertRelation>> {
+					let builder = apy::EntityObjectRelationBuilder { context };
+					let via_builder = apy::EntityObjectViaRelationBuilder { context };
+					match self {
+						#(#nested_insert_implementations,)*
+						_ => Ok(None),
+					}
+				}
 			}
 		})
 	}
@@ -1,6 +1,6 @@
 use crate::{
 	Config, ServerConfig, ServerType,
-	server::{grpc_server, http_server},
+	server::{grpc_server, http_server, thrift_server},
 };
 use apy::QueryRoot;
 use dynamic::{
@@ -48,7 +48,7 @@ impl App {
 		config: &ServerConfig,
 	) -> Result<Schema, SchemaError> {
 		if let Some(service) = self.http.as_mut() {
-			service.config_schema(config.limit.unwrap_or(0), config.complexity.unwrap_or(0));
+			service.config_schema(config.limit, config.complexity);
 			return service.root(database);
 		}
 
@@ -79,6 +79,11 @@ impl App {
 					let query_root = self.get_http(&database, service)?;
 					services.push(tokio::spawn(http_server(query_root, service.clone())));
 				}
+				// thrift is served from the same types as gRPC
+				ServerType::Thrift => {
+					let query_root = self.get_grpc(&database)?;
+					services.push(tokio::spawn(thrift_server(query_root, service.clone())));
+				}
 			};
 		}
 
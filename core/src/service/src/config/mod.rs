@@ -45,9 +45,9 @@ pub struct ServerConfig {
 	pub host: String,
 	pub port: u16,
 	#[serde(default)]
-	pub limit: Option<u16>, // Optional for Grpc servers
+	pub limit: Option<u16>, // Optional for Grpc and Thrift servers
 	#[serde(default)]
-	pub complexity: Option<u16>, // Optional for Grpc servers
+	pub complexity: Option<u16>, // Optional for Grpc and Thrift servers
 }
 
 impl ServerConfig {
@@ -60,4 +60,5 @@ impl ServerConfig {
 pub enum ServerType {
 	Grpc,
 	Http,
+	Thrift,
 }
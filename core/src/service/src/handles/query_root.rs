@@ -33,9 +33,9 @@ impl Schema {
 	}
 }
 impl QueryRoot<DynamicSchema> for Schema {
-	fn config_schema(&mut self, depth: u16, complexity: u16) {
-		self.depth = Some(depth);
-		self.complexity = Some(complexity);
+	fn config_schema(&mut self, depth: Option<u16>, complexity: Option<u16>) {
+		self.depth = depth;
+		self.complexity = complexity;
 	}
 	fn root(&self, database: &DatabaseConnection) -> Result<DynamicSchema, SchemaError> {
 		let builder = builder::<GraphQLTypeRef, GraphQLEnum, GraphQlFilterType>(database);
@@ -1,27 +1,26 @@
 use crate::ServerConfig;
 use actix_web::{
-	App, HttpResponse, HttpServer, Result, guard,
+	App, HttpServer, guard,
 	web::{self, Data},
 };
-use dynamic::prelude::{Schema, Value};
-use juniper::http::{GraphQLRequest, graphiql::graphiql_source};
-
-async fn index(schema: Data<Schema>, request: web::Json<GraphQLRequest<Value>>) -> HttpResponse {
-	schema.execute(request.into_inner()).await
-}
-
-async fn playground() -> Result<HttpResponse> {
-	Ok(HttpResponse::Ok()
-		.content_type("text/html; charset=utf-8")
-		.body(graphiql_source("/graphql", None)))
-}
+use dynamic::{
+	MAX_BODY_SIZE, graphql_get, graphql_post, graphql_sdl, prelude::Schema, subscriptions,
+};
 
 fn graphql(cfg: &mut web::ServiceConfig, schema: Schema) {
 	cfg.service(
 		web::scope("/graphql")
 			.app_data(Data::new(schema.clone()))
-			.service(web::resource("").guard(guard::Post()).to(index))
-			.service(web::resource("").guard(guard::Get()).to(playground)), // .service(web::resource("/sdl").guard(guard::Get()).to(sdl)), // .service(web::redirect("", "/graphql/")),
+			.app_data(web::PayloadConfig::new(MAX_BODY_SIZE))
+			.service(web::resource("").guard(guard::Post()).to(graphql_post))
+			.service(
+				web::resource("")
+					.guard(guard::Get())
+					.guard(guard::Header("upgrade", "websocket"))
+					.to(subscriptions),
+			)
+			.service(web::resource("").guard(guard::Get()).to(graphql_get))
+			.service(web::resource("/sdl").guard(guard::Get()).to(graphql_sdl)),
 	);
 }
 
@@ -1,5 +1,7 @@
 mod graphql;
 mod proto;
+mod thrift;
 
 pub use graphql::*;
 pub use proto::*;
+pub use thrift::*;
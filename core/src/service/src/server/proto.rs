@@ -1,5 +1,8 @@
 use crate::ServerConfig;
-use dynamic::prelude::{Proto, WrapperMutation, WrapperQuery};
+use dynamic::{
+	ProtoJsonLayer, ProtoSdlLayer,
+	prelude::{Proto, WrapperMutation, WrapperQuery},
+};
 use tokio::signal;
 use tonic::transport::Server;
 use tonic_health::server::health_reporter;
@@ -16,12 +19,16 @@ pub async fn grpc_server(proto: Proto, config: ServerConfig) {
 	let (mut _health_reporter, health_service) = health_reporter();
 
 	println!("Visit gRPC at grpc://{}", config.get_uri());
+	println!("Download the proto file at http://{}/apy.proto", config.get_uri());
+	println!("Call the methods with JSON at http://{}/apy.<Service>/<method>", config.get_uri());
 	Server::builder()
 		.accept_http1(true)
+		.layer(ProtoSdlLayer::new(&proto))
+		.layer(ProtoJsonLayer::new(&proto))
 		.layer(GrpcWebLayer::new())
 		.add_service(reflection)
-		.add_service(WrapperQuery::new(proto.get_data()))
-		.add_service(WrapperMutation::new(proto.get_data()))
+		.add_service(WrapperQuery::new(proto.get_data(), proto.get_types()))
+		.add_service(WrapperMutation::new(proto.get_data(), proto.get_types()))
 		.add_service(health_service)
 		.serve_with_shutdown(config.get_uri().parse().unwrap(), async {
 			signal::ctrl_c().await.expect("failed to listen for ctrl_c");
@@ -0,0 +1,13 @@
+use crate::ServerConfig;
+use dynamic::{prelude::Proto, serve_thrift};
+use tokio::{net::TcpListener, signal};
+
+pub async fn thrift_server(proto: Proto, config: ServerConfig) {
+	let listener = TcpListener::bind(config.get_uri()).await.unwrap();
+
+	println!("Visit Thrift at tcp://{}", config.get_uri());
+	tokio::select! {
+		res = serve_thrift(proto, listener) => res.unwrap(),
+		_ = signal::ctrl_c() => {}
+	}
+}